@@ -0,0 +1,59 @@
+use crate::{
+    app::LogItem,
+    repo::dto::{AlertRuleDto, LogLevelDto},
+    text_matching::TextMatcher,
+};
+
+/// Group name used when the event has no value for the `group_by` key.
+pub const NONE_GROUP: &str = "(none)";
+
+/// Alert rule with its phrase compiled once.
+pub struct CompiledAlertRule {
+    pub rule: AlertRuleDto,
+    phrase: Option<TextMatcher>,
+}
+
+impl CompiledAlertRule {
+    pub fn new(rule: AlertRuleDto) -> Result<Self, String> {
+        let phrase = rule.compile_phrase()?;
+        Ok(Self { rule, phrase })
+    }
+
+    pub fn matches(&self, item: &LogItem) -> bool {
+        if self.rule.disabled {
+            return false;
+        }
+
+        let level: LogLevelDto = (&item.level).into();
+        if level.severity() < self.rule.min_level.severity() {
+            return false;
+        }
+
+        if let Some(application) = self.rule.application.as_deref() {
+            if !item.is_application(application) {
+                return false;
+            }
+        }
+
+        for (key, value) in self.rule.context.iter() {
+            if item.ctx.get(key) != Some(value) {
+                return false;
+            }
+        }
+
+        match &self.phrase {
+            Some(phrase) => item.has_entry(phrase),
+            None => true,
+        }
+    }
+
+    pub fn get_group(&self, item: &LogItem) -> String {
+        match self.rule.group_by.as_deref() {
+            Some(key) => match item.ctx.get(key) {
+                Some(value) => value.to_string(),
+                None => NONE_GROUP.to_string(),
+            },
+            None => String::new(),
+        }
+    }
+}
@@ -0,0 +1,480 @@
+use std::collections::{HashMap, VecDeque};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::{LogItem, APPLICATION_KEY},
+    repo::dto::{AlertRuleDto, LogLevelDto},
+};
+
+use super::CompiledAlertRule;
+
+const MAX_GROUPS_PER_RULE: usize = 1000;
+const OTHER_GROUP: &str = "(other)";
+const MAX_SAMPLE_LEN: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertNotificationKind {
+    Firing,
+    Resolved,
+}
+
+#[derive(Debug, Clone)]
+pub struct AlertNotification {
+    pub kind: AlertNotificationKind,
+    pub rule_id: String,
+    pub rule_name: String,
+    pub min_level: LogLevelDto,
+    pub application: Option<String>,
+    pub group_by: Option<String>,
+    pub group: String,
+    /// Events within the window at the moment of notification.
+    pub count: usize,
+    pub threshold: u32,
+    pub window_sec: u64,
+    pub sample_message: Option<String>,
+    pub moment: DateTimeAsMicroseconds,
+    pub channels: Vec<String>,
+    pub saved_search_id: Option<String>,
+}
+
+impl AlertNotification {
+    /// Application of the rule, or of the group when grouped by application.
+    pub fn get_application(&self) -> Option<&str> {
+        if let Some(application) = self.application.as_deref() {
+            return Some(application);
+        }
+
+        match self.group_by.as_deref() {
+            Some(APPLICATION_KEY) => Some(self.group.as_str()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FiringAlert {
+    pub rule_name: String,
+    pub group: String,
+    pub application: Option<String>,
+}
+
+struct AlertState {
+    /// Receive moments of the latest `threshold + 1` matching events, oldest first.
+    moments: VecDeque<i64>,
+    firing: bool,
+    firing_notified: bool,
+    last_notified: Option<i64>,
+    below_since: Option<i64>,
+    sample_message: String,
+}
+
+impl AlertState {
+    fn new() -> Self {
+        Self {
+            moments: VecDeque::new(),
+            firing: false,
+            firing_notified: false,
+            last_notified: None,
+            below_since: None,
+            sample_message: String::new(),
+        }
+    }
+
+    /// Moments are receive times, so they are in order: the ones before the window are dropped.
+    fn count_in_window(&mut self, window_start: i64) -> usize {
+        while self
+            .moments
+            .front()
+            .is_some_and(|moment| *moment < window_start)
+        {
+            self.moments.pop_front();
+        }
+
+        self.moments.len()
+    }
+
+    fn is_cooldown_passed(&self, rule: &AlertRuleDto, now: i64) -> bool {
+        match self.last_notified {
+            Some(last_notified) => now - last_notified >= rule.cooldown_sec as i64 * 1_000_000,
+            None => true,
+        }
+    }
+}
+
+/// Evaluates alert rules against ingested events. Notifications are collected and sent by the timer.
+pub struct AlertsEngine {
+    rules: Vec<CompiledAlertRule>,
+    states: HashMap<String, HashMap<String, AlertState>>,
+    pending: Vec<AlertNotification>,
+}
+
+impl AlertsEngine {
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            states: HashMap::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Replaces the rule set. State of unchanged rules is kept, so firing alerts are not re-sent.
+    pub fn set_rules(&mut self, rules: Vec<AlertRuleDto>) {
+        let mut compiled = Vec::with_capacity(rules.len());
+
+        for rule in rules {
+            match CompiledAlertRule::new(rule) {
+                Ok(rule) => compiled.push(rule),
+                Err(err) => println!("Skipping invalid alert rule: {}", err),
+            }
+        }
+
+        self.states.retain(|rule_id, _| {
+            let old = self.rules.iter().find(|r| &r.rule.id == rule_id);
+            let new = compiled.iter().find(|r| &r.rule.id == rule_id);
+            match (old, new) {
+                (Some(old), Some(new)) => old.rule == new.rule,
+                _ => false,
+            }
+        });
+
+        self.rules = compiled;
+    }
+
+    /// Rule name, group and application of every firing alert.
+    pub fn get_firing(&self) -> Vec<FiringAlert> {
+        let mut result = Vec::new();
+
+        for compiled in self.rules.iter() {
+            let rule = &compiled.rule;
+            let Some(by_group) = self.states.get(&rule.id) else {
+                continue;
+            };
+
+            for (group, state) in by_group.iter() {
+                if !state.firing {
+                    continue;
+                }
+
+                let application = match rule.application.as_deref() {
+                    Some(application) => Some(application.to_string()),
+                    None if rule.group_by.as_deref() == Some(APPLICATION_KEY) => {
+                        Some(group.to_string())
+                    }
+                    None => None,
+                };
+
+                result.push(FiringAlert {
+                    rule_name: rule.name.clone(),
+                    group: group.clone(),
+                    application,
+                });
+            }
+        }
+
+        result
+    }
+
+    pub fn update(&mut self, item: &LogItem, now: DateTimeAsMicroseconds) {
+        let now = now.unix_microseconds;
+
+        for compiled in self.rules.iter() {
+            if !compiled.matches(item) {
+                continue;
+            }
+
+            let rule = &compiled.rule;
+
+            let by_group = match self.states.get_mut(&rule.id) {
+                Some(by_group) => by_group,
+                None => {
+                    self.states.insert(rule.id.clone(), HashMap::new());
+                    self.states.get_mut(&rule.id).unwrap()
+                }
+            };
+
+            let mut group = compiled.get_group(item);
+            if !by_group.contains_key(&group) && by_group.len() >= MAX_GROUPS_PER_RULE {
+                group = OTHER_GROUP.to_string();
+            }
+
+            let state = by_group
+                .entry(group.clone())
+                .or_insert_with(AlertState::new);
+
+            let capacity = rule.threshold as usize + 1;
+            state.moments.push_back(now);
+            while state.moments.len() > capacity {
+                state.moments.pop_front();
+            }
+
+            state.sample_message = truncate(&item.message, MAX_SAMPLE_LEN);
+
+            if state.firing {
+                continue;
+            }
+
+            let window_start = now - rule.window_sec as i64 * 1_000_000;
+            let count = state.count_in_window(window_start);
+
+            if count < capacity {
+                continue;
+            }
+
+            state.firing = true;
+
+            // Within the cooldown the firing is announced by `tick` once the cooldown ends.
+            if !state.is_cooldown_passed(rule, now) {
+                continue;
+            }
+
+            state.last_notified = Some(now);
+            state.firing_notified = true;
+
+            let sample_message = Some(state.sample_message.clone());
+            self.pending.push(compile_notification(
+                rule,
+                AlertNotificationKind::Firing,
+                group,
+                count,
+                sample_message,
+                now,
+            ));
+        }
+    }
+
+    /// Resolves alerts which stayed below the threshold long enough and returns everything to send.
+    pub fn tick(&mut self, now: DateTimeAsMicroseconds) -> Vec<AlertNotification> {
+        let now = now.unix_microseconds;
+
+        for compiled in self.rules.iter() {
+            let rule = &compiled.rule;
+            let Some(by_group) = self.states.get_mut(&rule.id) else {
+                continue;
+            };
+
+            let window_start = now - rule.window_sec as i64 * 1_000_000;
+            let capacity = rule.threshold as usize + 1;
+            let cooldown = rule.cooldown_sec as i64 * 1_000_000;
+
+            for (group, state) in by_group.iter_mut() {
+                if !state.firing {
+                    continue;
+                }
+
+                let count = state.count_in_window(window_start);
+                if count >= capacity {
+                    state.below_since = None;
+
+                    if !state.firing_notified && state.is_cooldown_passed(rule, now) {
+                        state.last_notified = Some(now);
+                        state.firing_notified = true;
+
+                        self.pending.push(compile_notification(
+                            rule,
+                            AlertNotificationKind::Firing,
+                            group.clone(),
+                            count,
+                            Some(state.sample_message.clone()),
+                            now,
+                        ));
+                    }
+
+                    continue;
+                }
+
+                let below_since = *state.below_since.get_or_insert(now);
+                if now - below_since < rule.resolve_after_sec as i64 * 1_000_000 {
+                    continue;
+                }
+
+                state.firing = false;
+                state.below_since = None;
+
+                if !state.firing_notified {
+                    continue;
+                }
+
+                state.firing_notified = false;
+
+                self.pending.push(compile_notification(
+                    rule,
+                    AlertNotificationKind::Resolved,
+                    group.clone(),
+                    count,
+                    None,
+                    now,
+                ));
+            }
+
+            by_group.retain(|_, state| {
+                if state.firing {
+                    return true;
+                }
+
+                if state.count_in_window(window_start) > 0 {
+                    return true;
+                }
+
+                match state.last_notified {
+                    Some(last_notified) => now - last_notified < cooldown,
+                    None => false,
+                }
+            });
+        }
+
+        std::mem::take(&mut self.pending)
+    }
+}
+
+fn compile_notification(
+    rule: &AlertRuleDto,
+    kind: AlertNotificationKind,
+    group: String,
+    count: usize,
+    sample_message: Option<String>,
+    now: i64,
+) -> AlertNotification {
+    AlertNotification {
+        kind,
+        rule_id: rule.id.clone(),
+        rule_name: rule.name.clone(),
+        min_level: rule.min_level.clone(),
+        application: rule.application.clone(),
+        group_by: rule.group_by.clone(),
+        group,
+        count,
+        threshold: rule.threshold,
+        window_sec: rule.window_sec,
+        sample_message,
+        moment: DateTimeAsMicroseconds::new(now),
+        channels: rule.channels.clone(),
+        saved_search_id: rule.saved_search_id.clone(),
+    }
+}
+
+fn truncate(src: &str, max_len: usize) -> String {
+    if src.len() <= max_len {
+        return src.to_string();
+    }
+
+    let mut end = max_len;
+    while !src.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    format!("{}…", &src[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{AlertNotificationKind, AlertsEngine};
+    use crate::{
+        app::LogItem,
+        repo::dto::{AlertRuleDto, LogLevelDto, MatchModeDto},
+    };
+
+    fn log_item(application: &str, level: my_logger::LogLevel) -> LogItem {
+        let mut ctx = BTreeMap::new();
+        ctx.insert("Application".to_string(), application.to_string());
+
+        LogItem {
+            id: "1".to_string(),
+            level,
+            process: None,
+            message: "Payment failed".to_string(),
+            timestamp: DateTimeAsMicroseconds::now(),
+            ctx,
+        }
+    }
+
+    fn payments_rule(threshold: u32) -> AlertRuleDto {
+        AlertRuleDto {
+            id: "1".to_string(),
+            name: "Payments errors".to_string(),
+            disabled: false,
+            application: Some("payments".to_string()),
+            min_level: LogLevelDto::Error,
+            context: BTreeMap::new(),
+            phrase: None,
+            match_mode: MatchModeDto::Contains,
+            case_insensitive: false,
+            threshold,
+            window_sec: 60,
+            group_by: None,
+            cooldown_sec: 600,
+            resolve_after_sec: 30,
+            channels: vec![],
+            saved_search_id: Some("payments-errors".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_fires_above_threshold_and_resolves() {
+        let mut engine = AlertsEngine::new();
+        engine.set_rules(vec![payments_rule(2)]);
+
+        let start = DateTimeAsMicroseconds::new(1_000_000_000_000);
+
+        engine.update(&log_item("payments", my_logger::LogLevel::Warning), start);
+        engine.update(&log_item("billing", my_logger::LogLevel::Error), start);
+        engine.update(&log_item("payments", my_logger::LogLevel::Error), start);
+        engine.update(
+            &log_item("payments", my_logger::LogLevel::FatalError),
+            start,
+        );
+        assert!(engine.tick(start).is_empty());
+
+        engine.update(&log_item("payments", my_logger::LogLevel::Error), start);
+        let notifications = engine.tick(start);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].kind, AlertNotificationKind::Firing);
+        assert_eq!(notifications[0].count, 3);
+        assert_eq!(
+            notifications[0].saved_search_id.as_deref(),
+            Some("payments-errors")
+        );
+
+        let later = DateTimeAsMicroseconds::new(start.unix_microseconds + 61_000_000);
+        assert!(engine.tick(later).is_empty());
+
+        let later = DateTimeAsMicroseconds::new(later.unix_microseconds + 30_000_000);
+        let notifications = engine.tick(later);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].kind, AlertNotificationKind::Resolved);
+    }
+
+    #[test]
+    fn test_firing_within_cooldown_is_sent_after_cooldown() {
+        let mut engine = AlertsEngine::new();
+        engine.set_rules(vec![payments_rule(0)]);
+
+        let start = 1_000_000_000_000;
+        let at = |sec: i64| DateTimeAsMicroseconds::new(start + sec * 1_000_000);
+        let error = || log_item("payments", my_logger::LogLevel::Error);
+
+        engine.update(&error(), at(0));
+        let notifications = engine.tick(at(0));
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].kind, AlertNotificationKind::Firing);
+
+        assert!(engine.tick(at(61)).is_empty());
+        let notifications = engine.tick(at(91));
+        assert_eq!(notifications[0].kind, AlertNotificationKind::Resolved);
+
+        // Fires again within the 600s cooldown
+        engine.update(&error(), at(120));
+        assert!(engine.tick(at(120)).is_empty());
+
+        engine.update(&error(), at(599));
+        let notifications = engine.tick(at(600));
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].kind, AlertNotificationKind::Firing);
+
+        assert!(engine.tick(at(601)).is_empty());
+    }
+}
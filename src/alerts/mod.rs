@@ -0,0 +1,4 @@
+mod alert_rule;
+pub use alert_rule::*;
+mod alerts_engine;
+pub use alerts_engine::*;
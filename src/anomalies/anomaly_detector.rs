@@ -0,0 +1,302 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    alerts::{AlertNotification, AlertNotificationKind},
+    hourly_statistics::{HourlyStatisticsItem, StatisticsHour},
+    repo::dto::LogLevelDto,
+    settings::AnomalyDetectionSettings,
+};
+
+const MIN_BASELINE_HOURS: usize = 6;
+const HOUR_SEC: i64 = 3600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnomalyKind {
+    ErrorSpike,
+    WarningSpike,
+    Silence,
+}
+
+impl AnomalyKind {
+    fn get_rule_id(&self) -> &'static str {
+        match self {
+            AnomalyKind::ErrorSpike => "anomaly:error-spike",
+            AnomalyKind::WarningSpike => "anomaly:warning-spike",
+            AnomalyKind::Silence => "anomaly:silence",
+        }
+    }
+
+    fn get_rule_name(&self) -> &'static str {
+        match self {
+            AnomalyKind::ErrorSpike => "Error spike",
+            AnomalyKind::WarningSpike => "Warning spike",
+            AnomalyKind::Silence => "Application went silent",
+        }
+    }
+
+    fn get_level(&self) -> LogLevelDto {
+        match self {
+            AnomalyKind::WarningSpike => LogLevelDto::Warning,
+            _ => LogLevelDto::Error,
+        }
+    }
+
+    fn get_count(&self, item: Option<&HourlyStatisticsItem>) -> u64 {
+        let Some(item) = item else {
+            return 0;
+        };
+
+        match self {
+            AnomalyKind::ErrorSpike => item.error as u64 + item.fatal_error as u64,
+            AnomalyKind::WarningSpike => item.warning as u64,
+            AnomalyKind::Silence => {
+                item.info as u64
+                    + item.warning as u64
+                    + item.error as u64
+                    + item.fatal_error as u64
+                    + item.debug as u64
+            }
+        }
+    }
+}
+
+/// Mean and standard deviation of hourly counts.
+fn get_baseline(samples: &[u64]) -> (f64, f64) {
+    let len = samples.len() as f64;
+    let mean = samples.iter().sum::<u64>() as f64 / len;
+    let variance = samples
+        .iter()
+        .map(|value| (*value as f64 - mean).powi(2))
+        .sum::<f64>()
+        / len;
+
+    (mean, variance.sqrt())
+}
+
+/// Compares the current hour of every application with the previous hour buckets of the rollup statistics.
+/// The current hour is extrapolated from the elapsed part of it.
+pub struct AnomalyDetector {
+    firing: HashSet<(String, AnomalyKind)>,
+}
+
+impl AnomalyDetector {
+    pub fn new() -> Self {
+        Self {
+            firing: HashSet::new(),
+        }
+    }
+
+    /// `hours` are newest first, as returned by `RollupStatistics::get_max_hours`.
+    pub fn check(
+        &mut self,
+        hours: &[(StatisticsHour, BTreeMap<String, HourlyStatisticsItem>)],
+        settings: &AnomalyDetectionSettings,
+        now: DateTimeAsMicroseconds,
+    ) -> Vec<AlertNotification> {
+        let mut result = Vec::new();
+
+        let elapsed_sec = (now.unix_microseconds / 1_000_000) % HOUR_SEC;
+        if elapsed_sec < settings.get_min_elapsed_sec() {
+            return result;
+        }
+
+        let current_key: StatisticsHour = now.into();
+        let current = hours
+            .iter()
+            .find(|(hour, _)| *hour == current_key)
+            .map(|(_, apps)| apps);
+
+        let baseline: Vec<_> = hours
+            .iter()
+            .filter(|(hour, _)| *hour < current_key)
+            .take(settings.get_baseline_hours())
+            .map(|(_, apps)| apps)
+            .collect();
+
+        if baseline.len() < MIN_BASELINE_HOURS {
+            return result;
+        }
+
+        let mut applications = BTreeSet::new();
+        for apps in baseline.iter().chain(current.iter()) {
+            applications.extend(apps.keys().map(|app| app.as_str()));
+        }
+
+        let fraction = elapsed_sec as f64 / HOUR_SEC as f64;
+
+        for application in applications.iter() {
+            let current_item = current.and_then(|apps| apps.get(*application));
+
+            for kind in [
+                AnomalyKind::ErrorSpike,
+                AnomalyKind::WarningSpike,
+                AnomalyKind::Silence,
+            ] {
+                let samples: Vec<u64> = baseline
+                    .iter()
+                    .map(|apps| kind.get_count(apps.get(*application)))
+                    .collect();
+
+                let (mean, std_dev) = get_baseline(&samples);
+                let observed = kind.get_count(current_item);
+                let expected = mean * fraction;
+
+                let key = (application.to_string(), kind);
+                let firing = self.firing.contains(&key);
+
+                let (anomalous, recovered, description) = match kind {
+                    AnomalyKind::Silence => (
+                        observed == 0 && mean >= settings.get_silence_min_hourly_events() as f64,
+                        observed > 0,
+                        format!(
+                            "No events for {} min, usually {:.0} per hour",
+                            elapsed_sec / 60,
+                            mean
+                        ),
+                    ),
+                    _ => {
+                        let sigma = (std_dev * fraction).max(expected.sqrt()).max(1.0);
+                        let z_score = (observed as f64 - expected) / sigma;
+                        let z_threshold = settings.get_z_score();
+
+                        (
+                            observed >= settings.get_min_events() as u64 && z_score >= z_threshold,
+                            z_score < z_threshold / 2.0,
+                            format!(
+                                "{} events in {} min, expected {:.0} (z-score {:.1})",
+                                observed,
+                                elapsed_sec / 60,
+                                expected,
+                                z_score
+                            ),
+                        )
+                    }
+                };
+
+                let notification_kind = if !firing && anomalous {
+                    self.firing.insert(key);
+                    AlertNotificationKind::Firing
+                } else if firing && recovered {
+                    self.firing.remove(&key);
+                    AlertNotificationKind::Resolved
+                } else {
+                    continue;
+                };
+
+                result.push(AlertNotification {
+                    kind: notification_kind,
+                    rule_id: kind.get_rule_id().to_string(),
+                    rule_name: kind.get_rule_name().to_string(),
+                    min_level: kind.get_level(),
+                    application: Some(application.to_string()),
+                    group_by: None,
+                    group: application.to_string(),
+                    count: observed as usize,
+                    threshold: expected.round() as u32,
+                    window_sec: elapsed_sec as u64,
+                    sample_message: Some(description),
+                    moment: now,
+                    channels: settings.channels.clone().unwrap_or_default(),
+                    saved_search_id: None,
+                });
+            }
+        }
+
+        self.firing
+            .retain(|(application, _)| applications.contains(application.as_str()));
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{AnomalyDetector, HOUR_SEC};
+    use crate::{
+        alerts::AlertNotificationKind,
+        hourly_statistics::{HourlyStatisticsItem, StatisticsHour},
+        settings::AnomalyDetectionSettings,
+    };
+
+    fn hours(
+        now: DateTimeAsMicroseconds,
+        current: HourlyStatisticsItem,
+    ) -> Vec<(StatisticsHour, BTreeMap<String, HourlyStatisticsItem>)> {
+        let mut result = Vec::new();
+
+        for hour_no in 0..12 {
+            let moment =
+                DateTimeAsMicroseconds::new(now.unix_microseconds - hour_no * HOUR_SEC * 1_000_000);
+
+            let item = if hour_no == 0 {
+                current
+            } else {
+                HourlyStatisticsItem {
+                    info: 600,
+                    warning: 30,
+                    error: 10 + hour_no as u32 % 3,
+                    fatal_error: 0,
+                    debug: 0,
+                    sampled: 0,
+                }
+            };
+
+            let mut apps = BTreeMap::new();
+            apps.insert("payments".to_string(), item);
+            result.push((moment.into(), apps));
+        }
+
+        result
+    }
+
+    #[test]
+    fn test_spike_and_silence_fire_once_and_resolve() {
+        let settings = AnomalyDetectionSettings::default();
+        let mut detector = AnomalyDetector::new();
+
+        // 30 minutes into the hour
+        let now = DateTimeAsMicroseconds::new(
+            1_700_000_000_000_000 / 3_600_000_000 * 3_600_000_000 + 1_800_000_000,
+        );
+
+        let normal = HourlyStatisticsItem {
+            info: 300,
+            warning: 15,
+            error: 6,
+            fatal_error: 0,
+            debug: 0,
+            sampled: 0,
+        };
+        assert!(detector
+            .check(&hours(now, normal), &settings, now)
+            .is_empty());
+
+        let spike = HourlyStatisticsItem {
+            error: 200,
+            ..normal
+        };
+        let notifications = detector.check(&hours(now, spike), &settings, now);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].rule_name, "Error spike");
+        assert_eq!(notifications[0].kind, AlertNotificationKind::Firing);
+        assert!(detector
+            .check(&hours(now, spike), &settings, now)
+            .is_empty());
+
+        let notifications = detector.check(&hours(now, normal), &settings, now);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].kind, AlertNotificationKind::Resolved);
+
+        let notifications =
+            detector.check(&hours(now, HourlyStatisticsItem::default()), &settings, now);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].rule_name, "Application went silent");
+        assert_eq!(notifications[0].kind, AlertNotificationKind::Firing);
+    }
+}
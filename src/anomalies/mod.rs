@@ -0,0 +1,2 @@
+mod anomaly_detector;
+pub use anomaly_detector::*;
@@ -1,21 +1,39 @@
 use std::sync::Arc;
 
 use elastic_client::{ElasticClient, ElasticClientAuth};
-use rust_extensions::AppStates;
+use rust_extensions::{date_time::DateTimeAsMicroseconds, AppStates};
 use tokio::sync::Mutex;
 
 use crate::{
+    alerts::AlertsEngine,
     cache::FilterEventsCache,
-    hourly_statistics::HourlyStatistics,
+    error_signatures::NewErrorDetector,
+    field_extraction::FieldExtractors,
+    heartbeats::Heartbeats,
+    ignore_rule_hits::IgnoreRuleHits,
     ignore_single_events::IgnoreSingleEventCache,
     insights_repo::InsightsRepo,
-    repo::{LogsRepo, SettingsRepo, SqliteLogsRepo},
+    issues::Issues,
+    notifications::NotificationSilences,
+    redaction::{RedactionStatistics, Redactor},
+    repo::{
+        AlertRulesRepo, AuditLogRepo, LogsRepo, SavedSearchesRepo, SettingsRepo, SqliteLogsRepo,
+    },
+    rollup_statistics::RollupStatistics,
+    sampling::Sampler,
+    settings::NewErrorDetectionSettings,
+    statistic_dimensions::StatisticDimensions,
     telegram::TelegramNotificationData,
+    version_statistics::VersionStatistics,
 };
 
 use super::LogsQueue;
 
 pub const PROCESS_CONTEXT_KEY: &'static str = "Process";
+pub const APPLICATION_KEY: &'static str = "Application";
+pub const VERSION_KEY: &'static str = "Version";
+/// Groups events without the `Application` context.
+pub const NO_APPLICATION: &'static str = "(unknown)";
 
 pub struct ElasticInner {
     pub client: ElasticClient,
@@ -30,18 +48,55 @@ pub struct AppContext {
     pub sqlite_logs_repo: SqliteLogsRepo,
     pub sqlite_logs_queue: LogsQueue,
     pub settings_repo: SettingsRepo,
+    pub saved_searches_repo: SavedSearchesRepo,
+    pub alert_rules_repo: AlertRulesRepo,
+    pub audit_log_repo: AuditLogRepo,
+    pub alerts_engine: Mutex<AlertsEngine>,
     pub filter_events_cache: FilterEventsCache,
+    pub field_extractors: FieldExtractors,
+    pub redactor: Redactor,
+    pub redaction_statistics: Mutex<RedactionStatistics>,
+    pub sampler: Mutex<Sampler>,
     pub elastic: Option<ElasticInner>,
     pub is_debug: bool,
     pub ignore_single_event_cache: Mutex<IgnoreSingleEventCache>,
 
     pub telegram_notification_data: Mutex<TelegramNotificationData>,
 
+    pub notification_silences: Mutex<NotificationSilences>,
+
+    pub notification_silences_path: String,
+
     pub insights_repo: InsightsRepo,
 
-    pub hourly_statistics: Mutex<HourlyStatistics>,
+    pub new_error_detector: Mutex<NewErrorDetector>,
+
+    pub new_error_detection: Option<NewErrorDetectionSettings>,
+
+    pub issues: Mutex<Issues>,
+
+    pub issues_path: String,
+
+    pub heartbeats: Mutex<Heartbeats>,
+
+    pub heartbeats_path: String,
+
+    pub rollup_statistics: Mutex<RollupStatistics>,
+
+    /// Directory with a file per UTC day.
+    pub rollup_statistics_path: String,
+
+    pub statistic_dimensions: Mutex<StatisticDimensions>,
+
+    pub statistic_dimensions_path: String,
+
+    pub version_statistics: Mutex<VersionStatistics>,
+
+    pub version_statistics_path: String,
 
-    pub statistics_path: String,
+    pub digest_state_path: String,
+
+    pub ignore_rule_hits: Mutex<IgnoreRuleHits>,
 
     pub env_name: String,
 
@@ -57,8 +112,35 @@ impl AppContext {
         let settings_db_path = settings_reader
             .get_logs_db_path("settings.json".into())
             .await;
-        let statistics_path = settings_reader
-            .get_logs_db_path("statistics.json".into())
+        let saved_searches_path = settings_reader
+            .get_logs_db_path("saved-searches.json".into())
+            .await;
+        let alert_rules_path = settings_reader
+            .get_logs_db_path("alert-rules.json".into())
+            .await;
+        let audit_log_path = settings_reader
+            .get_logs_db_path("audit-log.jsonl".into())
+            .await;
+        let issues_path = settings_reader
+            .get_logs_db_path("issues.json".into())
+            .await;
+        let heartbeats_path = settings_reader
+            .get_logs_db_path("heartbeats.json".into())
+            .await;
+        let notification_silences_path = settings_reader
+            .get_logs_db_path("notification-silences.json".into())
+            .await;
+        let rollup_statistics_path = settings_reader
+            .get_logs_db_path("rollup-statistics".into())
+            .await;
+        let statistic_dimensions_path = settings_reader
+            .get_logs_db_path("dimension-statistics.json".into())
+            .await;
+        let version_statistics_path = settings_reader
+            .get_logs_db_path("version-statistics.json".into())
+            .await;
+        let digest_state_path = settings_reader
+            .get_logs_db_path("digest-state.json".into())
             .await;
 
         let mut is_debug = false;
@@ -79,6 +161,12 @@ impl AppContext {
 
         let insights_repo = InsightsRepo::new(insight_keys, 1024);
 
+        let field_extractors = FieldExtractors::new(settings_reader.get_field_extraction().await);
+        let redactor = Redactor::new(settings_reader.get_redaction_settings().await);
+        let sampler = Sampler::new(settings_reader.get_sampling().await);
+        let statistic_dimensions =
+            StatisticDimensions::new(settings_reader.get_statistic_dimensions().await);
+
         Self {
             env_name,
             app_states: Arc::new(AppStates::create_initialized()),
@@ -87,10 +175,30 @@ impl AppContext {
             sqlite_logs_repo: SqliteLogsRepo::new(logs_db_path),
             sqlite_logs_queue: LogsQueue::new(),
             settings_repo: SettingsRepo::new(settings_db_path).await,
+            saved_searches_repo: SavedSearchesRepo::new(saved_searches_path).await,
+            alert_rules_repo: AlertRulesRepo::new(alert_rules_path).await,
+            audit_log_repo: AuditLogRepo::new(audit_log_path),
+            alerts_engine: Mutex::new(AlertsEngine::new()),
             filter_events_cache: FilterEventsCache::new(),
+            field_extractors,
+            redactor,
+            redaction_statistics: Mutex::new(RedactionStatistics::new()),
+            sampler: Mutex::new(sampler),
             ignore_single_event_cache: Mutex::new(IgnoreSingleEventCache::new()),
-            hourly_statistics: Mutex::new(HourlyStatistics::new()),
-            statistics_path,
+            new_error_detector: Mutex::new(NewErrorDetector::new()),
+            new_error_detection: settings_reader.get_new_error_detection().await,
+            issues: Mutex::new(Issues::new()),
+            issues_path,
+            heartbeats: Mutex::new(Heartbeats::new(DateTimeAsMicroseconds::now())),
+            heartbeats_path,
+            rollup_statistics: Mutex::new(RollupStatistics::new()),
+            rollup_statistics_path,
+            statistic_dimensions: Mutex::new(statistic_dimensions),
+            statistic_dimensions_path,
+            version_statistics: Mutex::new(VersionStatistics::new()),
+            version_statistics_path,
+            digest_state_path,
+            ignore_rule_hits: Mutex::new(IgnoreRuleHits::new()),
             elastic: settings_reader
                 .get_elastic_settings()
                 .await
@@ -106,6 +214,8 @@ impl AppContext {
             is_debug,
             insights_repo,
             telegram_notification_data: Mutex::new(TelegramNotificationData::new()),
+            notification_silences: Mutex::new(NotificationSilences::new()),
+            notification_silences_path,
             ui_url: Mutex::new(String::new()),
         }
     }
@@ -121,4 +231,10 @@ impl AppContext {
         let read_access = self.ui_url.lock().await;
         read_access.clone()
     }
+
+    /// Deep link into the UI that opens the saved search. `None` until the UI reported its url.
+    pub async fn get_saved_search_url(&self, saved_search_id: &str) -> Option<String> {
+        let ui_url = self.get_ui_url().await;
+        super::ui_links::compile_saved_search_url(&ui_url, saved_search_id)
+    }
 }
@@ -6,7 +6,7 @@ use std::{
 use my_logger::LogLevel;
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 
-use crate::repo::dto::LogLevelDto;
+use crate::{repo::dto::LogLevelDto, text_matching::TextMatcher};
 
 #[derive(Debug)]
 pub struct LogItem {
@@ -27,21 +27,21 @@ impl LogItem {
         false
     }
 
-    pub fn has_entry(&self, entry: &str) -> bool {
+    pub fn has_entry(&self, entry: &TextMatcher) -> bool {
         if let Some(process) = &self.process {
-            return process.contains(entry) || self.message.contains(entry);
+            return entry.is_match(process) || entry.is_match(&self.message);
         }
 
-        self.message.contains(entry)
+        entry.is_match(&self.message)
     }
 
     pub fn is_level(&self, level: &LogLevelDto) -> bool {
         match level {
-            LogLevelDto::Info => level.is_info(),
-            LogLevelDto::Warning => level.is_warning(),
-            LogLevelDto::Error => level.is_error(),
-            LogLevelDto::FatalError => level.is_fatal_error(),
-            LogLevelDto::Debug => level.is_debug(),
+            LogLevelDto::Info => matches!(self.level, LogLevel::Info),
+            LogLevelDto::Warning => matches!(self.level, LogLevel::Warning),
+            LogLevelDto::Error => matches!(self.level, LogLevel::Error),
+            LogLevelDto::FatalError => matches!(self.level, LogLevel::FatalError),
+            LogLevelDto::Debug => matches!(self.level, LogLevel::Debug),
         }
     }
 }
@@ -111,3 +111,29 @@ impl LogsQueue {
         Some(result)
     }
 */
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::LogItem;
+    use crate::repo::dto::LogLevelDto;
+
+    #[test]
+    fn test_is_level_checks_the_event_level() {
+        let itm = LogItem {
+            id: "Test".to_string(),
+            level: my_logger::LogLevel::Warning,
+            process: None,
+            message: "Test".to_string(),
+            timestamp: DateTimeAsMicroseconds::now(),
+            ctx: BTreeMap::new(),
+        };
+
+        assert!(itm.is_level(&LogLevelDto::Warning));
+        assert!(!itm.is_level(&LogLevelDto::Error));
+        assert!(!itm.is_level(&LogLevelDto::Info));
+    }
+}
@@ -3,3 +3,6 @@ mod app;
 pub use app::*;
 mod logs_queue;
 pub use logs_queue::*;
+mod persistence;
+pub use persistence::*;
+pub mod ui_links;
@@ -0,0 +1,29 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Writes `value` as JSON to `path`. Errors are logged, `name` is used in the messages.
+pub async fn persist_json<T: Serialize>(path: &str, name: &str, value: &T) {
+    let bytes = match serde_json::to_vec(value) {
+        Ok(b) => b,
+        Err(e) => {
+            println!("Failed to serialize {}: {}", name, e);
+            return;
+        }
+    };
+
+    if let Err(e) = tokio::fs::write(path, bytes).await {
+        println!("Failed to write {} to {}: {}", name, path, e);
+    }
+}
+
+/// `None` if there is no file at `path` or it can not be parsed.
+pub async fn restore_json<T: DeserializeOwned>(path: &str, name: &str) -> Option<T> {
+    let bytes = tokio::fs::read(path).await.ok()?;
+
+    match serde_json::from_slice(&bytes) {
+        Ok(v) => Some(v),
+        Err(e) => {
+            println!("Failed to parse {} file at {}: {}", name, path, e);
+            None
+        }
+    }
+}
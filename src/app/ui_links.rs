@@ -0,0 +1,70 @@
+pub const SAVED_SEARCH_QUERY_PARAM: &str = "savedSearch";
+pub const APPLICATION_QUERY_PARAM: &str = "application";
+pub const FROM_QUERY_PARAM: &str = "from";
+pub const TO_QUERY_PARAM: &str = "to";
+
+pub fn compile_saved_search_url(ui_url: &str, saved_search_id: &str) -> Option<String> {
+    if ui_url.is_empty() {
+        return None;
+    }
+
+    let mut result = String::with_capacity(ui_url.len() + saved_search_id.len() + 16);
+    result.push_str(ui_url);
+    push_query_separator(&mut result);
+    result.push_str(SAVED_SEARCH_QUERY_PARAM);
+    result.push('=');
+    push_url_encoded(&mut result, saved_search_id);
+    Some(result)
+}
+
+/// Link to the UI filtered by application and time range. `from`/`to` are unix microseconds.
+pub fn compile_application_range_url(
+    ui_url: &str,
+    application: &str,
+    from: i64,
+    to: i64,
+) -> Option<String> {
+    if ui_url.is_empty() {
+        return None;
+    }
+
+    let mut result = String::with_capacity(ui_url.len() + application.len() + 64);
+    result.push_str(ui_url);
+    push_query_separator(&mut result);
+    result.push_str(APPLICATION_QUERY_PARAM);
+    result.push('=');
+    push_url_encoded(&mut result, application);
+    result.push('&');
+    result.push_str(FROM_QUERY_PARAM);
+    result.push('=');
+    result.push_str(&from.to_string());
+    result.push('&');
+    result.push_str(TO_QUERY_PARAM);
+    result.push('=');
+    result.push_str(&to.to_string());
+    Some(result)
+}
+
+fn push_query_separator(dst: &mut String) {
+    if dst.contains('?') {
+        if !dst.ends_with('?') && !dst.ends_with('&') {
+            dst.push('&');
+        }
+    } else {
+        dst.push('?');
+    }
+}
+
+pub fn push_url_encoded(dst: &mut String, value: &str) {
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                dst.push(b as char)
+            }
+            _ => {
+                dst.push('%');
+                dst.push_str(&format!("{:02X}", b));
+            }
+        }
+    }
+}
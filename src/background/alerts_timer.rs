@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
+
+use crate::{app::AppContext, notifications::Notification};
+
+pub struct AlertsTimer {
+    pub app: Arc<AppContext>,
+}
+
+impl AlertsTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for AlertsTimer {
+    async fn tick(&self) {
+        let notifications = {
+            let mut alerts_engine = self.app.alerts_engine.lock().await;
+            alerts_engine.tick(DateTimeAsMicroseconds::now())
+        };
+
+        for notification in notifications {
+            crate::flows::send_notification(&self.app, Notification::Alert(notification)).await;
+        }
+
+        let new_errors = self.app.new_error_detector.lock().await.take_pending();
+
+        for new_error in new_errors {
+            crate::flows::send_notification(&self.app, Notification::NewError(new_error)).await;
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
+use tokio::sync::Mutex;
+
+use crate::{
+    anomalies::AnomalyDetector, app::AppContext, hourly_statistics::MAX_HOURS_TO_KEEP,
+    notifications::Notification,
+};
+
+pub struct AnomalyDetectionTimer {
+    pub app: Arc<AppContext>,
+    detector: Mutex<AnomalyDetector>,
+}
+
+impl AnomalyDetectionTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self {
+            app,
+            detector: Mutex::new(AnomalyDetector::new()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for AnomalyDetectionTimer {
+    async fn tick(&self) {
+        let Some(settings) = self.app.settings_reader.get_anomaly_detection().await else {
+            return;
+        };
+
+        let hours = {
+            let read_access = self.app.rollup_statistics.lock().await;
+            read_access.get_max_hours(MAX_HOURS_TO_KEEP)
+        };
+
+        let notifications = {
+            let mut detector = self.detector.lock().await;
+            detector.check(&hours, &settings, DateTimeAsMicroseconds::now())
+        };
+
+        for notification in notifications {
+            crate::flows::send_notification(&self.app, Notification::Alert(notification)).await;
+        }
+    }
+}
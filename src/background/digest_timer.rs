@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
+use tokio::sync::Mutex;
+
+use crate::{
+    app::{persist_json, restore_json, AppContext},
+    digest::{DigestPeriod, DigestState},
+    hourly_statistics::get_day_key,
+    settings::DigestSettings,
+    telegram::{api, TelegramChat},
+};
+
+const HOUR_US: i64 = 3_600_000_000;
+const DAY_US: i64 = 24 * HOUR_US;
+
+/// 1 - Monday .. 7 - Sunday. 1970-01-01 was a Thursday.
+fn get_iso_weekday(now: DateTimeAsMicroseconds) -> i64 {
+    (now.unix_microseconds / DAY_US + 3) % 7 + 1
+}
+
+pub struct DigestTimer {
+    pub app: Arc<AppContext>,
+    /// Read from the file on the first tick, so every digest is sent once within its hour.
+    state: Mutex<Option<DigestState>>,
+}
+
+impl DigestTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self {
+            app,
+            state: Mutex::new(None),
+        }
+    }
+
+    async fn send(&self, settings: &DigestSettings, period: DigestPeriod) {
+        let Some(telegram_settings) = self.app.settings_reader.get_telegram_settings().await else {
+            return;
+        };
+
+        let report = crate::flows::build_digest(&self.app, period, settings.get_top()).await;
+        let ui_url = self.app.get_ui_url().await;
+        let text = api::compile_digest_text(&report, settings.get_top(), &ui_url);
+
+        let chat = TelegramChat {
+            chat_id: settings.chat_id.unwrap_or(telegram_settings.chat_id),
+            message_thread_id: match settings.chat_id {
+                Some(_) => settings.message_thread_id,
+                None => Some(telegram_settings.message_thread_id),
+            },
+        };
+
+        if let Err(err) = api::send_message(&telegram_settings, chat, "HTML", &text).await {
+            println!("Digest: sending {:?} digest failed: {}", period, err);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for DigestTimer {
+    async fn tick(&self) {
+        let Some(settings) = self.app.settings_reader.get_digest_settings().await else {
+            return;
+        };
+
+        let now = DateTimeAsMicroseconds::now();
+
+        if (now.unix_microseconds / HOUR_US) % 24 != settings.get_hour_utc() {
+            return;
+        }
+
+        let today = get_day_key(now);
+
+        let mut state_access = self.state.lock().await;
+        if state_access.is_none() {
+            let restored = restore_json(&self.app.digest_state_path, "digest state").await;
+            *state_access = Some(restored.unwrap_or_default());
+        }
+        let Some(state) = state_access.as_mut() else {
+            return;
+        };
+
+        let send_daily = settings.daily.unwrap_or(true) && state.last_daily != today;
+        let send_weekly = settings.weekly.unwrap_or(true)
+            && get_iso_weekday(now) == settings.get_weekday()
+            && state.last_weekly != today;
+
+        if !send_daily && !send_weekly {
+            return;
+        }
+
+        if send_daily {
+            state.last_daily = today;
+        }
+
+        if send_weekly {
+            state.last_weekly = today;
+        }
+
+        persist_json(&self.app.digest_state_path, "digest state", &*state).await;
+
+        if send_daily {
+            self.send(&settings, DigestPeriod::Daily).await;
+        }
+
+        if send_weekly {
+            self.send(&settings, DigestPeriod::Weekly).await;
+        }
+    }
+}
@@ -20,10 +20,17 @@ impl GcTimer {
 #[async_trait::async_trait]
 impl MyTimerTick for GcTimer {
     async fn tick(&self) {
-        {
-            let mut hourly_statistics = self.app.hourly_statistics.lock().await;
-            hourly_statistics.gc();
-        }
+        self.app
+            .rollup_statistics
+            .lock()
+            .await
+            .gc(DateTimeAsMicroseconds::now().unix_microseconds / 1_000_000);
+
+        self.app
+            .statistic_dimensions
+            .lock()
+            .await
+            .gc(DateTimeAsMicroseconds::now());
 
         let mut to_date = DateTimeAsMicroseconds::now();
         to_date.add_days(-1);
@@ -55,7 +62,9 @@ impl MyTimerTick for GcTimer {
 
         gc_files(&self.app).await;
 
-        self.app.ignore_single_event_cache.lock().await.gc();
+        self.app.ignore_single_event_cache.lock().await.gc(now);
+
+        self.app.issues.lock().await.gc();
 
         if self.app.settings_repo.gc_expired().await {
             self.app.filter_events_cache.reset().await;
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
+
+use crate::{app::AppContext, notifications::Notification};
+
+pub struct HeartbeatsTimer {
+    pub app: Arc<AppContext>,
+}
+
+impl HeartbeatsTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for HeartbeatsTimer {
+    async fn tick(&self) {
+        let Some(settings) = self.app.settings_reader.get_heartbeat_settings().await else {
+            return;
+        };
+
+        if settings.disabled {
+            return;
+        }
+
+        let notifications = {
+            let mut heartbeats = self.app.heartbeats.lock().await;
+            heartbeats.check(&settings, DateTimeAsMicroseconds::now())
+        };
+
+        for notification in notifications {
+            crate::flows::send_notification(&self.app, Notification::Alert(notification)).await;
+        }
+    }
+}
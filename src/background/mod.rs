@@ -10,3 +10,13 @@ mod notify_telegram_timer;
 pub use notify_telegram_timer::*;
 mod persist_statistics_timer;
 pub use persist_statistics_timer::*;
+mod alerts_timer;
+pub use alerts_timer::*;
+mod telegram_bot_timer;
+pub use telegram_bot_timer::*;
+mod anomaly_detection_timer;
+pub use anomaly_detection_timer::*;
+mod heartbeats_timer;
+pub use heartbeats_timer::*;
+mod digest_timer;
+pub use digest_timer::*;
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
 
-use crate::app::AppContext;
+use crate::{app::AppContext, notifications::Notification};
 
 pub struct NotifyTelegramTimer {
     pub app: Arc<AppContext>,
@@ -29,23 +29,12 @@ impl MyTimerTick for NotifyTelegramTimer {
 
         let to_telegram = to_telegram.unwrap();
 
-        let telegram_settings = self.app.settings_reader.get_telegram_settings().await;
+        self.app
+            .notification_silences
+            .lock()
+            .await
+            .on_minute(&to_telegram, now);
 
-        if telegram_settings.is_none() {
-            println!("Telegram is disabled");
-            return;
-        }
-
-        let telegram_settings = telegram_settings.unwrap();
-
-        let ui_url = self.app.get_ui_url().await;
-
-        crate::telegram::api::send_notification_data(
-            &telegram_settings,
-            &to_telegram,
-            &self.app.env_name,
-            ui_url,
-        )
-        .await;
+        crate::flows::send_notification(&self.app, Notification::Summary(to_telegram)).await;
     }
 }
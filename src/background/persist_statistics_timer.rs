@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
-use rust_extensions::MyTimerTick;
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
 
-use crate::app::AppContext;
+use crate::{
+    app::{persist_json, AppContext},
+    hourly_statistics::get_day_key,
+};
 
 pub struct PersistStatisticsTimer {
     pub app: Arc<AppContext>,
@@ -12,29 +15,133 @@ impl PersistStatisticsTimer {
     pub fn new(app: Arc<AppContext>) -> Self {
         Self { app }
     }
-}
 
-#[async_trait::async_trait]
-impl MyTimerTick for PersistStatisticsTimer {
-    async fn tick(&self) {
-        let snapshot = {
-            let read_access = self.app.hourly_statistics.lock().await;
-            read_access.snapshot()
-        };
-
-        let bytes = match serde_json::to_vec_pretty(&snapshot) {
-            Ok(b) => b,
-            Err(e) => {
-                println!("Failed to serialize statistics: {}", e);
-                return;
-            }
-        };
+    async fn persist_issues(&self) {
+        let snapshot = self.app.issues.lock().await.get_snapshot_to_persist();
+
+        if let Some(snapshot) = snapshot {
+            persist_json(&self.app.issues_path, "issues", &snapshot).await;
+        }
+    }
+
+    async fn persist_heartbeats(&self) {
+        let snapshot = self.app.heartbeats.lock().await.get_snapshot_to_persist();
+
+        if let Some(snapshot) = snapshot {
+            persist_json(&self.app.heartbeats_path, "heartbeats", &snapshot).await;
+        }
+    }
+
+    async fn persist_notification_silences(&self) {
+        let snapshot = self
+            .app
+            .notification_silences
+            .lock()
+            .await
+            .get_snapshot_to_persist();
+
+        if let Some(snapshot) = snapshot {
+            persist_json(
+                &self.app.notification_silences_path,
+                "notification silences",
+                &snapshot,
+            )
+            .await;
+        }
+    }
+
+    async fn persist_rollup_statistics(&self) {
+        let days = self
+            .app
+            .rollup_statistics
+            .lock()
+            .await
+            .get_days_to_persist();
 
-        if let Err(e) = tokio::fs::write(&self.app.statistics_path, bytes).await {
+        if days.is_empty() {
+            return;
+        }
+
+        if let Err(e) = tokio::fs::create_dir_all(&self.app.rollup_statistics_path).await {
             println!(
-                "Failed to write statistics to {}: {}",
-                self.app.statistics_path, e
+                "Failed to create rollup statistics directory {}: {}",
+                self.app.rollup_statistics_path, e
             );
+            return;
         }
+
+        for (day, snapshot) in days {
+            let day_key = get_day_key(DateTimeAsMicroseconds::new(day * 1_000_000));
+            let path = format!("{}/{}.json", self.app.rollup_statistics_path, day_key);
+
+            if snapshot.applications.is_empty() {
+                let _ = tokio::fs::remove_file(&path).await;
+                continue;
+            }
+
+            persist_json(&path, "rollup statistics", &snapshot).await;
+        }
+    }
+
+    async fn persist_statistic_dimensions(&self) {
+        let snapshot = self
+            .app
+            .statistic_dimensions
+            .lock()
+            .await
+            .get_snapshot_to_persist();
+
+        if let Some(snapshot) = snapshot {
+            persist_json(
+                &self.app.statistic_dimensions_path,
+                "dimension statistics",
+                &snapshot,
+            )
+            .await;
+        }
+    }
+
+    async fn persist_version_statistics(&self) {
+        let snapshot = self
+            .app
+            .version_statistics
+            .lock()
+            .await
+            .get_snapshot_to_persist();
+
+        if let Some(snapshot) = snapshot {
+            persist_json(
+                &self.app.version_statistics_path,
+                "version statistics",
+                &snapshot,
+            )
+            .await;
+        }
+    }
+
+    async fn persist_ignore_rule_statistics(&self) {
+        let hits = self.app.ignore_rule_hits.lock().await.take();
+        self.app.settings_repo.add_hits(hits).await;
+
+        // The lock is held while saving, as adding and deleting rules do, so a stale snapshot
+        // can not overwrite their changes.
+        let mut write_access = self.app.ignore_single_event_cache.lock().await;
+
+        if let Some(snapshot) = write_access.get_snapshot_to_persist() {
+            crate::flows::ignore_single_event::persistence::save(&self.app, snapshot).await;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for PersistStatisticsTimer {
+    async fn tick(&self) {
+        self.persist_issues().await;
+        self.persist_heartbeats().await;
+        self.persist_notification_silences().await;
+        self.persist_rollup_statistics().await;
+        self.persist_statistic_dimensions().await;
+        self.persist_version_statistics().await;
+        self.persist_ignore_rule_statistics().await;
     }
 }
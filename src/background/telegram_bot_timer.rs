@@ -0,0 +1,123 @@
+use std::sync::{
+    atomic::{AtomicI64, Ordering},
+    Arc,
+};
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
+use tokio::sync::Mutex;
+
+use crate::{
+    app::AppContext,
+    settings::TelegramSettings,
+    telegram::{api, TelegramChat},
+};
+
+/// Commands sent while the server was down are not executed if they are older than this.
+const MAX_COMMAND_AGE_SEC: i64 = 300;
+
+pub struct TelegramBotTimer {
+    pub app: Arc<AppContext>,
+    offset: AtomicI64,
+    /// Requested with `getMe` once.
+    bot_username: Mutex<Option<String>>,
+}
+
+impl TelegramBotTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self {
+            app,
+            offset: AtomicI64::new(0),
+            bot_username: Mutex::new(None),
+        }
+    }
+
+    async fn get_bot_username(&self, telegram_settings: &TelegramSettings) -> Option<String> {
+        let mut bot_username = self.bot_username.lock().await;
+
+        if bot_username.is_none() {
+            match api::get_me(telegram_settings).await {
+                Ok(user) => *bot_username = user.username,
+                Err(err) => println!("TelegramBot: {}", err),
+            }
+        }
+
+        bot_username.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for TelegramBotTimer {
+    async fn tick(&self) {
+        let Some(telegram_settings) = self.app.settings_reader.get_telegram_settings().await else {
+            return;
+        };
+
+        if telegram_settings.bot_commands != Some(true) {
+            return;
+        }
+
+        // Commands to other bots of a group chat are told apart by the username
+        let Some(bot_username) = self.get_bot_username(&telegram_settings).await else {
+            return;
+        };
+
+        let updates =
+            match api::get_updates(&telegram_settings, self.offset.load(Ordering::SeqCst)).await {
+                Ok(updates) => updates,
+                Err(err) => {
+                    println!("TelegramBot: {}", err);
+                    return;
+                }
+            };
+
+        let allowed_chats = crate::telegram::get_telegram_chat_ids(&telegram_settings);
+        let now_sec = DateTimeAsMicroseconds::now().unix_microseconds / 1_000_000;
+
+        for update in updates {
+            self.offset.store(update.update_id + 1, Ordering::SeqCst);
+
+            let Some(message) = update.message else {
+                continue;
+            };
+
+            if !allowed_chats.contains(&message.chat.id) {
+                continue;
+            }
+
+            if now_sec - message.date > MAX_COMMAND_AGE_SEC {
+                continue;
+            }
+
+            let Some(text) = message.text.as_deref() else {
+                continue;
+            };
+
+            let Some(command) = crate::telegram::parse_bot_command(text, &bot_username) else {
+                continue;
+            };
+
+            let user = message
+                .from
+                .as_ref()
+                .map(|from| from.get_display_name())
+                .unwrap_or("unknown");
+
+            let reply = match command {
+                Ok(command) => {
+                    println!("TelegramBot: {} executes {:?}", user, command);
+                    crate::flows::execute_bot_command(&self.app, command, user).await
+                }
+                Err(err) => err,
+            };
+
+            let chat = TelegramChat {
+                chat_id: message.chat.id,
+                message_thread_id: message.message_thread_id,
+            };
+
+            if let Err(err) = api::send_message(&telegram_settings, chat, "HTML", &reply).await {
+                println!("TelegramBot: reply failed: {}", err);
+            }
+        }
+    }
+}
@@ -1,6 +1,80 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 use tokio::sync::RwLock;
 
-use crate::repo::dto::IgnoreItemDto;
+use crate::{
+    app::{LogItem, PROCESS_CONTEXT_KEY},
+    repo::dto::{IgnoreConditionFieldDto, IgnoreItemDto, LogItemDto},
+    text_matching::ConditionMatcher,
+};
+
+/// Ignore rule with its conditions compiled once.
+pub struct IgnoreFilter {
+    pub item: IgnoreItemDto,
+    conditions: Vec<(IgnoreConditionFieldDto, ConditionMatcher)>,
+}
+
+impl IgnoreFilter {
+    pub fn new(item: IgnoreItemDto) -> Result<Self, String> {
+        let conditions = item.compile_conditions()?;
+        Ok(Self { item, conditions })
+    }
+
+    pub fn matches_ignore_filter(&self, log_event: &LogItem) -> bool {
+        if self.item.is_expired(DateTimeAsMicroseconds::now()) {
+            return false;
+        }
+
+        if !self.item.levels.is_empty()
+            && !self
+                .item
+                .levels
+                .iter()
+                .any(|level| log_event.is_level(level))
+        {
+            return false;
+        }
+
+        self.matches_conditions(&log_event.message, log_event.process.as_deref(), |key| {
+            log_event.ctx.get(key).map(|value| value.as_str())
+        })
+    }
+
+    /// Same rule applied to an event read back from the storage. Expiration is not checked.
+    pub fn matches_stored_event(&self, log_event: &LogItemDto) -> bool {
+        if !self.item.levels.is_empty() && !self.item.levels.contains(&log_event.level) {
+            return false;
+        }
+
+        let process = log_event
+            .context
+            .get(PROCESS_CONTEXT_KEY)
+            .map(|value| value.as_str());
+
+        self.matches_conditions(&log_event.message, process, |key| {
+            log_event.context.get(key).map(|value| value.as_str())
+        })
+    }
+
+    fn matches_conditions<'s>(
+        &self,
+        message: &str,
+        process: Option<&str>,
+        get_context: impl Fn(&str) -> Option<&'s str>,
+    ) -> bool {
+        self.conditions.iter().all(|(field, matcher)| match field {
+            IgnoreConditionFieldDto::Message => matcher.is_match(message),
+            IgnoreConditionFieldDto::Process => {
+                process.is_some_and(|value| matcher.is_match(value))
+            }
+            IgnoreConditionFieldDto::ProcessOrMessage => {
+                process.is_some_and(|value| matcher.is_match(value)) || matcher.is_match(message)
+            }
+            IgnoreConditionFieldDto::Context(key) => {
+                get_context(key).is_some_and(|value| matcher.is_match(value))
+            }
+        })
+    }
+}
 
 pub enum FilterEventResult<T> {
     Ok(T),
@@ -8,7 +82,7 @@ pub enum FilterEventResult<T> {
 }
 
 pub struct FilterEventsCache {
-    items: RwLock<Option<Vec<IgnoreItemDto>>>,
+    items: RwLock<Option<Vec<IgnoreFilter>>>,
 }
 
 impl FilterEventsCache {
@@ -19,8 +93,16 @@ impl FilterEventsCache {
     }
 
     pub async fn apply(&self, items: Vec<IgnoreItemDto>) {
+        let mut filters = Vec::with_capacity(items.len());
+        for item in items {
+            match IgnoreFilter::new(item) {
+                Ok(filter) => filters.push(filter),
+                Err(err) => println!("Skipping invalid ignore rule: {}", err),
+            }
+        }
+
         let mut write_access = self.items.write().await;
-        *write_access = Some(items);
+        *write_access = Some(filters);
     }
 
     pub async fn reset(&self) {
@@ -31,7 +113,7 @@ impl FilterEventsCache {
     pub async fn filter_events<T>(
         &self,
         events: Vec<T>,
-        filter: impl Fn(&T, &[IgnoreItemDto]) -> bool,
+        mut filter: impl FnMut(&T, &[IgnoreFilter]) -> bool,
     ) -> FilterEventResult<Vec<T>> {
         let read_access = self.items.read().await;
 
@@ -0,0 +1,82 @@
+use std::fmt::Write;
+
+use crate::telegram::api::append_html_escaped;
+
+use super::{DigestPeriod, DigestReport};
+
+fn write_change(dst: &mut String, current: u64, previous: u64) {
+    let _ = write!(dst, "<td>{}</td><td>", current);
+
+    if current > previous {
+        let _ = write!(dst, "+{}", current - previous);
+    } else if current < previous {
+        let _ = write!(dst, "-{}", previous - current);
+    }
+
+    dst.push_str("</td>");
+}
+
+/// Standalone HTML page with the same content as the JSON report.
+pub fn compile_digest_html(report: &DigestReport) -> String {
+    let mut html = String::with_capacity(8192);
+
+    let title = match report.period {
+        DigestPeriod::Daily => "Daily digest",
+        DigestPeriod::Weekly => "Weekly digest",
+    };
+
+    html.push_str("<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>");
+    html.push_str(title);
+    html.push_str("</title><style>body{font-family:sans-serif}table{border-collapse:collapse}td,th{border:1px solid #ccc;padding:4px 8px;text-align:left}</style></head><body><h1>");
+    html.push_str(title);
+    html.push_str(": ");
+    append_html_escaped(&mut html, &report.env_name);
+    html.push_str("</h1><p>");
+    append_html_escaped(&mut html, &report.from);
+    html.push_str(" — ");
+    append_html_escaped(&mut html, &report.to);
+    html.push_str("</p>");
+
+    html.push_str("<h2>Applications</h2><table><tr><th>Application</th><th>Fatal errors</th><th>Change</th><th>Errors</th><th>Change</th></tr>");
+    html.push_str("<tr><td><b>Total</b></td>");
+    write_change(&mut html, report.fatal_errors, report.prev_fatal_errors);
+    write_change(&mut html, report.errors, report.prev_errors);
+    html.push_str("</tr>");
+    for item in report.applications.iter() {
+        html.push_str("<tr><td>");
+        append_html_escaped(&mut html, &item.application);
+        html.push_str("</td>");
+        write_change(&mut html, item.fatal_errors, item.prev_fatal_errors);
+        write_change(&mut html, item.errors, item.prev_errors);
+        html.push_str("</tr>");
+    }
+    html.push_str("</table>");
+
+    html.push_str("<h2>New errors</h2><table><tr><th>Application</th><th>Level</th><th>Count</th><th>First seen</th><th>Template</th></tr>");
+    for item in report.new_errors.iter() {
+        html.push_str("<tr><td>");
+        append_html_escaped(&mut html, &item.application);
+        let _ = write!(
+            html,
+            "</td><td>{}</td><td>{}</td><td>",
+            item.level, item.count
+        );
+        append_html_escaped(&mut html, &item.first_seen);
+        html.push_str("</td><td>");
+        append_html_escaped(&mut html, &item.template);
+        html.push_str("</td></tr>");
+    }
+    html.push_str("</table>");
+
+    html.push_str("<h2>Busiest ignore rules</h2><table><tr><th>Rule</th><th>Levels</th><th>Conditions</th><th>Suppressed</th></tr>");
+    for item in report.ignore_rules.iter() {
+        html.push_str("<tr><td>");
+        append_html_escaped(&mut html, &item.id);
+        let _ = write!(html, "</td><td>{}</td><td>", item.levels);
+        append_html_escaped(&mut html, &item.conditions);
+        let _ = write!(html, "</td><td>{}</td></tr>", item.suppressed);
+    }
+    html.push_str("</table></body></html>");
+
+    html
+}
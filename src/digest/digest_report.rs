@@ -0,0 +1,211 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::Serialize;
+
+use crate::{hourly_statistics::HourlyStatisticsItem, issues::IssueItem, repo::dto::IgnoreItemDto};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DigestPeriod {
+    Daily,
+    Weekly,
+}
+
+impl DigestPeriod {
+    pub fn get_hours(&self) -> i64 {
+        match self {
+            Self::Daily => 24,
+            Self::Weekly => 7 * 24,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestApplicationItem {
+    pub application: String,
+    pub errors: u64,
+    pub fatal_errors: u64,
+    pub prev_errors: u64,
+    pub prev_fatal_errors: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestNewErrorItem {
+    pub application: String,
+    pub template: String,
+    pub level: String,
+    pub count: u64,
+    pub first_seen: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestIgnoreRuleItem {
+    pub id: String,
+    pub levels: String,
+    pub conditions: String,
+    pub suppressed: u64,
+}
+
+/// Error counts of the period compared with the period before, new error signatures and the busiest ignore rules.
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestReport {
+    pub env_name: String,
+    pub period: DigestPeriod,
+    pub from: String,
+    pub to: String,
+    pub errors: u64,
+    pub fatal_errors: u64,
+    pub prev_errors: u64,
+    pub prev_fatal_errors: u64,
+    pub applications: Vec<DigestApplicationItem>,
+    pub new_errors: Vec<DigestNewErrorItem>,
+    pub ignore_rules: Vec<DigestIgnoreRuleItem>,
+}
+
+impl DigestReport {
+    pub fn new(
+        env_name: String,
+        period: DigestPeriod,
+        now: DateTimeAsMicroseconds,
+        current: BTreeMap<String, HourlyStatisticsItem>,
+        previous: BTreeMap<String, HourlyStatisticsItem>,
+        new_errors: Vec<IssueItem>,
+        ignore_rules: Vec<(IgnoreItemDto, u64)>,
+    ) -> Self {
+        let from =
+            DateTimeAsMicroseconds::new(now.unix_microseconds - period.get_hours() * 3_600_000_000);
+
+        let applications: BTreeSet<_> = current.keys().chain(previous.keys()).collect();
+
+        let mut result = Self {
+            env_name,
+            period,
+            from: from.to_rfc3339(),
+            to: now.to_rfc3339(),
+            errors: 0,
+            fatal_errors: 0,
+            prev_errors: 0,
+            prev_fatal_errors: 0,
+            applications: Vec::new(),
+            new_errors: new_errors
+                .into_iter()
+                .map(|item| DigestNewErrorItem {
+                    application: item.application,
+                    template: item.template,
+                    level: format!("{:?}", item.level),
+                    count: item.count,
+                    first_seen: DateTimeAsMicroseconds::new(item.first_seen).to_rfc3339(),
+                })
+                .collect(),
+            ignore_rules: ignore_rules
+                .into_iter()
+                .map(|(rule, suppressed)| DigestIgnoreRuleItem {
+                    levels: rule.get_levels_description(),
+                    conditions: rule.get_conditions_description(),
+                    id: rule.id,
+                    suppressed,
+                })
+                .collect(),
+        };
+
+        for application in applications {
+            let current = current.get(application).copied().unwrap_or_default();
+            let previous = previous.get(application).copied().unwrap_or_default();
+
+            let item = DigestApplicationItem {
+                application: application.to_string(),
+                errors: current.error as u64,
+                fatal_errors: current.fatal_error as u64,
+                prev_errors: previous.error as u64,
+                prev_fatal_errors: previous.fatal_error as u64,
+            };
+
+            if item.errors + item.fatal_errors + item.prev_errors + item.prev_fatal_errors == 0 {
+                continue;
+            }
+
+            result.errors += item.errors;
+            result.fatal_errors += item.fatal_errors;
+            result.prev_errors += item.prev_errors;
+            result.prev_fatal_errors += item.prev_fatal_errors;
+            result.applications.push(item);
+        }
+
+        result.applications.sort_by(|a, b| {
+            b.fatal_errors
+                .cmp(&a.fatal_errors)
+                .then_with(|| b.errors.cmp(&a.errors))
+                .then_with(|| a.application.cmp(&b.application))
+        });
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{DigestPeriod, DigestReport};
+    use crate::{
+        hourly_statistics::HourlyStatisticsItem,
+        repo::dto::{IgnoreItemDto, LogLevelDto, MatchModeDto},
+    };
+
+    fn apps(items: &[(&str, u32, u32)]) -> BTreeMap<String, HourlyStatisticsItem> {
+        items
+            .iter()
+            .map(|(application, error, fatal_error)| {
+                (
+                    application.to_string(),
+                    HourlyStatisticsItem {
+                        info: 100,
+                        warning: 0,
+                        error: *error,
+                        fatal_error: *fatal_error,
+                        debug: 0,
+                        sampled: 0,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_applications_are_compared_with_previous_period() {
+        let report = DigestReport::new(
+            "prod".to_string(),
+            DigestPeriod::Daily,
+            DateTimeAsMicroseconds::now(),
+            apps(&[("billing", 5, 0), ("payments", 1, 2), ("quiet", 0, 0)]),
+            apps(&[("billing", 1, 0), ("legacy", 7, 0)]),
+            vec![],
+            vec![(
+                IgnoreItemDto::from_marker(
+                    LogLevelDto::Error,
+                    "billing",
+                    "*",
+                    MatchModeDto::Contains,
+                    false,
+                    None,
+                ),
+                42,
+            )],
+        );
+
+        let applications: Vec<_> = report
+            .applications
+            .iter()
+            .map(|item| item.application.as_str())
+            .collect();
+        assert_eq!(applications, vec!["payments", "billing", "legacy"]);
+
+        assert_eq!(report.errors, 6);
+        assert_eq!(report.fatal_errors, 2);
+        assert_eq!(report.prev_errors, 8);
+        assert_eq!(report.ignore_rules[0].suppressed, 42);
+        assert_eq!(report.ignore_rules[0].levels, "Error");
+    }
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// Day keys (YYYYMMDD) of the last sent digests, so a restart does not send them again.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct DigestState {
+    #[serde(default)]
+    pub last_daily: u64,
+    #[serde(default)]
+    pub last_weekly: u64,
+}
@@ -0,0 +1,6 @@
+mod digest_html;
+pub use digest_html::*;
+mod digest_report;
+pub use digest_report::*;
+mod digest_state;
+pub use digest_state::*;
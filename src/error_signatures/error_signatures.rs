@@ -0,0 +1,237 @@
+use my_logger::LogLevel;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::{LogItem, APPLICATION_KEY, NO_APPLICATION},
+    issues::IssueItem,
+    repo::dto::LogLevelDto,
+};
+
+use super::to_message_template;
+
+/// New error notifications above this per timer tick are only counted.
+const MAX_PENDING: usize = 50;
+const MAX_SAMPLE_LEN: usize = 512;
+
+/// Occurrence of a signature in a new error notification. Also the format of the former `error-signatures.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorSignatureItem {
+    pub signature: String,
+    pub application: String,
+    pub template: String,
+    pub sample_message: String,
+    /// Most severe level seen.
+    pub level: LogLevelDto,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewErrorNotification {
+    pub item: ErrorSignatureItem,
+    /// Set if the signature was seen before and came back after a long silence.
+    pub reappeared_after_sec: Option<i64>,
+}
+
+/// Signature of an Error/FatalError event: FNV-1a of the application and the message template.
+pub fn get_error_signature(application: &str, template: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in application
+        .bytes()
+        .chain(std::iter::once(0))
+        .chain(template.bytes())
+    {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Template and signature of an Error/FatalError event, computed once per event.
+pub struct ErrorSignature<'s> {
+    pub application: &'s str,
+    pub template: String,
+    pub id: String,
+}
+
+impl<'s> ErrorSignature<'s> {
+    /// `None` for events below Error.
+    pub fn from_log_item(itm: &'s LogItem) -> Option<Self> {
+        if !matches!(itm.level, LogLevel::Error | LogLevel::FatalError) {
+            return None;
+        }
+
+        let application = itm
+            .ctx
+            .get(APPLICATION_KEY)
+            .map(|s| s.as_str())
+            .unwrap_or(NO_APPLICATION);
+
+        let template = to_message_template(&itm.message);
+        let id = get_error_signature(application, &template);
+
+        Some(Self {
+            application,
+            template,
+            id,
+        })
+    }
+}
+
+/// Decides which occurrences of issues are reported as new errors. Known signatures are the issues.
+pub struct NewErrorDetector {
+    pending: Vec<NewErrorNotification>,
+    dropped_notifications: usize,
+    /// Signatures are recorded silently until this moment.
+    learning_until: i64,
+}
+
+impl NewErrorDetector {
+    pub fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+            dropped_notifications: 0,
+            learning_until: 0,
+        }
+    }
+
+    /// Used when starting with no issues, so existing errors are not reported as new.
+    pub fn learn_until(&mut self, moment: DateTimeAsMicroseconds) {
+        self.learning_until = moment.unix_microseconds;
+    }
+
+    /// `prev_last_seen` - last occurrence of the issue before this event, `None` for a new issue.
+    /// `reappear_after_sec` 0 - reappeared signatures are not reported.
+    pub fn update(
+        &mut self,
+        issue: &IssueItem,
+        itm: &LogItem,
+        prev_last_seen: Option<i64>,
+        now: DateTimeAsMicroseconds,
+        reappear_after_sec: i64,
+    ) {
+        let now = now.unix_microseconds;
+
+        let reappeared_after_sec = match prev_last_seen {
+            None => None,
+            Some(prev_last_seen) => {
+                let silent_for = now - prev_last_seen;
+                if reappear_after_sec == 0 || silent_for < reappear_after_sec * 1_000_000 {
+                    return;
+                }
+                Some(silent_for / 1_000_000)
+            }
+        };
+
+        if now < self.learning_until {
+            return;
+        }
+
+        if self.pending.len() >= MAX_PENDING {
+            self.dropped_notifications += 1;
+            return;
+        }
+
+        self.pending.push(NewErrorNotification {
+            item: ErrorSignatureItem {
+                signature: issue.id.clone(),
+                application: issue.application.clone(),
+                template: issue.template.clone(),
+                sample_message: truncate(&itm.message, MAX_SAMPLE_LEN),
+                level: issue.level.clone(),
+                first_seen: issue.first_seen,
+                last_seen: issue.last_seen,
+                count: issue.count,
+            },
+            reappeared_after_sec,
+        });
+    }
+
+    pub fn take_pending(&mut self) -> Vec<NewErrorNotification> {
+        if self.dropped_notifications > 0 {
+            println!(
+                "{} new error notifications are dropped: too many new signatures at once",
+                self.dropped_notifications
+            );
+            self.dropped_notifications = 0;
+        }
+
+        std::mem::take(&mut self.pending)
+    }
+}
+
+fn truncate(src: &str, max_len: usize) -> String {
+    if src.len() <= max_len {
+        return src.to_string();
+    }
+
+    let mut end = max_len;
+    while !src.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    format!("{}…", &src[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{ErrorSignature, NewErrorDetector};
+    use crate::{app::LogItem, issues::Issues};
+
+    fn log_item(message: &str) -> LogItem {
+        let mut ctx = BTreeMap::new();
+        ctx.insert("Application".to_string(), "payments".to_string());
+
+        LogItem {
+            id: "1".to_string(),
+            level: my_logger::LogLevel::Error,
+            process: None,
+            message: message.to_string(),
+            timestamp: DateTimeAsMicroseconds::now(),
+            ctx,
+        }
+    }
+
+    const DAY: i64 = 86_400;
+
+    fn update(
+        issues: &mut Issues,
+        detector: &mut NewErrorDetector,
+        message: &str,
+        now: DateTimeAsMicroseconds,
+    ) {
+        let itm = log_item(message);
+        let signature = ErrorSignature::from_log_item(&itm).unwrap();
+        let (issue, prev_last_seen) = issues.update(&itm, signature, now);
+        detector.update(issue, &itm, prev_last_seen, now, 7 * DAY);
+    }
+
+    #[test]
+    fn test_new_and_reappeared_signatures() {
+        let mut issues = Issues::new();
+        let mut detector = NewErrorDetector::new();
+        let start = DateTimeAsMicroseconds::new(1_700_000_000_000_000);
+
+        update(&mut issues, &mut detector, "Order 1 failed", start);
+        update(&mut issues, &mut detector, "Order 2 failed", start);
+        update(&mut issues, &mut detector, "Refund 3 failed", start);
+
+        let pending = detector.take_pending();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].item.template, "Order # failed");
+
+        let later = DateTimeAsMicroseconds::new(start.unix_microseconds + 8 * DAY * 1_000_000);
+        update(&mut issues, &mut detector, "Order 5 failed", later);
+
+        let pending = detector.take_pending();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].item.count, 3);
+        assert_eq!(pending[0].reappeared_after_sec, Some(8 * DAY));
+    }
+}
@@ -0,0 +1,132 @@
+const MAX_TEMPLATE_LEN: usize = 256;
+/// Tokens with digits at least this long are treated as ids.
+const MIN_ID_LEN: usize = 6;
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+fn is_guid(word: &str) -> bool {
+    word.len() == 36
+        && word.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+fn push_normalized_word(dst: &mut String, word: &str) {
+    if !word.chars().any(|c| c.is_ascii_digit()) {
+        dst.push_str(word);
+        return;
+    }
+
+    if is_guid(word) {
+        dst.push_str("<guid>");
+        return;
+    }
+
+    if word.chars().all(|c| c.is_ascii_digit()) {
+        dst.push('#');
+        return;
+    }
+
+    if word.chars().count() >= MIN_ID_LEN {
+        dst.push_str("<id>");
+        return;
+    }
+
+    let mut prev_digit = false;
+    for c in word.chars() {
+        if c.is_ascii_digit() {
+            if !prev_digit {
+                dst.push('#');
+            }
+            prev_digit = true;
+        } else {
+            dst.push(c);
+            prev_digit = false;
+        }
+    }
+}
+
+/// Templates this long may have been cut at [`MAX_TEMPLATE_LEN`] (after the char boundary and the trimmed space).
+pub fn is_template_truncated(template: &str) -> bool {
+    template.len() + 4 >= MAX_TEMPLATE_LEN
+}
+
+/// Message with numbers, GUIDs and ids replaced by placeholders and whitespace collapsed,
+/// so repeated occurrences of the same error produce the same template.
+pub fn to_message_template(message: &str) -> String {
+    let mut result = String::with_capacity(message.len().min(MAX_TEMPLATE_LEN));
+    let mut word_start = None;
+    let mut prev_space = false;
+
+    for (index, c) in message.char_indices() {
+        if is_word_char(c) {
+            if word_start.is_none() {
+                word_start = Some(index);
+            }
+            continue;
+        }
+
+        if let Some(start) = word_start.take() {
+            push_normalized_word(&mut result, &message[start..index]);
+            prev_space = false;
+        }
+
+        if c.is_whitespace() {
+            if !prev_space && !result.is_empty() {
+                result.push(' ');
+            }
+            prev_space = true;
+        } else {
+            result.push(c);
+            prev_space = false;
+        }
+
+        if result.len() >= MAX_TEMPLATE_LEN {
+            break;
+        }
+    }
+
+    if let Some(start) = word_start {
+        push_normalized_word(&mut result, &message[start..]);
+    }
+
+    if result.len() > MAX_TEMPLATE_LEN {
+        let mut end = MAX_TEMPLATE_LEN;
+        while !result.is_char_boundary(end) {
+            end -= 1;
+        }
+        result.truncate(end);
+    }
+
+    result.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_message_template;
+
+    #[test]
+    fn test_message_template() {
+        assert_eq!(
+            to_message_template(
+                "Order 42 of user 3f2b1c9e-7a4d-4e1b-9c3f-2a6b8d0e1f47 failed:  timeout 30s"
+            ),
+            "Order # of user <guid> failed: timeout #s"
+        );
+        assert_eq!(
+            to_message_template("Payment pay_8fK2j91 declined by psp2"),
+            "Payment <id> declined by psp#"
+        );
+        assert_eq!(
+            to_message_template(
+                "Order 43 of user 11111111-2222-3333-4444-555555555555 failed: timeout 10s"
+            ),
+            to_message_template(
+                "Order 42 of user 3f2b1c9e-7a4d-4e1b-9c3f-2a6b8d0e1f47 failed: timeout 30s"
+            ),
+        );
+    }
+}
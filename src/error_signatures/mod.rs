@@ -0,0 +1,4 @@
+mod error_signatures;
+pub use error_signatures::*;
+mod message_template;
+pub use message_template::*;
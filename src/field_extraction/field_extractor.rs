@@ -0,0 +1,255 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+
+use crate::{
+    app::{LogItem, APPLICATION_KEY},
+    settings::{FieldExtractionFormat, FieldExtractionSettings},
+    text_matching::compile_regex,
+};
+
+use super::formats;
+
+const MAX_FIELDS_PER_EVENT: usize = 32;
+const MAX_VALUE_LEN: usize = 1024;
+
+enum ExtractorKind {
+    Json {
+        /// Context key -> path. All top level scalars are taken if empty.
+        fields: Vec<(String, String)>,
+    },
+    Logfmt {
+        /// Logfmt key -> context key. All pairs are taken if empty.
+        fields: BTreeMap<String, String>,
+    },
+    Regex(Regex),
+}
+
+/// Extraction rule compiled once from settings.
+pub struct FieldExtractor {
+    application: Option<String>,
+    kind: ExtractorKind,
+}
+
+impl FieldExtractor {
+    pub fn new(settings: &FieldExtractionSettings) -> Result<Self, String> {
+        let fields = settings.fields.clone().unwrap_or_default();
+
+        let kind = match settings.format {
+            FieldExtractionFormat::Json => ExtractorKind::Json {
+                fields: fields.into_iter().collect(),
+            },
+            FieldExtractionFormat::Logfmt => ExtractorKind::Logfmt {
+                fields: fields
+                    .into_iter()
+                    .map(|(ctx_key, logfmt_key)| (logfmt_key, ctx_key))
+                    .collect(),
+            },
+            FieldExtractionFormat::Regex => {
+                let pattern = match settings.pattern.as_deref() {
+                    Some(pattern) => pattern,
+                    None => return Err("Regex extraction rule has no pattern".to_string()),
+                };
+
+                let regex = compile_regex(pattern, false)?;
+
+                if regex.capture_names().flatten().next().is_none() {
+                    return Err(format!(
+                        "Regex '{}' has no named groups to extract",
+                        pattern
+                    ));
+                }
+
+                ExtractorKind::Regex(regex)
+            }
+        };
+
+        Ok(Self {
+            application: settings.application.clone(),
+            kind,
+        })
+    }
+
+    fn is_for(&self, item: &LogItem) -> bool {
+        match &self.application {
+            Some(application) => item.is_application(application),
+            None => true,
+        }
+    }
+
+    /// Adds extracted values to the context. Keys which are already present are never overwritten.
+    pub fn extract(&self, item: &mut LogItem) {
+        if !self.is_for(item) {
+            return;
+        }
+
+        let mut extracted = Vec::new();
+
+        match &self.kind {
+            ExtractorKind::Json { fields } => {
+                let Some(json) = formats::find_json_object(&item.message) else {
+                    return;
+                };
+
+                if fields.is_empty() {
+                    for (key, value) in json.iter() {
+                        if value.is_object() || value.is_array() {
+                            continue;
+                        }
+                        if let Some(value) = formats::json_value_to_string(value) {
+                            extracted.push((key.to_string(), value));
+                        }
+                    }
+                } else {
+                    for (ctx_key, path) in fields {
+                        let value = formats::get_by_path(&json, path)
+                            .and_then(formats::json_value_to_string);
+                        if let Some(value) = value {
+                            extracted.push((ctx_key.to_string(), value));
+                        }
+                    }
+                }
+            }
+            ExtractorKind::Logfmt { fields } => {
+                for (key, value) in formats::parse_logfmt(&item.message) {
+                    if fields.is_empty() {
+                        extracted.push((key, value));
+                    } else if let Some(ctx_key) = fields.get(&key) {
+                        extracted.push((ctx_key.to_string(), value));
+                    }
+                }
+            }
+            ExtractorKind::Regex(regex) => {
+                let Some(captures) = regex.captures(&item.message) else {
+                    return;
+                };
+
+                for name in regex.capture_names().flatten() {
+                    if let Some(value) = captures.name(name) {
+                        extracted.push((name.to_string(), value.as_str().to_string()));
+                    }
+                }
+            }
+        }
+
+        let mut added = 0;
+        for (key, value) in extracted {
+            if added >= MAX_FIELDS_PER_EVENT {
+                break;
+            }
+
+            if key.is_empty() || value.is_empty() || value.len() > MAX_VALUE_LEN {
+                continue;
+            }
+
+            if item.ctx.contains_key(&key) {
+                continue;
+            }
+
+            item.ctx.insert(key, value);
+            added += 1;
+        }
+    }
+}
+
+pub struct FieldExtractors {
+    items: Vec<FieldExtractor>,
+}
+
+impl FieldExtractors {
+    pub fn new(settings: Vec<FieldExtractionSettings>) -> Self {
+        let mut items = Vec::with_capacity(settings.len());
+
+        for rule in settings.iter() {
+            match FieldExtractor::new(rule) {
+                Ok(extractor) => items.push(extractor),
+                Err(err) => {
+                    println!("Skipping invalid field extraction rule {:?}: {}", rule, err)
+                }
+            }
+        }
+
+        Self { items }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn apply(&self, item: &mut LogItem) {
+        for extractor in self.items.iter() {
+            extractor.extract(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::FieldExtractors;
+    use crate::{
+        app::LogItem,
+        settings::{FieldExtractionFormat, FieldExtractionSettings},
+    };
+
+    fn log_item(application: &str, message: &str) -> LogItem {
+        let mut ctx = BTreeMap::new();
+        ctx.insert(super::APPLICATION_KEY.to_string(), application.to_string());
+        ctx.insert("UserId".to_string(), "already-set".to_string());
+
+        LogItem {
+            id: "1".to_string(),
+            level: my_logger::LogLevel::Error,
+            process: None,
+            message: message.to_string(),
+            timestamp: DateTimeAsMicroseconds::now(),
+            ctx,
+        }
+    }
+
+    #[test]
+    fn test_json_and_logfmt_extraction() {
+        let mut json_fields = BTreeMap::new();
+        json_fields.insert("OrderId".to_string(), "order.id".to_string());
+        json_fields.insert("Sku".to_string(), "order.items.0.sku".to_string());
+        json_fields.insert("UserId".to_string(), "user".to_string());
+
+        let extractors = FieldExtractors::new(vec![
+            FieldExtractionSettings {
+                application: Some("orders".to_string()),
+                format: FieldExtractionFormat::Json,
+                fields: Some(json_fields),
+                pattern: None,
+            },
+            FieldExtractionSettings {
+                application: Some("payments".to_string()),
+                format: FieldExtractionFormat::Logfmt,
+                fields: None,
+                pattern: None,
+            },
+        ]);
+
+        let mut item = log_item(
+            "orders",
+            r#"Order failed: {"user":"u-1","order":{"id":42,"items":[{"sku":"A-1"}]}} retrying"#,
+        );
+        extractors.apply(&mut item);
+
+        assert_eq!(item.ctx.get("OrderId").unwrap(), "42");
+        assert_eq!(item.ctx.get("Sku").unwrap(), "A-1");
+        assert_eq!(item.ctx.get("UserId").unwrap(), "already-set");
+
+        let mut item = log_item(
+            "payments",
+            r#"charge failed psp=stripe reason="card declined""#,
+        );
+        extractors.apply(&mut item);
+
+        assert_eq!(item.ctx.get("psp").unwrap(), "stripe");
+        assert_eq!(item.ctx.get("reason").unwrap(), "card declined");
+        assert!(!item.ctx.contains_key("OrderId"));
+    }
+}
@@ -0,0 +1,100 @@
+use serde_json::Value;
+
+/// Finds the first JSON object in the message. Text before and after the object is ignored.
+pub fn find_json_object(message: &str) -> Option<serde_json::Map<String, Value>> {
+    let start = message.find('{')?;
+
+    let mut stream = serde_json::Deserializer::from_str(&message[start..]).into_iter::<Value>();
+
+    match stream.next()? {
+        Ok(Value::Object(map)) => Some(map),
+        _ => None,
+    }
+}
+
+/// Resolves dot separated path. Numeric segments index arrays: `items.0.sku`.
+pub fn get_by_path<'s>(root: &'s serde_json::Map<String, Value>, path: &str) -> Option<&'s Value> {
+    let path = path.strip_prefix("$.").unwrap_or(path);
+    let mut segments = path.split('.');
+
+    let mut current = root.get(segments.next()?)?;
+
+    for segment in segments {
+        current = match current {
+            Value::Object(map) => map.get(segment)?,
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+
+    Some(current)
+}
+
+/// Scalars are rendered as is, objects and arrays as compact JSON. `null` is skipped.
+pub fn json_value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(value) => Some(value.clone()),
+        Value::Bool(value) => Some(value.to_string()),
+        Value::Number(value) => Some(value.to_string()),
+        Value::Array(_) | Value::Object(_) => serde_json::to_string(value).ok(),
+    }
+}
+
+/// Parses `key=value key2="quoted value"` pairs. Tokens without `=` are skipped.
+pub fn parse_logfmt(message: &str) -> Vec<(String, String)> {
+    let mut result = Vec::new();
+    let mut chars = message.chars().peekable();
+
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+
+        if chars.peek().is_none() {
+            return result;
+        }
+
+        let mut key = String::new();
+        while let Some(c) = chars.peek() {
+            if c.is_whitespace() || *c == '=' {
+                break;
+            }
+            key.push(*c);
+            chars.next();
+        }
+
+        if chars.peek() != Some(&'=') {
+            continue;
+        }
+        chars.next();
+
+        let mut value = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => {
+                        if let Some(escaped) = chars.next() {
+                            value.push(escaped);
+                        }
+                    }
+                    _ => value.push(c),
+                }
+            }
+        } else {
+            while let Some(c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                value.push(*c);
+                chars.next();
+            }
+        }
+
+        if !key.is_empty() {
+            result.push((key, value));
+        }
+    }
+}
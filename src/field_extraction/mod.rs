@@ -0,0 +1,3 @@
+mod field_extractor;
+pub use field_extractor::*;
+mod formats;
@@ -1,52 +1,81 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, sync::Arc};
 
-use rust_extensions::date_time::DateTimeAsMicroseconds;
+use rust_extensions::{date_time::DateTimeAsMicroseconds, SortableId};
 
 use crate::{
-    app::{AppContext, LogItem},
-    repo::dto::IgnoreItemDto,
+    app::{AppContext, LogItem, APPLICATION_KEY},
+    notifications::Notification,
+    repo::dto::{AuditActorDto, AuditTargetDto, IgnoreItemDto},
 };
 
-pub async fn add_ignore_event(app: &AppContext, event: IgnoreItemDto) {
-    app.settings_repo
-        .add_ignore_event(&IgnoreItemDto {
-            level: event.level.clone(),
-            application: event.application.to_string(),
-            marker: event.marker.to_string(),
-            expires_at: event.expires_at,
-        })
-        .await;
+/// Creates a rule when `id` is empty, otherwise adds or replaces the rule with this id.
+pub async fn add_ignore_event(
+    app: &AppContext,
+    mut event: IgnoreItemDto,
+    actor: &AuditActorDto,
+) -> Result<IgnoreItemDto, String> {
+    normalize_ignore_event(&mut event)?;
 
-    app.filter_events_cache.reset().await;
+    if event.id.is_empty() {
+        event.id = SortableId::generate().into();
+    }
+
+    store_ignore_event(app, &event, actor).await;
+
+    let dt = DateTimeAsMicroseconds::now();
 
-    let telegram_settings = app.settings_reader.get_telegram_settings().await;
-
-    if let Some(telegram_settings) = telegram_settings {
-        let dt = DateTimeAsMicroseconds::now();
-
-        let mut ctx = BTreeMap::new();
-        ctx.insert("Level".to_string(), format!("{:?}", &event.level));
-        ctx.insert("Application".to_string(), event.application);
-        ctx.insert("Marker".to_string(), event.marker);
-        if let Some(expires_at) = event.expires_at {
-            ctx.insert(
-                "ExpiresAt".to_string(),
-                DateTimeAsMicroseconds::new(expires_at).to_rfc3339(),
-            );
-        }
-
-        crate::telegram::api::send_log_item(
-            &telegram_settings,
-            &LogItem {
-                id: dt.to_rfc3339(),
-                level: my_logger::LogLevel::Info,
-                process: None,
-                message: "Ignore event is added".to_string(),
-                timestamp: dt,
-                ctx,
-            },
-            &app.env_name,
-        )
-        .await;
+    let mut ctx = BTreeMap::new();
+    ctx.insert("Id".to_string(), event.id.to_string());
+    ctx.insert("Levels".to_string(), event.get_levels_description());
+    ctx.insert("Conditions".to_string(), event.get_conditions_description());
+    if let Some(application) = event.get_application() {
+        ctx.insert(APPLICATION_KEY.to_string(), application.to_string());
     }
+    if let Some(expires_at) = event.expires_at {
+        ctx.insert(
+            "ExpiresAt".to_string(),
+            DateTimeAsMicroseconds::new(expires_at).to_rfc3339(),
+        );
+    }
+
+    super::send_notification(
+        app,
+        Notification::Event(Arc::new(LogItem {
+            id: dt.to_rfc3339(),
+            level: my_logger::LogLevel::Info,
+            process: None,
+            message: "Ignore event is added".to_string(),
+            timestamp: dt,
+            ctx,
+        })),
+    )
+    .await;
+
+    Ok(event)
+}
+
+/// Stores an already normalized rule without notifying about it.
+pub async fn store_ignore_event(app: &AppContext, event: &IgnoreItemDto, actor: &AuditActorDto) {
+    let before = app.settings_repo.add_ignore_event(event).await;
+
+    super::write_audit_log(
+        app,
+        actor,
+        AuditTargetDto::IgnoreRule,
+        &event.id,
+        before.as_ref(),
+        Some(event),
+    )
+    .await;
+
+    app.filter_events_cache.reset().await;
+}
+
+pub fn normalize_ignore_event(event: &mut IgnoreItemDto) -> Result<(), String> {
+    event.id = event.id.trim().to_string();
+    event.levels.sort();
+    event.levels.dedup();
+
+    event.compile_conditions()?;
+    Ok(())
 }
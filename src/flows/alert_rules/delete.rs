@@ -0,0 +1,24 @@
+use crate::{
+    app::AppContext,
+    repo::dto::{AuditActorDto, AuditTargetDto},
+};
+
+pub async fn delete(app: &AppContext, id: &str, actor: &AuditActorDto) -> bool {
+    let Some(before) = app.alert_rules_repo.delete(id).await else {
+        return false;
+    };
+
+    crate::flows::write_audit_log(
+        app,
+        actor,
+        AuditTargetDto::AlertRule,
+        id,
+        Some(&before),
+        None,
+    )
+    .await;
+
+    let rules = app.alert_rules_repo.get_all().await;
+    app.alerts_engine.lock().await.set_rules(rules);
+    true
+}
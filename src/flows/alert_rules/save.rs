@@ -0,0 +1,122 @@
+use rust_extensions::SortableId;
+
+use crate::{
+    app::AppContext,
+    repo::dto::{AlertRuleDto, AuditActorDto, AuditTargetDto},
+};
+
+pub const MAX_ALERT_THRESHOLD: u32 = 100_000;
+pub const MAX_ALERT_WINDOW_SEC: u64 = 60 * 60 * 24;
+pub const MAX_ALERT_COOLDOWN_SEC: u64 = 60 * 60 * 24 * 7;
+
+/// Creates an alert rule when `id` is empty, otherwise replaces the existing one.
+pub async fn save(
+    app: &AppContext,
+    mut item: AlertRuleDto,
+    actor: &AuditActorDto,
+) -> Result<AlertRuleDto, String> {
+    normalize(&mut item)?;
+
+    if item.id.is_empty() {
+        item.id = SortableId::generate().into();
+    } else if app.alert_rules_repo.get(&item.id).await.is_none() {
+        return Err(format!("Alert rule with id '{}' is not found", item.id));
+    }
+
+    if let Some(saved_search_id) = item.saved_search_id.as_deref() {
+        if app.saved_searches_repo.get(saved_search_id).await.is_none() {
+            return Err(format!(
+                "Saved search with id '{}' is not found",
+                saved_search_id
+            ));
+        }
+    }
+
+    Ok(store(app, item, actor).await)
+}
+
+pub fn normalize(item: &mut AlertRuleDto) -> Result<(), String> {
+    item.id = item.id.trim().to_string();
+    item.name = item.name.trim().to_string();
+    if item.name.is_empty() {
+        return Err("Alert rule name can not be empty".to_string());
+    }
+
+    if item.threshold > MAX_ALERT_THRESHOLD {
+        return Err(format!(
+            "threshold must be in range 0..{}",
+            MAX_ALERT_THRESHOLD
+        ));
+    }
+
+    if item.window_sec == 0 || item.window_sec > MAX_ALERT_WINDOW_SEC {
+        return Err(format!(
+            "window_sec must be in range 1..{}",
+            MAX_ALERT_WINDOW_SEC
+        ));
+    }
+
+    if item.cooldown_sec > MAX_ALERT_COOLDOWN_SEC || item.resolve_after_sec > MAX_ALERT_COOLDOWN_SEC
+    {
+        return Err(format!(
+            "cooldown_sec and resolve_after_sec must be in range 0..{}",
+            MAX_ALERT_COOLDOWN_SEC
+        ));
+    }
+
+    item.application = item
+        .application
+        .take()
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty());
+
+    item.phrase = item
+        .phrase
+        .take()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+
+    item.group_by = item
+        .group_by
+        .take()
+        .map(|g| g.trim().to_string())
+        .filter(|g| !g.is_empty());
+
+    item.channels = item
+        .channels
+        .iter()
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect();
+    item.channels.sort();
+    item.channels.dedup();
+
+    item.saved_search_id = item
+        .saved_search_id
+        .take()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty());
+
+    item.compile_phrase()?;
+    Ok(())
+}
+
+/// Adds or replaces the normalized alert rule keeping its id.
+pub async fn store(app: &AppContext, item: AlertRuleDto, actor: &AuditActorDto) -> AlertRuleDto {
+    let before = app.alert_rules_repo.upsert(item.clone()).await;
+
+    crate::flows::write_audit_log(
+        app,
+        actor,
+        AuditTargetDto::AlertRule,
+        &item.id,
+        before.as_ref(),
+        Some(&item),
+    )
+    .await;
+
+    let rules = app.alert_rules_repo.get_all().await;
+    app.alerts_engine.lock().await.set_rules(rules);
+
+    item
+}
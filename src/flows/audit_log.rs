@@ -0,0 +1,53 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::Serialize;
+
+use crate::{
+    app::AppContext,
+    repo::dto::{AuditActionDto, AuditActorDto, AuditLogEntryDto, AuditTargetDto},
+};
+
+/// Appends a configuration change to the audit trail. Nothing is written if the value did not change.
+pub async fn write_audit_log<T: Serialize>(
+    app: &AppContext,
+    actor: &AuditActorDto,
+    target: AuditTargetDto,
+    id: &str,
+    before: Option<&T>,
+    after: Option<&T>,
+) {
+    let before = before.and_then(to_audit_value);
+    let after = after.and_then(to_audit_value);
+
+    let action = match (&before, &after) {
+        (None, None) => return,
+        (None, Some(_)) => AuditActionDto::Created,
+        (Some(_), None) => AuditActionDto::Deleted,
+        (Some(before), Some(after)) => {
+            if before == after {
+                return;
+            }
+            AuditActionDto::Updated
+        }
+    };
+
+    app.audit_log_repo
+        .append(&AuditLogEntryDto {
+            moment: DateTimeAsMicroseconds::now().unix_microseconds,
+            actor: actor.clone(),
+            target,
+            action,
+            id: id.to_string(),
+            before,
+            after,
+        })
+        .await;
+}
+
+/// Statistics change on every matched event and are not a part of the configuration.
+pub fn to_audit_value<T: Serialize>(value: &T) -> Option<serde_json::Value> {
+    let mut result = serde_json::to_value(value).ok()?;
+    if let Some(object) = result.as_object_mut() {
+        object.remove("statistics");
+    }
+    Some(result)
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    flows::ignore_single_event::persistence::IgnoreSingleEventFileModel,
+    repo::dto::{AlertRuleDto, IgnoreItemDto, SavedSearchDto},
+};
+
+pub const CONFIG_DOCUMENT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigDocumentFormat {
+    Json,
+    Yaml,
+}
+
+/// Server configuration moved between environments. A section which is absent is not touched on import.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigDocument {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exported_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignore_rules: Option<Vec<IgnoreItemDto>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub single_event_rules: Option<Vec<IgnoreSingleEventFileModel>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saved_searches: Option<Vec<SavedSearchDto>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alert_rules: Option<Vec<AlertRuleDto>>,
+}
+
+impl ConfigDocument {
+    /// Accepts JSON or YAML.
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let result: Self = if src.trim_start().starts_with('{') {
+            serde_json::from_str(src).map_err(|err| format!("Invalid JSON document: {}", err))?
+        } else {
+            serde_yaml::from_str(src).map_err(|err| format!("Invalid YAML document: {}", err))?
+        };
+
+        if result.version == 0 || result.version > CONFIG_DOCUMENT_VERSION {
+            return Err(format!(
+                "Unsupported document version {}. Expected 1..{}",
+                result.version, CONFIG_DOCUMENT_VERSION
+            ));
+        }
+
+        Ok(result)
+    }
+
+    pub fn serialize(&self, format: ConfigDocumentFormat) -> Result<String, String> {
+        match format {
+            ConfigDocumentFormat::Json => {
+                serde_json::to_string_pretty(self).map_err(|err| err.to_string())
+            }
+            ConfigDocumentFormat::Yaml => {
+                serde_yaml::to_string(self).map_err(|err| err.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repo::dto::{IgnoreItemDto, LogLevelDto, MatchModeDto};
+
+    use super::{ConfigDocument, ConfigDocumentFormat, CONFIG_DOCUMENT_VERSION};
+
+    #[test]
+    fn test_document_round_trip() {
+        let document = ConfigDocument {
+            version: CONFIG_DOCUMENT_VERSION,
+            ignore_rules: Some(vec![IgnoreItemDto::from_marker(
+                LogLevelDto::Warning,
+                "app",
+                "timeout",
+                MatchModeDto::Contains,
+                false,
+                None,
+            )]),
+            ..Default::default()
+        };
+
+        for format in [ConfigDocumentFormat::Json, ConfigDocumentFormat::Yaml] {
+            let serialized = document.serialize(format).unwrap();
+            assert!(!serialized.contains("statistics"));
+
+            let parsed = ConfigDocument::parse(&serialized).unwrap();
+            assert_eq!(parsed.ignore_rules, document.ignore_rules);
+            assert!(parsed.saved_searches.is_none());
+        }
+
+        assert!(ConfigDocument::parse("version: 2").is_err());
+        assert!(ConfigDocument::parse("ignore_rules: []").is_err());
+    }
+}
@@ -0,0 +1,38 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{app::AppContext, flows::ignore_single_event::persistence::IgnoreSingleEventFileModel};
+
+use super::{ConfigDocument, CONFIG_DOCUMENT_VERSION};
+
+/// Every section of the configuration without the statistics collected by the rules.
+pub async fn export_config(app: &AppContext) -> ConfigDocument {
+    let ignore_rules = app
+        .settings_repo
+        .get_ignore_events()
+        .await
+        .into_iter()
+        .map(|mut item| {
+            item.statistics = Default::default();
+            item
+        })
+        .collect();
+
+    let single_event_rules = crate::flows::ignore_single_event::get_all(app)
+        .await
+        .into_iter()
+        .map(|(item, _)| {
+            let item: IgnoreSingleEventFileModel = item.into();
+            item
+        })
+        .collect();
+
+    ConfigDocument {
+        version: CONFIG_DOCUMENT_VERSION,
+        exported_at: Some(DateTimeAsMicroseconds::now().to_rfc3339()),
+        env_name: Some(app.env_name.clone()),
+        ignore_rules: Some(ignore_rules),
+        single_event_rules: Some(single_event_rules),
+        saved_searches: Some(app.saved_searches_repo.get_all().await),
+        alert_rules: Some(app.alert_rules_repo.get_all().await),
+    }
+}
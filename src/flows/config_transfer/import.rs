@@ -0,0 +1,544 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::Serialize;
+
+use crate::{
+    app::{AppContext, LogItem},
+    flows::ignore_single_event::persistence::IgnoreSingleEventFileModel,
+    ignore_single_events::IgnoreSingleEventMatcher,
+    my_logger_grpc::IgnoreSingleEventGrpcModel,
+    notifications::Notification,
+    repo::dto::{AlertRuleDto, AuditActionDto, AuditActorDto, AuditTargetDto, SavedSearchDto},
+};
+
+use super::ConfigDocument;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigImportMode {
+    /// Adds new and updates existing items. Items missing in the document are kept.
+    Merge,
+    /// Same as merge, but items missing in a section present in the document are deleted.
+    Replace,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigChange {
+    pub target: AuditTargetDto,
+    pub id: String,
+    pub action: AuditActionDto,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConfigImportResult {
+    pub changes: Vec<ConfigChange>,
+    pub unchanged: usize,
+    /// `false` for a dry run.
+    pub applied: bool,
+}
+
+/// The whole document is validated and every rule is compiled before anything is applied,
+/// so an invalid item leaves the configuration untouched and applying can not fail halfway.
+/// One notification is sent for the whole import.
+pub async fn import_config(
+    app: &AppContext,
+    document: ConfigDocument,
+    mode: ConfigImportMode,
+    dry_run: bool,
+    actor: &AuditActorDto,
+) -> Result<ConfigImportResult, String> {
+    let mut ignore_rules = document
+        .ignore_rules
+        .map(|items| {
+            index_by_id(items, "Ignore rule", |item| {
+                crate::flows::normalize_ignore_event(item)?;
+                item.statistics = Default::default();
+                Ok(item.id.clone())
+            })
+        })
+        .transpose()?;
+
+    let mut single_event_matchers = BTreeMap::new();
+    let mut single_event_rules = document
+        .single_event_rules
+        .map(|items| {
+            index_by_id(items, "Single event rule", |item| {
+                item.id = item.id.trim().to_string();
+                item.levels.sort();
+                item.levels.dedup();
+                item.statistics = Default::default();
+                item.window = vec![];
+                let grpc_model: IgnoreSingleEventGrpcModel = item.clone().into();
+                let matcher = IgnoreSingleEventMatcher::new(&grpc_model)?;
+                single_event_matchers.insert(item.id.clone(), matcher);
+                Ok(item.id.clone())
+            })
+        })
+        .transpose()?;
+
+    let mut saved_searches = document
+        .saved_searches
+        .map(|items| {
+            index_by_id(items, "Saved search", |item| {
+                crate::flows::saved_searches::normalize(item)?;
+                Ok(item.id.clone())
+            })
+        })
+        .transpose()?;
+
+    let mut alert_rules = document
+        .alert_rules
+        .map(|items| {
+            index_by_id(items, "Alert rule", |item| {
+                crate::flows::alert_rules::normalize(item)?;
+                Ok(item.id.clone())
+            })
+        })
+        .transpose()?;
+
+    if saved_searches.is_some() || alert_rules.is_some() {
+        check_saved_search_links(
+            mode,
+            app.saved_searches_repo.get_all().await,
+            saved_searches.as_ref(),
+            app.alert_rules_repo.get_all().await,
+            alert_rules.as_ref(),
+        )?;
+    }
+
+    let mut result = ConfigImportResult::default();
+
+    if let Some(incoming) = ignore_rules.as_ref() {
+        let current = app.settings_repo.get_ignore_events().await;
+        let current = current.into_iter().map(|item| (item.id.clone(), item));
+        plan_section(
+            &mut result,
+            AuditTargetDto::IgnoreRule,
+            mode,
+            current,
+            incoming,
+        );
+    }
+
+    if let Some(incoming) = single_event_rules.as_ref() {
+        let current = crate::flows::ignore_single_event::get_all(app).await;
+        let current = current.into_iter().map(|(item, _)| {
+            let item: IgnoreSingleEventFileModel = item.into();
+            (item.id.clone(), item)
+        });
+        plan_section(
+            &mut result,
+            AuditTargetDto::SingleEventRule,
+            mode,
+            current,
+            incoming,
+        );
+    }
+
+    if let Some(incoming) = saved_searches.as_ref() {
+        let current = app.saved_searches_repo.get_all().await;
+        let current = current.into_iter().map(|item| (item.id.clone(), item));
+        plan_section(
+            &mut result,
+            AuditTargetDto::SavedSearch,
+            mode,
+            current,
+            incoming,
+        );
+    }
+
+    if let Some(incoming) = alert_rules.as_ref() {
+        let current = app.alert_rules_repo.get_all().await;
+        let current = current.into_iter().map(|item| (item.id.clone(), item));
+        plan_section(
+            &mut result,
+            AuditTargetDto::AlertRule,
+            mode,
+            current,
+            incoming,
+        );
+    }
+
+    if dry_run {
+        return Ok(result);
+    }
+
+    for change in result.changes.iter() {
+        let id = change.id.as_str();
+        let delete = change.action == AuditActionDto::Deleted;
+
+        match change.target {
+            AuditTargetDto::IgnoreRule => {
+                if delete {
+                    crate::flows::delete_ignore_event(app, id, actor).await;
+                } else if let Some(item) = ignore_rules.as_mut().and_then(|items| items.remove(id))
+                {
+                    crate::flows::store_ignore_event(app, &item, actor).await;
+                }
+            }
+            AuditTargetDto::SingleEventRule => {
+                if delete {
+                    crate::flows::ignore_single_event::delete(app, id.to_string(), actor).await;
+                } else if let (Some(item), Some(matcher)) = (
+                    single_event_rules
+                        .as_mut()
+                        .and_then(|items| items.remove(id)),
+                    single_event_matchers.remove(id),
+                ) {
+                    crate::flows::ignore_single_event::store(app, item.into(), matcher, actor)
+                        .await;
+                }
+            }
+            AuditTargetDto::SavedSearch => {
+                if delete {
+                    // Links of the alert rules are checked for the whole import above
+                    crate::flows::saved_searches::remove(app, id, actor).await;
+                } else if let Some(item) =
+                    saved_searches.as_mut().and_then(|items| items.remove(id))
+                {
+                    crate::flows::saved_searches::store(app, item, actor).await;
+                }
+            }
+            AuditTargetDto::AlertRule => {
+                if delete {
+                    crate::flows::alert_rules::delete(app, id, actor).await;
+                } else if let Some(item) = alert_rules.as_mut().and_then(|items| items.remove(id)) {
+                    crate::flows::alert_rules::store(app, item, actor).await;
+                }
+            }
+        }
+    }
+
+    result.applied = true;
+
+    if !result.changes.is_empty() {
+        send_import_notification(app, &result, mode, actor).await;
+    }
+
+    Ok(result)
+}
+
+async fn send_import_notification(
+    app: &AppContext,
+    result: &ConfigImportResult,
+    mode: ConfigImportMode,
+    actor: &AuditActorDto,
+) {
+    let count = |action| {
+        result
+            .changes
+            .iter()
+            .filter(|change| change.action == action)
+            .count()
+            .to_string()
+    };
+
+    let dt = DateTimeAsMicroseconds::now();
+
+    let mut ctx = BTreeMap::new();
+    ctx.insert("Mode".to_string(), format!("{:?}", mode));
+    ctx.insert("Created".to_string(), count(AuditActionDto::Created));
+    ctx.insert("Updated".to_string(), count(AuditActionDto::Updated));
+    ctx.insert("Deleted".to_string(), count(AuditActionDto::Deleted));
+    ctx.insert("Unchanged".to_string(), result.unchanged.to_string());
+    if let Some(name) = actor.name.as_ref() {
+        ctx.insert("Actor".to_string(), name.to_string());
+    }
+
+    crate::flows::send_notification(
+        app,
+        Notification::Event(Arc::new(LogItem {
+            id: dt.to_rfc3339(),
+            level: my_logger::LogLevel::Info,
+            process: None,
+            message: "Configuration is imported".to_string(),
+            timestamp: dt,
+            ctx,
+        })),
+    )
+    .await;
+}
+
+/// Items are matched by id, so every item of the document has to have a unique one.
+fn index_by_id<T>(
+    items: Vec<T>,
+    name: &str,
+    mut prepare: impl FnMut(&mut T) -> Result<String, String>,
+) -> Result<BTreeMap<String, T>, String> {
+    let mut result = BTreeMap::new();
+
+    for (index, mut item) in items.into_iter().enumerate() {
+        let id = prepare(&mut item).map_err(|err| format!("{} #{}: {}", name, index + 1, err))?;
+
+        if id.is_empty() {
+            return Err(format!("{} #{} has no id", name, index + 1));
+        }
+
+        if result.insert(id.clone(), item).is_some() {
+            return Err(format!("{} id '{}' is used more than once", name, id));
+        }
+    }
+
+    Ok(result)
+}
+
+/// Alert rules present after the import may only reference saved searches present after it.
+fn check_saved_search_links(
+    mode: ConfigImportMode,
+    current_saved_searches: Vec<SavedSearchDto>,
+    saved_searches: Option<&BTreeMap<String, SavedSearchDto>>,
+    current_alert_rules: Vec<AlertRuleDto>,
+    alert_rules: Option<&BTreeMap<String, AlertRuleDto>>,
+) -> Result<(), String> {
+    let keep_current = |imported: bool| !imported || mode == ConfigImportMode::Merge;
+
+    let mut saved_search_ids: BTreeSet<String> = saved_searches
+        .map(|items| items.keys().cloned().collect())
+        .unwrap_or_default();
+    if keep_current(saved_searches.is_some()) {
+        saved_search_ids.extend(current_saved_searches.into_iter().map(|item| item.id));
+    }
+
+    let mut links = BTreeMap::new();
+    if keep_current(alert_rules.is_some()) {
+        links.extend(
+            current_alert_rules
+                .into_iter()
+                .map(|item| (item.id, item.saved_search_id)),
+        );
+    }
+    if let Some(items) = alert_rules {
+        links.extend(
+            items
+                .values()
+                .map(|item| (item.id.clone(), item.saved_search_id.clone())),
+        );
+    }
+
+    for (id, saved_search_id) in links {
+        if let Some(saved_search_id) = saved_search_id {
+            if !saved_search_ids.contains(&saved_search_id) {
+                return Err(format!(
+                    "Alert rule '{}' references saved search '{}' which does not exist after the import",
+                    id, saved_search_id
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn plan_section<T: Serialize>(
+    result: &mut ConfigImportResult,
+    target: AuditTargetDto,
+    mode: ConfigImportMode,
+    current: impl Iterator<Item = (String, T)>,
+    incoming: &BTreeMap<String, T>,
+) {
+    let current: BTreeMap<String, T> = current.collect();
+
+    for (id, item) in incoming {
+        let before = current.get(id).and_then(crate::flows::to_audit_value);
+        let after = crate::flows::to_audit_value(item);
+
+        if before.is_some() && before == after {
+            result.unchanged += 1;
+            continue;
+        }
+
+        result.changes.push(ConfigChange {
+            target,
+            id: id.clone(),
+            action: if before.is_some() {
+                AuditActionDto::Updated
+            } else {
+                AuditActionDto::Created
+            },
+            before,
+            after,
+        });
+    }
+
+    if mode == ConfigImportMode::Replace {
+        for (id, item) in current {
+            if !incoming.contains_key(&id) {
+                result.changes.push(ConfigChange {
+                    target,
+                    before: crate::flows::to_audit_value(&item),
+                    id,
+                    action: AuditActionDto::Deleted,
+                    after: None,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::repo::dto::{
+        AlertRuleDto, AuditActionDto, AuditTargetDto, LogLevelDto, MatchModeDto, SavedSearchDto,
+    };
+
+    use super::{check_saved_search_links, plan_section, ConfigImportMode, ConfigImportResult};
+
+    fn saved_search(id: &str, last_minutes: i64) -> SavedSearchDto {
+        SavedSearchDto {
+            id: id.to_string(),
+            name: id.to_string(),
+            levels: vec![LogLevelDto::Error],
+            context: BTreeMap::new(),
+            phrase: None,
+            last_minutes,
+        }
+    }
+
+    fn alert_rule(id: &str, saved_search_id: &str) -> AlertRuleDto {
+        AlertRuleDto {
+            id: id.to_string(),
+            name: id.to_string(),
+            disabled: false,
+            application: None,
+            min_level: LogLevelDto::Error,
+            context: BTreeMap::new(),
+            phrase: None,
+            match_mode: MatchModeDto::Contains,
+            case_insensitive: false,
+            threshold: 0,
+            window_sec: 60,
+            group_by: None,
+            cooldown_sec: 600,
+            resolve_after_sec: 30,
+            channels: vec![],
+            saved_search_id: Some(saved_search_id.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_plan_section() {
+        let current = vec![
+            saved_search("a", 60),
+            saved_search("b", 60),
+            saved_search("c", 60),
+        ];
+        let incoming: BTreeMap<_, _> = [
+            saved_search("a", 60),
+            saved_search("b", 120),
+            saved_search("d", 60),
+        ]
+        .into_iter()
+        .map(|item| (item.id.clone(), item))
+        .collect();
+
+        let plan = |mode| {
+            let mut result = ConfigImportResult::default();
+            let current = current
+                .clone()
+                .into_iter()
+                .map(|item| (item.id.clone(), item));
+            plan_section(
+                &mut result,
+                AuditTargetDto::SavedSearch,
+                mode,
+                current,
+                &incoming,
+            );
+            result
+        };
+
+        let merge = plan(ConfigImportMode::Merge);
+        assert_eq!(merge.unchanged, 1);
+        let actions: Vec<_> = merge
+            .changes
+            .iter()
+            .map(|c| (c.id.as_str(), c.action))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                ("b", AuditActionDto::Updated),
+                ("d", AuditActionDto::Created)
+            ]
+        );
+
+        let replace = plan(ConfigImportMode::Replace);
+        let actions: Vec<_> = replace
+            .changes
+            .iter()
+            .map(|c| (c.id.as_str(), c.action))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                ("b", AuditActionDto::Updated),
+                ("d", AuditActionDto::Created),
+                ("c", AuditActionDto::Deleted)
+            ]
+        );
+        assert!(replace.changes[2].after.is_none());
+    }
+
+    #[test]
+    fn test_alert_rules_reference_saved_searches_left_after_import() {
+        let current_searches = vec![saved_search("a", 60), saved_search("b", 60)];
+        let current_rules = vec![alert_rule("1", "a")];
+        let incoming_searches: BTreeMap<_, _> = [saved_search("b", 60)]
+            .into_iter()
+            .map(|item| (item.id.clone(), item))
+            .collect();
+
+        // The kept rule still references the removed saved search
+        let result = check_saved_search_links(
+            ConfigImportMode::Replace,
+            current_searches.clone(),
+            Some(&incoming_searches),
+            current_rules.clone(),
+            None,
+        );
+        assert!(result.is_err());
+
+        assert!(check_saved_search_links(
+            ConfigImportMode::Merge,
+            current_searches.clone(),
+            Some(&incoming_searches),
+            current_rules.clone(),
+            None,
+        )
+        .is_ok());
+
+        // The imported rule replaces the kept one
+        let incoming_rules: BTreeMap<_, _> = [alert_rule("1", "b")]
+            .into_iter()
+            .map(|item| (item.id.clone(), item))
+            .collect();
+        assert!(check_saved_search_links(
+            ConfigImportMode::Replace,
+            current_searches.clone(),
+            Some(&incoming_searches),
+            current_rules.clone(),
+            Some(&incoming_rules),
+        )
+        .is_ok());
+
+        let incoming_rules: BTreeMap<_, _> = [alert_rule("2", "c")]
+            .into_iter()
+            .map(|item| (item.id.clone(), item))
+            .collect();
+        assert!(check_saved_search_links(
+            ConfigImportMode::Merge,
+            current_searches,
+            None,
+            current_rules,
+            Some(&incoming_rules),
+        )
+        .is_err());
+    }
+}
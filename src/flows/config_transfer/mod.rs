@@ -0,0 +1,6 @@
+mod document;
+pub use document::*;
+mod export;
+pub use export::*;
+mod import;
+pub use import::*;
@@ -0,0 +1,61 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::AppContext,
+    digest::{DigestPeriod, DigestReport},
+    rollup_statistics::RollupResolution,
+};
+
+const HOUR_US: i64 = 3_600_000_000;
+
+/// Daily digest compares the last 24 hour buckets of the rollups with the 24 before, weekly - the last 7 day buckets.
+pub async fn build_digest(app: &AppContext, period: DigestPeriod, take: usize) -> DigestReport {
+    let now = DateTimeAsMicroseconds::now();
+    let now_sec = now.unix_microseconds / 1_000_000;
+
+    let resolution = match period {
+        DigestPeriod::Daily => RollupResolution::Hour,
+        DigestPeriod::Weekly => RollupResolution::Day,
+    };
+
+    let period_sec = period.get_hours() * 3600;
+    let current_from =
+        resolution.get_bucket_start(now_sec - period_sec + resolution.get_bucket_sec());
+
+    let (current, previous) = {
+        let rollup_statistics = app.rollup_statistics.lock().await;
+        (
+            rollup_statistics.get_sum(resolution, current_from, now_sec),
+            rollup_statistics.get_sum(resolution, current_from - period_sec, current_from - 1),
+        )
+    };
+
+    let from = DateTimeAsMicroseconds::new(now.unix_microseconds - period.get_hours() * HOUR_US);
+
+    let new_errors = app.issues.lock().await.get_first_seen_since(from, take);
+
+    let mut ignore_rules: Vec<_> = app
+        .settings_repo
+        .get_ignore_events()
+        .await
+        .into_iter()
+        .map(|itm| {
+            let suppressed = itm.statistics.get_since(from);
+            (itm, suppressed)
+        })
+        .filter(|(_, suppressed)| *suppressed > 0)
+        .collect();
+
+    ignore_rules.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.id.cmp(&b.0.id)));
+    ignore_rules.truncate(take);
+
+    DigestReport::new(
+        app.env_name.clone(),
+        period,
+        now,
+        current,
+        previous,
+        new_errors,
+        ignore_rules,
+    )
+}
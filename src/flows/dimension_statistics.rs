@@ -0,0 +1,54 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::AppContext,
+    statistic_dimensions::{DimensionOrder, DimensionValueStatistics},
+};
+
+const DEFAULT_RANGE_US: i64 = 24 * 60 * 60 * 1_000_000;
+const DEFAULT_TAKE: usize = 10;
+pub const MAX_DIMENSION_VALUES_TAKE: usize = 100;
+
+/// Range is in unix microseconds: the last 24 hours by default. `take` is capped at [`MAX_DIMENSION_VALUES_TAKE`].
+pub async fn get_dimension_statistics(
+    app: &AppContext,
+    key: &str,
+    from: Option<i64>,
+    to: Option<i64>,
+    order: DimensionOrder,
+    take: Option<usize>,
+) -> Result<Vec<DimensionValueStatistics>, String> {
+    let to = to.unwrap_or_else(|| DateTimeAsMicroseconds::now().unix_microseconds);
+    let from = from.unwrap_or(to - DEFAULT_RANGE_US);
+
+    if from > to {
+        return Err("from can not be later than to".to_string());
+    }
+
+    let take = take
+        .filter(|take| *take > 0)
+        .unwrap_or(DEFAULT_TAKE)
+        .min(MAX_DIMENSION_VALUES_TAKE);
+
+    let dimensions = app.statistic_dimensions.lock().await;
+
+    if dimensions.is_empty() {
+        return Err("No statistic dimensions are configured".to_string());
+    }
+
+    dimensions
+        .query(
+            key.trim(),
+            DateTimeAsMicroseconds::new(from),
+            DateTimeAsMicroseconds::new(to),
+            order,
+            take,
+        )
+        .ok_or_else(|| {
+            format!(
+                "Unknown dimension '{}'. Configured: {}",
+                key,
+                dimensions.get_keys().join(", ")
+            )
+        })
+}
@@ -2,7 +2,11 @@ use std::collections::BTreeMap;
 
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 
-use crate::{app::AppContext, my_logger_grpc::*, repo::dto::LogItemDto};
+use crate::{
+    app::AppContext,
+    my_logger_grpc::*,
+    repo::dto::{LogItemDto, SearchPage},
+};
 
 pub async fn get_events(
     app: &AppContext,
@@ -10,7 +14,7 @@ pub async fn get_events(
     context_keys: Vec<LogEventContext>,
     from_date: DateTimeAsMicroseconds,
     to_date: Option<DateTimeAsMicroseconds>,
-    take: usize,
+    page: SearchPage,
 ) -> Vec<LogItemDto> {
     let log_levels = if levels.len() > 0 {
         Some(levels.into_iter().map(|level| level.into()).collect())
@@ -30,5 +34,5 @@ pub async fn get_events(
 
     let to_date = to_date.unwrap_or_else(DateTimeAsMicroseconds::now);
 
-    super::search_logs(app, from_date, to_date, log_levels, context, None, take).await
+    super::search_logs(app, from_date, to_date, log_levels, context, None, page).await
 }
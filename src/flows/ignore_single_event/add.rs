@@ -1,14 +1,55 @@
-use crate::{app::AppContext, my_logger_grpc::*};
+use crate::{
+    app::AppContext,
+    ignore_single_events::IgnoreSingleEventMatcher,
+    my_logger_grpc::*,
+    repo::dto::{AuditActorDto, AuditTargetDto},
+};
 
-pub async fn add(app: &AppContext, item: IgnoreSingleEventGrpcModel) {
+use super::persistence::IgnoreSingleEventFileModel;
+
+pub async fn add(
+    app: &AppContext,
+    item: IgnoreSingleEventGrpcModel,
+    actor: &AuditActorDto,
+) -> Result<(), String> {
+    let matcher = IgnoreSingleEventMatcher::new(&item)?;
+    store(app, item, matcher, actor).await;
+    Ok(())
+}
+
+/// Adds a rule whose matcher is already compiled, so it can not fail.
+pub async fn store(
+    app: &AppContext,
+    item: IgnoreSingleEventGrpcModel,
+    matcher: IgnoreSingleEventMatcher,
+    actor: &AuditActorDto,
+) {
     let mut write_access = app.ignore_single_event_cache.lock().await;
 
     if !write_access.initialized {
         let items = super::persistence::get_all(app).await;
-        write_access.init(items.clone());
+        write_access.init(items);
     }
 
-    write_access.add(item.clone());
+    let before: Option<IgnoreSingleEventFileModel> = write_access
+        .get_all_with_statistics()
+        .into_iter()
+        .find(|(itm, _)| itm.id == item.id)
+        .map(|(itm, _)| itm.into());
+
+    write_access.add(item.clone(), matcher);
+
+    super::persistence::save(app, write_access.get_snapshot()).await;
 
-    super::persistence::save(app, write_access.get_all()).await;
+    let id = item.id.clone();
+    let after: IgnoreSingleEventFileModel = item.into();
+    crate::flows::write_audit_log(
+        app,
+        actor,
+        AuditTargetDto::SingleEventRule,
+        &id,
+        before.as_ref(),
+        Some(&after),
+    )
+    .await;
 }
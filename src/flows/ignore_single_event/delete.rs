@@ -1,14 +1,35 @@
-use crate::app::AppContext;
+use crate::{
+    app::AppContext,
+    repo::dto::{AuditActorDto, AuditTargetDto},
+};
 
-pub async fn delete(app: &AppContext, id: String) {
+use super::persistence::IgnoreSingleEventFileModel;
+
+pub async fn delete(app: &AppContext, id: String, actor: &AuditActorDto) {
     let mut write_access = app.ignore_single_event_cache.lock().await;
 
     if !write_access.initialized {
         let items = super::persistence::get_all(app).await;
-        write_access.init(items.clone());
+        write_access.init(items);
     }
 
+    let before: Option<IgnoreSingleEventFileModel> = write_access
+        .get_all_with_statistics()
+        .into_iter()
+        .find(|(itm, _)| itm.id == id)
+        .map(|(itm, _)| itm.into());
+
     write_access.delete(&id);
 
-    super::persistence::save(app, write_access.get_all()).await;
+    super::persistence::save(app, write_access.get_snapshot()).await;
+
+    crate::flows::write_audit_log(
+        app,
+        actor,
+        AuditTargetDto::SingleEventRule,
+        &id,
+        before.as_ref(),
+        None,
+    )
+    .await;
 }
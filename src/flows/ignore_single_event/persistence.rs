@@ -2,24 +2,47 @@ use std::collections::HashMap;
 
 use serde::*;
 
-use crate::{app::AppContext, my_logger_grpc::*};
+use crate::{
+    app::AppContext,
+    ignore_single_events::IgnoreSingleEventSnapshot,
+    my_logger_grpc::*,
+    repo::dto::{IgnoreRuleStatisticsDto, MatchModeDto},
+};
 
 const FILE_NAME: &'static str = "one-time-skip.yaml";
 
-pub async fn save(app: &AppContext, items: Vec<IgnoreSingleEventGrpcModel>) {
-    let items_to_save: Vec<IgnoreSingleEventFileModel> =
-        items.into_iter().map(|itm| itm.into()).collect();
-
-    let as_yaml = serde_yaml::to_string(&items_to_save).unwrap();
+pub async fn save(app: &AppContext, items: Vec<IgnoreSingleEventSnapshot>) {
+    let items_to_save: Vec<IgnoreSingleEventFileModel> = items
+        .into_iter()
+        .map(|snapshot| {
+            let mut file_model: IgnoreSingleEventFileModel = snapshot.item.into();
+            file_model.statistics = snapshot.statistics;
+            file_model.window = snapshot.window;
+            file_model
+        })
+        .collect();
+
+    let as_yaml = match serde_yaml::to_string(&items_to_save) {
+        Ok(as_yaml) => as_yaml,
+        Err(e) => {
+            println!("Failed to serialize ignore_single_event: {}", e);
+            return;
+        }
+    };
 
     let file_name = app.settings_reader.get_logs_db_path(FILE_NAME.into()).await;
 
     println!("Saving ignore_single_event to {:?}", file_name);
 
-    tokio::fs::write(file_name, as_yaml).await.unwrap();
+    if let Err(e) = tokio::fs::write(&file_name, as_yaml).await {
+        println!(
+            "Failed to write ignore_single_event to {}: {}",
+            file_name, e
+        );
+    }
 }
 
-pub async fn get_all(app: &AppContext) -> Vec<IgnoreSingleEventGrpcModel> {
+pub async fn get_all(app: &AppContext) -> Vec<IgnoreSingleEventSnapshot> {
     let file_name = app.settings_reader.get_logs_db_path(FILE_NAME.into()).await;
     let as_yaml = tokio::fs::read_to_string(file_name).await;
 
@@ -31,7 +54,18 @@ pub async fn get_all(app: &AppContext) -> Vec<IgnoreSingleEventGrpcModel> {
 
     let items: Vec<IgnoreSingleEventFileModel> = serde_yaml::from_str(&as_yaml).unwrap();
 
-    items.into_iter().map(|itm| itm.into()).collect()
+    items
+        .into_iter()
+        .map(|mut itm| {
+            let statistics = std::mem::take(&mut itm.statistics);
+            let window = std::mem::take(&mut itm.window);
+            IgnoreSingleEventSnapshot {
+                item: itm.into(),
+                statistics,
+                window,
+            }
+        })
+        .collect()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,11 +76,22 @@ pub struct IgnoreSingleEventFileModel {
     pub context_match: HashMap<String, String>,
     pub skip_amount: u64,
     pub minutes_to_wait: u64,
+    #[serde(default)]
+    pub match_mode: MatchModeDto,
+    #[serde(default)]
+    pub case_insensitive: bool,
+    #[serde(default, skip_serializing_if = "IgnoreRuleStatisticsDto::is_empty")]
+    pub statistics: IgnoreRuleStatisticsDto,
+    /// Matches within the sliding window as unix microseconds. Not a part of the configuration.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub window: Vec<i64>,
 }
 
 impl Into<IgnoreSingleEventFileModel> for IgnoreSingleEventGrpcModel {
     fn into(self) -> IgnoreSingleEventFileModel {
         IgnoreSingleEventFileModel {
+            match_mode: self.match_mode().into(),
+            case_insensitive: self.case_insensitive,
             id: self.id,
             levels: self.levels,
             message_match: self.message_match,
@@ -57,6 +102,8 @@ impl Into<IgnoreSingleEventFileModel> for IgnoreSingleEventGrpcModel {
                 .collect(),
             skip_amount: self.skip_amount,
             minutes_to_wait: self.minutes_to_wait,
+            statistics: IgnoreRuleStatisticsDto::default(),
+            window: vec![],
         }
     }
 }
@@ -77,6 +124,12 @@ impl Into<IgnoreSingleEventGrpcModel> for IgnoreSingleEventFileModel {
                 .collect(),
             skip_amount: self.skip_amount,
             minutes_to_wait: self.minutes_to_wait,
+            match_mode: {
+                let match_mode: MatchModeGrpcModel = self.match_mode.into();
+                match_mode as i32
+            },
+            case_insensitive: self.case_insensitive,
+            statistics: None,
         }
     }
 }
@@ -1,33 +1,243 @@
-use crate::{app::AppContext, hourly_statistics::PersistedHour};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::{restore_json, AppContext},
+    error_signatures::ErrorSignatureItem,
+    heartbeats::HeartbeatItem,
+    hourly_statistics::{PersistedDay, PersistedHour},
+    issues::IssueItem,
+    notifications::NotificationSilence,
+    rollup_statistics::PersistedRollupStatistics,
+    statistic_dimensions::PersistedDimension,
+    version_statistics::VersionSummary,
+};
+
+/// Files of the former hourly statistics and daily totals, now imported into the rollups.
+const LEGACY_HOURLY_STATISTICS_FILE: &str = "statistics.json";
+const LEGACY_DAILY_STATISTICS_FILE: &str = "daily-statistics.json";
+/// File of the former error signature store, now imported into the issues.
+const LEGACY_ERROR_SIGNATURES_FILE: &str = "error-signatures.json";
 
 pub async fn init(app: &AppContext) {
-    let bytes = match tokio::fs::read(&app.statistics_path).await {
-        Ok(b) => b,
-        Err(_) => {
-            println!(
-                "No statistics file at {}, starting empty",
-                app.statistics_path
-            );
-            return;
-        }
+    let alert_rules = app.alert_rules_repo.get_all().await;
+    app.alerts_engine.lock().await.set_rules(alert_rules);
+
+    restore_issues(app).await;
+    migrate_legacy_error_signatures(app).await;
+    start_new_error_learning(app).await;
+    restore_heartbeats(app).await;
+    restore_notification_silences(app).await;
+    restore_rollup_statistics(app).await;
+    migrate_legacy_statistics(app).await;
+    restore_statistic_dimensions(app).await;
+    restore_version_statistics(app).await;
+}
+
+async fn restore_issues(app: &AppContext) {
+    let Some(parsed) = restore_json::<Vec<IssueItem>>(&app.issues_path, "issues").await else {
+        return;
     };
 
-    let parsed: Vec<PersistedHour> = match serde_json::from_slice(&bytes) {
-        Ok(v) => v,
-        Err(e) => {
-            println!(
-                "Failed to parse statistics file at {}: {}",
-                app.statistics_path, e
-            );
-            return;
-        }
+    let mut access = app.issues.lock().await;
+    access.restore(parsed);
+    println!("Restored {} issues from {}", access.len(), app.issues_path);
+}
+
+/// Known error signatures are the issues now: signatures without an issue are imported.
+async fn migrate_legacy_error_signatures(app: &AppContext) {
+    let path = app
+        .settings_reader
+        .get_logs_db_path(LEGACY_ERROR_SIGNATURES_FILE.into())
+        .await;
+
+    let Some(parsed) = restore_json::<Vec<ErrorSignatureItem>>(&path, "error signatures").await
+    else {
+        return;
     };
 
     let count = parsed.len();
-    let mut access = app.hourly_statistics.lock().await;
-    access.restore_from_vec(parsed);
+    app.issues.lock().await.restore_legacy_signatures(parsed);
+    println!("Migrated {} error signatures from {}", count, path);
+    remove_legacy_file(&path).await;
+}
+
+async fn start_new_error_learning(app: &AppContext) {
+    let Some(settings) = app.new_error_detection.as_ref() else {
+        return;
+    };
+
+    if !app.issues.lock().await.is_empty() {
+        return;
+    }
+
+    let learning_until = DateTimeAsMicroseconds::new(
+        DateTimeAsMicroseconds::now().unix_microseconds + settings.get_learning_sec() * 1_000_000,
+    );
+    app.new_error_detector
+        .lock()
+        .await
+        .learn_until(learning_until);
+    println!(
+        "No issues at {}, new errors are learned until {}",
+        app.issues_path,
+        learning_until.to_rfc3339()
+    );
+}
+
+async fn restore_heartbeats(app: &AppContext) {
+    let Some(parsed) = restore_json::<Vec<HeartbeatItem>>(&app.heartbeats_path, "heartbeats").await
+    else {
+        return;
+    };
+
+    let mut access = app.heartbeats.lock().await;
+    access.restore(parsed);
+    println!(
+        "Restored {} application heartbeats from {}",
+        access.len(),
+        app.heartbeats_path
+    );
+}
+
+async fn restore_notification_silences(app: &AppContext) {
+    let Some(parsed) = restore_json::<Vec<NotificationSilence>>(
+        &app.notification_silences_path,
+        "notification silences",
+    )
+    .await
+    else {
+        return;
+    };
+
+    let mut access = app.notification_silences.lock().await;
+    access.restore(parsed);
+    println!(
+        "Restored {} notification silences from {}",
+        access.len(),
+        app.notification_silences_path
+    );
+}
+
+async fn restore_rollup_statistics(app: &AppContext) {
+    let mut dir = match tokio::fs::read_dir(&app.rollup_statistics_path).await {
+        Ok(dir) => dir,
+        Err(_) => return,
+    };
+
+    let mut access = app.rollup_statistics.lock().await;
+
+    loop {
+        let entry = match dir.next_entry().await {
+            Ok(Some(entry)) => entry,
+            Ok(None) => break,
+            Err(e) => {
+                println!(
+                    "Failed to read rollup statistics directory {}: {}",
+                    app.rollup_statistics_path, e
+                );
+                break;
+            }
+        };
+
+        let path = entry.path();
+        if path.extension().and_then(|x| x.to_str()) != Some("json") {
+            continue;
+        }
+
+        let path = path.to_string_lossy();
+        if let Some(parsed) =
+            restore_json::<PersistedRollupStatistics>(&path, "rollup statistics").await
+        {
+            access.restore(parsed);
+        }
+    }
+
+    access.gc(DateTimeAsMicroseconds::now().unix_microseconds / 1_000_000);
+    println!(
+        "Restored {} rollup statistics buckets from {}",
+        access.len(),
+        app.rollup_statistics_path
+    );
+}
+
+/// Imports the hours and days the rollups do not have yet and removes the legacy files.
+async fn migrate_legacy_statistics(app: &AppContext) {
+    let hours_path = app
+        .settings_reader
+        .get_logs_db_path(LEGACY_HOURLY_STATISTICS_FILE.into())
+        .await;
+
+    if let Some(parsed) = restore_json::<Vec<PersistedHour>>(&hours_path, "statistics").await {
+        let count = parsed.len();
+        app.rollup_statistics
+            .lock()
+            .await
+            .restore_legacy_hours(parsed);
+        println!(
+            "Migrated statistics for {} hours from {}",
+            count, hours_path
+        );
+        remove_legacy_file(&hours_path).await;
+    }
+
+    let days_path = app
+        .settings_reader
+        .get_logs_db_path(LEGACY_DAILY_STATISTICS_FILE.into())
+        .await;
+
+    if let Some(parsed) = restore_json::<Vec<PersistedDay>>(&days_path, "daily statistics").await {
+        let count = parsed.len();
+        app.rollup_statistics
+            .lock()
+            .await
+            .restore_legacy_days(parsed);
+        println!(
+            "Migrated daily statistics for {} days from {}",
+            count, days_path
+        );
+        remove_legacy_file(&days_path).await;
+    }
+}
+
+async fn remove_legacy_file(path: &str) {
+    if let Err(e) = tokio::fs::remove_file(path).await {
+        println!("Failed to remove migrated file {}: {}", path, e);
+    }
+}
+
+async fn restore_statistic_dimensions(app: &AppContext) {
+    let Some(parsed) = restore_json::<Vec<PersistedDimension>>(
+        &app.statistic_dimensions_path,
+        "dimension statistics",
+    )
+    .await
+    else {
+        return;
+    };
+
+    let mut access = app.statistic_dimensions.lock().await;
+    access.restore(parsed);
+    access.gc(DateTimeAsMicroseconds::now());
+    println!(
+        "Restored statistics for dimensions {:?} from {}",
+        access.get_keys(),
+        app.statistic_dimensions_path
+    );
+}
+
+async fn restore_version_statistics(app: &AppContext) {
+    let Some(parsed) =
+        restore_json::<Vec<VersionSummary>>(&app.version_statistics_path, "version statistics")
+            .await
+    else {
+        return;
+    };
+
+    let mut access = app.version_statistics.lock().await;
+    access.restore(parsed);
     println!(
-        "Restored statistics for {} hours from {}",
-        count, app.statistics_path
+        "Restored statistics of {} versions from {}",
+        access.len(),
+        app.version_statistics_path
     );
 }
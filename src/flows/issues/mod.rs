@@ -0,0 +1,2 @@
+mod set_status;
+pub use set_status::*;
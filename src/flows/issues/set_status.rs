@@ -0,0 +1,75 @@
+use crate::{
+    app::AppContext,
+    issues::{IssueItem, IssueStatus},
+    repo::dto::{get_marker_rule_id, AuditActorDto, IgnoreItemDto, LogLevelDto, MatchModeDto},
+};
+
+const IGNORED_LEVELS: [LogLevelDto; 2] = [LogLevelDto::Error, LogLevelDto::FatalError];
+
+/// Ignoring an issue adds a regex ignore rule for its template; leaving the state removes it.
+pub async fn set_status(
+    app: &AppContext,
+    id: &str,
+    status: IssueStatus,
+    actor: &AuditActorDto,
+) -> Result<IssueItem, String> {
+    let issue = app
+        .issues
+        .lock()
+        .await
+        .get(id)
+        .ok_or_else(|| format!("Issue '{}' is not found", id))?;
+
+    if issue.status == status {
+        return Ok(issue);
+    }
+
+    let marker = issue.get_ignore_marker();
+
+    if status == IssueStatus::Ignored {
+        if issue.application == crate::app::NO_APPLICATION {
+            return Err("Issues of events without Application can not be ignored".to_string());
+        }
+
+        let mut rule = IgnoreItemDto::from_marker(
+            LogLevelDto::Error,
+            &issue.application,
+            &marker,
+            MatchModeDto::Regex,
+            false,
+            None,
+        );
+        rule.id = get_issue_rule_id(&issue.id);
+        rule.levels = IGNORED_LEVELS.to_vec();
+
+        crate::flows::add_ignore_event(app, rule, actor).await?;
+    }
+
+    if issue.status == IssueStatus::Ignored {
+        crate::flows::remove_ignore_event(app, &get_issue_rule_id(&issue.id), actor).await;
+
+        // Issues ignored before rules had ids got a level/application/marker rule per level.
+        for level in IGNORED_LEVELS {
+            crate::flows::remove_ignore_event(
+                app,
+                &get_marker_rule_id(
+                    &level,
+                    &issue.application,
+                    &issue.get_legacy_ignore_marker(),
+                ),
+                actor,
+            )
+            .await;
+        }
+    }
+
+    app.issues
+        .lock()
+        .await
+        .set_status(id, status)
+        .ok_or_else(|| format!("Issue '{}' is not found", id))
+}
+
+fn get_issue_rule_id(issue_id: &str) -> String {
+    format!("issue:{}", issue_id)
+}
@@ -4,6 +4,8 @@ mod add_ignore_event;
 pub use add_ignore_event::*;
 mod remove_ignore_event;
 pub use remove_ignore_event::*;
+mod preview_ignore_event;
+pub use preview_ignore_event::*;
 mod search_and_scan;
 pub use search_and_scan::*;
 mod get_events;
@@ -12,4 +14,22 @@ mod search_logs;
 pub use search_logs::*;
 mod init;
 pub use init::*;
+mod send_notification;
+pub use send_notification::*;
+mod telegram_bot;
+pub use telegram_bot::*;
+mod digest;
+pub use digest::*;
+mod audit_log;
+pub use audit_log::*;
+mod rollup_statistics;
+pub use rollup_statistics::*;
+mod dimension_statistics;
+pub use dimension_statistics::*;
+mod version_regression;
+pub use version_regression::*;
+pub mod alert_rules;
+pub mod config_transfer;
 pub mod ignore_single_event;
+pub mod issues;
+pub mod saved_searches;
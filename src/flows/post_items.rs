@@ -1,20 +1,72 @@
 use std::sync::Arc;
 
-use crate::app::{AppContext, LogItem};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::{AppContext, LogItem},
+    error_signatures::ErrorSignature,
+};
+
+pub async fn post_items(app: &AppContext, mut log_events: Vec<LogItem>) {
+    if !app.field_extractors.is_empty() {
+        for log_event in log_events.iter_mut() {
+            app.field_extractors.apply(log_event);
+        }
+    }
+
+    // After field extraction, so extracted values are redacted too, and before ignore rules,
+    // notifications and storage see the event.
+    if !app.redactor.is_empty() {
+        let mut redaction_statistics = app.redaction_statistics.lock().await;
+        for log_event in log_events.iter_mut() {
+            app.redactor.apply(log_event, &mut redaction_statistics);
+        }
+    }
 
-pub async fn post_items(app: &AppContext, log_events: Vec<LogItem>) {
     let log_events = filter_events(app, log_events).await;
 
     let log_events = filter_single_ignore_events(app, log_events).await;
 
-    {
-        let mut hourly_statistics = app.hourly_statistics.lock().await;
+    // Sampled out events are still counted, only their storage is skipped.
+    let log_events = {
         let mut telegram_notification_data = app.telegram_notification_data.lock().await;
-        for itm in log_events.iter() {
-            hourly_statistics.update(&itm);
+        let mut alerts_engine = app.alerts_engine.lock().await;
+        let mut new_error_detector = app.new_error_detector.lock().await;
+        let mut issues = app.issues.lock().await;
+        let mut heartbeats = app.heartbeats.lock().await;
+        let mut rollup_statistics = app.rollup_statistics.lock().await;
+        let mut statistic_dimensions = app.statistic_dimensions.lock().await;
+        let mut version_statistics = app.version_statistics.lock().await;
+        let reappear_after_sec = app
+            .new_error_detection
+            .as_ref()
+            .map(|settings| settings.get_reappear_after_sec());
+        let mut sampler = app.sampler.lock().await;
+        let now = DateTimeAsMicroseconds::now();
+        let mut kept = Vec::with_capacity(log_events.len());
+        for itm in log_events {
             telegram_notification_data.update(&itm);
+            alerts_engine.update(&itm, now);
+            if let Some(signature) = ErrorSignature::from_log_item(&itm) {
+                let (issue, prev_last_seen) = issues.update(&itm, signature, now);
+                if let Some(reappear_after_sec) = reappear_after_sec {
+                    new_error_detector.update(issue, &itm, prev_last_seen, now, reappear_after_sec);
+                }
+            }
+            heartbeats.update(&itm, now);
+            rollup_statistics.update(&itm);
+            statistic_dimensions.update(&itm);
+            version_statistics.update(&itm, now);
+
+            if sampler.is_empty() || sampler.keep(&itm) {
+                kept.push(itm);
+            } else {
+                rollup_statistics.add_sampled(&itm);
+                statistic_dimensions.add_sampled(&itm);
+            }
         }
-    }
+        kept
+    };
 
     if log_events.len() == 0 {
         return;
@@ -55,19 +107,24 @@ async fn filter_single_ignore_events(
         cache.init(items);
     }
 
+    let now = DateTimeAsMicroseconds::now();
+
     log_events
         .into_iter()
-        .filter(|itm| !cache.skip_by_filtering(itm))
+        .filter(|itm| !cache.skip_by_filtering(itm, now))
         .collect()
 }
 
 async fn filter_events(app: &AppContext, mut log_events: Vec<LogItem>) -> Vec<Arc<LogItem>> {
     loop {
+        let mut hits = Vec::new();
+
         let result = app
             .filter_events_cache
             .filter_events(log_events, |event, filter_events| {
                 for filter in filter_events {
                     if filter.matches_ignore_filter(event) {
+                        hits.push((filter.item.id.clone(), event.timestamp));
                         return false;
                     }
                 }
@@ -78,7 +135,14 @@ async fn filter_events(app: &AppContext, mut log_events: Vec<LogItem>) -> Vec<Ar
 
         match result {
             crate::cache::FilterEventResult::Ok(items) => {
-                return items.into_iter().map(Arc::new).collect()
+                if !hits.is_empty() {
+                    let mut ignore_rule_hits = app.ignore_rule_hits.lock().await;
+                    for (rule_id, moment) in hits {
+                        ignore_rule_hits.add(&rule_id, moment);
+                    }
+                }
+
+                return items.into_iter().map(Arc::new).collect();
             }
             crate::cache::FilterEventResult::NotInitialized(items) => {
                 log_events = items;
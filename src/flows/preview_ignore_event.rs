@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::{AppContext, APPLICATION_KEY},
+    cache::IgnoreFilter,
+    repo::dto::{IgnoreItemDto, LogItemDto, LogLevelDto, SearchPage},
+    text_matching::MAX_MATCHER_SCAN_ITEMS,
+};
+
+const HOUR_US: i64 = 3_600_000_000;
+
+pub struct IgnoreEventPreviewGroup {
+    pub level: LogLevelDto,
+    pub application: String,
+    pub count: usize,
+}
+
+/// What a candidate ignore rule would have suppressed within the last hours.
+pub struct IgnoreEventPreview {
+    pub hours: i64,
+    pub scanned: usize,
+    pub matched: usize,
+    /// The scan stopped at `MAX_MATCHER_SCAN_ITEMS` newest events; older events of the window were not checked.
+    pub truncated: bool,
+    pub groups: Vec<IgnoreEventPreviewGroup>,
+    pub samples: Vec<LogItemDto>,
+}
+
+/// Runs the rule over the stored events of both `LogsRepo` and `SqliteLogsRepo` without adding it.
+pub async fn preview_ignore_event(
+    app: &AppContext,
+    event: IgnoreItemDto,
+    hours: i64,
+    samples_amount: usize,
+) -> Result<IgnoreEventPreview, String> {
+    if hours <= 0 {
+        return Err("hours must be positive".to_string());
+    }
+
+    let hours = hours.min(app.settings_reader.get_hours_to_gc().await.max(1) as i64);
+
+    let filter = IgnoreFilter::new(event)?;
+
+    let to_date = DateTimeAsMicroseconds::now();
+    let from_date = DateTimeAsMicroseconds::new(to_date.unix_microseconds - hours * HOUR_US);
+
+    let levels = if filter.item.levels.is_empty() {
+        None
+    } else {
+        Some(filter.item.levels.clone())
+    };
+
+    let context = filter.item.get_application().map(|application| {
+        let mut context = BTreeMap::new();
+        context.insert(APPLICATION_KEY.to_string(), application.to_string());
+        context
+    });
+
+    let events = super::search_logs(
+        app,
+        from_date,
+        to_date,
+        levels,
+        context,
+        None,
+        SearchPage::newest(MAX_MATCHER_SCAN_ITEMS),
+    )
+    .await;
+
+    let scanned = events.len();
+    let mut matched = 0;
+    let mut groups: BTreeMap<(LogLevelDto, String), usize> = BTreeMap::new();
+    let mut samples = Vec::new();
+
+    for itm in events {
+        if !filter.matches_stored_event(&itm) {
+            continue;
+        }
+
+        matched += 1;
+
+        let application = itm
+            .context
+            .get(APPLICATION_KEY)
+            .cloned()
+            .unwrap_or_default();
+        *groups.entry((itm.level.clone(), application)).or_default() += 1;
+
+        if samples.len() < samples_amount {
+            samples.push(itm);
+        }
+    }
+
+    let mut groups: Vec<_> = groups
+        .into_iter()
+        .map(|((level, application), count)| IgnoreEventPreviewGroup {
+            level,
+            application,
+            count,
+        })
+        .collect();
+    groups.sort_by(|a, b| b.count.cmp(&a.count));
+
+    Ok(IgnoreEventPreview {
+        hours,
+        scanned,
+        matched,
+        truncated: scanned >= MAX_MATCHER_SCAN_ITEMS,
+        groups,
+        samples,
+    })
+}
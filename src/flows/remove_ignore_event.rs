@@ -1,45 +1,65 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, sync::Arc};
 
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 
 use crate::{
-    app::{AppContext, LogItem},
-    repo::dto::IgnoreWhereModel,
+    app::{AppContext, LogItem, APPLICATION_KEY},
+    notifications::Notification,
+    repo::dto::{AuditActorDto, AuditTargetDto, IgnoreItemDto},
 };
 
-pub async fn remove_ignore_event(app: &AppContext, event: IgnoreWhereModel) {
-    app.settings_repo
-        .delete_ignore_event(&IgnoreWhereModel {
-            level: event.level.clone().into(),
-            application: event.application.clone(),
-            marker: event.marker.clone(),
-        })
-        .await;
+/// Returns `true` if a rule with the given id existed.
+pub async fn remove_ignore_event(app: &AppContext, id: &str, actor: &AuditActorDto) -> bool {
+    let rule = match delete_ignore_event(app, id, actor).await {
+        Some(rule) => rule,
+        None => return false,
+    };
 
-    app.filter_events_cache.reset().await;
+    let dt = DateTimeAsMicroseconds::now();
 
-    let telegram_settings = app.settings_reader.get_telegram_settings().await;
-
-    if let Some(telegram_settings) = telegram_settings {
-        let dt = DateTimeAsMicroseconds::now();
-
-        let mut ctx = BTreeMap::new();
-        ctx.insert("Level".to_string(), format!("{:?}", &event.level));
-        ctx.insert("Application".to_string(), event.application);
-        ctx.insert("Marker".to_string(), event.marker);
-
-        crate::telegram::api::send_log_item(
-            &telegram_settings,
-            &LogItem {
-                id: dt.to_rfc3339(),
-                level: my_logger::LogLevel::Info,
-                process: None,
-                message: "Ignore event removed".to_string(),
-                timestamp: dt,
-                ctx,
-            },
-            &app.env_name,
-        )
-        .await;
+    let mut ctx = BTreeMap::new();
+    ctx.insert("Id".to_string(), id.to_string());
+    ctx.insert("Levels".to_string(), rule.get_levels_description());
+    ctx.insert("Conditions".to_string(), rule.get_conditions_description());
+    if let Some(application) = rule.get_application() {
+        ctx.insert(APPLICATION_KEY.to_string(), application.to_string());
     }
+
+    super::send_notification(
+        app,
+        Notification::Event(Arc::new(LogItem {
+            id: dt.to_rfc3339(),
+            level: my_logger::LogLevel::Info,
+            process: None,
+            message: "Ignore event removed".to_string(),
+            timestamp: dt,
+            ctx,
+        })),
+    )
+    .await;
+
+    true
+}
+
+/// Deletes the rule without notifying about it. Returns the deleted rule.
+pub async fn delete_ignore_event(
+    app: &AppContext,
+    id: &str,
+    actor: &AuditActorDto,
+) -> Option<IgnoreItemDto> {
+    let rule = app.settings_repo.delete_ignore_event(id).await?;
+
+    app.filter_events_cache.reset().await;
+
+    super::write_audit_log(
+        app,
+        actor,
+        AuditTargetDto::IgnoreRule,
+        id,
+        Some(&rule),
+        None,
+    )
+    .await;
+
+    Some(rule)
 }
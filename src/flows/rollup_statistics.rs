@@ -0,0 +1,41 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::AppContext,
+    rollup_statistics::{RollupBucket, RollupResolution},
+};
+
+const DEFAULT_RANGE_SEC: i64 = 24 * 60 * 60;
+
+/// Range is in unix microseconds: the last 24 hours by default. The resolution is selected by the range if not set.
+pub async fn get_rollup_statistics(
+    app: &AppContext,
+    from: Option<i64>,
+    to: Option<i64>,
+    resolution: Option<RollupResolution>,
+    application: Option<&str>,
+) -> Result<(RollupResolution, Vec<RollupBucket>), String> {
+    let now_sec = DateTimeAsMicroseconds::now().unix_microseconds / 1_000_000;
+
+    let to_sec = to.map(|to| to / 1_000_000).unwrap_or(now_sec);
+    let from_sec = from
+        .map(|from| from / 1_000_000)
+        .unwrap_or(to_sec - DEFAULT_RANGE_SEC);
+
+    if from_sec > to_sec {
+        return Err("from can not be later than to".to_string());
+    }
+
+    let resolution =
+        resolution.unwrap_or_else(|| RollupResolution::select(from_sec, to_sec, now_sec));
+
+    let application = application.map(|a| a.trim()).filter(|a| !a.is_empty());
+
+    let buckets =
+        app.rollup_statistics
+            .lock()
+            .await
+            .query(resolution, from_sec, to_sec, application);
+
+    Ok((resolution, buckets))
+}
@@ -0,0 +1,46 @@
+use crate::{
+    app::AppContext,
+    repo::dto::{AuditActorDto, AuditTargetDto},
+};
+
+/// Refuses to delete a saved search while alert rules link to it.
+/// Returns `false` if there is no saved search with the id.
+pub async fn delete(app: &AppContext, id: &str, actor: &AuditActorDto) -> Result<bool, String> {
+    let linked_rules: Vec<_> = app
+        .alert_rules_repo
+        .get_all()
+        .await
+        .into_iter()
+        .filter(|rule| rule.saved_search_id.as_deref() == Some(id))
+        .map(|rule| rule.id)
+        .collect();
+
+    if !linked_rules.is_empty() {
+        return Err(format!(
+            "Saved search '{}' is linked to alert rules: {}",
+            id,
+            linked_rules.join(", ")
+        ));
+    }
+
+    Ok(remove(app, id, actor).await)
+}
+
+/// Deletes the saved search without checking the alert rules linked to it.
+pub async fn remove(app: &AppContext, id: &str, actor: &AuditActorDto) -> bool {
+    let Some(before) = app.saved_searches_repo.delete(id).await else {
+        return false;
+    };
+
+    crate::flows::write_audit_log(
+        app,
+        actor,
+        AuditTargetDto::SavedSearch,
+        id,
+        Some(&before),
+        None,
+    )
+    .await;
+
+    true
+}
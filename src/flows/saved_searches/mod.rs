@@ -0,0 +1,4 @@
+mod save;
+pub use save::*;
+mod delete;
+pub use delete::*;
@@ -0,0 +1,107 @@
+use rust_extensions::SortableId;
+
+use crate::{
+    app::AppContext,
+    repo::dto::{AuditActorDto, AuditTargetDto, SavedSearchDto},
+};
+
+pub const MAX_SAVED_SEARCH_MINUTES: i64 = 60 * 24 * 30;
+
+/// Creates a saved search when `id` is empty, otherwise replaces the existing one.
+pub async fn save(
+    app: &AppContext,
+    mut item: SavedSearchDto,
+    actor: &AuditActorDto,
+) -> Result<SavedSearchDto, String> {
+    normalize(&mut item)?;
+
+    if item.id.is_empty() {
+        item.id = SortableId::generate().into();
+    } else if app.saved_searches_repo.get(&item.id).await.is_none() {
+        return Err(format!("Saved search with id '{}' is not found", item.id));
+    }
+
+    Ok(store(app, item, actor).await)
+}
+
+pub fn normalize(item: &mut SavedSearchDto) -> Result<(), String> {
+    item.id = item.id.trim().to_string();
+    item.name = item.name.trim().to_string();
+    if item.name.is_empty() {
+        return Err("Saved search name can not be empty".to_string());
+    }
+
+    if item.last_minutes <= 0 || item.last_minutes > MAX_SAVED_SEARCH_MINUTES {
+        return Err(format!(
+            "last_minutes must be in range 1..{}",
+            MAX_SAVED_SEARCH_MINUTES
+        ));
+    }
+
+    item.phrase = item
+        .phrase
+        .take()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+
+    item.levels.sort();
+    item.levels.dedup();
+
+    Ok(())
+}
+
+/// Adds or replaces the normalized saved search keeping its id.
+pub async fn store(
+    app: &AppContext,
+    item: SavedSearchDto,
+    actor: &AuditActorDto,
+) -> SavedSearchDto {
+    let before = app.saved_searches_repo.upsert(item.clone()).await;
+
+    crate::flows::write_audit_log(
+        app,
+        actor,
+        AuditTargetDto::SavedSearch,
+        &item.id,
+        before.as_ref(),
+        Some(&item),
+    )
+    .await;
+
+    item
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::repo::dto::{LogLevelDto, SavedSearchDto};
+
+    use super::{normalize, MAX_SAVED_SEARCH_MINUTES};
+
+    fn saved_search(name: &str, last_minutes: i64) -> SavedSearchDto {
+        SavedSearchDto {
+            id: " 1 ".to_string(),
+            name: name.to_string(),
+            levels: vec![LogLevelDto::Error, LogLevelDto::Warning, LogLevelDto::Error],
+            context: BTreeMap::new(),
+            phrase: Some("  ".to_string()),
+            last_minutes,
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut item = saved_search(" Payments ", 60);
+        normalize(&mut item).unwrap();
+
+        assert_eq!(item.id, "1");
+        assert_eq!(item.name, "Payments");
+        assert_eq!(item.levels, vec![LogLevelDto::Warning, LogLevelDto::Error]);
+        assert_eq!(item.phrase, None);
+
+        assert!(normalize(&mut saved_search(" ", 60)).is_err());
+        assert!(normalize(&mut saved_search("Payments", 0)).is_err());
+        assert!(normalize(&mut saved_search("Payments", MAX_SAVED_SEARCH_MINUTES + 1)).is_err());
+    }
+}
@@ -1,13 +1,16 @@
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 
-use crate::{app::AppContext, repo::dto::LogItemDto};
+use crate::{
+    app::AppContext,
+    repo::dto::{LogItemDto, PhraseFilter, SearchPage},
+};
 
 pub async fn search_and_scan(
     app: &AppContext,
     from_date: DateTimeAsMicroseconds,
     to_date: DateTimeAsMicroseconds,
-    phrase: &str,
-    limit: usize,
+    phrase: &PhraseFilter,
+    page: SearchPage,
 ) -> Vec<LogItemDto> {
-    super::search_logs(app, from_date, to_date, None, None, Some(phrase), limit).await
+    super::search_logs(app, from_date, to_date, None, None, Some(phrase), page).await
 }
@@ -4,7 +4,10 @@ use rust_extensions::date_time::DateTimeAsMicroseconds;
 
 use crate::{
     app::AppContext,
-    repo::dto::{LogItemDto, LogLevelDto},
+    repo::{
+        dto::{LogItemDto, LogLevelDto, PhraseFilter, SearchPage},
+        merge_sorted,
+    },
 };
 
 pub async fn search_logs(
@@ -13,12 +16,14 @@ pub async fn search_logs(
     to_date: DateTimeAsMicroseconds,
     levels: Option<Vec<LogLevelDto>>,
     context: Option<BTreeMap<String, String>>,
-    phrase: Option<&str>,
-    limit: usize,
+    phrase: Option<&PhraseFilter>,
+    page: SearchPage,
 ) -> Vec<LogItemDto> {
     let want_tantivy = has_tantivy_level(&levels);
     let want_sqlite = has_sqlite_level(&levels);
 
+    let limit = page.limit();
+
     let tantivy_fut = async {
         if want_tantivy {
             app.logs_repo
@@ -28,6 +33,7 @@ pub async fn search_logs(
                     levels.clone(),
                     context.clone(),
                     phrase,
+                    page.order,
                     limit,
                 )
                 .await
@@ -39,20 +45,28 @@ pub async fn search_logs(
     let sqlite_fut = async {
         if want_sqlite {
             app.sqlite_logs_repo
-                .search(from_date, to_date, levels.clone(), context.clone(), phrase, limit)
+                .search(
+                    from_date,
+                    to_date,
+                    levels.clone(),
+                    context.clone(),
+                    phrase,
+                    page.order,
+                    limit,
+                )
                 .await
         } else {
             Vec::new()
         }
     };
 
-    let (mut tantivy_results, sqlite_results) = tokio::join!(tantivy_fut, sqlite_fut);
+    let (tantivy_results, sqlite_results) = tokio::join!(tantivy_fut, sqlite_fut);
 
-    tantivy_results.extend(sqlite_results);
-    tantivy_results
-        .sort_by(|a, b| b.moment.unix_microseconds.cmp(&a.moment.unix_microseconds));
-    tantivy_results.truncate(limit);
-    tantivy_results
+    let mut result = merge_sorted(vec![tantivy_results, sqlite_results], page.order, limit);
+    if page.skip > 0 {
+        result.drain(..page.skip.min(result.len()));
+    }
+    result
 }
 
 fn has_tantivy_level(levels: &Option<Vec<LogLevelDto>>) -> bool {
@@ -0,0 +1,31 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::AppContext,
+    notifications::{Notification, NotificationContext, NotificationsDispatcher},
+};
+
+/// Channels are built from the current settings on every call, so settings changes apply without restart.
+pub async fn send_notification(app: &AppContext, notification: Notification) {
+    let notification = {
+        let silences = app.notification_silences.lock().await;
+        silences.filter(notification, DateTimeAsMicroseconds::now())
+    };
+
+    let Some(notification) = notification else {
+        return;
+    };
+
+    let dispatcher = NotificationsDispatcher::new(
+        app.settings_reader.get_notification_channels().await,
+        app.settings_reader.get_telegram_settings().await,
+        app.settings_reader.get_notification_routes().await,
+    );
+
+    let ctx = NotificationContext {
+        env_name: app.env_name.clone(),
+        ui_url: app.get_ui_url().await,
+    };
+
+    dispatcher.dispatch(notification, &ctx);
+}
@@ -0,0 +1,279 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::{AppContext, APPLICATION_KEY},
+    notifications::NotificationSilence,
+    repo::dto::{
+        AuditActorDto, AuditActorKindDto, IgnoreItemDto, LogLevelDto, MatchModeDto, SearchPage,
+    },
+    telegram::{
+        api::{append_html_escaped, MAX_TELEGRAM_MESSAGE_LEN},
+        BotCommand, BOT_HELP,
+    },
+};
+
+/// `/ack` silences last at most this long if the application does not get quiet.
+const ACK_MAX_SEC: i64 = 24 * 3600;
+const LAST_EVENTS_HOURS: i64 = 24;
+const MAX_REPLY_MESSAGE_LEN: usize = 300;
+const STATS_TOP_APPLICATIONS: usize = 5;
+
+/// Executes the command and returns the reply as Telegram HTML.
+pub async fn execute_bot_command(app: &AppContext, command: BotCommand, user: &str) -> String {
+    let now = DateTimeAsMicroseconds::now();
+
+    match command {
+        BotCommand::Mute {
+            application,
+            duration_sec,
+            drop_events,
+        } => mute(app, application, duration_sec, drop_events, user, now).await,
+        BotCommand::Unmute { application } => {
+            let removed = app.notification_silences.lock().await.remove(&application);
+            let mut result = String::new();
+            append_html_escaped(&mut result, &application);
+            if removed {
+                result.push_str(" is unmuted");
+            } else {
+                result.push_str(" is not muted");
+            }
+            result
+        }
+        BotCommand::Ack => ack(app, user, now).await,
+        BotCommand::Stats => stats(app, now).await,
+        BotCommand::Last {
+            levels,
+            application,
+            take,
+        } => last(app, levels, application, take, now).await,
+        BotCommand::Help => BOT_HELP.to_string(),
+    }
+}
+
+async fn mute(
+    app: &AppContext,
+    application: String,
+    duration_sec: i64,
+    drop_events: bool,
+    user: &str,
+    now: DateTimeAsMicroseconds,
+) -> String {
+    let until = DateTimeAsMicroseconds::new(now.unix_microseconds + duration_sec * 1_000_000);
+
+    let mut result = String::new();
+
+    if drop_events {
+        if application.contains(['*', '?']) {
+            return "drop needs an exact application name".to_string();
+        }
+
+        let mut rule = IgnoreItemDto::from_marker(
+            LogLevelDto::Error,
+            &application,
+            "*",
+            MatchModeDto::Contains,
+            false,
+            Some(until.unix_microseconds),
+        );
+        rule.id = format!("mute:{}", application);
+        rule.levels.push(LogLevelDto::FatalError);
+
+        let actor = AuditActorDto::new(AuditActorKindDto::Telegram, Some(user.to_string()));
+        if let Err(err) = super::add_ignore_event(app, rule, &actor).await {
+            append_html_escaped(&mut result, &err);
+            return result;
+        }
+
+        result.push_str("Errors of ");
+        append_html_escaped(&mut result, &application);
+        let _ = write!(result, " are dropped until {}", until.to_rfc3339());
+        return result;
+    }
+
+    app.notification_silences
+        .lock()
+        .await
+        .add(NotificationSilence {
+            application: application.clone(),
+            until: until.unix_microseconds,
+            until_quiet: false,
+            created_by: user.to_string(),
+        });
+
+    append_html_escaped(&mut result, &application);
+    let _ = write!(result, " is muted until {}", until.to_rfc3339());
+    result
+}
+
+async fn ack(app: &AppContext, user: &str, now: DateTimeAsMicroseconds) -> String {
+    let mut applications: Vec<String> = app
+        .notification_silences
+        .lock()
+        .await
+        .get_last_summary_applications()
+        .to_vec();
+
+    let firing = app.alerts_engine.lock().await.get_firing();
+    for alert in firing.iter() {
+        if let Some(application) = alert.application.as_ref() {
+            if !applications.contains(application) {
+                applications.push(application.to_string());
+            }
+        }
+    }
+
+    if applications.is_empty() {
+        return "Nothing to acknowledge".to_string();
+    }
+
+    let until = DateTimeAsMicroseconds::new(now.unix_microseconds + ACK_MAX_SEC * 1_000_000);
+
+    let mut silences = app.notification_silences.lock().await;
+    for application in applications.iter() {
+        silences.add(NotificationSilence {
+            application: application.to_string(),
+            until: until.unix_microseconds,
+            until_quiet: true,
+            created_by: user.to_string(),
+        });
+    }
+
+    let mut result = String::from("Acknowledged until quiet: ");
+    append_html_escaped(&mut result, &applications.join(", "));
+    result
+}
+
+async fn stats(app: &AppContext, now: DateTimeAsMicroseconds) -> String {
+    let hours = app.rollup_statistics.lock().await.get_max_hours(1);
+
+    let mut result = String::new();
+
+    match hours.first() {
+        Some((hour, by_app)) => {
+            let mut totals = [0u64; 4];
+            let mut top: Vec<_> = by_app.iter().collect();
+            for (_, item) in top.iter() {
+                totals[0] += item.fatal_error as u64;
+                totals[1] += item.error as u64;
+                totals[2] += item.warning as u64;
+                totals[3] += item.info as u64;
+            }
+
+            let _ = write!(
+                result,
+                "📊<b>Hour {}</b>: ☠️{} 🟥{} ⚠️{} ☑{}",
+                hour.get_value(),
+                totals[0],
+                totals[1],
+                totals[2],
+                totals[3]
+            );
+
+            top.sort_by(|a, b| (b.1.fatal_error + b.1.error).cmp(&(a.1.fatal_error + a.1.error)));
+
+            for (application, item) in top.into_iter().take(STATS_TOP_APPLICATIONS) {
+                if item.fatal_error + item.error == 0 {
+                    break;
+                }
+                result.push_str("\n • ");
+                append_html_escaped(&mut result, application);
+                let _ = write!(result, " ☠️{} 🟥{}", item.fatal_error, item.error);
+            }
+        }
+        None => result.push_str("No events this hour"),
+    }
+
+    let firing = app.alerts_engine.lock().await.get_firing();
+    let _ = write!(result, "\n🚨<b>Firing alerts</b>: {}", firing.len());
+    for alert in firing.iter() {
+        result.push_str("\n • ");
+        append_html_escaped(&mut result, &alert.rule_name);
+        if !alert.group.is_empty() {
+            result.push_str(" / ");
+            append_html_escaped(&mut result, &alert.group);
+        }
+    }
+
+    let silences = app.notification_silences.lock().await.get_active(now);
+    let _ = write!(result, "\n🔕<b>Muted</b>: {}", silences.len());
+    for silence in silences.iter() {
+        result.push_str("\n • ");
+        append_html_escaped(&mut result, &silence.application);
+        if silence.until_quiet {
+            result.push_str(" until quiet");
+        } else {
+            let until = DateTimeAsMicroseconds::new(silence.until);
+            let _ = write!(result, " until {}", until.to_rfc3339());
+        }
+        result.push_str(" by ");
+        append_html_escaped(&mut result, &silence.created_by);
+    }
+
+    result
+}
+
+async fn last(
+    app: &AppContext,
+    levels: Vec<LogLevelDto>,
+    application: Option<String>,
+    take: usize,
+    now: DateTimeAsMicroseconds,
+) -> String {
+    let mut from = now;
+    from.add_hours(-LAST_EVENTS_HOURS);
+
+    let context = application.map(|application| {
+        let mut context = BTreeMap::new();
+        context.insert(APPLICATION_KEY.to_string(), application);
+        context
+    });
+
+    let items = super::search_logs(
+        app,
+        from,
+        now,
+        Some(levels),
+        context,
+        None,
+        SearchPage::newest(take),
+    )
+    .await;
+
+    if items.is_empty() {
+        return format!("No events in the last {} hours", LAST_EVENTS_HOURS);
+    }
+
+    let mut result = String::new();
+    for item in items.iter() {
+        // Room for one more event is left, HTML escaping may grow it.
+        if result.chars().count() + 2 * MAX_REPLY_MESSAGE_LEN > MAX_TELEGRAM_MESSAGE_LEN {
+            result.push_str("\n…");
+            break;
+        }
+
+        if !result.is_empty() {
+            result.push('\n');
+        }
+
+        let _ = write!(
+            result,
+            "<b>{:?}</b> {} ",
+            item.level,
+            item.moment.to_rfc3339()
+        );
+        if let Some(application) = item.context.get(APPLICATION_KEY) {
+            append_html_escaped(&mut result, application);
+        }
+        result.push('\n');
+
+        let message = match item.message.char_indices().nth(MAX_REPLY_MESSAGE_LEN) {
+            Some((index, _)) => &item.message[..index],
+            None => item.message.as_str(),
+        };
+        append_html_escaped(&mut result, message);
+    }
+
+    result
+}
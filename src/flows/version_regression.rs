@@ -0,0 +1,85 @@
+use crate::{
+    app::AppContext,
+    issues::{IssueItem, IssueStatus},
+    version_statistics::{RegressionVerdict, VersionSummary},
+};
+
+pub struct VersionRegressionReport {
+    pub current: VersionSummary,
+    pub previous: Option<VersionSummary>,
+    /// Issues with events of the current version and none of the previous one. Ignored issues are skipped.
+    pub new_signatures: Vec<IssueItem>,
+    pub verdict: RegressionVerdict,
+}
+
+/// Every application with a `Version` if `application` is not set. `version` is the latest one if not set.
+pub async fn get_version_regression_report(
+    app: &AppContext,
+    application: Option<&str>,
+    version: Option<&str>,
+) -> Result<Vec<VersionRegressionReport>, String> {
+    let application = application.map(|a| a.trim()).filter(|a| !a.is_empty());
+    let version = version.map(|v| v.trim()).filter(|v| !v.is_empty());
+
+    if version.is_some() && application.is_none() {
+        return Err("application is required when version is set".to_string());
+    }
+
+    let pairs = {
+        let version_statistics = app.version_statistics.lock().await;
+
+        let applications = match application {
+            Some(application) => vec![application.to_string()],
+            None => version_statistics.get_applications(),
+        };
+
+        let mut result = Vec::with_capacity(applications.len());
+        for application in applications {
+            match version_statistics.get_with_previous(&application, version) {
+                Some(pair) => result.push(pair),
+                None if version.is_some() => {
+                    return Err(format!(
+                        "Version '{}' of '{}' was not seen",
+                        version.unwrap_or_default(),
+                        application
+                    ));
+                }
+                None => {}
+            }
+        }
+
+        result
+    };
+
+    let issues = app.issues.lock().await;
+
+    let result = pairs
+        .into_iter()
+        .map(|(current, previous)| {
+            let new_signatures: Vec<IssueItem> = match previous.as_ref() {
+                Some(previous) => issues
+                    .get_filtered(None, Some(current.application.as_str()), usize::MAX)
+                    .into_iter()
+                    .filter(|issue| {
+                        issue.status != IssueStatus::Ignored
+                            && issue.versions.contains_key(&current.version)
+                            && !issue.versions.contains_key(&previous.version)
+                    })
+                    .collect(),
+                None => vec![],
+            };
+
+            let verdict =
+                RegressionVerdict::evaluate(&current, previous.as_ref(), new_signatures.len());
+
+            VersionRegressionReport {
+                current,
+                previous,
+                new_signatures,
+                verdict,
+            }
+        })
+        .collect();
+
+    Ok(result)
+}
@@ -4,8 +4,23 @@ use rust_extensions::SortableId;
 
 use crate::{
     app::PROCESS_CONTEXT_KEY,
+    flows::{
+        config_transfer::{
+            ConfigChange, ConfigDocumentFormat, ConfigImportMode, ConfigImportResult,
+        },
+        IgnoreEventPreview, VersionRegressionReport,
+    },
+    issues::{IssueItem, IssueStatus},
     my_logger_grpc::*,
-    repo::dto::{IgnoreItemDto, LogItemDto},
+    repo::dto::{
+        AlertRuleDto, AuditActionDto, AuditActorKindDto, AuditLogEntryDto, AuditTargetDto,
+        ConditionOperatorDto, IgnoreConditionDto, IgnoreConditionFieldDto,
+        IgnoreItemDto, IgnoreRuleStatisticsSummary, LogItemDto, LogLevelDto, MatchModeDto,
+        SavedSearchDto, SortOrderDto,
+    },
+    rollup_statistics::RollupResolution,
+    statistic_dimensions::DimensionOrder,
+    version_statistics::{RegressionVerdict, VersionSummary},
 };
 
 impl Into<crate::app::LogItem> for LogEventGrpcModel {
@@ -84,25 +99,553 @@ pub fn to_log_event_grpc_model(mut src: LogItemDto) -> LogEventGrpcModel {
     }
 }
 
+impl Into<MatchModeDto> for MatchModeGrpcModel {
+    fn into(self) -> MatchModeDto {
+        match self {
+            MatchModeGrpcModel::Contains => MatchModeDto::Contains,
+            MatchModeGrpcModel::Regex => MatchModeDto::Regex,
+        }
+    }
+}
+
+impl Into<MatchModeGrpcModel> for MatchModeDto {
+    fn into(self) -> MatchModeGrpcModel {
+        match self {
+            MatchModeDto::Contains => MatchModeGrpcModel::Contains,
+            MatchModeDto::Regex => MatchModeGrpcModel::Regex,
+        }
+    }
+}
+
+impl Into<SortOrderDto> for SortOrderGrpcModel {
+    fn into(self) -> SortOrderDto {
+        match self {
+            SortOrderGrpcModel::Desc => SortOrderDto::Desc,
+            SortOrderGrpcModel::Asc => SortOrderDto::Asc,
+        }
+    }
+}
+
+impl Into<IgnoreConditionDto> for IgnoreConditionGrpcModel {
+    fn into(self) -> IgnoreConditionDto {
+        let field = match self.field() {
+            IgnoreConditionFieldGrpcModel::ProcessOrMessage => {
+                IgnoreConditionFieldDto::ProcessOrMessage
+            }
+            IgnoreConditionFieldGrpcModel::Message => IgnoreConditionFieldDto::Message,
+            IgnoreConditionFieldGrpcModel::Process => IgnoreConditionFieldDto::Process,
+            IgnoreConditionFieldGrpcModel::Context => {
+                IgnoreConditionFieldDto::Context(self.context_key.clone().unwrap_or_default())
+            }
+        };
+
+        let operator = match self.operator() {
+            ConditionOperatorGrpcModel::ConditionContains => ConditionOperatorDto::Contains,
+            ConditionOperatorGrpcModel::ConditionEquals => ConditionOperatorDto::Equals,
+            ConditionOperatorGrpcModel::ConditionPrefix => ConditionOperatorDto::Prefix,
+            ConditionOperatorGrpcModel::ConditionRegex => ConditionOperatorDto::Regex,
+        };
+
+        IgnoreConditionDto {
+            field,
+            operator,
+            value: self.value,
+            case_insensitive: self.case_insensitive,
+        }
+    }
+}
+
+impl Into<IgnoreConditionGrpcModel> for IgnoreConditionDto {
+    fn into(self) -> IgnoreConditionGrpcModel {
+        let (field, context_key) = match self.field {
+            IgnoreConditionFieldDto::ProcessOrMessage => {
+                (IgnoreConditionFieldGrpcModel::ProcessOrMessage, None)
+            }
+            IgnoreConditionFieldDto::Message => (IgnoreConditionFieldGrpcModel::Message, None),
+            IgnoreConditionFieldDto::Process => (IgnoreConditionFieldGrpcModel::Process, None),
+            IgnoreConditionFieldDto::Context(key) => {
+                (IgnoreConditionFieldGrpcModel::Context, Some(key))
+            }
+        };
+
+        let operator = match self.operator {
+            ConditionOperatorDto::Contains => ConditionOperatorGrpcModel::ConditionContains,
+            ConditionOperatorDto::Equals => ConditionOperatorGrpcModel::ConditionEquals,
+            ConditionOperatorDto::Prefix => ConditionOperatorGrpcModel::ConditionPrefix,
+            ConditionOperatorDto::Regex => ConditionOperatorGrpcModel::ConditionRegex,
+        };
+
+        IgnoreConditionGrpcModel {
+            field: field as i32,
+            context_key,
+            operator: operator as i32,
+            value: self.value,
+            case_insensitive: self.case_insensitive,
+        }
+    }
+}
+
 impl Into<IgnoreItemDto> for IgnoreEventGrpcModel {
     fn into(self) -> IgnoreItemDto {
+        // Clients not aware of conditions send the level/application/marker shape only
+        if self.levels.is_empty() && self.conditions.is_empty() {
+            let mut result = IgnoreItemDto::from_marker(
+                self.level().into(),
+                &self.application,
+                &self.marker,
+                self.match_mode().into(),
+                self.case_insensitive,
+                self.expiration,
+            );
+
+            if !self.id.is_empty() {
+                result.id = self.id;
+            }
+
+            return result;
+        }
+
         IgnoreItemDto {
-            level: self.level().into(),
-            application: self.application,
-            marker: self.marker,
+            levels: self.levels().map(|level| level.into()).collect(),
+            id: self.id,
+            conditions: self.conditions.into_iter().map(|itm| itm.into()).collect(),
             expires_at: self.expiration,
+            statistics: Default::default(),
         }
     }
 }
 
 impl Into<IgnoreEventGrpcModel> for IgnoreItemDto {
     fn into(self) -> IgnoreEventGrpcModel {
-        let log_level_grpc: LogLevelGrpcModel = self.level.into();
+        // Level/application/marker are filled as good as the rule allows for clients not aware of conditions
+        let level: LogLevelGrpcModel = self
+            .levels
+            .first()
+            .cloned()
+            .unwrap_or(LogLevelDto::Info)
+            .into();
+
+        let marker_condition = self
+            .conditions
+            .iter()
+            .find(|itm| itm.field == IgnoreConditionFieldDto::ProcessOrMessage);
+
+        let match_mode: MatchModeGrpcModel = match marker_condition {
+            Some(condition) if condition.operator == ConditionOperatorDto::Regex => {
+                MatchModeDto::Regex
+            }
+            _ => MatchModeDto::Contains,
+        }
+        .into();
+
+        let application = self.get_application().unwrap_or("*").to_string();
+        let marker = marker_condition
+            .map(|itm| itm.value.clone())
+            .unwrap_or_else(|| "*".to_string());
+        let case_insensitive = marker_condition
+            .map(|itm| itm.case_insensitive)
+            .unwrap_or_default();
+
+        let is_marker_rule = self.levels.len() == 1
+            && IgnoreItemDto::from_marker(
+                self.levels[0].clone(),
+                &application,
+                &marker,
+                match_mode.into(),
+                case_insensitive,
+                None,
+            )
+            .conditions
+                == self.conditions;
+
         IgnoreEventGrpcModel {
-            application: self.application,
-            marker: self.marker,
-            level: log_level_grpc as i32,
+            level: level as i32,
+            application,
+            marker,
             expiration: self.expires_at,
+            match_mode: match_mode as i32,
+            case_insensitive,
+            is_marker_rule,
+            statistics: None,
+            levels: self
+                .levels
+                .into_iter()
+                .map(|level| {
+                    let level: LogLevelGrpcModel = level.into();
+                    level as i32
+                })
+                .collect(),
+            conditions: self.conditions.into_iter().map(|itm| itm.into()).collect(),
+            id: self.id,
+        }
+    }
+}
+
+impl Into<PreviewIgnoreEventGrpcResponse> for IgnoreEventPreview {
+    fn into(self) -> PreviewIgnoreEventGrpcResponse {
+        PreviewIgnoreEventGrpcResponse {
+            hours: self.hours,
+            scanned: self.scanned as u64,
+            matched: self.matched as u64,
+            truncated: self.truncated,
+            groups: self
+                .groups
+                .into_iter()
+                .map(|group| {
+                    let level: LogLevelGrpcModel = group.level.into();
+                    IgnoreEventPreviewGroupGrpcModel {
+                        level: level as i32,
+                        application: group.application,
+                        count: group.count as u64,
+                    }
+                })
+                .collect(),
+            samples: self
+                .samples
+                .into_iter()
+                .map(to_log_event_grpc_model)
+                .collect(),
+        }
+    }
+}
+
+impl Into<IgnoreRuleStatisticsGrpcModel> for IgnoreRuleStatisticsSummary {
+    fn into(self) -> IgnoreRuleStatisticsGrpcModel {
+        IgnoreRuleStatisticsGrpcModel {
+            total: self.total,
+            last_hour: self.last_hour,
+            last_day: self.last_day,
+            last_matched: self.last_matched,
+            unused: self.unused,
+        }
+    }
+}
+
+impl Into<SavedSearchDto> for SavedSearchGrpcModel {
+    fn into(self) -> SavedSearchDto {
+        SavedSearchDto {
+            levels: self.levels().map(|level| level.into()).collect(),
+            id: self.id,
+            name: self.name,
+            context: self
+                .context_keys
+                .into_iter()
+                .map(|itm| (itm.key, itm.value))
+                .collect(),
+            phrase: self.phrase,
+            last_minutes: self.last_minutes,
+        }
+    }
+}
+
+pub fn to_saved_search_grpc_model(
+    src: SavedSearchDto,
+    url: Option<String>,
+) -> SavedSearchGrpcModel {
+    SavedSearchGrpcModel {
+        id: src.id,
+        name: src.name,
+        levels: src
+            .levels
+            .into_iter()
+            .map(|level| {
+                let level: LogLevelGrpcModel = level.into();
+                level as i32
+            })
+            .collect(),
+        context_keys: src
+            .context
+            .into_iter()
+            .map(|(key, value)| LogEventContext { key, value })
+            .collect(),
+        phrase: src.phrase,
+        last_minutes: src.last_minutes,
+        url,
+    }
+}
+
+impl Into<AlertRuleDto> for AlertRuleGrpcModel {
+    fn into(self) -> AlertRuleDto {
+        AlertRuleDto {
+            min_level: self.min_level().into(),
+            match_mode: self.match_mode().into(),
+            id: self.id,
+            name: self.name,
+            disabled: self.disabled,
+            application: self.application,
+            context: self
+                .context_keys
+                .into_iter()
+                .map(|itm| (itm.key, itm.value))
+                .collect(),
+            phrase: self.phrase,
+            case_insensitive: self.case_insensitive,
+            threshold: self.threshold,
+            window_sec: self.window_sec,
+            group_by: self.group_by,
+            cooldown_sec: self.cooldown_sec,
+            resolve_after_sec: self.resolve_after_sec,
+            channels: self.channels,
+            saved_search_id: self.saved_search_id,
+        }
+    }
+}
+
+impl Into<AlertRuleGrpcModel> for AlertRuleDto {
+    fn into(self) -> AlertRuleGrpcModel {
+        let min_level: LogLevelGrpcModel = self.min_level.into();
+        let match_mode: MatchModeGrpcModel = self.match_mode.into();
+        AlertRuleGrpcModel {
+            id: self.id,
+            name: self.name,
+            disabled: self.disabled,
+            application: self.application,
+            min_level: min_level as i32,
+            context_keys: self
+                .context
+                .into_iter()
+                .map(|(key, value)| LogEventContext { key, value })
+                .collect(),
+            phrase: self.phrase,
+            match_mode: match_mode as i32,
+            case_insensitive: self.case_insensitive,
+            threshold: self.threshold,
+            window_sec: self.window_sec,
+            group_by: self.group_by,
+            cooldown_sec: self.cooldown_sec,
+            resolve_after_sec: self.resolve_after_sec,
+            channels: self.channels,
+            saved_search_id: self.saved_search_id,
+        }
+    }
+}
+
+impl Into<IssueStatus> for IssueStatusGrpcModel {
+    fn into(self) -> IssueStatus {
+        match self {
+            IssueStatusGrpcModel::Open => IssueStatus::Open,
+            IssueStatusGrpcModel::Resolved => IssueStatus::Resolved,
+            IssueStatusGrpcModel::Ignored => IssueStatus::Ignored,
+        }
+    }
+}
+
+impl Into<IssueStatusGrpcModel> for IssueStatus {
+    fn into(self) -> IssueStatusGrpcModel {
+        match self {
+            IssueStatus::Open => IssueStatusGrpcModel::Open,
+            IssueStatus::Resolved => IssueStatusGrpcModel::Resolved,
+            IssueStatus::Ignored => IssueStatusGrpcModel::Ignored,
+        }
+    }
+}
+
+impl Into<IssueGrpcModel> for IssueItem {
+    fn into(self) -> IssueGrpcModel {
+        let level: LogLevelGrpcModel = self.level.into();
+        let status: IssueStatusGrpcModel = self.status.into();
+        IssueGrpcModel {
+            id: self.id,
+            application: self.application,
+            template: self.template,
+            level: level as i32,
+            status: status as i32,
+            first_seen: self.first_seen,
+            last_seen: self.last_seen,
+            count: self.count,
+            hourly: self
+                .hourly
+                .into_iter()
+                .map(|(hour_key, count)| IssueHourGrpcModel { hour_key, count })
+                .collect(),
+            versions: self
+                .versions
+                .into_iter()
+                .map(|(version, count)| IssueVersionGrpcModel { version, count })
+                .collect(),
+            sample_event_ids: self.sample_event_ids,
+        }
+    }
+}
+
+impl Into<AuditActorKindDto> for AuditActorKindGrpcModel {
+    fn into(self) -> AuditActorKindDto {
+        match self {
+            AuditActorKindGrpcModel::ActorHttp => AuditActorKindDto::Http,
+            AuditActorKindGrpcModel::ActorGrpc => AuditActorKindDto::Grpc,
+            AuditActorKindGrpcModel::ActorMcp => AuditActorKindDto::Mcp,
+            AuditActorKindGrpcModel::ActorTelegram => AuditActorKindDto::Telegram,
+        }
+    }
+}
+
+impl Into<AuditActorKindGrpcModel> for AuditActorKindDto {
+    fn into(self) -> AuditActorKindGrpcModel {
+        match self {
+            AuditActorKindDto::Http => AuditActorKindGrpcModel::ActorHttp,
+            AuditActorKindDto::Grpc => AuditActorKindGrpcModel::ActorGrpc,
+            AuditActorKindDto::Mcp => AuditActorKindGrpcModel::ActorMcp,
+            AuditActorKindDto::Telegram => AuditActorKindGrpcModel::ActorTelegram,
+        }
+    }
+}
+
+impl Into<AuditTargetDto> for AuditTargetGrpcModel {
+    fn into(self) -> AuditTargetDto {
+        match self {
+            AuditTargetGrpcModel::IgnoreRule => AuditTargetDto::IgnoreRule,
+            AuditTargetGrpcModel::SingleEventRule => AuditTargetDto::SingleEventRule,
+            AuditTargetGrpcModel::SavedSearch => AuditTargetDto::SavedSearch,
+            AuditTargetGrpcModel::AlertRule => AuditTargetDto::AlertRule,
+        }
+    }
+}
+
+impl Into<AuditTargetGrpcModel> for AuditTargetDto {
+    fn into(self) -> AuditTargetGrpcModel {
+        match self {
+            AuditTargetDto::IgnoreRule => AuditTargetGrpcModel::IgnoreRule,
+            AuditTargetDto::SingleEventRule => AuditTargetGrpcModel::SingleEventRule,
+            AuditTargetDto::SavedSearch => AuditTargetGrpcModel::SavedSearch,
+            AuditTargetDto::AlertRule => AuditTargetGrpcModel::AlertRule,
+        }
+    }
+}
+
+impl Into<AuditActionGrpcModel> for AuditActionDto {
+    fn into(self) -> AuditActionGrpcModel {
+        match self {
+            AuditActionDto::Created => AuditActionGrpcModel::Created,
+            AuditActionDto::Updated => AuditActionGrpcModel::Updated,
+            AuditActionDto::Deleted => AuditActionGrpcModel::Deleted,
+        }
+    }
+}
+
+impl Into<AuditLogEntryGrpcModel> for AuditLogEntryDto {
+    fn into(self) -> AuditLogEntryGrpcModel {
+        let actor_kind: AuditActorKindGrpcModel = self.actor.kind.into();
+        let target: AuditTargetGrpcModel = self.target.into();
+        let action: AuditActionGrpcModel = self.action.into();
+        AuditLogEntryGrpcModel {
+            moment: self.moment,
+            actor_kind: actor_kind as i32,
+            actor_name: self.actor.name,
+            target: target as i32,
+            action: action as i32,
+            id: self.id,
+            before_json: self.before.map(|value| value.to_string()),
+            after_json: self.after.map(|value| value.to_string()),
+        }
+    }
+}
+
+impl Into<ConfigDocumentFormat> for ConfigFormatGrpcModel {
+    fn into(self) -> ConfigDocumentFormat {
+        match self {
+            ConfigFormatGrpcModel::ConfigJson => ConfigDocumentFormat::Json,
+            ConfigFormatGrpcModel::ConfigYaml => ConfigDocumentFormat::Yaml,
+        }
+    }
+}
+
+impl Into<ConfigImportMode> for ConfigImportModeGrpcModel {
+    fn into(self) -> ConfigImportMode {
+        match self {
+            ConfigImportModeGrpcModel::ImportMerge => ConfigImportMode::Merge,
+            ConfigImportModeGrpcModel::ImportReplace => ConfigImportMode::Replace,
+        }
+    }
+}
+
+impl Into<ConfigChangeGrpcModel> for ConfigChange {
+    fn into(self) -> ConfigChangeGrpcModel {
+        let target: AuditTargetGrpcModel = self.target.into();
+        let action: AuditActionGrpcModel = self.action.into();
+        ConfigChangeGrpcModel {
+            target: target as i32,
+            id: self.id,
+            action: action as i32,
+            before_json: self.before.map(|value| value.to_string()),
+            after_json: self.after.map(|value| value.to_string()),
+        }
+    }
+}
+
+impl Into<ImportConfigGrpcResponse> for ConfigImportResult {
+    fn into(self) -> ImportConfigGrpcResponse {
+        ImportConfigGrpcResponse {
+            changes: self.changes.into_iter().map(|change| change.into()).collect(),
+            unchanged: self.unchanged as u32,
+            applied: self.applied,
+        }
+    }
+}
+
+impl Into<RollupResolution> for RollupResolutionGrpcModel {
+    fn into(self) -> RollupResolution {
+        match self {
+            RollupResolutionGrpcModel::ResolutionMinute => RollupResolution::Minute,
+            RollupResolutionGrpcModel::ResolutionHour => RollupResolution::Hour,
+            RollupResolutionGrpcModel::ResolutionDay => RollupResolution::Day,
+        }
+    }
+}
+
+impl Into<RollupResolutionGrpcModel> for RollupResolution {
+    fn into(self) -> RollupResolutionGrpcModel {
+        match self {
+            RollupResolution::Minute => RollupResolutionGrpcModel::ResolutionMinute,
+            RollupResolution::Hour => RollupResolutionGrpcModel::ResolutionHour,
+            RollupResolution::Day => RollupResolutionGrpcModel::ResolutionDay,
+        }
+    }
+}
+
+impl Into<DimensionOrder> for DimensionOrderGrpcModel {
+    fn into(self) -> DimensionOrder {
+        match self {
+            DimensionOrderGrpcModel::OrderByTotal => DimensionOrder::Total,
+            DimensionOrderGrpcModel::OrderByErrors => DimensionOrder::Errors,
+        }
+    }
+}
+
+impl Into<VersionSummaryGrpcModel> for VersionSummary {
+    fn into(self) -> VersionSummaryGrpcModel {
+        VersionSummaryGrpcModel {
+            total: self.get_total(),
+            error_rate: self.get_error_rate(),
+            fatal_rate: self.get_fatal_rate(),
+            version: self.version,
+            first_seen: self.first_seen,
+            last_seen: self.last_seen,
+            error_count: self.counts.error,
+            fatal_count: self.counts.fatal_error,
+        }
+    }
+}
+
+impl Into<RegressionVerdictGrpcModel> for RegressionVerdict {
+    fn into(self) -> RegressionVerdictGrpcModel {
+        match self {
+            RegressionVerdict::NotEnoughData => RegressionVerdictGrpcModel::VerdictNotEnoughData,
+            RegressionVerdict::Same => RegressionVerdictGrpcModel::VerdictSame,
+            RegressionVerdict::Better => RegressionVerdictGrpcModel::VerdictBetter,
+            RegressionVerdict::Worse => RegressionVerdictGrpcModel::VerdictWorse,
+        }
+    }
+}
+
+impl Into<VersionRegressionGrpcModel> for VersionRegressionReport {
+    fn into(self) -> VersionRegressionGrpcModel {
+        let verdict: RegressionVerdictGrpcModel = self.verdict.into();
+        VersionRegressionGrpcModel {
+            application: self.current.application.clone(),
+            current: Some(self.current.into()),
+            previous: self.previous.map(|previous| previous.into()),
+            new_signatures: self.new_signatures.into_iter().map(|item| item.into()).collect(),
+            verdict: verdict as i32,
         }
     }
 }
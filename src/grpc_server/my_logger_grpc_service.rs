@@ -5,7 +5,10 @@ use super::server::GrpcService;
 use crate::app::APP_VERSION;
 use crate::my_logger_grpc::my_logger_server::MyLogger;
 use crate::my_logger_grpc::*;
-use crate::repo::dto::IgnoreWhereModel;
+use crate::repo::dto::{
+    get_marker_rule_id, AuditActorDto, AuditActorKindDto, AuditLogFilterDto, MatchModeDto,
+    PhraseFilter, SearchPage,
+};
 use crate::repo::DateHourKey;
 
 use my_grpc_extensions::server::generate_server_stream;
@@ -51,6 +54,12 @@ impl MyLogger for GrpcService {
         let range = RequestType::from_request(request.from_time, request.to_time);
         println!("ReadLogEventRequest in range: {:?}", range);
 
+        let page = SearchPage {
+            order: request.order().into(),
+            skip: request.skip.max(0) as usize,
+            take: request.take.max(0) as usize,
+        };
+
         let response = match range {
             RequestType::HourKey(date_key) => {
                 let log_levels = if levels.len() > 0 {
@@ -76,7 +85,7 @@ impl MyLogger for GrpcService {
                     log_levels,
                     context,
                     None,
-                    request.take as usize,
+                    page,
                 )
                 .await
             }
@@ -87,7 +96,7 @@ impl MyLogger for GrpcService {
                     request.context_keys,
                     from_date,
                     Some(to_date),
-                    request.take as usize,
+                    page,
                 )
                 .await
             }
@@ -157,6 +166,28 @@ impl MyLogger for GrpcService {
 
         println!("ScanAndSearchRequest in range: {:?}", range);
 
+        let phrase = match request.phrase_mode() {
+            SearchPhraseModeGrpcModel::FullText => PhraseFilter::full_text(&request.phrase),
+            SearchPhraseModeGrpcModel::ContainsText => PhraseFilter::matcher(
+                &request.phrase,
+                MatchModeDto::Contains,
+                request.case_insensitive,
+            )
+            .map_err(tonic::Status::invalid_argument)?,
+            SearchPhraseModeGrpcModel::RegexText => PhraseFilter::matcher(
+                &request.phrase,
+                MatchModeDto::Regex,
+                request.case_insensitive,
+            )
+            .map_err(tonic::Status::invalid_argument)?,
+        };
+
+        let page = SearchPage {
+            order: request.order().into(),
+            skip: request.skip.max(0) as usize,
+            take: request.take.max(0) as usize,
+        };
+
         let response = match range {
             RequestType::HourKey(date_hour_key) => {
                 crate::flows::search_logs(
@@ -165,21 +196,35 @@ impl MyLogger for GrpcService {
                     date_hour_key.hour_end(),
                     None,
                     None,
-                    Some(request.phrase.as_str()),
-                    request.take as usize,
-                )
-                .await
-            }
-            RequestType::DateRange(from_date, to_date) => {
-                crate::flows::search_and_scan(
-                    &self.app,
-                    from_date,
-                    to_date,
-                    &request.phrase,
-                    request.take as usize,
+                    phrase.as_ref(),
+                    page,
                 )
                 .await
             }
+            RequestType::DateRange(from_date, to_date) => match phrase.as_ref() {
+                Some(phrase) => {
+                    crate::flows::search_and_scan(
+                        &self.app,
+                        from_date,
+                        to_date,
+                        phrase,
+                        page,
+                    )
+                    .await
+                }
+                None => {
+                    crate::flows::search_logs(
+                        &self.app,
+                        from_date,
+                        to_date,
+                        None,
+                        None,
+                        None,
+                        page,
+                    )
+                    .await
+                }
+            },
         };
 
         my_grpc_extensions::grpc_server_streams::send_from_iterator_with_transformation(
@@ -193,9 +238,12 @@ impl MyLogger for GrpcService {
         &self,
         request: tonic::Request<IgnoreEventGrpcModel>,
     ) -> Result<tonic::Response<()>, tonic::Status> {
+        let actor = get_audit_actor(&request);
         let request = request.into_inner();
 
-        crate::flows::add_ignore_event(&self.app, request.into()).await;
+        crate::flows::add_ignore_event(&self.app, request.into(), &actor)
+            .await
+            .map_err(tonic::Status::invalid_argument)?;
         return Ok(tonic::Response::new(()));
     }
 
@@ -206,8 +254,14 @@ impl MyLogger for GrpcService {
         _request: tonic::Request<()>,
     ) -> Result<tonic::Response<Self::GetIgnoreEventsStream>, tonic::Status> {
         let response = self.app.settings_repo.get_ignore_events().await;
-        my_grpc_extensions::grpc_server_streams::send_from_iterator_with_transformation(response.into_iter(), |dto| {
-            dto.into()
+        let now = DateTimeAsMicroseconds::now();
+        let unused_days = self.app.settings_reader.get_ignore_rule_unused_days().await;
+
+        my_grpc_extensions::grpc_server_streams::send_from_iterator_with_transformation(response.into_iter(), move |dto| {
+            let statistics = dto.statistics.get_summary(now, unused_days);
+            let mut model: IgnoreEventGrpcModel = dto.into();
+            model.statistics = Some(statistics.into());
+            model
         })
         .await
     }
@@ -216,26 +270,60 @@ impl MyLogger for GrpcService {
         &self,
         request: tonic::Request<DeleteIgnoreEventGrpcRequest>,
     ) -> Result<tonic::Response<()>, tonic::Status> {
+        let actor = get_audit_actor(&request);
         let request = request.into_inner();
-        crate::flows::remove_ignore_event(
+
+        let id = match request.id {
+            Some(id) => id,
+            None => get_marker_rule_id(
+                &request.level().into(),
+                &request.application,
+                &request.marker,
+            ),
+        };
+
+        crate::flows::remove_ignore_event(&self.app, &id, &actor).await;
+        return Ok(tonic::Response::new(()));
+    }
+
+    async fn preview_ignore_event(
+        &self,
+        request: tonic::Request<PreviewIgnoreEventGrpcRequest>,
+    ) -> Result<tonic::Response<PreviewIgnoreEventGrpcResponse>, tonic::Status> {
+        let request = request.into_inner();
+
+        let Some(rule) = request.rule else {
+            return Err(tonic::Status::invalid_argument("rule is required"));
+        };
+
+        let samples_amount = if request.samples_amount > 0 {
+            request.samples_amount as usize
+        } else {
+            10
+        };
+
+        let preview = crate::flows::preview_ignore_event(
             &self.app,
-            IgnoreWhereModel {
-                level: request.level().into(),
-                application: request.application,
-                marker: request.marker,
-            },
+            rule.into(),
+            request.hours,
+            samples_amount,
         )
-        .await;
-        return Ok(tonic::Response::new(()));
+        .await
+        .map_err(tonic::Status::invalid_argument)?;
+
+        Ok(tonic::Response::new(preview.into()))
     }
 
     async fn set_ignore_single_event(
         &self,
         request: tonic::Request<IgnoreSingleEventGrpcModel>,
     ) -> Result<tonic::Response<()>, tonic::Status> {
+        let actor = get_audit_actor(&request);
         let request = request.into_inner();
 
-        crate::flows::ignore_single_event::add(&self.app, request).await;
+        crate::flows::ignore_single_event::add(&self.app, request, &actor)
+            .await
+            .map_err(tonic::Status::invalid_argument)?;
         return Ok(tonic::Response::new(()));
     }
 
@@ -245,9 +333,12 @@ impl MyLogger for GrpcService {
         _request: tonic::Request<()>,
     ) -> Result<tonic::Response<Self::GetIgnoreSingleEventsStream>, tonic::Status> {
         let result = crate::flows::ignore_single_event::get_all(&self.app).await;
+        let now = DateTimeAsMicroseconds::now();
+        let unused_days = self.app.settings_reader.get_ignore_rule_unused_days().await;
 
-        my_grpc_extensions::grpc_server_streams::send_from_iterator_with_transformation(result.into_iter(), |dto| {
-            dto.into()
+        my_grpc_extensions::grpc_server_streams::send_from_iterator_with_transformation(result.into_iter(), move |(mut model, statistics)| {
+            model.statistics = Some(statistics.get_summary(now, unused_days).into());
+            model
         })
         .await
     }
@@ -256,9 +347,10 @@ impl MyLogger for GrpcService {
         &self,
         request: tonic::Request<DeleteIgnoreSingleEventGrpcRequest>,
     ) -> Result<tonic::Response<()>, tonic::Status> {
+        let actor = get_audit_actor(&request);
         let request = request.into_inner();
 
-        crate::flows::ignore_single_event::delete(&self.app, request.id).await;
+        crate::flows::ignore_single_event::delete(&self.app, request.id, &actor).await;
         return Ok(tonic::Response::new(()));
     }
 
@@ -275,7 +367,7 @@ impl MyLogger for GrpcService {
         let app = self.app.clone();
         tokio::spawn(async move {
             let result = {
-                let read_access = app.hourly_statistics.lock().await;
+                let read_access = app.rollup_statistics.lock().await;
                 read_access.get_max_hours(request.amount_of_hours as usize)
             };
 
@@ -290,6 +382,7 @@ impl MyLogger for GrpcService {
                             error_count: statistics.error,
                             fatal_count: statistics.fatal_error,
                             debug_count: statistics.debug,
+                            sampled_count: statistics.sampled,
                         })
                         .await;
                 }
@@ -299,6 +392,98 @@ impl MyLogger for GrpcService {
         Ok(result)
     }
 
+    generate_server_stream!(stream_name:"GetRollupStatisticsStream", item_name:"RollupStatisticsGrpcModel");
+
+    async fn get_rollup_statistics(
+        &self,
+        request: tonic::Request<GetRollupStatisticsGrpcRequest>,
+    ) -> Result<tonic::Response<Self::GetRollupStatisticsStream>, tonic::Status> {
+        let request = request.into_inner();
+
+        let (resolution, buckets) = crate::flows::get_rollup_statistics(
+            &self.app,
+            request.from_time,
+            request.to_time,
+            request.resolution.map(|_| request.resolution().into()),
+            request.app.as_deref(),
+        )
+        .await
+        .map_err(tonic::Status::invalid_argument)?;
+
+        let resolution: RollupResolutionGrpcModel = resolution.into();
+
+        let items: Vec<RollupStatisticsGrpcModel> = buckets
+            .into_iter()
+            .flat_map(|bucket| {
+                let bucket_start = bucket.start * 1_000_000;
+                bucket
+                    .apps
+                    .into_iter()
+                    .map(move |(app, statistics)| RollupStatisticsGrpcModel {
+                        resolution: resolution as i32,
+                        bucket_start,
+                        app,
+                        info_count: statistics.info,
+                        warning_count: statistics.warning,
+                        error_count: statistics.error,
+                        fatal_count: statistics.fatal_error,
+                        debug_count: statistics.debug,
+                        sampled_count: statistics.sampled,
+                    })
+            })
+            .collect();
+
+        my_grpc_extensions::grpc_server_streams::send_from_iterator_with_transformation(
+            items.into_iter(),
+            |item| item,
+        )
+        .await
+    }
+
+    generate_server_stream!(stream_name:"GetDimensionStatisticsStream", item_name:"DimensionValueStatisticsGrpcModel");
+
+    async fn get_dimension_statistics(
+        &self,
+        request: tonic::Request<GetDimensionStatisticsGrpcRequest>,
+    ) -> Result<tonic::Response<Self::GetDimensionStatisticsStream>, tonic::Status> {
+        let request = request.into_inner();
+
+        let values = crate::flows::get_dimension_statistics(
+            &self.app,
+            request.key.as_str(),
+            request.from_time,
+            request.to_time,
+            request.order_by().into(),
+            request.take.map(|take| take as usize),
+        )
+        .await
+        .map_err(tonic::Status::invalid_argument)?;
+
+        let key = request.key.trim().to_string();
+
+        let items: Vec<DimensionValueStatisticsGrpcModel> = values
+            .into_iter()
+            .map(|item| DimensionValueStatisticsGrpcModel {
+                key: key.clone(),
+                value: item.value,
+                info_count: item.counts.info,
+                warning_count: item.counts.warning,
+                error_count: item.counts.error,
+                fatal_count: item.counts.fatal_error,
+                debug_count: item.counts.debug,
+                sampled_count: item.counts.sampled,
+                first_seen: item.seen.first_seen,
+                last_seen: item.seen.last_seen,
+            })
+            .collect();
+
+        my_grpc_extensions::grpc_server_streams::send_from_iterator_with_transformation(
+            items.into_iter(),
+            |item| item,
+        )
+        .await
+    }
+
     async fn get_insights_keys(
         &self,
         request: tonic::Request<()>,
@@ -329,6 +514,236 @@ impl MyLogger for GrpcService {
         Ok(tonic::Response::new(result))
     }
 
+    async fn set_saved_search(
+        &self,
+        request: tonic::Request<SavedSearchGrpcModel>,
+    ) -> Result<tonic::Response<SavedSearchGrpcModel>, tonic::Status> {
+        let actor = get_audit_actor(&request);
+        let request = request.into_inner();
+
+        let saved = crate::flows::saved_searches::save(&self.app, request.into(), &actor)
+            .await
+            .map_err(tonic::Status::invalid_argument)?;
+
+        let url = self.app.get_saved_search_url(&saved.id).await;
+
+        Ok(tonic::Response::new(super::mapper::to_saved_search_grpc_model(
+            saved, url,
+        )))
+    }
+
+    generate_server_stream!(stream_name:"GetSavedSearchesStream", item_name:"SavedSearchGrpcModel");
+    async fn get_saved_searches(
+        &self,
+        _request: tonic::Request<()>,
+    ) -> Result<tonic::Response<Self::GetSavedSearchesStream>, tonic::Status> {
+        let ui_url = self.app.get_ui_url().await;
+        let result = self.app.saved_searches_repo.get_all().await;
+
+        my_grpc_extensions::grpc_server_streams::send_from_iterator_with_transformation(
+            result.into_iter(),
+            move |dto| {
+                let url = crate::app::ui_links::compile_saved_search_url(&ui_url, &dto.id);
+                super::mapper::to_saved_search_grpc_model(dto, url)
+            },
+        )
+        .await
+    }
+
+    async fn delete_saved_search(
+        &self,
+        request: tonic::Request<DeleteSavedSearchGrpcRequest>,
+    ) -> Result<tonic::Response<()>, tonic::Status> {
+        let actor = get_audit_actor(&request);
+        let request = request.into_inner();
+
+        crate::flows::saved_searches::delete(&self.app, &request.id, &actor)
+            .await
+            .map_err(tonic::Status::failed_precondition)?;
+        return Ok(tonic::Response::new(()));
+    }
+
+    async fn set_alert_rule(
+        &self,
+        request: tonic::Request<AlertRuleGrpcModel>,
+    ) -> Result<tonic::Response<AlertRuleGrpcModel>, tonic::Status> {
+        let actor = get_audit_actor(&request);
+        let request = request.into_inner();
+
+        let saved = crate::flows::alert_rules::save(&self.app, request.into(), &actor)
+            .await
+            .map_err(tonic::Status::invalid_argument)?;
+
+        Ok(tonic::Response::new(saved.into()))
+    }
+
+    generate_server_stream!(stream_name:"GetAlertRulesStream", item_name:"AlertRuleGrpcModel");
+    async fn get_alert_rules(
+        &self,
+        _request: tonic::Request<()>,
+    ) -> Result<tonic::Response<Self::GetAlertRulesStream>, tonic::Status> {
+        let result = self.app.alert_rules_repo.get_all().await;
+
+        my_grpc_extensions::grpc_server_streams::send_from_iterator_with_transformation(
+            result.into_iter(),
+            |dto| dto.into(),
+        )
+        .await
+    }
+
+    async fn delete_alert_rule(
+        &self,
+        request: tonic::Request<DeleteAlertRuleGrpcRequest>,
+    ) -> Result<tonic::Response<()>, tonic::Status> {
+        let actor = get_audit_actor(&request);
+        let request = request.into_inner();
+
+        crate::flows::alert_rules::delete(&self.app, &request.id, &actor).await;
+        return Ok(tonic::Response::new(()));
+    }
+
+    generate_server_stream!(stream_name:"GetIssuesStream", item_name:"IssueGrpcModel");
+    async fn get_issues(
+        &self,
+        request: tonic::Request<GetIssuesGrpcRequest>,
+    ) -> Result<tonic::Response<Self::GetIssuesStream>, tonic::Status> {
+        let request = request.into_inner();
+
+        let status = if request.status.is_some() {
+            Some(request.status().into())
+        } else {
+            None
+        };
+
+        let take = if request.take > 0 {
+            request.take as usize
+        } else {
+            100
+        };
+
+        let result = self.app.issues.lock().await.get_filtered(
+            status,
+            request.application.as_deref(),
+            take,
+        );
+
+        my_grpc_extensions::grpc_server_streams::send_from_iterator_with_transformation(
+            result.into_iter(),
+            |dto| dto.into(),
+        )
+        .await
+    }
+
+    async fn set_issue_status(
+        &self,
+        request: tonic::Request<SetIssueStatusGrpcRequest>,
+    ) -> Result<tonic::Response<IssueGrpcModel>, tonic::Status> {
+        let actor = get_audit_actor(&request);
+        let request = request.into_inner();
+
+        let issue = crate::flows::issues::set_status(
+            &self.app,
+            &request.id,
+            request.status().into(),
+            &actor,
+        )
+        .await
+        .map_err(tonic::Status::invalid_argument)?;
+
+        Ok(tonic::Response::new(issue.into()))
+    }
+
+    generate_server_stream!(stream_name:"GetVersionRegressionStream", item_name:"VersionRegressionGrpcModel");
+
+    async fn get_version_regression(
+        &self,
+        request: tonic::Request<GetVersionRegressionGrpcRequest>,
+    ) -> Result<tonic::Response<Self::GetVersionRegressionStream>, tonic::Status> {
+        let request = request.into_inner();
+
+        let result = crate::flows::get_version_regression_report(
+            &self.app,
+            request.application.as_deref(),
+            request.version.as_deref(),
+        )
+        .await
+        .map_err(tonic::Status::invalid_argument)?;
+
+        my_grpc_extensions::grpc_server_streams::send_from_iterator_with_transformation(
+            result.into_iter(),
+            |report| report.into(),
+        )
+        .await
+    }
+
+    generate_server_stream!(stream_name:"GetAuditLogStream", item_name:"AuditLogEntryGrpcModel");
+
+    async fn get_audit_log(
+        &self,
+        request: tonic::Request<GetAuditLogGrpcRequest>,
+    ) -> Result<tonic::Response<Self::GetAuditLogStream>, tonic::Status> {
+        let request = request.into_inner();
+
+        let filter = AuditLogFilterDto {
+            from: request.from_time,
+            to: request.to_time,
+            target: request.target.map(|_| request.target().into()),
+            id: request.id.clone(),
+            actor_kind: request.actor_kind.map(|_| request.actor_kind().into()),
+        };
+
+        let take = if request.take > 0 {
+            request.take as usize
+        } else {
+            100
+        };
+
+        let result = self.app.audit_log_repo.query(&filter, take).await;
+
+        my_grpc_extensions::grpc_server_streams::send_from_iterator_with_transformation(
+            result.into_iter(),
+            |dto| dto.into(),
+        )
+        .await
+    }
+
+    async fn export_config(
+        &self,
+        request: tonic::Request<ExportConfigGrpcRequest>,
+    ) -> Result<tonic::Response<ExportConfigGrpcResponse>, tonic::Status> {
+        let request = request.into_inner();
+
+        let document = crate::flows::config_transfer::export_config(&self.app).await;
+        let document = document
+            .serialize(request.format().into())
+            .map_err(tonic::Status::internal)?;
+
+        Ok(tonic::Response::new(ExportConfigGrpcResponse { document }))
+    }
+
+    async fn import_config(
+        &self,
+        request: tonic::Request<ImportConfigGrpcRequest>,
+    ) -> Result<tonic::Response<ImportConfigGrpcResponse>, tonic::Status> {
+        let actor = get_audit_actor(&request);
+        let request = request.into_inner();
+
+        let document = crate::flows::config_transfer::ConfigDocument::parse(&request.document)
+            .map_err(tonic::Status::invalid_argument)?;
+
+        let result = crate::flows::config_transfer::import_config(
+            &self.app,
+            document,
+            request.mode().into(),
+            request.dry_run,
+            &actor,
+        )
+        .await
+        .map_err(tonic::Status::invalid_argument)?;
+
+        Ok(tonic::Response::new(result.into()))
+    }
+
     async fn get_server_info(
         &self,
         _request: tonic::Request<()>,
@@ -374,3 +789,14 @@ impl RequestType {
 pub fn is_valid_url_to_update(url: &str) -> bool {
     url.starts_with("https")
 }
+
+/// Clients may name themselves with the `x-actor` metadata.
+fn get_audit_actor<T>(request: &tonic::Request<T>) -> AuditActorDto {
+    let name = request
+        .metadata()
+        .get("x-actor")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+
+    AuditActorDto::new(AuditActorKindDto::Grpc, name)
+}
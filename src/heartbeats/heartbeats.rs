@@ -0,0 +1,276 @@
+use std::collections::BTreeMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    alerts::{AlertNotification, AlertNotificationKind},
+    app::{LogItem, APPLICATION_KEY},
+    repo::dto::LogLevelDto,
+    settings::HeartbeatSettings,
+};
+
+const RULE_ID: &str = "heartbeat";
+const RULE_NAME: &str = "No logs from application";
+pub const MAX_APPLICATIONS: usize = 1000;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HeartbeatItem {
+    pub application: String,
+    pub last_seen: i64,
+    /// Last seen moment at the time the silence alert was sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub silent_since: Option<i64>,
+}
+
+/// Last seen moment of every application. Which of them are monitored is decided by the settings on every check.
+pub struct Heartbeats {
+    items: BTreeMap<String, HeartbeatItem>,
+    /// Silence is counted from here at the earliest, so a restart does not alert on stale moments.
+    monitoring_since: i64,
+    has_changes: bool,
+}
+
+impl Heartbeats {
+    pub fn new(now: DateTimeAsMicroseconds) -> Self {
+        Self {
+            items: BTreeMap::new(),
+            monitoring_since: now.unix_microseconds,
+            has_changes: false,
+        }
+    }
+
+    pub fn restore(&mut self, items: Vec<HeartbeatItem>) {
+        for item in items {
+            self.items.insert(item.application.clone(), item);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// `now` is the moment the server received the event, so the clocks of the applications do not matter.
+    pub fn update(&mut self, itm: &LogItem, now: DateTimeAsMicroseconds) {
+        let Some(application) = itm.ctx.get(APPLICATION_KEY) else {
+            return;
+        };
+
+        let moment = now.unix_microseconds;
+
+        if let Some(item) = self.items.get_mut(application.as_str()) {
+            if moment > item.last_seen {
+                item.last_seen = moment;
+                self.has_changes = true;
+            }
+            return;
+        }
+
+        if self.items.len() >= MAX_APPLICATIONS {
+            return;
+        }
+
+        self.items.insert(
+            application.to_string(),
+            HeartbeatItem {
+                application: application.to_string(),
+                last_seen: moment,
+                silent_since: None,
+            },
+        );
+        self.has_changes = true;
+    }
+
+    /// Alerts on applications silent longer than their limit and on the ones which resumed.
+    pub fn check(
+        &mut self,
+        settings: &HeartbeatSettings,
+        now: DateTimeAsMicroseconds,
+    ) -> Vec<AlertNotification> {
+        let now_us = now.unix_microseconds;
+
+        for declared in settings.applications.iter().flatten() {
+            if !self.items.contains_key(&declared.application) {
+                self.items.insert(
+                    declared.application.clone(),
+                    HeartbeatItem {
+                        application: declared.application.clone(),
+                        last_seen: self.monitoring_since,
+                        silent_since: None,
+                    },
+                );
+                self.has_changes = true;
+            }
+        }
+
+        let forget_after = settings.get_forget_after_sec() * 1_000_000;
+        let mut result = Vec::new();
+
+        self.items.retain(|application, item| {
+            if !settings.is_declared(application) && now_us - item.last_seen >= forget_after {
+                self.has_changes = true;
+                return false;
+            }
+
+            let silent_for = now_us - item.last_seen.max(self.monitoring_since);
+
+            let max_silence_sec = match settings.get_max_silence_sec(application) {
+                Some(max_silence_sec) => max_silence_sec,
+                None => {
+                    item.silent_since = None;
+                    return true;
+                }
+            };
+
+            match item.silent_since {
+                None if silent_for > max_silence_sec * 1_000_000 => {
+                    item.silent_since = Some(item.last_seen);
+                    self.has_changes = true;
+
+                    result.push(to_notification(
+                        AlertNotificationKind::Firing,
+                        application,
+                        max_silence_sec,
+                        format!(
+                            "No events for {} min (limit {} min)",
+                            silent_for / 60_000_000,
+                            max_silence_sec / 60
+                        ),
+                        settings,
+                        now,
+                    ));
+                }
+                Some(silent_since) if item.last_seen > silent_since => {
+                    item.silent_since = None;
+                    self.has_changes = true;
+
+                    result.push(to_notification(
+                        AlertNotificationKind::Resolved,
+                        application,
+                        max_silence_sec,
+                        format!(
+                            "Resumed after {} min of silence",
+                            (item.last_seen - silent_since) / 60_000_000
+                        ),
+                        settings,
+                        now,
+                    ));
+                }
+                _ => {}
+            }
+
+            true
+        });
+
+        result
+    }
+
+    /// `None` if nothing changed since the previous call.
+    pub fn get_snapshot_to_persist(&mut self) -> Option<Vec<HeartbeatItem>> {
+        if !self.has_changes {
+            return None;
+        }
+
+        self.has_changes = false;
+        Some(self.items.values().cloned().collect())
+    }
+}
+
+fn to_notification(
+    kind: AlertNotificationKind,
+    application: &str,
+    max_silence_sec: i64,
+    description: String,
+    settings: &HeartbeatSettings,
+    now: DateTimeAsMicroseconds,
+) -> AlertNotification {
+    AlertNotification {
+        kind,
+        rule_id: RULE_ID.to_string(),
+        rule_name: RULE_NAME.to_string(),
+        min_level: LogLevelDto::Error,
+        application: Some(application.to_string()),
+        group_by: None,
+        group: application.to_string(),
+        count: 0,
+        threshold: 0,
+        window_sec: max_silence_sec as u64,
+        sample_message: Some(description),
+        moment: now,
+        channels: settings.channels.clone().unwrap_or_default(),
+        saved_search_id: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::Heartbeats;
+    use crate::{
+        alerts::AlertNotificationKind,
+        app::LogItem,
+        settings::{HeartbeatApplicationSettings, HeartbeatSettings},
+    };
+
+    /// The event timestamp is far in the past: only the receive moment counts.
+    fn log_item(application: &str) -> LogItem {
+        let mut ctx = BTreeMap::new();
+        ctx.insert("Application".to_string(), application.to_string());
+
+        LogItem {
+            id: "1".to_string(),
+            level: my_logger::LogLevel::Info,
+            process: None,
+            message: "Started".to_string(),
+            timestamp: DateTimeAsMicroseconds::new(0),
+            ctx,
+        }
+    }
+
+    #[test]
+    fn test_silent_application_alerts_once_and_recovers() {
+        let start = 1_000_000_000_000;
+        let minute = 60_000_000;
+
+        let settings = HeartbeatSettings {
+            disabled: false,
+            auto_learn: Some(false),
+            max_silence_minutes: Some(10),
+            forget_after_days: None,
+            applications: Some(vec![HeartbeatApplicationSettings {
+                application: "payments".to_string(),
+                max_silence_minutes: Some(5),
+            }]),
+            channels: None,
+        };
+
+        let mut heartbeats = Heartbeats::new(DateTimeAsMicroseconds::new(start));
+        heartbeats.update(&log_item("payments"), DateTimeAsMicroseconds::new(start));
+        heartbeats.update(&log_item("billing"), DateTimeAsMicroseconds::new(start));
+
+        let now = DateTimeAsMicroseconds::new(start + 4 * minute);
+        assert!(heartbeats.check(&settings, now).is_empty());
+
+        let now = DateTimeAsMicroseconds::new(start + 6 * minute);
+        let notifications = heartbeats.check(&settings, now);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].group, "payments");
+        assert_eq!(notifications[0].kind, AlertNotificationKind::Firing);
+        assert!(heartbeats.check(&settings, now).is_empty());
+
+        heartbeats.update(
+            &log_item("payments"),
+            DateTimeAsMicroseconds::new(start + 7 * minute),
+        );
+        let notifications = heartbeats.check(&settings, now);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].kind, AlertNotificationKind::Resolved);
+    }
+}
@@ -0,0 +1,2 @@
+mod heartbeats;
+pub use heartbeats::*;
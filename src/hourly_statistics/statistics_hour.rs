@@ -1,4 +1,4 @@
-use rust_extensions::date_time::{DateTimeAsMicroseconds, DateTimeStruct};
+use rust_extensions::date_time::{DateTimeAsMicroseconds, DateTimeStruct, TimeStruct};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct StatisticsHour(u64);
@@ -7,6 +7,30 @@ impl StatisticsHour {
     pub fn get_value(&self) -> u64 {
         self.0
     }
+
+    /// `None` if the key is not a valid YYYYMMDDHH.
+    pub fn get_start(&self) -> Option<DateTimeAsMicroseconds> {
+        let dts = DateTimeStruct {
+            year: (self.0 / 1000000) as i32,
+            month: ((self.0 / 10000) % 100) as u32,
+            day: ((self.0 / 100) % 100) as u32,
+            time: TimeStruct {
+                hour: (self.0 % 100) as u32,
+                min: 0,
+                sec: 0,
+                micros: 0,
+            },
+            dow: None,
+        };
+
+        dts.try_into().ok()
+    }
+}
+
+/// YYYYMMDD of the moment.
+pub fn get_day_key(moment: DateTimeAsMicroseconds) -> u64 {
+    let hour: StatisticsHour = moment.into();
+    hour.get_value() / 100
 }
 
 impl Into<StatisticsHour> for DateTimeAsMicroseconds {
@@ -28,5 +28,73 @@ pub fn build_controllers(app: &Arc<AppContext>) -> ControllersMiddleware {
         super::controllers::settings::DeleteIgnoreAction::new(app.clone()),
     ));
 
+    result.register_post_action(Arc::new(
+        super::controllers::settings::PreviewIgnoreAction::new(app.clone()),
+    ));
+
+    result.register_post_action(Arc::new(
+        super::controllers::settings::PostIgnoreRuleAction::new(app.clone()),
+    ));
+
+    result.register_post_action(Arc::new(
+        super::controllers::settings::PreviewIgnoreRuleAction::new(app.clone()),
+    ));
+
+    // Saved searches controller
+
+    result.register_get_action(Arc::new(
+        super::controllers::saved_searches::GetSavedSearchesAction::new(app.clone()),
+    ));
+    result.register_post_action(Arc::new(
+        super::controllers::saved_searches::PostSavedSearchAction::new(app.clone()),
+    ));
+    result.register_delete_action(Arc::new(
+        super::controllers::saved_searches::DeleteSavedSearchAction::new(app.clone()),
+    ));
+
+    // Redaction controller
+
+    result.register_get_action(Arc::new(
+        super::controllers::redaction::GetRedactionStatisticsAction::new(app.clone()),
+    ));
+
+    // Sampling controller
+
+    result.register_get_action(Arc::new(
+        super::controllers::sampling::GetSamplingStatisticsAction::new(app.clone()),
+    ));
+
+    // Statistics controller
+
+    result.register_get_action(Arc::new(
+        super::controllers::statistics::GetRollupStatisticsAction::new(app.clone()),
+    ));
+
+    result.register_get_action(Arc::new(
+        super::controllers::statistics::GetDimensionStatisticsAction::new(app.clone()),
+    ));
+
+    // Audit log controller
+
+    result.register_get_action(Arc::new(
+        super::controllers::audit_log::GetAuditLogAction::new(app.clone()),
+    ));
+
+    // Config controller
+
+    result.register_get_action(Arc::new(
+        super::controllers::config_transfer::ExportConfigAction::new(app.clone()),
+    ));
+
+    result.register_post_action(Arc::new(
+        super::controllers::config_transfer::ImportConfigAction::new(app.clone()),
+    ));
+
+    // Digest controller
+
+    result.register_get_action(Arc::new(
+        super::controllers::digest::GetDigestAction::new(app.clone()),
+    ));
+
     result
 }
@@ -0,0 +1,58 @@
+use my_http_server::macros::{MyHttpInput, MyHttpObjectStructure};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::Serialize;
+
+use crate::repo::dto::AuditLogEntryDto;
+
+#[derive(Debug, MyHttpInput)]
+pub struct GetAuditLogHttpInput {
+    #[http_query(description: "Optional start moment as unix microseconds")]
+    pub from: Option<i64>,
+
+    #[http_query(description: "Optional end moment as unix microseconds")]
+    pub to: Option<i64>,
+
+    #[http_query(description: "Optional target: IgnoreRule, SingleEventRule, SavedSearch or AlertRule")]
+    pub target: Option<String>,
+
+    #[http_query(description: "Optional id of the changed rule or saved search")]
+    pub id: Option<String>,
+
+    #[http_query(description: "Optional actor kind: Http, Grpc, Mcp or Telegram")]
+    pub actor_kind: Option<String>,
+
+    #[http_query(description: "Amount of newest entries to return. 100 by default")]
+    pub take: Option<usize>,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct AuditLogEntryHttpModel {
+    pub moment: String,
+    pub actor_kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor_name: Option<String>,
+    pub target: String,
+    pub action: String,
+    pub id: String,
+    /// Value before the change as JSON. Omitted for created values.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    /// Value after the change as JSON. Omitted for deleted values.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+impl AuditLogEntryHttpModel {
+    pub fn new(src: AuditLogEntryDto) -> Self {
+        Self {
+            moment: DateTimeAsMicroseconds::new(src.moment).to_rfc3339(),
+            actor_kind: format!("{:?}", src.actor.kind),
+            actor_name: src.actor.name,
+            target: format!("{:?}", src.target),
+            action: format!("{:?}", src.action),
+            id: src.id,
+            before: src.before.map(|value| value.to_string()),
+            after: src.after.map(|value| value.to_string()),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use super::contracts::*;
+use crate::{
+    app::AppContext,
+    repo::dto::{AuditActorKindDto, AuditLogFilterDto, AuditTargetDto},
+};
+
+const DEFAULT_TAKE: usize = 100;
+
+#[http_route(
+    method: "GET",
+    route: "/api/audit-log",
+    summary: "Get audit log",
+    description: "Changes of ignore rules, single event rules, saved searches and alert rules, newest first",
+    input_data: GetAuditLogHttpInput,
+    controller: "AuditLog",
+    result:[
+        {status_code: 200, description: "Ok response", model:"Vec<AuditLogEntryHttpModel>"},
+    ]
+)]
+pub struct GetAuditLogAction {
+    app: Arc<AppContext>,
+}
+
+impl GetAuditLogAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+async fn handle_request(
+    action: &GetAuditLogAction,
+    input_data: GetAuditLogHttpInput,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let filter = AuditLogFilterDto {
+        from: input_data.from,
+        to: input_data.to,
+        target: input_data
+            .target
+            .as_deref()
+            .map(AuditTargetDto::parse)
+            .transpose()
+            .map_err(HttpFailResult::as_validation_error)?,
+        id: input_data.id,
+        actor_kind: input_data
+            .actor_kind
+            .as_deref()
+            .map(AuditActorKindDto::parse)
+            .transpose()
+            .map_err(HttpFailResult::as_validation_error)?,
+    };
+
+    let result = action
+        .app
+        .audit_log_repo
+        .query(&filter, input_data.take.unwrap_or(DEFAULT_TAKE))
+        .await;
+
+    let model: Vec<_> = result
+        .into_iter()
+        .map(AuditLogEntryHttpModel::new)
+        .collect();
+
+    return HttpOutput::as_json(model).into_ok_result(true).into();
+}
@@ -0,0 +1,107 @@
+use my_http_server::{
+    macros::{MyHttpInput, MyHttpObjectStructure, MyHttpStringEnum},
+    types::RawData,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::flows::config_transfer::{
+    ConfigChange, ConfigDocumentFormat, ConfigImportMode, ConfigImportResult,
+};
+
+#[derive(MyHttpStringEnum, Deserialize, Debug)]
+pub enum ConfigFormatHttpModel {
+    #[http_enum_case(id:0, description = "JSON document")]
+    Json,
+    #[http_enum_case(id:1, description = "YAML document")]
+    Yaml,
+}
+
+impl Into<ConfigDocumentFormat> for ConfigFormatHttpModel {
+    fn into(self) -> ConfigDocumentFormat {
+        match self {
+            ConfigFormatHttpModel::Json => ConfigDocumentFormat::Json,
+            ConfigFormatHttpModel::Yaml => ConfigDocumentFormat::Yaml,
+        }
+    }
+}
+
+#[derive(MyHttpStringEnum, Deserialize, Debug)]
+pub enum ConfigImportModeHttpModel {
+    #[http_enum_case(id:0, description = "Add new and update existing items, keep the rest")]
+    Merge,
+    #[http_enum_case(id:1, description = "Also delete items missing in the sections of the document")]
+    Replace,
+}
+
+impl Into<ConfigImportMode> for ConfigImportModeHttpModel {
+    fn into(self) -> ConfigImportMode {
+        match self {
+            ConfigImportModeHttpModel::Merge => ConfigImportMode::Merge,
+            ConfigImportModeHttpModel::Replace => ConfigImportMode::Replace,
+        }
+    }
+}
+
+#[derive(Debug, MyHttpInput)]
+pub struct ExportConfigHttpInput {
+    #[http_query(description: "Document format. Yaml by default")]
+    pub format: Option<ConfigFormatHttpModel>,
+}
+
+#[derive(MyHttpInput)]
+pub struct ImportConfigHttpInput {
+    #[http_body_raw(description = "Configuration document as JSON or YAML")]
+    pub body: RawData,
+
+    #[http_query(description: "Import mode. Merge by default")]
+    pub mode: Option<ConfigImportModeHttpModel>,
+
+    #[http_query(description: "Only return the changes without applying them. False by default")]
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct ConfigChangeHttpModel {
+    pub target: String,
+    pub id: String,
+    pub action: String,
+    /// Current value as JSON. Omitted for created items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    /// Value from the document as JSON. Omitted for deleted items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+impl ConfigChangeHttpModel {
+    pub fn new(src: ConfigChange) -> Self {
+        Self {
+            target: format!("{:?}", src.target),
+            id: src.id,
+            action: format!("{:?}", src.action),
+            before: src.before.map(|value| value.to_string()),
+            after: src.after.map(|value| value.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct ImportConfigHttpResponse {
+    pub applied: bool,
+    pub unchanged: usize,
+    pub changes: Vec<ConfigChangeHttpModel>,
+}
+
+impl ImportConfigHttpResponse {
+    pub fn new(src: ConfigImportResult) -> Self {
+        Self {
+            applied: src.applied,
+            unchanged: src.unchanged,
+            changes: src
+                .changes
+                .into_iter()
+                .map(ConfigChangeHttpModel::new)
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use super::contracts::*;
+use crate::{app::AppContext, flows::config_transfer::ConfigDocumentFormat};
+
+#[http_route(
+    method: "GET",
+    route: "/api/config/export",
+    summary: "Export configuration",
+    description: "Ignore rules, single event rules, saved searches and alert rules as one versioned document",
+    input_data: ExportConfigHttpInput,
+    controller: "Config",
+    result:[
+        {status_code: 200, description: "Ok response"},
+    ]
+)]
+pub struct ExportConfigAction {
+    app: Arc<AppContext>,
+}
+
+impl ExportConfigAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+async fn handle_request(
+    action: &ExportConfigAction,
+    input_data: ExportConfigHttpInput,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let document = crate::flows::config_transfer::export_config(&action.app).await;
+
+    let format = input_data
+        .format
+        .map(|format| format.into())
+        .unwrap_or(ConfigDocumentFormat::Yaml);
+
+    match format {
+        ConfigDocumentFormat::Json => HttpOutput::as_json(document).into_ok_result(true).into(),
+        ConfigDocumentFormat::Yaml => {
+            let document = document
+                .serialize(format)
+                .map_err(HttpFailResult::as_fatal_error)?;
+            HttpOutput::as_text(document).into_ok_result(true).into()
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use super::contracts::*;
+use crate::{
+    app::AppContext,
+    flows::config_transfer::{ConfigDocument, ConfigImportMode},
+    http::controllers::shared_contract::get_http_audit_actor,
+};
+
+#[http_route(
+    method: "POST",
+    route: "/api/config/import",
+    summary: "Import configuration",
+    description: "Applies an exported configuration document. Use dry_run to preview the changes",
+    input_data: ImportConfigHttpInput,
+    controller: "Config",
+    result:[
+        {status_code: 200, description: "Ok response", model:"ImportConfigHttpResponse"},
+    ]
+)]
+pub struct ImportConfigAction {
+    app: Arc<AppContext>,
+}
+
+impl ImportConfigAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+async fn handle_request(
+    action: &ImportConfigAction,
+    input_data: ImportConfigHttpInput,
+    ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let actor = get_http_audit_actor(ctx);
+
+    let document = std::str::from_utf8(input_data.body.as_slice()).map_err(|_| {
+        HttpFailResult::as_validation_error("Document is not a valid UTF-8 text".to_string())
+    })?;
+
+    let document = ConfigDocument::parse(document).map_err(HttpFailResult::as_validation_error)?;
+
+    let result = crate::flows::config_transfer::import_config(
+        &action.app,
+        document,
+        input_data
+            .mode
+            .map(|mode| mode.into())
+            .unwrap_or(ConfigImportMode::Merge),
+        input_data.dry_run.unwrap_or(false),
+        &actor,
+    )
+    .await
+    .map_err(HttpFailResult::as_validation_error)?;
+
+    return HttpOutput::as_json(ImportConfigHttpResponse::new(result))
+        .into_ok_result(true)
+        .into();
+}
@@ -0,0 +1,5 @@
+mod contracts;
+mod export_config_action;
+pub use export_config_action::*;
+mod import_config_action;
+pub use import_config_action::*;
@@ -0,0 +1,41 @@
+use my_http_server::macros::{MyHttpInput, MyHttpStringEnum};
+use serde::Deserialize;
+
+use crate::digest::DigestPeriod;
+
+#[derive(MyHttpStringEnum, Deserialize, Debug)]
+pub enum DigestPeriodHttpModel {
+    #[http_enum_case(id:0, description = "Last 24 hours compared with the 24 hours before")]
+    Daily,
+    #[http_enum_case(id:1, description = "Last 7 days compared with the 7 days before")]
+    Weekly,
+}
+
+impl Into<DigestPeriod> for DigestPeriodHttpModel {
+    fn into(self) -> DigestPeriod {
+        match self {
+            DigestPeriodHttpModel::Daily => DigestPeriod::Daily,
+            DigestPeriodHttpModel::Weekly => DigestPeriod::Weekly,
+        }
+    }
+}
+
+#[derive(MyHttpStringEnum, Deserialize, Debug)]
+pub enum DigestFormatHttpModel {
+    #[http_enum_case(id:0, description = "JSON document")]
+    Json,
+    #[http_enum_case(id:1, description = "HTML page")]
+    Html,
+}
+
+#[derive(Debug, MyHttpInput)]
+pub struct GetDigestHttpInput {
+    #[http_query(description: "Report period. Daily by default")]
+    pub period: Option<DigestPeriodHttpModel>,
+
+    #[http_query(description: "Response format. Json by default")]
+    pub format: Option<DigestFormatHttpModel>,
+
+    #[http_query(description: "Items per section. 50 by default")]
+    pub take: Option<usize>,
+}
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use super::contracts::*;
+use crate::{
+    app::AppContext,
+    digest::{compile_digest_html, DigestPeriod},
+};
+
+const DEFAULT_TAKE: usize = 50;
+
+#[http_route(
+    method: "GET",
+    route: "/api/digest",
+    summary: "Get digest report",
+    description: "Error counts per application compared with the previous period, new error signatures and the busiest ignore rules",
+    input_data: GetDigestHttpInput,
+    controller: "Digest",
+    result:[
+        {status_code: 200, description: "Ok response"},
+    ]
+)]
+pub struct GetDigestAction {
+    app: Arc<AppContext>,
+}
+
+impl GetDigestAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+async fn handle_request(
+    action: &GetDigestAction,
+    input_data: GetDigestHttpInput,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let period: DigestPeriod = match input_data.period {
+        Some(period) => period.into(),
+        None => DigestPeriod::Daily,
+    };
+
+    let take = input_data.take.unwrap_or(DEFAULT_TAKE);
+
+    let report = crate::flows::build_digest(&action.app, period, take).await;
+
+    match input_data.format {
+        Some(DigestFormatHttpModel::Html) => HttpOutput::as_html(compile_digest_html(&report))
+            .into_ok_result(true)
+            .into(),
+        _ => HttpOutput::as_json(report).into_ok_result(true).into(),
+    }
+}
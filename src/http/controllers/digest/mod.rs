@@ -0,0 +1,3 @@
+mod contracts;
+mod get_digest_action;
+pub use get_digest_action::*;
@@ -1,3 +1,10 @@
+pub mod audit_log;
+pub mod config_transfer;
+pub mod digest;
 pub mod logs_income;
+pub mod redaction;
+pub mod sampling;
+pub mod saved_searches;
 pub mod settings;
+pub mod statistics;
 mod shared_contract;
@@ -0,0 +1,26 @@
+use std::collections::BTreeMap;
+
+use my_http_server::macros::MyHttpObjectStructure;
+use serde::Serialize;
+
+use crate::redaction::RedactionStatisticsItem;
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct RedactionStatisticsHttpModel {
+    pub application: String,
+    pub total: u64,
+    pub by_detector: BTreeMap<String, u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_redacted: Option<String>,
+}
+
+impl RedactionStatisticsHttpModel {
+    pub fn new(src: RedactionStatisticsItem) -> Self {
+        Self {
+            application: src.application,
+            total: src.by_detector.values().sum(),
+            by_detector: src.by_detector,
+            last_redacted: src.last_redacted.map(|dt| dt.to_rfc3339()),
+        }
+    }
+}
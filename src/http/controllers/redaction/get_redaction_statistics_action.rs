@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use super::contracts::*;
+use crate::app::AppContext;
+
+#[http_route(
+    method: "GET",
+    route: "/api/redaction/statistics",
+    summary: "Get redaction statistics",
+    description: "Number of redacted values per application and detector since the server start",
+    controller: "Redaction",
+    result:[
+        {status_code: 200, description: "Ok response", model:"Vec<RedactionStatisticsHttpModel>"},
+    ]
+)]
+pub struct GetRedactionStatisticsAction {
+    app: Arc<AppContext>,
+}
+
+impl GetRedactionStatisticsAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+async fn handle_request(
+    action: &GetRedactionStatisticsAction,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let result = {
+        let read_access = action.app.redaction_statistics.lock().await;
+        read_access.get_all()
+    };
+
+    let model: Vec<_> = result
+        .into_iter()
+        .map(RedactionStatisticsHttpModel::new)
+        .collect();
+
+    return HttpOutput::as_json(model).into_ok_result(true).into();
+}
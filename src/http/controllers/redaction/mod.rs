@@ -0,0 +1,3 @@
+mod contracts;
+mod get_redaction_statistics_action;
+pub use get_redaction_statistics_action::*;
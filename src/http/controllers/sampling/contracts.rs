@@ -0,0 +1,24 @@
+use my_http_server::macros::MyHttpObjectStructure;
+use serde::Serialize;
+
+use crate::sampling::SamplingStatisticsItem;
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct SamplingStatisticsHttpModel {
+    pub rule: String,
+    pub kept: u64,
+    pub dropped: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_dropped: Option<String>,
+}
+
+impl SamplingStatisticsHttpModel {
+    pub fn new(src: SamplingStatisticsItem) -> Self {
+        Self {
+            rule: src.rule,
+            kept: src.kept,
+            dropped: src.dropped,
+            last_dropped: src.last_dropped.map(|dt| dt.to_rfc3339()),
+        }
+    }
+}
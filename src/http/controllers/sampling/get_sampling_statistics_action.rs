@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use super::contracts::*;
+use crate::app::AppContext;
+
+#[http_route(
+    method: "GET",
+    route: "/api/sampling/statistics",
+    summary: "Get sampling statistics",
+    description: "Number of kept and dropped events per sampling rule since the server start",
+    controller: "Sampling",
+    result:[
+        {status_code: 200, description: "Ok response", model:"Vec<SamplingStatisticsHttpModel>"},
+    ]
+)]
+pub struct GetSamplingStatisticsAction {
+    app: Arc<AppContext>,
+}
+
+impl GetSamplingStatisticsAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+async fn handle_request(
+    action: &GetSamplingStatisticsAction,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let result = {
+        let read_access = action.app.sampler.lock().await;
+        read_access.get_statistics()
+    };
+
+    let model: Vec<_> = result
+        .into_iter()
+        .map(SamplingStatisticsHttpModel::new)
+        .collect();
+
+    return HttpOutput::as_json(model).into_ok_result(true).into();
+}
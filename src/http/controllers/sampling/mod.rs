@@ -0,0 +1,3 @@
+mod contracts;
+mod get_sampling_statistics_action;
+pub use get_sampling_statistics_action::*;
@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+
+use my_http_server::{
+    macros::{MyHttpInput, MyHttpInputObjectStructure, MyHttpObjectStructure},
+    types::RawDataTyped,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    http::controllers::shared_contract::LogLevelHttpModel,
+    repo::dto::{LogLevelDto, SavedSearchDto},
+};
+
+#[derive(MyHttpInput)]
+pub struct PostSavedSearchHttpInput {
+    #[http_body_raw(description = "Saved search. Omit id to create a new one")]
+    pub body: RawDataTyped<SavedSearchHttpInputModel>,
+}
+
+#[derive(MyHttpInputObjectStructure, Deserialize, Debug)]
+pub struct SavedSearchHttpInputModel {
+    pub id: Option<String>,
+    pub name: String,
+    pub levels: Option<Vec<LogLevelHttpModel>>,
+    pub context: Option<BTreeMap<String, String>>,
+    pub phrase: Option<String>,
+    pub last_minutes: i64,
+}
+
+impl Into<SavedSearchDto> for SavedSearchHttpInputModel {
+    fn into(self) -> SavedSearchDto {
+        SavedSearchDto {
+            id: self.id.unwrap_or_default(),
+            name: self.name,
+            levels: self
+                .levels
+                .unwrap_or_default()
+                .into_iter()
+                .map(|level| level.into())
+                .collect(),
+            context: self.context.unwrap_or_default(),
+            phrase: self.phrase,
+            last_minutes: self.last_minutes,
+        }
+    }
+}
+
+#[derive(Debug, MyHttpInput)]
+pub struct DeleteSavedSearchHttpInput {
+    #[http_query(description: "Saved search id")]
+    pub id: String,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct SavedSearchHttpModel {
+    pub id: String,
+    pub name: String,
+    pub levels: Vec<String>,
+    pub context: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phrase: Option<String>,
+    pub last_minutes: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl SavedSearchHttpModel {
+    pub fn new(src: SavedSearchDto, url: Option<String>) -> Self {
+        Self {
+            id: src.id,
+            name: src.name,
+            levels: src
+                .levels
+                .iter()
+                .map(|level: &LogLevelDto| format!("{:?}", level))
+                .collect(),
+            context: src.context,
+            phrase: src.phrase,
+            last_minutes: src.last_minutes,
+            url,
+        }
+    }
+}
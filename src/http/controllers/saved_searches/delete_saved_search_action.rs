@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use super::contracts::*;
+use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::{app::AppContext, http::controllers::shared_contract::get_http_audit_actor};
+
+#[http_route(
+    method: "DELETE",
+    route: "/api/saved-searches",
+    summary: "Delete saved search",
+    description: "Delete saved search",
+    input_data: DeleteSavedSearchHttpInput,
+    controller: "SavedSearches",
+    result:[
+        {status_code: 204, description: "Ok response"},
+    ]
+)]
+pub struct DeleteSavedSearchAction {
+    app: Arc<AppContext>,
+}
+
+impl DeleteSavedSearchAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+async fn handle_request(
+    action: &DeleteSavedSearchAction,
+    input_data: DeleteSavedSearchHttpInput,
+    ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let actor = get_http_audit_actor(ctx);
+    crate::flows::saved_searches::delete(&action.app, &input_data.id, &actor)
+        .await
+        .map_err(HttpFailResult::as_validation_error)?;
+
+    return HttpOutput::Empty.into_ok_result(true).into();
+}
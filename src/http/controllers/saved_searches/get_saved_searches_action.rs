@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use super::contracts::*;
+use crate::app::AppContext;
+
+#[http_route(
+    method: "GET",
+    route: "/api/saved-searches",
+    summary: "Get saved searches",
+    description: "Get saved searches",
+    controller: "SavedSearches",
+    result:[
+        {status_code: 200, description: "Ok response", model:"Vec<SavedSearchHttpModel>"},
+    ]
+)]
+pub struct GetSavedSearchesAction {
+    app: Arc<AppContext>,
+}
+
+impl GetSavedSearchesAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+async fn handle_request(
+    action: &GetSavedSearchesAction,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let ui_url = action.app.get_ui_url().await;
+    let result = action.app.saved_searches_repo.get_all().await;
+
+    let mut model = Vec::with_capacity(result.len());
+
+    for itm in result {
+        let url = crate::app::ui_links::compile_saved_search_url(&ui_url, &itm.id);
+        model.push(SavedSearchHttpModel::new(itm, url));
+    }
+
+    return HttpOutput::as_json(model).into_ok_result(true).into();
+}
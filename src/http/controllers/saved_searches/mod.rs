@@ -0,0 +1,7 @@
+mod contracts;
+mod get_saved_searches_action;
+pub use get_saved_searches_action::*;
+mod post_saved_search_action;
+pub use post_saved_search_action::*;
+mod delete_saved_search_action;
+pub use delete_saved_search_action::*;
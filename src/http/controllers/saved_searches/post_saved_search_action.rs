@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use super::contracts::*;
+use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::{app::AppContext, http::controllers::shared_contract::get_http_audit_actor};
+
+#[http_route(
+    method: "POST",
+    route: "/api/saved-searches",
+    summary: "Create or update saved search",
+    description: "Creates a saved search when id is omitted, otherwise updates the existing one",
+    input_data: PostSavedSearchHttpInput,
+    controller: "SavedSearches",
+    result:[
+        {status_code: 200, description: "Ok response", model:"SavedSearchHttpModel"},
+    ]
+)]
+pub struct PostSavedSearchAction {
+    app: Arc<AppContext>,
+}
+
+impl PostSavedSearchAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+async fn handle_request(
+    action: &PostSavedSearchAction,
+    input_data: PostSavedSearchHttpInput,
+    ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let actor = get_http_audit_actor(ctx);
+    let item = input_data.body.deserialize_json()?;
+
+    let saved = crate::flows::saved_searches::save(&action.app, item.into(), &actor)
+        .await
+        .map_err(HttpFailResult::as_validation_error)?;
+
+    let url = action.app.get_saved_search_url(&saved.id).await;
+
+    return HttpOutput::as_json(SavedSearchHttpModel::new(saved, url))
+        .into_ok_result(true)
+        .into();
+}
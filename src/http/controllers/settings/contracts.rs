@@ -1,7 +1,19 @@
-use my_http_server::macros::{MyHttpInput, MyHttpObjectStructure};
-use serde::Serialize;
+use std::collections::BTreeMap;
 
-use crate::http::controllers::shared_contract::LogLevelHttpModel;
+use my_http_server::{
+    macros::{MyHttpInput, MyHttpInputObjectStructure, MyHttpObjectStructure},
+    types::RawDataTyped,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    flows::IgnoreEventPreview,
+    http::controllers::shared_contract::{LogLevelHttpModel, MatchModeHttpModel},
+    repo::dto::{
+        ConditionOperatorDto, IgnoreConditionDto, IgnoreConditionFieldDto, IgnoreItemDto,
+        IgnoreRuleStatisticsSummary, LogItemDto,
+    },
+};
 
 #[derive(Debug, MyHttpInput)]
 pub struct PostIgnoreMaskHttpInput {
@@ -16,25 +28,255 @@ pub struct PostIgnoreMaskHttpInput {
 
     #[http_body(description: "Optional expiration moment as unix microseconds. Omit for a rule that never expires.")]
     pub expiration: Option<i64>,
+
+    #[http_body(description: "How the marker is matched. Contains by default")]
+    pub match_mode: Option<MatchModeHttpModel>,
+
+    #[http_body(description: "Match the marker ignoring case. False by default")]
+    pub case_insensitive: Option<bool>,
+}
+
+#[derive(MyHttpInput)]
+pub struct PostIgnoreRuleHttpInput {
+    #[http_body_raw(description = "Ignore rule with conditions. Omit id to create a new one")]
+    pub body: RawDataTyped<IgnoreRuleHttpInputModel>,
+}
+
+#[derive(MyHttpInputObjectStructure, Deserialize, Debug)]
+pub struct IgnoreConditionHttpInputModel {
+    /// Message, Process, ProcessOrMessage or Context.
+    pub field: String,
+    /// Context key. Required for the Context field.
+    pub key: Option<String>,
+    /// Equals, Contains, Prefix or Regex. Contains by default.
+    pub operator: Option<String>,
+    pub value: String,
+    pub case_insensitive: Option<bool>,
+}
+
+#[derive(MyHttpInputObjectStructure, Deserialize, Debug)]
+pub struct IgnoreRuleHttpInputModel {
+    pub id: Option<String>,
+    /// Every level if omitted.
+    pub levels: Option<Vec<LogLevelHttpModel>>,
+    pub conditions: Vec<IgnoreConditionHttpInputModel>,
+    pub expiration: Option<i64>,
+}
+
+impl IgnoreRuleHttpInputModel {
+    pub fn to_dto(self) -> Result<IgnoreItemDto, String> {
+        let mut conditions = Vec::with_capacity(self.conditions.len());
+
+        for condition in self.conditions {
+            conditions.push(IgnoreConditionDto {
+                field: IgnoreConditionFieldDto::parse(&condition.field, condition.key)?,
+                operator: ConditionOperatorDto::parse(condition.operator.as_deref())?,
+                value: condition.value,
+                case_insensitive: condition.case_insensitive.unwrap_or(false),
+            });
+        }
+
+        Ok(IgnoreItemDto {
+            id: self.id.unwrap_or_default(),
+            levels: self
+                .levels
+                .unwrap_or_default()
+                .into_iter()
+                .map(|level| level.into())
+                .collect(),
+            conditions,
+            expires_at: self.expiration,
+            statistics: Default::default(),
+        })
+    }
+}
+
+#[derive(MyHttpInput)]
+pub struct PreviewIgnoreRuleHttpInput {
+    #[http_body_raw(description = "Ignore rule with conditions to preview")]
+    pub body: RawDataTyped<PreviewIgnoreRuleHttpInputModel>,
+}
+
+#[derive(MyHttpInputObjectStructure, Deserialize, Debug)]
+pub struct PreviewIgnoreRuleHttpInputModel {
+    pub rule: IgnoreRuleHttpInputModel,
+    /// How many last hours of stored events to check. 24 by default.
+    pub hours: Option<i64>,
+    /// Amount of matched events to return. 10 by default.
+    pub samples: Option<usize>,
 }
 
 #[derive(Debug, MyHttpInput)]
 pub struct DeleteIgnoreMaskHttpInput {
+    #[http_query(description: "Rule id. If omitted, the rule is found by level, application and marker")]
+    pub id: Option<String>,
+
     #[http_query(description: "Log Level")]
-    pub level: LogLevelHttpModel,
+    pub level: Option<LogLevelHttpModel>,
 
     #[http_query(description: "Application name")]
-    pub application: String,
+    pub application: Option<String>,
 
     #[http_query(description: "Filter marker")]
-    pub marker: String,
+    pub marker: Option<String>,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct IgnoreConditionHttpModel {
+    pub field: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub operator: String,
+    pub value: String,
+    pub case_insensitive: bool,
+}
+
+impl IgnoreConditionHttpModel {
+    pub fn new(src: IgnoreConditionDto) -> Self {
+        let (field, key) = match src.field {
+            IgnoreConditionFieldDto::Context(key) => ("Context".to_string(), Some(key)),
+            field => (field.to_string(), None),
+        };
+
+        Self {
+            field,
+            key,
+            operator: format!("{:?}", src.operator),
+            value: src.value,
+            case_insensitive: src.case_insensitive,
+        }
+    }
 }
 
 #[derive(Debug, MyHttpObjectStructure, Serialize)]
 pub struct IgnoreEventHttpModel {
-    pub level: String,
-    pub application: String,
-    pub marker: String,
+    pub id: String,
+    /// Empty means every level.
+    pub levels: Vec<String>,
+    pub conditions: Vec<IgnoreConditionHttpModel>,
+    pub description: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expiration: Option<i64>,
+    /// Events suppressed since the rule was created.
+    pub suppressed_total: u64,
+    pub suppressed_last_hour: u64,
+    pub suppressed_last_day: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_matched: Option<i64>,
+    /// No matches for `IgnoreRuleUnusedDays`.
+    pub unused: bool,
+}
+
+impl IgnoreEventHttpModel {
+    pub fn new(src: IgnoreItemDto, statistics: IgnoreRuleStatisticsSummary) -> Self {
+        Self {
+            description: format!(
+                "{} | {}",
+                src.get_levels_description(),
+                src.get_conditions_description()
+            ),
+            id: src.id,
+            levels: src
+                .levels
+                .iter()
+                .map(|level| format!("{:?}", level))
+                .collect(),
+            conditions: src
+                .conditions
+                .into_iter()
+                .map(IgnoreConditionHttpModel::new)
+                .collect(),
+            expiration: src.expires_at,
+            suppressed_total: statistics.total,
+            suppressed_last_hour: statistics.last_hour,
+            suppressed_last_day: statistics.last_day,
+            last_matched: statistics.last_matched,
+            unused: statistics.unused,
+        }
+    }
+}
+
+#[derive(Debug, MyHttpInput)]
+pub struct PreviewIgnoreMaskHttpInput {
+    #[http_body(description: "Log Level")]
+    pub level: LogLevelHttpModel,
+
+    #[http_body(description: "Application name")]
+    pub application: String,
+
+    #[http_body(description: "Filter marker")]
+    pub marker: String,
+
+    #[http_body(description: "How the marker is matched. Contains by default")]
+    pub match_mode: Option<MatchModeHttpModel>,
+
+    #[http_body(description: "Match the marker ignoring case. False by default")]
+    pub case_insensitive: Option<bool>,
+
+    #[http_body(description: "How many last hours of stored events to check. 24 by default")]
+    pub hours: Option<i64>,
+
+    #[http_body(description: "Amount of matched events to return. 10 by default")]
+    pub samples: Option<usize>,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct IgnoreEventPreviewGroupHttpModel {
+    pub level: String,
+    pub application: String,
+    pub count: usize,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct IgnoreEventPreviewSampleHttpModel {
+    pub moment: String,
+    pub level: String,
+    pub message: String,
+    pub context: BTreeMap<String, String>,
+}
+
+impl IgnoreEventPreviewSampleHttpModel {
+    pub fn new(src: LogItemDto) -> Self {
+        Self {
+            moment: src.moment.to_rfc3339(),
+            level: format!("{:?}", src.level),
+            message: src.message,
+            context: src.context,
+        }
+    }
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct IgnoreEventPreviewHttpModel {
+    pub hours: i64,
+    pub scanned: usize,
+    pub matched: usize,
+    pub truncated: bool,
+    pub groups: Vec<IgnoreEventPreviewGroupHttpModel>,
+    pub samples: Vec<IgnoreEventPreviewSampleHttpModel>,
+}
+
+impl IgnoreEventPreviewHttpModel {
+    pub fn new(src: IgnoreEventPreview) -> Self {
+        Self {
+            hours: src.hours,
+            scanned: src.scanned,
+            matched: src.matched,
+            truncated: src.truncated,
+            groups: src
+                .groups
+                .into_iter()
+                .map(|group| IgnoreEventPreviewGroupHttpModel {
+                    level: format!("{:?}", group.level),
+                    application: group.application,
+                    count: group.count,
+                })
+                .collect(),
+            samples: src
+                .samples
+                .into_iter()
+                .map(IgnoreEventPreviewSampleHttpModel::new)
+                .collect(),
+        }
+    }
 }
@@ -3,13 +3,16 @@ use std::sync::Arc;
 use super::contracts::*;
 use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
 
-use crate::{app::AppContext, repo::dto::IgnoreWhereModel};
+use crate::{
+    app::AppContext, http::controllers::shared_contract::get_http_audit_actor,
+    repo::dto::get_marker_rule_id,
+};
 
 #[http_route(
     method: "DELETE",
     route: "/api/settings/ignore",
-    summary: "Delete ignore events marker",
-    description: "Delete ignore events marker",
+    summary: "Delete ignore rule",
+    description: "Delete ignore rule by id or by level, application and marker",
     input_data: DeleteIgnoreMaskHttpInput,
     controller: "Settings",
     result:[
@@ -28,17 +31,24 @@ impl DeleteIgnoreAction {
 async fn handle_request(
     action: &DeleteIgnoreAction,
     input_data: DeleteIgnoreMaskHttpInput,
-    _ctx: &HttpContext,
+    ctx: &HttpContext,
 ) -> Result<HttpOkResult, HttpFailResult> {
-    crate::flows::remove_ignore_event(
-        &action.app,
-        IgnoreWhereModel {
-            level: input_data.level.into(),
-            application: input_data.application,
-            marker: input_data.marker,
+    let id = match input_data.id {
+        Some(id) => id,
+        None => match (input_data.level, input_data.application, input_data.marker) {
+            (Some(level), Some(application), Some(marker)) => {
+                get_marker_rule_id(&level.into(), &application, &marker)
+            }
+            _ => {
+                return Err(HttpFailResult::as_validation_error(
+                    "Either id or level, application and marker are required".to_string(),
+                ));
+            }
         },
-    )
-    .await;
+    };
+
+    let actor = get_http_audit_actor(ctx);
+    crate::flows::remove_ignore_event(&action.app, &id, &actor).await;
 
     return HttpOutput::Empty.into_ok_result(true).into();
 }
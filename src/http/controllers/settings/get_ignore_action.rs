@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 
 use super::contracts::*;
 use crate::app::AppContext;
@@ -30,15 +31,18 @@ async fn handle_request(
 ) -> Result<HttpOkResult, HttpFailResult> {
     let result = action.app.settings_repo.get_ignore_events().await;
 
+    let now = DateTimeAsMicroseconds::now();
+    let unused_days = action
+        .app
+        .settings_reader
+        .get_ignore_rule_unused_days()
+        .await;
+
     let mut model = Vec::with_capacity(result.len());
 
     for itm in result {
-        model.push(IgnoreEventHttpModel {
-            level: format!("{:?}", itm.level),
-            application: itm.application,
-            marker: itm.marker,
-            expiration: itm.expires_at,
-        });
+        let statistics = itm.statistics.get_summary(now, unused_days);
+        model.push(IgnoreEventHttpModel::new(itm, statistics));
     }
 
     return HttpOutput::as_json(model).into_ok_result(true).into();
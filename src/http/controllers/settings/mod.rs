@@ -5,3 +5,9 @@ mod get_ignore_action;
 pub use get_ignore_action::*;
 mod delete_ignore_action;
 pub use delete_ignore_action::*;
+mod preview_ignore_action;
+pub use preview_ignore_action::*;
+mod post_ignore_rule_action;
+pub use post_ignore_rule_action::*;
+mod preview_ignore_rule_action;
+pub use preview_ignore_rule_action::*;
@@ -3,7 +3,10 @@ use std::sync::Arc;
 use super::contracts::*;
 use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
 
-use crate::{app::AppContext, repo::dto::IgnoreItemDto};
+use crate::{
+    app::AppContext, http::controllers::shared_contract::get_http_audit_actor,
+    repo::dto::IgnoreItemDto,
+};
 
 #[http_route(
     method: "POST",
@@ -28,18 +31,27 @@ impl PostIgnoreAction {
 async fn handle_request(
     action: &PostIgnoreAction,
     input_data: PostIgnoreMaskHttpInput,
-    _ctx: &HttpContext,
+    ctx: &HttpContext,
 ) -> Result<HttpOkResult, HttpFailResult> {
+    let actor = get_http_audit_actor(ctx);
+
     crate::flows::add_ignore_event(
         &action.app,
-        IgnoreItemDto {
-            level: input_data.level.into(),
-            application: input_data.application,
-            marker: input_data.marker,
-            expires_at: input_data.expiration,
-        },
+        IgnoreItemDto::from_marker(
+            input_data.level.into(),
+            &input_data.application,
+            &input_data.marker,
+            input_data
+                .match_mode
+                .map(|mode| mode.into())
+                .unwrap_or_default(),
+            input_data.case_insensitive.unwrap_or(false),
+            input_data.expiration,
+        ),
+        &actor,
     )
-    .await;
+    .await
+    .map_err(HttpFailResult::as_validation_error)?;
 
     action.app.filter_events_cache.reset().await;
     return HttpOutput::Empty.into_ok_result(true).into();
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use super::contracts::*;
+use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{app::AppContext, http::controllers::shared_contract::get_http_audit_actor};
+
+#[http_route(
+    method: "POST",
+    route: "/api/settings/ignore/rule",
+    summary: "Set ignore rule with conditions",
+    description: "Creates an ignore rule when id is omitted, otherwise replaces the rule with the same id",
+    input_data: PostIgnoreRuleHttpInput,
+    controller: "Settings",
+    result:[
+        {status_code: 200, description: "Ok response", model:"IgnoreEventHttpModel"},
+    ]
+)]
+pub struct PostIgnoreRuleAction {
+    app: Arc<AppContext>,
+}
+
+impl PostIgnoreRuleAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+async fn handle_request(
+    action: &PostIgnoreRuleAction,
+    input_data: PostIgnoreRuleHttpInput,
+    ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let actor = get_http_audit_actor(ctx);
+
+    let rule = input_data
+        .body
+        .deserialize_json()?
+        .to_dto()
+        .map_err(HttpFailResult::as_validation_error)?;
+
+    let saved = crate::flows::add_ignore_event(&action.app, rule, &actor)
+        .await
+        .map_err(HttpFailResult::as_validation_error)?;
+
+    let unused_days = action
+        .app
+        .settings_reader
+        .get_ignore_rule_unused_days()
+        .await;
+    let statistics = saved
+        .statistics
+        .get_summary(DateTimeAsMicroseconds::now(), unused_days);
+
+    return HttpOutput::as_json(IgnoreEventHttpModel::new(saved, statistics))
+        .into_ok_result(true)
+        .into();
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use super::contracts::*;
+use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::{app::AppContext, repo::dto::IgnoreItemDto};
+
+const DEFAULT_HOURS: i64 = 24;
+const DEFAULT_SAMPLES: usize = 10;
+
+#[http_route(
+    method: "POST",
+    route: "/api/settings/ignore/preview",
+    summary: "Preview ignore events marker",
+    description: "Counts stored events of the last hours the marker would suppress, without adding it",
+    input_data: PreviewIgnoreMaskHttpInput,
+    controller: "Settings",
+    result:[
+        {status_code: 200, description: "Ok response", model:"IgnoreEventPreviewHttpModel"},
+    ]
+)]
+pub struct PreviewIgnoreAction {
+    app: Arc<AppContext>,
+}
+
+impl PreviewIgnoreAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+async fn handle_request(
+    action: &PreviewIgnoreAction,
+    input_data: PreviewIgnoreMaskHttpInput,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let preview = crate::flows::preview_ignore_event(
+        &action.app,
+        IgnoreItemDto::from_marker(
+            input_data.level.into(),
+            &input_data.application,
+            &input_data.marker,
+            input_data
+                .match_mode
+                .map(|mode| mode.into())
+                .unwrap_or_default(),
+            input_data.case_insensitive.unwrap_or(false),
+            None,
+        ),
+        input_data.hours.unwrap_or(DEFAULT_HOURS),
+        input_data.samples.unwrap_or(DEFAULT_SAMPLES),
+    )
+    .await
+    .map_err(HttpFailResult::as_validation_error)?;
+
+    return HttpOutput::as_json(IgnoreEventPreviewHttpModel::new(preview))
+        .into_ok_result(true)
+        .into();
+}
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use super::contracts::*;
+use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+const DEFAULT_HOURS: i64 = 24;
+const DEFAULT_SAMPLES: usize = 10;
+
+#[http_route(
+    method: "POST",
+    route: "/api/settings/ignore/rule/preview",
+    summary: "Preview ignore rule with conditions",
+    description: "Counts stored events of the last hours the rule would suppress, without adding it",
+    input_data: PreviewIgnoreRuleHttpInput,
+    controller: "Settings",
+    result:[
+        {status_code: 200, description: "Ok response", model:"IgnoreEventPreviewHttpModel"},
+    ]
+)]
+pub struct PreviewIgnoreRuleAction {
+    app: Arc<AppContext>,
+}
+
+impl PreviewIgnoreRuleAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+async fn handle_request(
+    action: &PreviewIgnoreRuleAction,
+    input_data: PreviewIgnoreRuleHttpInput,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let input = input_data.body.deserialize_json()?;
+
+    let rule = input
+        .rule
+        .to_dto()
+        .map_err(HttpFailResult::as_validation_error)?;
+
+    let preview = crate::flows::preview_ignore_event(
+        &action.app,
+        rule,
+        input.hours.unwrap_or(DEFAULT_HOURS),
+        input.samples.unwrap_or(DEFAULT_SAMPLES),
+    )
+    .await
+    .map_err(HttpFailResult::as_validation_error)?;
+
+    return HttpOutput::as_json(IgnoreEventPreviewHttpModel::new(preview))
+        .into_ok_result(true)
+        .into();
+}
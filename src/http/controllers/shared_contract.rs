@@ -1,8 +1,8 @@
-use my_http_server::macros::MyHttpStringEnum;
+use my_http_server::{macros::MyHttpStringEnum, HttpContext, HttpRequestHeaders};
 use my_logger::LogLevel;
 use serde::Deserialize;
 
-use crate::repo::dto::LogLevelDto;
+use crate::repo::dto::{AuditActorDto, AuditActorKindDto, LogLevelDto, MatchModeDto};
 
 #[derive(MyHttpStringEnum, Deserialize, Debug)]
 pub enum LogLevelHttpModel {
@@ -41,3 +41,38 @@ impl Into<LogLevel> for LogLevelHttpModel {
         }
     }
 }
+
+#[derive(MyHttpStringEnum, Deserialize, Debug)]
+pub enum MatchModeHttpModel {
+    #[http_enum_case(id:0, description = "Substring match")]
+    Contains,
+    #[http_enum_case(id:1, description = "Regular expression match")]
+    Regex,
+}
+
+impl Into<MatchModeDto> for MatchModeHttpModel {
+    fn into(self) -> MatchModeDto {
+        match self {
+            MatchModeHttpModel::Contains => MatchModeDto::Contains,
+            MatchModeHttpModel::Regex => MatchModeDto::Regex,
+        }
+    }
+}
+
+const ACTOR_HEADER: &str = "X-Actor";
+const REFERER_HEADER: &str = "Referer";
+
+/// The `X-Actor` header names the caller; the UI is recognized by its `Referer`.
+pub fn get_http_audit_actor(ctx: &HttpContext) -> AuditActorDto {
+    let headers = ctx.request.get_headers();
+    let get_header = |name: &str| {
+        headers
+            .try_get_case_insensitive(name)
+            .and_then(|value| value.as_str().ok())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    let name = get_header(ACTOR_HEADER).or_else(|| get_header(REFERER_HEADER));
+    AuditActorDto::new(AuditActorKindDto::Http, name)
+}
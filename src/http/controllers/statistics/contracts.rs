@@ -0,0 +1,132 @@
+use my_http_server::macros::{MyHttpInput, MyHttpObjectStructure};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::Serialize;
+
+use crate::{
+    rollup_statistics::{RollupBucket, RollupResolution},
+    statistic_dimensions::DimensionValueStatistics,
+};
+
+#[derive(Debug, MyHttpInput)]
+pub struct GetRollupStatisticsHttpInput {
+    #[http_query(description: "Optional start moment as unix microseconds. 24 hours before `to` by default")]
+    pub from: Option<i64>,
+
+    #[http_query(description: "Optional end moment as unix microseconds. Now by default")]
+    pub to: Option<i64>,
+
+    #[http_query(description: "Optional bucket size: Minute (last 24 hours), Hour (last 30 days) or Day (last year). Selected by the range if omitted")]
+    pub resolution: Option<String>,
+
+    #[http_query(description: "Optional application name")]
+    pub application: Option<String>,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct RollupApplicationHttpModel {
+    pub application: String,
+    pub info: u32,
+    pub warning: u32,
+    pub error: u32,
+    pub fatal_error: u32,
+    pub debug: u32,
+    pub sampled: u32,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct RollupBucketHttpModel {
+    pub start: String,
+    pub applications: Vec<RollupApplicationHttpModel>,
+}
+
+impl RollupBucketHttpModel {
+    pub fn new(src: RollupBucket) -> Self {
+        Self {
+            start: DateTimeAsMicroseconds::new(src.start * 1_000_000).to_rfc3339(),
+            applications: src
+                .apps
+                .into_iter()
+                .map(|(application, counts)| RollupApplicationHttpModel {
+                    application,
+                    info: counts.info,
+                    warning: counts.warning,
+                    error: counts.error,
+                    fatal_error: counts.fatal_error,
+                    debug: counts.debug,
+                    sampled: counts.sampled,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct RollupStatisticsHttpResponse {
+    pub resolution: String,
+    pub buckets: Vec<RollupBucketHttpModel>,
+}
+
+impl RollupStatisticsHttpResponse {
+    pub fn new(resolution: RollupResolution, buckets: Vec<RollupBucket>) -> Self {
+        Self {
+            resolution: format!("{:?}", resolution),
+            buckets: buckets
+                .into_iter()
+                .map(RollupBucketHttpModel::new)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, MyHttpInput)]
+pub struct GetDimensionStatisticsHttpInput {
+    #[http_query(description: "Configured context key, e.g. Version")]
+    pub key: String,
+
+    #[http_query(description: "Optional start moment as unix microseconds. 24 hours before `to` by default")]
+    pub from: Option<i64>,
+
+    #[http_query(description: "Optional end moment as unix microseconds. Now by default")]
+    pub to: Option<i64>,
+
+    #[http_query(description: "Optional number of values to return. 10 by default, 100 at most")]
+    pub take: Option<usize>,
+
+    #[http_query(description: "Optional order: Total (default) or Errors")]
+    pub order: Option<String>,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct DimensionValueHttpModel {
+    pub value: String,
+    pub info: u32,
+    pub warning: u32,
+    pub error: u32,
+    pub fatal_error: u32,
+    pub debug: u32,
+    pub sampled: u32,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+impl DimensionValueHttpModel {
+    pub fn new(src: DimensionValueStatistics) -> Self {
+        Self {
+            value: src.value,
+            info: src.counts.info,
+            warning: src.counts.warning,
+            error: src.counts.error,
+            fatal_error: src.counts.fatal_error,
+            debug: src.counts.debug,
+            sampled: src.counts.sampled,
+            first_seen: DateTimeAsMicroseconds::new(src.seen.first_seen).to_rfc3339(),
+            last_seen: DateTimeAsMicroseconds::new(src.seen.last_seen).to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct DimensionStatisticsHttpResponse {
+    pub key: String,
+    pub values: Vec<DimensionValueHttpModel>,
+}
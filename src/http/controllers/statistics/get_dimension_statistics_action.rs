@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use super::contracts::*;
+use crate::{app::AppContext, statistic_dimensions::DimensionOrder};
+
+#[http_route(
+    method: "GET",
+    route: "/api/statistics/dimensions",
+    summary: "Get dimension statistics",
+    description: "Top values of a configured context key with counts by level",
+    input_data: GetDimensionStatisticsHttpInput,
+    controller: "Statistics",
+    result:[
+        {status_code: 200, description: "Ok response", model:"DimensionStatisticsHttpResponse"},
+    ]
+)]
+pub struct GetDimensionStatisticsAction {
+    app: Arc<AppContext>,
+}
+
+impl GetDimensionStatisticsAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+async fn handle_request(
+    action: &GetDimensionStatisticsAction,
+    input_data: GetDimensionStatisticsHttpInput,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let order = input_data
+        .order
+        .as_deref()
+        .map(DimensionOrder::parse)
+        .transpose()
+        .map_err(HttpFailResult::as_validation_error)?
+        .unwrap_or(DimensionOrder::Total);
+
+    let values = crate::flows::get_dimension_statistics(
+        &action.app,
+        input_data.key.as_str(),
+        input_data.from,
+        input_data.to,
+        order,
+        input_data.take,
+    )
+    .await
+    .map_err(HttpFailResult::as_validation_error)?;
+
+    return HttpOutput::as_json(DimensionStatisticsHttpResponse {
+        key: input_data.key.trim().to_string(),
+        values: values
+            .into_iter()
+            .map(DimensionValueHttpModel::new)
+            .collect(),
+    })
+    .into_ok_result(true)
+    .into();
+}
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use my_http_server::{macros::http_route, HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use super::contracts::*;
+use crate::{app::AppContext, rollup_statistics::RollupResolution};
+
+#[http_route(
+    method: "GET",
+    route: "/api/statistics/rollup",
+    summary: "Get rollup statistics",
+    description: "Per-application counts by level in minute, hour or day buckets, oldest first",
+    input_data: GetRollupStatisticsHttpInput,
+    controller: "Statistics",
+    result:[
+        {status_code: 200, description: "Ok response", model:"RollupStatisticsHttpResponse"},
+    ]
+)]
+pub struct GetRollupStatisticsAction {
+    app: Arc<AppContext>,
+}
+
+impl GetRollupStatisticsAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+async fn handle_request(
+    action: &GetRollupStatisticsAction,
+    input_data: GetRollupStatisticsHttpInput,
+    _ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let resolution = input_data
+        .resolution
+        .as_deref()
+        .map(RollupResolution::parse)
+        .transpose()
+        .map_err(HttpFailResult::as_validation_error)?;
+
+    let (resolution, buckets) = crate::flows::get_rollup_statistics(
+        &action.app,
+        input_data.from,
+        input_data.to,
+        resolution,
+        input_data.application.as_deref(),
+    )
+    .await
+    .map_err(HttpFailResult::as_validation_error)?;
+
+    return HttpOutput::as_json(RollupStatisticsHttpResponse::new(resolution, buckets))
+        .into_ok_result(true)
+        .into();
+}
@@ -0,0 +1,5 @@
+mod contracts;
+mod get_dimension_statistics_action;
+pub use get_dimension_statistics_action::*;
+mod get_rollup_statistics_action;
+pub use get_rollup_statistics_action::*;
@@ -0,0 +1,33 @@
+use std::collections::BTreeMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::repo::dto::IgnoreRuleStatisticsDto;
+
+/// Hits of ignore rules by rule id, collected on ingestion and flushed into the rules by the persist timer.
+pub struct IgnoreRuleHits {
+    pending: BTreeMap<String, IgnoreRuleStatisticsDto>,
+}
+
+impl IgnoreRuleHits {
+    pub fn new() -> Self {
+        Self {
+            pending: BTreeMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, rule_id: &str, moment: DateTimeAsMicroseconds) {
+        match self.pending.get_mut(rule_id) {
+            Some(statistics) => statistics.add_hit(moment),
+            None => {
+                let mut statistics = IgnoreRuleStatisticsDto::default();
+                statistics.add_hit(moment);
+                self.pending.insert(rule_id.to_string(), statistics);
+            }
+        }
+    }
+
+    pub fn take(&mut self) -> BTreeMap<String, IgnoreRuleStatisticsDto> {
+        std::mem::take(&mut self.pending)
+    }
+}
@@ -0,0 +1,2 @@
+mod ignore_rule_hits;
+pub use ignore_rule_hits::*;
@@ -1,6 +1,44 @@
-use crate::{app::LogItem, my_logger_grpc::IgnoreSingleEventGrpcModel};
+use crate::{
+    app::LogItem, my_logger_grpc::IgnoreSingleEventGrpcModel, text_matching::TextMatcher,
+};
 
-pub fn match_event(itm: &LogItem, ignore_single_event: &IgnoreSingleEventGrpcModel) -> bool {
+/// Message and context patterns of a single event rule, compiled once when the rule is added.
+pub struct IgnoreSingleEventMatcher {
+    message: Option<TextMatcher>,
+    context: Vec<(String, TextMatcher)>,
+}
+
+impl IgnoreSingleEventMatcher {
+    pub fn new(item: &IgnoreSingleEventGrpcModel) -> Result<Self, String> {
+        let match_mode = item.match_mode().into();
+
+        let message = if item.message_match.is_empty() {
+            None
+        } else {
+            Some(TextMatcher::new(
+                &item.message_match,
+                match_mode,
+                item.case_insensitive,
+            )?)
+        };
+
+        let mut context = Vec::with_capacity(item.context_match.len());
+        for ctx in &item.context_match {
+            context.push((
+                ctx.key.clone(),
+                TextMatcher::new(&ctx.value, match_mode, item.case_insensitive)?,
+            ));
+        }
+
+        Ok(Self { message, context })
+    }
+}
+
+pub fn match_event(
+    itm: &LogItem,
+    ignore_single_event: &IgnoreSingleEventGrpcModel,
+    matcher: &IgnoreSingleEventMatcher,
+) -> bool {
     let level_as_i32 = level_is_i32(itm.level);
     let matched = ignore_single_event
         .levels
@@ -11,14 +49,16 @@ pub fn match_event(itm: &LogItem, ignore_single_event: &IgnoreSingleEventGrpcMod
         return false;
     }
 
-    if !itm.message.contains(&ignore_single_event.message_match) {
-        return false;
+    if let Some(message) = &matcher.message {
+        if !message.is_match(&itm.message) {
+            return false;
+        }
     }
 
-    for da_ctx in &ignore_single_event.context_match {
-        match itm.ctx.get(&da_ctx.key) {
+    for (key, value_matcher) in &matcher.context {
+        match itm.ctx.get(key) {
             Some(value) => {
-                if !value.contains(&da_ctx.value) {
+                if !value_matcher.is_match(value) {
                     return false;
                 }
             }
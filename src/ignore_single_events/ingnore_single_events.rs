@@ -1,17 +1,34 @@
-use std::time::Duration;
+use std::collections::VecDeque;
 
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 
-use crate::{app::LogItem, my_logger_grpc::*};
+use crate::{app::LogItem, my_logger_grpc::*, repo::dto::IgnoreRuleStatisticsDto};
+
+use super::event_matching::IgnoreSingleEventMatcher;
+
+const MINUTE_US: i64 = 60_000_000;
 
 pub struct IgnoreSingleEventItem {
     pub item: IgnoreSingleEventGrpcModel,
-    pub prev_events: Vec<DateTimeAsMicroseconds>,
+    pub matcher: IgnoreSingleEventMatcher,
+    /// Receive moments of the latest matches within the window, oldest first.
+    pub prev_events: VecDeque<DateTimeAsMicroseconds>,
+    pub statistics: IgnoreRuleStatisticsDto,
+}
+
+/// Rule with the state collected on matching, kept between restarts.
+#[derive(Debug, Clone)]
+pub struct IgnoreSingleEventSnapshot {
+    pub item: IgnoreSingleEventGrpcModel,
+    pub statistics: IgnoreRuleStatisticsDto,
+    /// Unix microseconds of the matches within the window.
+    pub window: Vec<i64>,
 }
 
 pub struct IgnoreSingleEventCache {
     data: Vec<IgnoreSingleEventItem>,
     pub initialized: bool,
+    has_changes: bool,
 }
 
 impl IgnoreSingleEventCache {
@@ -19,46 +36,151 @@ impl IgnoreSingleEventCache {
         Self {
             data: Vec::new(),
             initialized: false,
+            has_changes: false,
         }
     }
 
-    pub fn add(&mut self, item: IgnoreSingleEventGrpcModel) {
+    /// Statistics of a replaced rule with the same id are kept, its window starts over.
+    pub fn add(&mut self, item: IgnoreSingleEventGrpcModel, matcher: IgnoreSingleEventMatcher) {
+        let statistics = match self.data.iter().find(|itm| itm.item.id == item.id) {
+            Some(existing) => existing.statistics.clone(),
+            None => IgnoreRuleStatisticsDto {
+                since: DateTimeAsMicroseconds::now().unix_microseconds,
+                ..Default::default()
+            },
+        };
+
+        self.add_with_state(item, matcher, statistics, vec![])
+    }
+
+    fn add_with_state(
+        &mut self,
+        item: IgnoreSingleEventGrpcModel,
+        matcher: IgnoreSingleEventMatcher,
+        statistics: IgnoreRuleStatisticsDto,
+        mut window: Vec<i64>,
+    ) {
+        window.sort();
         self.data.retain(|data_itm| data_itm.item.id != item.id);
         self.data.push(IgnoreSingleEventItem {
             item,
-            prev_events: vec![],
+            matcher,
+            prev_events: window
+                .into_iter()
+                .map(DateTimeAsMicroseconds::new)
+                .collect(),
+            statistics,
         });
     }
 
-    pub fn init(&mut self, items: Vec<IgnoreSingleEventGrpcModel>) {
-        for item in items {
-            self.add(item);
+    pub fn init(&mut self, items: Vec<IgnoreSingleEventSnapshot>) {
+        let now = DateTimeAsMicroseconds::now().unix_microseconds;
+
+        for mut snapshot in items {
+            if snapshot.statistics.since == 0 {
+                snapshot.statistics.since = now;
+                self.has_changes = true;
+            }
+
+            match IgnoreSingleEventMatcher::new(&snapshot.item) {
+                Ok(matcher) => self.add_with_state(
+                    snapshot.item,
+                    matcher,
+                    snapshot.statistics,
+                    snapshot.window,
+                ),
+                Err(err) => {
+                    println!(
+                        "Skipping invalid ignore single event {}: {}",
+                        snapshot.item.id, err
+                    );
+                }
+            }
         }
 
         self.initialized = true;
     }
 
-    pub fn get_all(&self) -> Vec<IgnoreSingleEventGrpcModel> {
-        self.data.iter().map(|itm| itm.item.clone()).collect()
+    pub fn get_all_with_statistics(
+        &self,
+    ) -> Vec<(IgnoreSingleEventGrpcModel, IgnoreRuleStatisticsDto)> {
+        self.data
+            .iter()
+            .map(|itm| (itm.item.clone(), itm.statistics.clone()))
+            .collect()
+    }
+
+    pub fn get_snapshot(&self) -> Vec<IgnoreSingleEventSnapshot> {
+        self.data
+            .iter()
+            .map(|itm| IgnoreSingleEventSnapshot {
+                item: itm.item.clone(),
+                statistics: itm.statistics.clone(),
+                window: itm
+                    .prev_events
+                    .iter()
+                    .map(|moment| moment.unix_microseconds)
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// `None` if no rule matched since the previous call.
+    pub fn get_snapshot_to_persist(&mut self) -> Option<Vec<IgnoreSingleEventSnapshot>> {
+        if !self.has_changes {
+            return None;
+        }
+
+        self.has_changes = false;
+        Some(self.get_snapshot())
     }
 
     pub fn delete(&mut self, id: &str) {
         self.data.retain(|itm| itm.item.id != id);
     }
 
-    pub fn skip_by_filtering(&mut self, itm: &LogItem) -> bool {
+    /// Skips the event while the rule matched at most `skip_amount` times within the last `minutes_to_wait` minutes.
+    /// The window is counted by `now`, the moment the events were received, so it only moves forward.
+    pub fn skip_by_filtering(&mut self, itm: &LogItem, now: DateTimeAsMicroseconds) -> bool {
         for delay_itm in self.data.iter_mut() {
-            if !super::event_matching::match_event(itm, &delay_itm.item) {
+            if !super::event_matching::match_event(itm, &delay_itm.item, &delay_itm.matcher) {
                 continue;
             }
 
-            delay_itm.prev_events.push(itm.timestamp.clone());
+            let window_start =
+                now.unix_microseconds - delay_itm.item.minutes_to_wait as i64 * MINUTE_US;
 
+            while let Some(moment) = delay_itm.prev_events.front() {
+                if moment.unix_microseconds > window_start {
+                    break;
+                }
+                delay_itm.prev_events.pop_front();
+                self.has_changes = true;
+            }
+
+            // Older matches can not change the decision until the newer ones leave the window,
+            // so a full window of matches at the same moment stays as it is.
             let items_before_alerting = delay_itm.item.skip_amount as usize;
+            let window_is_full = delay_itm.prev_events.len() > items_before_alerting;
+            let same_moment = delay_itm
+                .prev_events
+                .front()
+                .map(|moment| moment.unix_microseconds == now.unix_microseconds)
+                .unwrap_or(false);
+
+            if !(window_is_full && same_moment) {
+                delay_itm.prev_events.push_back(now);
+                if delay_itm.prev_events.len() > items_before_alerting + 1 {
+                    delay_itm.prev_events.pop_front();
+                }
+                self.has_changes = true;
+            }
+
             let skip = delay_itm.prev_events.len() <= items_before_alerting;
 
-            while delay_itm.prev_events.len() > items_before_alerting + 1 {
-                delay_itm.prev_events.remove(0);
+            if skip {
+                delay_itm.statistics.add_hit(now);
+                self.has_changes = true;
             }
 
             return skip;
@@ -67,22 +189,13 @@ impl IgnoreSingleEventCache {
         false
     }
 
-    pub fn gc(&mut self) {
+    /// Frees the windows of rules which did not match for a while. Matching does not depend on it
+    /// and drops expired moments itself, so it does not mark the snapshot as changed.
+    pub fn gc(&mut self, now: DateTimeAsMicroseconds) {
         for itm in &mut self.data {
-            let mut to_deleted: Vec<DateTimeAsMicroseconds> = Vec::new();
-
-            let gc_moment = DateTimeAsMicroseconds::now()
-                .sub(Duration::from_secs(itm.item.minutes_to_wait * 60));
-
-            for itm in itm.prev_events.iter() {
-                if itm < &gc_moment {
-                    to_deleted.push(itm.clone());
-                }
-            }
-
-            for to_delete in to_deleted {
-                itm.prev_events.retain(|x| x != &to_delete);
-            }
+            let window_start = now.unix_microseconds - itm.item.minutes_to_wait as i64 * MINUTE_US;
+            itm.prev_events
+                .retain(|moment| moment.unix_microseconds > window_start);
         }
     }
 }
@@ -96,20 +209,25 @@ mod tests {
 
     use crate::app::LogItem;
 
-    use super::{IgnoreSingleEventCache, LogLevelGrpcModel};
+    use super::{IgnoreSingleEventCache, IgnoreSingleEventMatcher, LogLevelGrpcModel};
 
     #[test]
     fn test_simple_case() {
         let mut cache = IgnoreSingleEventCache::new();
 
-        cache.add(super::IgnoreSingleEventGrpcModel {
+        let rule = super::IgnoreSingleEventGrpcModel {
             id: "1".to_string(),
             levels: vec![LogLevelGrpcModel::Error as i32],
             message_match: "Test".to_string(),
             context_match: vec![],
             skip_amount: 2,
             minutes_to_wait: 1,
-        });
+            match_mode: super::MatchModeGrpcModel::Contains as i32,
+            case_insensitive: false,
+            statistics: None,
+        };
+        let matcher = IgnoreSingleEventMatcher::new(&rule).unwrap();
+        cache.add(rule, matcher);
 
         let mut now = DateTimeAsMicroseconds::now();
 
@@ -121,7 +239,7 @@ mod tests {
             timestamp: now,
             ctx: BTreeMap::new(),
         };
-        let skip = cache.skip_by_filtering(&log_item);
+        let skip = cache.skip_by_filtering(&log_item, now);
         assert_eq!(skip, true);
 
         now.add_seconds(1);
@@ -133,7 +251,7 @@ mod tests {
             timestamp: now,
             ctx: BTreeMap::new(),
         };
-        let skip = cache.skip_by_filtering(&log_item);
+        let skip = cache.skip_by_filtering(&log_item, now);
         assert_eq!(skip, true);
 
         now.add_seconds(1);
@@ -145,7 +263,97 @@ mod tests {
             timestamp: now,
             ctx: BTreeMap::new(),
         };
-        let skip = cache.skip_by_filtering(&log_item);
+        let skip = cache.skip_by_filtering(&log_item, now);
         assert_eq!(skip, false);
     }
+
+    #[test]
+    fn test_sliding_window_survives_restart() {
+        let rule = super::IgnoreSingleEventGrpcModel {
+            id: "1".to_string(),
+            levels: vec![LogLevelGrpcModel::Error as i32],
+            message_match: "Test".to_string(),
+            context_match: vec![],
+            skip_amount: 1,
+            minutes_to_wait: 1,
+            match_mode: super::MatchModeGrpcModel::Contains as i32,
+            case_insensitive: false,
+            statistics: None,
+        };
+
+        let mut cache = IgnoreSingleEventCache::new();
+        let matcher = IgnoreSingleEventMatcher::new(&rule).unwrap();
+        cache.add(rule, matcher);
+
+        // The event timestamp is far in the past, only the receive moments count.
+        let log_item = LogItem {
+            id: "Test".to_string(),
+            level: my_logger::LogLevel::Error,
+            process: None,
+            message: "Test".to_string(),
+            timestamp: DateTimeAsMicroseconds::new(0),
+            ctx: BTreeMap::new(),
+        };
+
+        let start = DateTimeAsMicroseconds::now().unix_microseconds;
+        let received = |seconds: i64| DateTimeAsMicroseconds::new(start + seconds * 1_000_000);
+
+        assert!(cache.skip_by_filtering(&log_item, received(0)));
+        assert!(!cache.skip_by_filtering(&log_item, received(10)));
+
+        // Both matches left the window
+        assert!(cache.skip_by_filtering(&log_item, received(80)));
+
+        let snapshot = cache.get_snapshot_to_persist().unwrap();
+        assert!(cache.get_snapshot_to_persist().is_none());
+        assert_eq!(snapshot[0].window, vec![start + 80 * 1_000_000]);
+
+        let mut restarted = IgnoreSingleEventCache::new();
+        restarted.init(snapshot);
+        assert!(!restarted.skip_by_filtering(&log_item, received(90)));
+        assert!(restarted.skip_by_filtering(&log_item, received(200)));
+    }
+
+    #[test]
+    fn test_unchanged_window_is_not_persisted() {
+        let rule = super::IgnoreSingleEventGrpcModel {
+            id: "1".to_string(),
+            levels: vec![LogLevelGrpcModel::Error as i32],
+            message_match: "Test".to_string(),
+            context_match: vec![],
+            skip_amount: 1,
+            minutes_to_wait: 1,
+            match_mode: super::MatchModeGrpcModel::Contains as i32,
+            case_insensitive: false,
+            statistics: None,
+        };
+
+        let mut cache = IgnoreSingleEventCache::new();
+        let matcher = IgnoreSingleEventMatcher::new(&rule).unwrap();
+        cache.add(rule, matcher);
+
+        let log_item = LogItem {
+            id: "Test".to_string(),
+            level: my_logger::LogLevel::Error,
+            process: None,
+            message: "Test".to_string(),
+            timestamp: DateTimeAsMicroseconds::now(),
+            ctx: BTreeMap::new(),
+        };
+
+        let now = DateTimeAsMicroseconds::now();
+        assert!(cache.skip_by_filtering(&log_item, now));
+        assert!(!cache.skip_by_filtering(&log_item, now));
+        assert!(cache.get_snapshot_to_persist().is_some());
+
+        // The same batch keeps matching: the window is full of the same moment
+        assert!(!cache.skip_by_filtering(&log_item, now));
+        assert!(cache.get_snapshot_to_persist().is_none());
+
+        let mut later = now;
+        later.add_minutes(5);
+        cache.gc(later);
+        assert!(cache.get_snapshot_to_persist().is_none());
+        assert!(cache.get_snapshot()[0].window.is_empty());
+    }
 }
@@ -1,3 +1,4 @@
 mod ingnore_single_events;
 pub use ingnore_single_events::*;
 mod event_matching;
+pub use event_matching::IgnoreSingleEventMatcher;
@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error_signatures::is_template_truncated, repo::dto::LogLevelDto};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IssueStatus {
+    #[default]
+    Open,
+    Resolved,
+    /// Events of the issue are dropped by ignore rules.
+    Ignored,
+}
+
+/// Error and FatalError events of one application clustered by message template.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IssueItem {
+    /// Same as the error signature of its events.
+    pub id: String,
+    pub application: String,
+    pub template: String,
+    /// Most severe level seen.
+    pub level: LogLevelDto,
+    #[serde(default)]
+    pub status: IssueStatus,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    pub count: u64,
+    /// Hour key (YYYYMMDDHH) -> events.
+    #[serde(default)]
+    pub hourly: BTreeMap<u64, u64>,
+    /// Value of the `Version` context -> events.
+    #[serde(default)]
+    pub versions: BTreeMap<String, u64>,
+    /// Latest event ids, newest last.
+    #[serde(default)]
+    pub sample_event_ids: Vec<String>,
+}
+
+impl IssueItem {
+    /// Regex matching whole messages of the template, used as the ignore rule marker.
+    /// A truncated template only has to match the start of the message.
+    pub fn get_ignore_marker(&self) -> String {
+        if !is_template_truncated(&self.template) {
+            return format!(r"^\s*{}\s*$", template_to_regex(&self.template));
+        }
+
+        // The last word may be cut in the middle, so it is left out.
+        let template = match self.template.rfind(' ') {
+            Some(index) => &self.template[..index],
+            None => self.template.as_str(),
+        };
+
+        format!(r"^\s*{}", template_to_regex(template))
+    }
+
+    /// Unanchored marker of the rules added before the markers were anchored, used to find them by id.
+    pub fn get_legacy_ignore_marker(&self) -> String {
+        template_to_regex(&self.template)
+    }
+}
+
+fn template_to_regex(template: &str) -> String {
+    let mut result = String::with_capacity(template.len() * 2);
+    let mut rest = template;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(tail) = rest.strip_prefix("<guid>") {
+            result.push_str("[0-9a-fA-F-]{36}");
+            rest = tail;
+            continue;
+        }
+
+        if let Some(tail) = rest.strip_prefix("<id>") {
+            result.push_str(r"[\w-]+");
+            rest = tail;
+            continue;
+        }
+
+        match c {
+            '#' => result.push_str(r"\d+"),
+            ' ' => result.push_str(r"\s+"),
+            _ => {
+                let mut buf = [0u8; 4];
+                result.push_str(&regex::escape(c.encode_utf8(&mut buf)));
+            }
+        }
+
+        rest = &rest[c.len_utf8()..];
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error_signatures::{is_template_truncated, to_message_template},
+        text_matching::compile_regex,
+    };
+
+    use super::{IssueItem, IssueStatus};
+
+    fn issue(message: &str) -> IssueItem {
+        IssueItem {
+            id: "1".to_string(),
+            application: "payments".to_string(),
+            template: to_message_template(message),
+            level: crate::repo::dto::LogLevelDto::Error,
+            status: IssueStatus::Open,
+            first_seen: 0,
+            last_seen: 0,
+            count: 1,
+            hourly: Default::default(),
+            versions: Default::default(),
+            sample_event_ids: vec![],
+        }
+    }
+
+    #[test]
+    fn test_ignore_marker_matches_events_of_issue() {
+        let message = "Order 42 (user 3f2b1c9e-7a4d-4e1b-9c3f-2a6b8d0e1f47) failed: pay_8fK2j91";
+
+        let regex = compile_regex(&issue(message).get_ignore_marker(), false).unwrap();
+
+        assert!(regex.is_match(message));
+        assert!(regex
+            .is_match("Order 7 (user 11111111-2222-3333-4444-555555555555) failed: pay_1aaaaaa"));
+        assert!(!regex.is_match("Refund 7 failed"));
+        assert!(!regex.is_match(&format!("Retry of {}", message)));
+        assert!(!regex.is_match(&format!("{} twice", message)));
+    }
+
+    #[test]
+    fn test_ignore_marker_of_truncated_template() {
+        let message = format!("Batch failed: {}", "row 1 is invalid, ".repeat(40));
+        let issue = issue(&message);
+        assert!(is_template_truncated(&issue.template));
+
+        let regex = compile_regex(&issue.get_ignore_marker(), false).unwrap();
+        assert!(regex.is_match(&message));
+        assert!(!regex.is_match(&format!("Retry of {}", message)));
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::{LogItem, VERSION_KEY},
+    error_signatures::{ErrorSignature, ErrorSignatureItem},
+    hourly_statistics::StatisticsHour,
+    repo::dto::LogLevelDto,
+};
+
+use super::{IssueItem, IssueStatus};
+
+pub const MAX_ISSUES: usize = 10_000;
+const MAX_HOURS_PER_ISSUE: usize = 48;
+const MAX_VERSIONS_PER_ISSUE: usize = 20;
+const MAX_SAMPLES_PER_ISSUE: usize = 10;
+
+pub struct Issues {
+    items: HashMap<String, IssueItem>,
+    has_changes: bool,
+}
+
+impl Issues {
+    pub fn new() -> Self {
+        Self {
+            items: HashMap::new(),
+            has_changes: false,
+        }
+    }
+
+    pub fn restore(&mut self, items: Vec<IssueItem>) {
+        for item in items {
+            self.items.insert(item.id.clone(), item);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Imports the signatures of the former `error-signatures.json` that have no issue yet.
+    pub fn restore_legacy_signatures(&mut self, items: Vec<ErrorSignatureItem>) {
+        for item in items {
+            if self.items.contains_key(&item.signature) {
+                continue;
+            }
+
+            self.items.insert(
+                item.signature.clone(),
+                IssueItem {
+                    id: item.signature,
+                    application: item.application,
+                    template: item.template,
+                    level: item.level,
+                    status: IssueStatus::Open,
+                    first_seen: item.first_seen,
+                    last_seen: item.last_seen,
+                    count: item.count,
+                    hourly: Default::default(),
+                    versions: Default::default(),
+                    sample_event_ids: Vec::new(),
+                },
+            );
+            self.has_changes = true;
+        }
+    }
+
+    /// First and last seen are the receive time, hourly counts follow the event timestamp.
+    /// A resolved issue is reopened by a new event.
+    /// Returns the issue and its last seen before this event, `None` for a new issue.
+    pub fn update(
+        &mut self,
+        itm: &LogItem,
+        signature: ErrorSignature,
+        now: DateTimeAsMicroseconds,
+    ) -> (&IssueItem, Option<i64>) {
+        let level: LogLevelDto = (&itm.level).into();
+        let now = now.unix_microseconds;
+
+        self.has_changes = true;
+
+        let item = self
+            .items
+            .entry(signature.id)
+            .or_insert_with_key(|id| IssueItem {
+                id: id.clone(),
+                application: signature.application.to_string(),
+                template: signature.template,
+                level: level.clone(),
+                status: IssueStatus::Open,
+                first_seen: now,
+                last_seen: now,
+                count: 0,
+                hourly: Default::default(),
+                versions: Default::default(),
+                sample_event_ids: Vec::new(),
+            });
+
+        let prev_last_seen = if item.count > 0 {
+            Some(item.last_seen)
+        } else {
+            None
+        };
+
+        item.count += 1;
+        item.last_seen = item.last_seen.max(now);
+        if level.severity() > item.level.severity() {
+            item.level = level;
+        }
+        if item.status == IssueStatus::Resolved {
+            item.status = IssueStatus::Open;
+        }
+
+        let hour: StatisticsHour = itm.timestamp.into();
+        *item.hourly.entry(hour.get_value()).or_insert(0) += 1;
+        while item.hourly.len() > MAX_HOURS_PER_ISSUE {
+            item.hourly.pop_first();
+        }
+
+        if let Some(version) = itm.ctx.get(VERSION_KEY) {
+            if let Some(count) = item.versions.get_mut(version) {
+                *count += 1;
+            } else if item.versions.len() < MAX_VERSIONS_PER_ISSUE {
+                item.versions.insert(version.to_string(), 1);
+            }
+        }
+
+        item.sample_event_ids.push(itm.id.clone());
+        if item.sample_event_ids.len() > MAX_SAMPLES_PER_ISSUE {
+            item.sample_event_ids.remove(0);
+        }
+
+        (item, prev_last_seen)
+    }
+
+    /// New issues are always recorded, the store is trimmed to [`MAX_ISSUES`] here.
+    pub fn gc(&mut self) {
+        self.remove_least_recent(MAX_ISSUES);
+    }
+
+    fn remove_least_recent(&mut self, max_items: usize) {
+        if self.items.len() <= max_items {
+            return;
+        }
+
+        let mut to_remove = self.items.len() - max_items;
+
+        let mut last_seen: Vec<i64> = self.items.values().map(|item| item.last_seen).collect();
+        let (_, cutoff, _) = last_seen.select_nth_unstable(to_remove - 1);
+        let cutoff = *cutoff;
+
+        // Fewer than `to_remove` items are older than the cutoff, the rest is taken from the ties.
+        self.items.retain(|_, item| {
+            if item.last_seen < cutoff {
+                to_remove -= 1;
+                return false;
+            }
+            true
+        });
+
+        self.items.retain(|_, item| {
+            if to_remove > 0 && item.last_seen == cutoff {
+                to_remove -= 1;
+                return false;
+            }
+            true
+        });
+
+        self.has_changes = true;
+    }
+
+    pub fn get(&self, id: &str) -> Option<IssueItem> {
+        self.items.get(id).cloned()
+    }
+
+    /// Most recently seen first.
+    pub fn get_filtered(
+        &self,
+        status: Option<IssueStatus>,
+        application: Option<&str>,
+        take: usize,
+    ) -> Vec<IssueItem> {
+        let mut result: Vec<_> = self
+            .items
+            .values()
+            .filter(|item| match status {
+                Some(status) => item.status == status,
+                None => true,
+            })
+            .filter(|item| match application {
+                Some(application) => item.application == application,
+                None => true,
+            })
+            .collect();
+
+        result.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+        result.into_iter().take(take).cloned().collect()
+    }
+
+    /// Issues first seen at or after `from`, most frequent first.
+    pub fn get_first_seen_since(
+        &self,
+        from: DateTimeAsMicroseconds,
+        take: usize,
+    ) -> Vec<IssueItem> {
+        let mut result: Vec<_> = self
+            .items
+            .values()
+            .filter(|item| item.first_seen >= from.unix_microseconds)
+            .collect();
+
+        result.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.id.cmp(&b.id)));
+        result.into_iter().take(take).cloned().collect()
+    }
+
+    pub fn set_status(&mut self, id: &str, status: IssueStatus) -> Option<IssueItem> {
+        let item = self.items.get_mut(id)?;
+        item.status = status;
+        self.has_changes = true;
+        Some(item.clone())
+    }
+
+    /// `None` if nothing changed since the previous call.
+    pub fn get_snapshot_to_persist(&mut self) -> Option<Vec<IssueItem>> {
+        if !self.has_changes {
+            return None;
+        }
+
+        self.has_changes = false;
+        Some(self.items.values().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::Issues;
+    use crate::{app::LogItem, error_signatures::ErrorSignature, issues::IssueStatus};
+
+    fn log_item(id: &str, message: &str, version: &str) -> LogItem {
+        let mut ctx = BTreeMap::new();
+        ctx.insert("Application".to_string(), "payments".to_string());
+        ctx.insert("Version".to_string(), version.to_string());
+
+        LogItem {
+            id: id.to_string(),
+            level: my_logger::LogLevel::Error,
+            process: None,
+            message: message.to_string(),
+            timestamp: DateTimeAsMicroseconds::new(1_700_000_000_000_000),
+            ctx,
+        }
+    }
+
+    fn update(issues: &mut Issues, itm: LogItem, now: i64) -> Option<i64> {
+        let signature = ErrorSignature::from_log_item(&itm).unwrap();
+        let (_, prev_last_seen) = issues.update(&itm, signature, DateTimeAsMicroseconds::new(now));
+        prev_last_seen
+    }
+
+    #[test]
+    fn test_events_are_grouped_and_resolved_issue_reopens() {
+        let mut issues = Issues::new();
+        let now = 1_700_000_000_000_000;
+
+        assert_eq!(
+            update(&mut issues, log_item("1", "Order 1 failed", "1.0.0"), now),
+            None
+        );
+        assert_eq!(
+            update(
+                &mut issues,
+                log_item("2", "Order 2 failed", "1.0.1"),
+                now + 1
+            ),
+            Some(now)
+        );
+        update(
+            &mut issues,
+            log_item("3", "Refund 3 failed", "1.0.1"),
+            now + 2,
+        );
+
+        let items = issues.get_filtered(None, Some("payments"), 10);
+        assert_eq!(items.len(), 2);
+
+        let order = items
+            .iter()
+            .find(|i| i.template == "Order # failed")
+            .unwrap();
+        assert_eq!(order.count, 2);
+        assert_eq!(order.versions.len(), 2);
+        assert_eq!(order.sample_event_ids, vec!["1", "2"]);
+
+        issues.set_status(&order.id, IssueStatus::Resolved);
+        update(
+            &mut issues,
+            log_item("4", "Order 4 failed", "1.0.2"),
+            now + 3,
+        );
+        assert_eq!(issues.get(&order.id).unwrap().status, IssueStatus::Open);
+    }
+
+    #[test]
+    fn test_least_recent_issues_are_removed() {
+        let mut issues = Issues::new();
+        let now = 1_700_000_000_000_000;
+
+        update(&mut issues, log_item("1", "Order 1 failed", "1.0.0"), now);
+        update(
+            &mut issues,
+            log_item("2", "Refund 2 failed", "1.0.0"),
+            now + 1,
+        );
+        update(
+            &mut issues,
+            log_item("3", "Payout 3 failed", "1.0.0"),
+            now + 2,
+        );
+        update(
+            &mut issues,
+            log_item("4", "Order 4 failed", "1.0.0"),
+            now + 3,
+        );
+
+        issues.remove_least_recent(2);
+
+        let templates: Vec<_> = issues
+            .get_filtered(None, None, 10)
+            .into_iter()
+            .map(|item| item.template)
+            .collect();
+        assert_eq!(templates, vec!["Order # failed", "Payout # failed"]);
+    }
+}
@@ -0,0 +1,4 @@
+mod issue;
+pub use issue::*;
+mod issues;
+pub use issues::*;
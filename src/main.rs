@@ -4,19 +4,34 @@ use app::AppContext;
 use background::*;
 use rust_extensions::MyTimer;
 
+mod alerts;
+mod anomalies;
 mod app;
 mod background;
 mod cache;
+mod digest;
+mod error_signatures;
+mod field_extraction;
 mod flows;
 mod grpc_server;
+mod heartbeats;
 mod hourly_statistics;
 mod http;
+mod ignore_rule_hits;
 mod ignore_single_events;
 mod insights_repo;
+mod issues;
 mod mcp;
+mod notifications;
+mod redaction;
 mod repo;
+mod rollup_statistics;
+mod sampling;
 mod settings;
+mod statistic_dimensions;
 mod telegram;
+mod text_matching;
+mod version_statistics;
 #[allow(non_snake_case)]
 pub mod my_logger_grpc {
     tonic::include_proto!("my_logger");
@@ -60,11 +75,22 @@ async fn main() {
     );
     gc_timer.start(app.app_states.clone(), my_logger::LOGGER.clone());
 
+    let mut alerts_timer = MyTimer::new(Duration::from_secs(5));
+    alerts_timer.register_timer("AlertsNotifier", Arc::new(AlertsTimer::new(app.clone())));
+    alerts_timer.register_timer("TelegramBot", Arc::new(TelegramBotTimer::new(app.clone())));
+    alerts_timer.register_timer("Heartbeats", Arc::new(HeartbeatsTimer::new(app.clone())));
+    alerts_timer.start(app.app_states.clone(), my_logger::LOGGER.clone());
+
     let mut persist_timer = MyTimer::new(Duration::from_secs(60));
     persist_timer.register_timer(
         "PersistStatistics",
         Arc::new(PersistStatisticsTimer::new(app.clone())),
     );
+    persist_timer.register_timer(
+        "AnomalyDetection",
+        Arc::new(AnomalyDetectionTimer::new(app.clone())),
+    );
+    persist_timer.register_timer("Digest", Arc::new(DigestTimer::new(app.clone())));
     persist_timer.start(app.app_states.clone(), my_logger::LOGGER.clone());
 
     crate::grpc_server::start(app.clone());
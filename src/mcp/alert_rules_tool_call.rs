@@ -0,0 +1,247 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use my_ai_agent::{macros::ApplyJsonSchema, ToolDefinition};
+use serde::{Deserialize, Serialize};
+
+use mcp_server_middleware::McpToolCall;
+
+use crate::{app::AppContext, repo::dto::AlertRuleDto};
+
+fn alert_rule_to_json(item: &AlertRuleDto) -> serde_json::Value {
+    serde_json::json!({
+        "id": item.id,
+        "name": item.name,
+        "disabled": item.disabled,
+        "application": item.application,
+        "min_level": format!("{:?}", item.min_level),
+        "context": item.context,
+        "phrase": item.phrase,
+        "match_mode": format!("{:?}", item.match_mode),
+        "case_insensitive": item.case_insensitive,
+        "threshold": item.threshold,
+        "window_sec": item.window_sec,
+        "group_by": item.group_by,
+        "cooldown_sec": item.cooldown_sec,
+        "resolve_after_sec": item.resolve_after_sec,
+        "channels": item.channels,
+        "saved_search_id": item.saved_search_id,
+    })
+}
+
+fn non_negative(value: i64, field: &str) -> Result<u64, String> {
+    if value < 0 || value > u32::MAX as i64 {
+        return Err(format!("`{}` is out of range: {}", field, value));
+    }
+    Ok(value as u64)
+}
+
+// ====================== get_alert_rules ======================
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct GetAlertRulesInputData {}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct GetAlertRulesResponse {
+    #[property(description: "Number of alert rules.")]
+    pub count: i64,
+    #[property(description: "Alert rules as JSON array string. Each item: {id, name, disabled, application, min_level, context, phrase, match_mode, case_insensitive, threshold, window_sec, group_by, cooldown_sec, resolve_after_sec, channels, saved_search_id}.")]
+    pub alert_rules_json: String,
+}
+
+pub struct GetAlertRulesHandler {
+    app: Arc<AppContext>,
+}
+
+impl GetAlertRulesHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for GetAlertRulesHandler {
+    const FUNC_NAME: &'static str = "get_alert_rules";
+    const DESCRIPTION: &'static str = "List alert rules. A rule fires when more than `threshold` matching events arrive within `window_sec`, separately for every value of the `group_by` context key, and resolves after the count stays at or below the threshold for `resolve_after_sec`.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<GetAlertRulesInputData, GetAlertRulesResponse> for GetAlertRulesHandler {
+    async fn execute_tool_call(
+        &self,
+        _model: GetAlertRulesInputData,
+    ) -> Result<GetAlertRulesResponse, String> {
+        let items = self.app.alert_rules_repo.get_all().await;
+
+        let result: Vec<_> = items.iter().map(alert_rule_to_json).collect();
+
+        Ok(GetAlertRulesResponse {
+            count: result.len() as i64,
+            alert_rules_json: serde_json::to_string(&result).unwrap_or_else(|_| "[]".to_string()),
+        })
+    }
+}
+
+// ====================== save_alert_rule ======================
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct SaveAlertRuleInputData {
+    #[property(description: "Optional. Id of an existing alert rule to update. Omit to create a new one.")]
+    pub id: Option<String>,
+    #[property(description: "Human-readable name, e.g. \"payments errors burst\".")]
+    pub name: String,
+    #[property(description: "Optional. Disable the rule without deleting it. Default false.")]
+    pub disabled: Option<bool>,
+    #[property(description: "Optional. Application name (exact 'Application' context value). Omit for all applications.")]
+    pub application: Option<String>,
+    #[property(description: "Minimal level of counted events. One of: Debug, Info, Warning, Error, FatalError. Error also counts FatalError.")]
+    pub min_level: String,
+    #[property(description: "Optional. Exact context filters as `key=value` entries, e.g. [\"Version=1.4.2\"].")]
+    pub context_filters: Option<Vec<String>>,
+    #[property(description: "Optional. Phrase matched against message and process.")]
+    pub phrase: Option<String>,
+    #[property(enum: ["Contains", "Regex"], description: "Optional. How `phrase` is matched. Default Contains.")]
+    pub match_mode: Option<String>,
+    #[property(description: "Optional. Case-insensitive phrase matching. Default false.")]
+    pub case_insensitive: Option<bool>,
+    #[property(description: "Fires when more than `threshold` events arrive within the window. 0 means the first matching event fires.")]
+    pub threshold: i64,
+    #[property(description: "Window length in seconds. Range 1..86400.")]
+    pub window_sec: i64,
+    #[property(description: "Optional. Context key to group by, e.g. \"Application\". Every value is alerted separately.")]
+    pub group_by: Option<String>,
+    #[property(description: "Optional. Minimal seconds between two firing notifications of the same group. Default 600.")]
+    pub cooldown_sec: Option<i64>,
+    #[property(description: "Optional. Seconds the count has to stay at or below the threshold before the alert resolves. Default 300.")]
+    pub resolve_after_sec: Option<i64>,
+    #[property(description: "Optional. Notification channel names from settings. Omit to route by application.")]
+    pub channels: Option<Vec<String>>,
+    #[property(description: "Optional. Id of the saved search the notifications link to.")]
+    pub saved_search_id: Option<String>,
+    #[property(description: "Optional. Who makes the change, e.g. the client or user name. Recorded in the audit log.")]
+    pub actor: Option<String>,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct SaveAlertRuleResponse {
+    #[property(description: "Alert rule as JSON object string.")]
+    pub alert_rule_json: String,
+}
+
+pub struct SaveAlertRuleHandler {
+    app: Arc<AppContext>,
+}
+
+impl SaveAlertRuleHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for SaveAlertRuleHandler {
+    const FUNC_NAME: &'static str = "save_alert_rule";
+    const DESCRIPTION: &'static str = "Create or update an alert rule, e.g. application=payments, min_level=Error, threshold=20, window_sec=300 (more than 20 errors in 5 minutes), or phrase=deadlock with threshold=0 (any occurrence).";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<SaveAlertRuleInputData, SaveAlertRuleResponse> for SaveAlertRuleHandler {
+    async fn execute_tool_call(
+        &self,
+        model: SaveAlertRuleInputData,
+    ) -> Result<SaveAlertRuleResponse, String> {
+        let min_level = super::ignore_rules_tool_call::parse_level(&model.min_level)?;
+        let match_mode =
+            super::ignore_rules_tool_call::parse_match_mode(model.match_mode.as_deref())?;
+
+        let mut context = BTreeMap::new();
+        for entry in model.context_filters.unwrap_or_default() {
+            let (key, value) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("Context filter '{}' must be in form key=value", entry))?;
+            context.insert(key.trim().to_string(), value.trim().to_string());
+        }
+
+        let saved = crate::flows::alert_rules::save(
+            &self.app,
+            AlertRuleDto {
+                id: model.id.unwrap_or_default(),
+                name: model.name,
+                disabled: model.disabled.unwrap_or(false),
+                application: model.application,
+                min_level,
+                context,
+                phrase: model.phrase,
+                match_mode,
+                case_insensitive: model.case_insensitive.unwrap_or(false),
+                threshold: non_negative(model.threshold, "threshold")? as u32,
+                window_sec: non_negative(model.window_sec, "window_sec")?,
+                group_by: model.group_by,
+                cooldown_sec: non_negative(model.cooldown_sec.unwrap_or(600), "cooldown_sec")?,
+                resolve_after_sec: non_negative(
+                    model.resolve_after_sec.unwrap_or(300),
+                    "resolve_after_sec",
+                )?,
+                channels: model.channels.unwrap_or_default(),
+                saved_search_id: model.saved_search_id,
+            },
+            &super::get_mcp_audit_actor(model.actor.as_deref()),
+        )
+        .await?;
+
+        Ok(SaveAlertRuleResponse {
+            alert_rule_json: alert_rule_to_json(&saved).to_string(),
+        })
+    }
+}
+
+// ====================== delete_alert_rule ======================
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct DeleteAlertRuleInputData {
+    #[property(description: "Id of the alert rule to delete (see `get_alert_rules`).")]
+    pub id: String,
+    #[property(description: "Optional. Who makes the change, e.g. the client or user name. Recorded in the audit log.")]
+    pub actor: Option<String>,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct DeleteAlertRuleResponse {
+    #[property(description: "Human-readable result message.")]
+    pub message: String,
+}
+
+pub struct DeleteAlertRuleHandler {
+    app: Arc<AppContext>,
+}
+
+impl DeleteAlertRuleHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for DeleteAlertRuleHandler {
+    const FUNC_NAME: &'static str = "delete_alert_rule";
+    const DESCRIPTION: &'static str = "Delete an alert rule by id.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<DeleteAlertRuleInputData, DeleteAlertRuleResponse> for DeleteAlertRuleHandler {
+    async fn execute_tool_call(
+        &self,
+        model: DeleteAlertRuleInputData,
+    ) -> Result<DeleteAlertRuleResponse, String> {
+        let deleted = crate::flows::alert_rules::delete(
+            &self.app,
+            &model.id,
+            &super::get_mcp_audit_actor(model.actor.as_deref()),
+        )
+        .await;
+
+        let message = if deleted {
+            format!("Alert rule '{}' deleted.", model.id)
+        } else {
+            format!("Alert rule '{}' not found.", model.id)
+        };
+
+        Ok(DeleteAlertRuleResponse { message })
+    }
+}
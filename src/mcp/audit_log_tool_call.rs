@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use my_ai_agent::{macros::ApplyJsonSchema, ToolDefinition};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::{Deserialize, Serialize};
+
+use mcp_server_middleware::McpToolCall;
+
+use crate::{
+    app::AppContext,
+    repo::dto::{AuditActorKindDto, AuditLogEntryDto, AuditLogFilterDto, AuditTargetDto},
+};
+
+const DEFAULT_TAKE: i64 = 50;
+const MAX_TAKE: i64 = 500;
+
+fn audit_log_entry_to_json(item: &AuditLogEntryDto) -> serde_json::Value {
+    serde_json::json!({
+        "moment": DateTimeAsMicroseconds::new(item.moment).to_rfc3339(),
+        "actor": item.actor.to_string(),
+        "target": format!("{:?}", item.target),
+        "action": format!("{:?}", item.action),
+        "id": item.id,
+        "before": item.before,
+        "after": item.after,
+    })
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct GetAuditLogInputData {
+    #[property(description: "Optional. Range start, ISO-8601 / RFC-3339 in UTC. Inclusive.")]
+    pub from_date: Option<String>,
+    #[property(description: "Optional. Range end, ISO-8601 / RFC-3339 in UTC. Inclusive.")]
+    pub to_date: Option<String>,
+    #[property(
+        enum: ["IgnoreRule", "SingleEventRule", "SavedSearch", "AlertRule"],
+        description: "Optional. Only changes of this kind of configuration."
+    )]
+    pub target: Option<String>,
+    #[property(description: "Optional. Only changes of the rule or saved search with this id.")]
+    pub id: Option<String>,
+    #[property(
+        enum: ["Http", "Grpc", "Mcp", "Telegram"],
+        description: "Optional. Only changes made through this interface."
+    )]
+    pub actor_kind: Option<String>,
+    #[property(description: "Optional. Max number of entries, newest first. Default 50, max 500.")]
+    pub take: Option<i64>,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct GetAuditLogResponse {
+    #[property(description: "Number of returned entries.")]
+    pub count: i64,
+    #[property(description: "Entries as JSON array string, newest first. Each item: {moment, actor (kind and the name the client gave), target, action (Created, Updated, Deleted), id, before, after}.")]
+    pub entries_json: String,
+}
+
+pub struct GetAuditLogHandler {
+    app: Arc<AppContext>,
+}
+
+impl GetAuditLogHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for GetAuditLogHandler {
+    const FUNC_NAME: &'static str = "get_audit_log";
+    const DESCRIPTION: &'static str = "Read the audit trail of configuration changes: ignore rules, single event rules, saved searches and alert rules. Every entry tells who changed what and when, with the value before and after the change. Use it to find out why events started to be suppressed or alerts stopped.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<GetAuditLogInputData, GetAuditLogResponse> for GetAuditLogHandler {
+    async fn execute_tool_call(
+        &self,
+        model: GetAuditLogInputData,
+    ) -> Result<GetAuditLogResponse, String> {
+        let mut filter = AuditLogFilterDto {
+            id: model.id,
+            ..Default::default()
+        };
+
+        if let Some(from_date) = model.from_date.as_deref() {
+            filter.from = Some(
+                super::search_logs_tool_call::parse_iso_date(from_date, "from_date")?
+                    .unix_microseconds,
+            );
+        }
+
+        if let Some(to_date) = model.to_date.as_deref() {
+            filter.to = Some(
+                super::search_logs_tool_call::parse_iso_date(to_date, "to_date")?.unix_microseconds,
+            );
+        }
+
+        if let Some(target) = model.target.as_deref() {
+            filter.target = Some(AuditTargetDto::parse(target)?);
+        }
+
+        if let Some(actor_kind) = model.actor_kind.as_deref() {
+            filter.actor_kind = Some(AuditActorKindDto::parse(actor_kind)?);
+        }
+
+        let take = model.take.unwrap_or(DEFAULT_TAKE).clamp(1, MAX_TAKE) as usize;
+
+        let items = self.app.audit_log_repo.query(&filter, take).await;
+
+        let result: Vec<_> = items.iter().map(audit_log_entry_to_json).collect();
+
+        Ok(GetAuditLogResponse {
+            count: result.len() as i64,
+            entries_json: serde_json::to_string(&result).unwrap_or_else(|_| "[]".to_string()),
+        })
+    }
+}
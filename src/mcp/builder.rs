@@ -7,7 +7,7 @@ use crate::app::AppContext;
 const MCP_PATH: &str = "/mcp";
 const MCP_NAME: &str = "MyLogger";
 const MCP_VERSION: &str = env!("CARGO_PKG_VERSION");
-const MCP_INSTRUCTIONS: &str = "MyLogger MCP server. Workflow for incident investigation: (1) call `get_dashboard_statistics` first to see hourly per-application Error / FatalError counts and identify which application and hour have problems; (2) call `search_logs` with a tight date range, the offending `application` (and optionally `version`), and an optional `phrase` for full-text search to retrieve the actual log records. All times are ISO-8601 / RFC-3339 in UTC. To suppress known-noise events, manage ignore rules with `get_ignore_rules`, `add_ignore_rule` and `delete_ignore_rule` (level + application + context marker; \"*\" is a wildcard).";
+const MCP_INSTRUCTIONS: &str = "MyLogger MCP server. Workflow for incident investigation: (1) call `get_dashboard_statistics` first to see per-application Error / FatalError counts (minute buckets for the last day, hourly for 30 days, daily for a year) and identify which application and period have problems, then `get_dimension_statistics` to break the events down by a configured context key such as Version or Host; (2) call `search_logs` with a tight date range, the offending `application` (and optionally `version`), and an optional `phrase` for full-text search to retrieve the actual log records. All times are ISO-8601 / RFC-3339 in UTC. To suppress known-noise events, manage ignore rules with `get_ignore_rules`, `add_ignore_rule` and `delete_ignore_rule` (either level + application + a marker searched in process and message, \"*\" being a wildcard, or levels + conditions over message, process and any context key); dry-run a candidate with `preview_ignore_rule` before adding it. Recurring investigations are stored as saved searches: `get_saved_searches`, `save_search`, `delete_saved_search`. Alerting thresholds are managed with `get_alert_rules`, `save_alert_rule`, `delete_alert_rule`. Recurring errors are grouped into issues by message template: `get_issues` lists them and `set_issue_status` resolves, ignores or reopens one. After a deploy, `get_version_regression` compares the error and fatal rates of the latest (or a given) version with the previous one and lists the issues new in it. Every configuration change is recorded: `get_audit_log` tells who changed which rule and when, with the value before and after.";
 
 pub async fn build_mcp_middleware(app: &Arc<AppContext>) -> McpMiddleware {
     let mut middleware = McpMiddleware::new(MCP_PATH, MCP_NAME, MCP_VERSION, MCP_INSTRUCTIONS);
@@ -17,10 +17,29 @@ pub async fn build_mcp_middleware(app: &Arc<AppContext>) -> McpMiddleware {
     middleware.register_tool_call(Arc::new(super::DashboardStatisticsHandler::new(
         app.clone(),
     )));
+    middleware.register_tool_call(Arc::new(super::DimensionStatisticsHandler::new(
+        app.clone(),
+    )));
 
     middleware.register_tool_call(Arc::new(super::GetIgnoreRulesHandler::new(app.clone())));
     middleware.register_tool_call(Arc::new(super::AddIgnoreRuleHandler::new(app.clone())));
     middleware.register_tool_call(Arc::new(super::DeleteIgnoreRuleHandler::new(app.clone())));
+    middleware.register_tool_call(Arc::new(super::PreviewIgnoreRuleHandler::new(app.clone())));
+
+    middleware.register_tool_call(Arc::new(super::GetSavedSearchesHandler::new(app.clone())));
+    middleware.register_tool_call(Arc::new(super::SaveSearchHandler::new(app.clone())));
+    middleware.register_tool_call(Arc::new(super::DeleteSavedSearchHandler::new(app.clone())));
+
+    middleware.register_tool_call(Arc::new(super::GetAlertRulesHandler::new(app.clone())));
+    middleware.register_tool_call(Arc::new(super::SaveAlertRuleHandler::new(app.clone())));
+    middleware.register_tool_call(Arc::new(super::DeleteAlertRuleHandler::new(app.clone())));
+
+    middleware.register_tool_call(Arc::new(super::GetIssuesHandler::new(app.clone())));
+    middleware.register_tool_call(Arc::new(super::SetIssueStatusHandler::new(app.clone())));
+
+    middleware.register_tool_call(Arc::new(super::VersionRegressionHandler::new(app.clone())));
+
+    middleware.register_tool_call(Arc::new(super::GetAuditLogHandler::new(app.clone())));
 
     middleware
 }
@@ -1,27 +1,43 @@
 use std::sync::Arc;
 
 use my_ai_agent::{macros::ApplyJsonSchema, ToolDefinition};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 use serde::{Deserialize, Serialize};
 
 use mcp_server_middleware::McpToolCall;
 
-use crate::app::AppContext;
+use crate::{app::AppContext, rollup_statistics::RollupResolution};
 
 const DEFAULT_HOURS: i64 = 24;
-const MAX_HOURS: i64 = 48;
+const MAX_HOURS: i64 = 24 * 365;
 
 #[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
 pub struct DashboardStatisticsInputData {
-    #[property(description: "How many recent hours to include. Default 24, hard cap 48 (server keeps 48 hours of hourly stats).")]
+    #[property(description: "How many recent hours to include when `from_date` is omitted. Default 24, hard cap 8760 (one year).")]
     pub hours: Option<i64>,
+
+    #[property(description: "Optional start of the range, ISO-8601 / RFC-3339 in UTC. Overrides `hours`.")]
+    pub from_date: Option<String>,
+
+    #[property(description: "Optional end of the range, ISO-8601 / RFC-3339 in UTC. Now by default.")]
+    pub to_date: Option<String>,
+
+    #[property(description: "Optional bucket size: Minute (kept for 24 hours), Hour (30 days) or Day (one year). Selected by the range if omitted.")]
+    pub resolution: Option<String>,
+
+    #[property(description: "Optional application name to limit the buckets to.")]
+    pub application: Option<String>,
 }
 
 #[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
 pub struct DashboardStatisticsResponse {
-    #[property(description: "Hourly buckets, newest hour first, as JSON array string. Each item: {hour: u64 in YYYYMMDDHH UTC form, applications: [{application, info, warning, error, fatal, debug}]}.")]
+    #[property(description: "Bucket size used: Minute, Hour or Day.")]
+    pub resolution: String,
+
+    #[property(description: "Buckets, newest first, as JSON array string. Each item: {start: RFC-3339 UTC start of the bucket, applications: [{application, info, warning, error, fatal, debug, sampled}]}. `sampled` counts events included in the other counts but not stored because of sampling rules.")]
     pub buckets_json: String,
 
-    #[property(description: "Total Info-level events across all returned hours.")]
+    #[property(description: "Total Info-level events across all returned buckets.")]
     pub total_info: i64,
     #[property(description: "Total Warning-level events.")]
     pub total_warning: i64,
@@ -45,7 +61,7 @@ impl DashboardStatisticsHandler {
 
 impl ToolDefinition for DashboardStatisticsHandler {
     const FUNC_NAME: &'static str = "get_dashboard_statistics";
-    const DESCRIPTION: &'static str = "Get dashboard-style hourly statistics: per-hour, per-application counts of Info / Warning / Error / FatalError / Debug events. Use this first to spot which application/hour has errors, then call `search_logs` with a tighter time range and the offending Application to investigate.";
+    const DESCRIPTION: &'static str = "Get dashboard-style statistics: per-application counts of Info / Warning / Error / FatalError / Debug events in minute (last 24 hours), hour (last 30 days) or day (last year) buckets. Use this first to spot which application/period has errors, then call `search_logs` with a tighter time range and the offending Application to investigate.";
 }
 
 #[async_trait::async_trait]
@@ -56,16 +72,36 @@ impl McpToolCall<DashboardStatisticsInputData, DashboardStatisticsResponse>
         &self,
         model: DashboardStatisticsInputData,
     ) -> Result<DashboardStatisticsResponse, String> {
-        let hours = model
-            .hours
-            .unwrap_or(DEFAULT_HOURS)
-            .clamp(1, MAX_HOURS) as usize;
-
-        let snapshot = {
-            let read_access = self.app.hourly_statistics.lock().await;
-            read_access.get_max_hours(hours)
+        let to = model
+            .to_date
+            .as_deref()
+            .map(|value| super::search_logs_tool_call::parse_iso_date(value, "to_date"))
+            .transpose()?
+            .unwrap_or_else(DateTimeAsMicroseconds::now);
+
+        let from = match model.from_date.as_deref() {
+            Some(value) => super::search_logs_tool_call::parse_iso_date(value, "from_date")?,
+            None => {
+                let hours = model.hours.unwrap_or(DEFAULT_HOURS).clamp(1, MAX_HOURS);
+                DateTimeAsMicroseconds::new(to.unix_microseconds - hours * 60 * 60 * 1_000_000)
+            }
         };
 
+        let resolution = model
+            .resolution
+            .as_deref()
+            .map(RollupResolution::parse)
+            .transpose()?;
+
+        let (resolution, snapshot) = crate::flows::get_rollup_statistics(
+            &self.app,
+            Some(from.unix_microseconds),
+            Some(to.unix_microseconds),
+            resolution,
+            model.application.as_deref(),
+        )
+        .await?;
+
         let mut total_info: i64 = 0;
         let mut total_warning: i64 = 0;
         let mut total_error: i64 = 0;
@@ -74,9 +110,9 @@ impl McpToolCall<DashboardStatisticsInputData, DashboardStatisticsResponse>
 
         let mut buckets = Vec::with_capacity(snapshot.len());
 
-        for (hour, by_app) in snapshot {
-            let mut applications = Vec::with_capacity(by_app.len());
-            for (app_name, item) in by_app {
+        for bucket in snapshot.into_iter().rev() {
+            let mut applications = Vec::with_capacity(bucket.apps.len());
+            for (app_name, item) in bucket.apps {
                 total_info += item.info as i64;
                 total_warning += item.warning as i64;
                 total_error += item.error as i64;
@@ -90,11 +126,12 @@ impl McpToolCall<DashboardStatisticsInputData, DashboardStatisticsResponse>
                     "error": item.error,
                     "fatal": item.fatal_error,
                     "debug": item.debug,
+                    "sampled": item.sampled,
                 }));
             }
 
             buckets.push(serde_json::json!({
-                "hour": hour.get_value(),
+                "start": DateTimeAsMicroseconds::new(bucket.start * 1_000_000).to_rfc3339(),
                 "applications": applications,
             }));
         }
@@ -102,6 +139,7 @@ impl McpToolCall<DashboardStatisticsInputData, DashboardStatisticsResponse>
         let buckets_json = serde_json::to_string(&buckets).unwrap_or_else(|_| "[]".to_string());
 
         Ok(DashboardStatisticsResponse {
+            resolution: format!("{:?}", resolution),
             buckets_json,
             total_info,
             total_warning,
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use my_ai_agent::{macros::ApplyJsonSchema, ToolDefinition};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::{Deserialize, Serialize};
+
+use mcp_server_middleware::McpToolCall;
+
+use crate::{app::AppContext, statistic_dimensions::DimensionOrder};
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct DimensionStatisticsInputData {
+    #[property(description: "Context key configured as a statistic dimension, e.g. Version or Host. The error message lists the configured keys when the key is unknown.")]
+    pub key: String,
+
+    #[property(description: "Optional start of the range, ISO-8601 / RFC-3339 in UTC. 24 hours before `to_date` by default. Counts are kept by hour for 7 days.")]
+    pub from_date: Option<String>,
+
+    #[property(description: "Optional end of the range, ISO-8601 / RFC-3339 in UTC. Now by default.")]
+    pub to_date: Option<String>,
+
+    #[property(description: "Optional order: Total (default) or Errors (Error + FatalError).")]
+    pub order: Option<String>,
+
+    #[property(description: "Optional number of values to return. Default 10, hard cap 100.")]
+    pub take: Option<usize>,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct DimensionStatisticsResponse {
+    #[property(description: "Top values as JSON array string. Each item: {value, info, warning, error, fatal, debug, sampled, first_seen, last_seen}. `(none)` counts events without the key, `(other)` counts values above the cardinality limit of the dimension.")]
+    pub values_json: String,
+}
+
+pub struct DimensionStatisticsHandler {
+    app: Arc<AppContext>,
+}
+
+impl DimensionStatisticsHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for DimensionStatisticsHandler {
+    const FUNC_NAME: &'static str = "get_dimension_statistics";
+    const DESCRIPTION: &'static str = "Get the top values of a configured context key (e.g. Version, Host) with counts of events by level. Use it to find which version or host produces the errors.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<DimensionStatisticsInputData, DimensionStatisticsResponse>
+    for DimensionStatisticsHandler
+{
+    async fn execute_tool_call(
+        &self,
+        model: DimensionStatisticsInputData,
+    ) -> Result<DimensionStatisticsResponse, String> {
+        let from = model
+            .from_date
+            .as_deref()
+            .map(|value| super::search_logs_tool_call::parse_iso_date(value, "from_date"))
+            .transpose()?;
+
+        let to = model
+            .to_date
+            .as_deref()
+            .map(|value| super::search_logs_tool_call::parse_iso_date(value, "to_date"))
+            .transpose()?;
+
+        let order = model
+            .order
+            .as_deref()
+            .map(DimensionOrder::parse)
+            .transpose()?
+            .unwrap_or(DimensionOrder::Total);
+
+        let values = crate::flows::get_dimension_statistics(
+            &self.app,
+            model.key.as_str(),
+            from.map(|from| from.unix_microseconds),
+            to.map(|to| to.unix_microseconds),
+            order,
+            model.take,
+        )
+        .await?;
+
+        let values: Vec<_> = values
+            .into_iter()
+            .map(|item| {
+                serde_json::json!({
+                    "value": item.value,
+                    "info": item.counts.info,
+                    "warning": item.counts.warning,
+                    "error": item.counts.error,
+                    "fatal": item.counts.fatal_error,
+                    "debug": item.counts.debug,
+                    "sampled": item.counts.sampled,
+                    "first_seen": DateTimeAsMicroseconds::new(item.seen.first_seen).to_rfc3339(),
+                    "last_seen": DateTimeAsMicroseconds::new(item.seen.last_seen).to_rfc3339(),
+                })
+            })
+            .collect();
+
+        Ok(DimensionStatisticsResponse {
+            values_json: serde_json::to_string(&values).unwrap_or_else(|_| "[]".to_string()),
+        })
+    }
+}
@@ -9,10 +9,13 @@ use mcp_server_middleware::McpToolCall;
 
 use crate::{
     app::AppContext,
-    repo::dto::{IgnoreItemDto, IgnoreWhereModel, LogLevelDto},
+    repo::dto::{
+        get_marker_rule_id, ConditionOperatorDto, IgnoreConditionDto, IgnoreConditionFieldDto,
+        IgnoreItemDto, LogLevelDto, MatchModeDto,
+    },
 };
 
-fn parse_level(level: &str) -> Result<LogLevelDto, String> {
+pub(super) fn parse_level(level: &str) -> Result<LogLevelDto, String> {
     match level.trim().to_lowercase().as_str() {
         "info" => Ok(LogLevelDto::Info),
         "warning" | "warn" => Ok(LogLevelDto::Warning),
@@ -26,6 +29,115 @@ fn parse_level(level: &str) -> Result<LogLevelDto, String> {
     }
 }
 
+pub(super) fn parse_match_mode(value: Option<&str>) -> Result<MatchModeDto, String> {
+    let Some(value) = value else {
+        return Ok(MatchModeDto::Contains);
+    };
+
+    match value.trim().to_lowercase().as_str() {
+        "" | "contains" => Ok(MatchModeDto::Contains),
+        "regex" => Ok(MatchModeDto::Regex),
+        _ => Err(format!(
+            "Unknown match mode '{}'. Expected one of: Contains, Regex.",
+            value
+        )),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct IgnoreConditionJsonModel {
+    field: String,
+    key: Option<String>,
+    operator: Option<String>,
+    value: String,
+    #[serde(default)]
+    case_insensitive: bool,
+}
+
+fn parse_conditions(conditions_json: Option<&str>) -> Result<Vec<IgnoreConditionDto>, String> {
+    let Some(conditions_json) = conditions_json else {
+        return Ok(vec![]);
+    };
+
+    let items: Vec<IgnoreConditionJsonModel> = serde_json::from_str(conditions_json)
+        .map_err(|err| format!("Invalid `conditions_json`: {}", err))?;
+
+    items
+        .into_iter()
+        .map(|itm| {
+            Ok(IgnoreConditionDto {
+                field: IgnoreConditionFieldDto::parse(&itm.field, itm.key)?,
+                operator: ConditionOperatorDto::parse(itm.operator.as_deref())?,
+                value: itm.value,
+                case_insensitive: itm.case_insensitive,
+            })
+        })
+        .collect()
+}
+
+/// Builds the rule either of the level/application/marker shape or of levels and conditions.
+fn build_rule(
+    level: Option<&str>,
+    application: Option<&str>,
+    marker: Option<&str>,
+    match_mode: Option<&str>,
+    case_insensitive: Option<bool>,
+    levels: Option<&[String]>,
+    conditions_json: Option<&str>,
+) -> Result<IgnoreItemDto, String> {
+    if let Some(level) = level {
+        if levels.is_some() || conditions_json.is_some() {
+            return Err(
+                "Use either `level`/`application`/`marker` or `levels`/`conditions_json`."
+                    .to_string(),
+            );
+        }
+
+        return Ok(IgnoreItemDto::from_marker(
+            parse_level(level)?,
+            application.unwrap_or("*"),
+            marker.unwrap_or("*"),
+            parse_match_mode(match_mode)?,
+            case_insensitive.unwrap_or(false),
+            None,
+        ));
+    }
+
+    if application.is_some() || marker.is_some() {
+        return Err(
+            "`application` and `marker` need `level`. Use `conditions_json` with `levels` instead."
+                .to_string(),
+        );
+    }
+
+    Ok(IgnoreItemDto {
+        id: String::new(),
+        levels: levels
+            .unwrap_or_default()
+            .iter()
+            .map(|level| parse_level(level))
+            .collect::<Result<_, _>>()?,
+        conditions: parse_conditions(conditions_json)?,
+        expires_at: None,
+        statistics: Default::default(),
+    })
+}
+
+fn condition_to_json(condition: &IgnoreConditionDto) -> serde_json::Value {
+    let (field, key) = match &condition.field {
+        IgnoreConditionFieldDto::Context(key) => ("Context".to_string(), Some(key.clone())),
+        field => (field.to_string(), None),
+    };
+
+    serde_json::json!({
+        "field": field,
+        "key": key,
+        "operator": format!("{:?}", condition.operator),
+        "value": condition.value,
+        "case_insensitive": condition.case_insensitive,
+    })
+}
+
 // ====================== get_ignore_rules ======================
 
 #[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
@@ -35,7 +147,7 @@ pub struct GetIgnoreRulesInputData {}
 pub struct GetIgnoreRulesResponse {
     #[property(description: "Number of ignore rules currently configured.")]
     pub count: i64,
-    #[property(description: "Ignore rules as JSON array string. Each item: {level, application, marker, expires_at}. `expires_at` is an ISO-8601 / RFC-3339 UTC moment, or null for a rule that never expires.")]
+    #[property(description: "Ignore rules as JSON array string. Each item: {id, levels, conditions: [{field, key, operator, value, case_insensitive}], description, expires_at}. Empty `levels` means every level. `expires_at` is an ISO-8601 / RFC-3339 UTC moment, or null for a rule that never expires.")]
     pub rules_json: String,
 }
 
@@ -51,7 +163,7 @@ impl GetIgnoreRulesHandler {
 
 impl ToolDefinition for GetIgnoreRulesHandler {
     const FUNC_NAME: &'static str = "get_ignore_rules";
-    const DESCRIPTION: &'static str = "List the ignore (suppression) rules. A matching log record (by level + application + context marker) is dropped before it is stored or alerted on. Use this to see what is currently being suppressed before adding or removing a rule.";
+    const DESCRIPTION: &'static str = "List the ignore (suppression) rules. A log record of one of the rule's levels matching all of its conditions (over message, process or any context key) is dropped before it is stored or alerted on. Use this to see what is currently being suppressed before adding or removing a rule. Every rule reports how many records it suppressed (total, last hour, last day), when it last matched and whether it is unused (no matches for IgnoreRuleUnusedDays, 30 by default) and can be removed.";
 }
 
 #[async_trait::async_trait]
@@ -61,17 +173,40 @@ impl McpToolCall<GetIgnoreRulesInputData, GetIgnoreRulesResponse> for GetIgnoreR
         _model: GetIgnoreRulesInputData,
     ) -> Result<GetIgnoreRulesResponse, String> {
         let items = self.app.settings_repo.get_ignore_events().await;
+        let now = DateTimeAsMicroseconds::now();
+        let unused_days = self.app.settings_reader.get_ignore_rule_unused_days().await;
 
         let rules: Vec<_> = items
             .iter()
             .map(|itm| {
+                let statistics = itm.statistics.get_summary(now, unused_days);
                 serde_json::json!({
-                    "level": format!("{:?}", itm.level),
-                    "application": itm.application,
-                    "marker": itm.marker,
+                    "id": itm.id,
+                    "levels": itm
+                        .levels
+                        .iter()
+                        .map(|level| format!("{:?}", level))
+                        .collect::<Vec<_>>(),
+                    "conditions": itm
+                        .conditions
+                        .iter()
+                        .map(condition_to_json)
+                        .collect::<Vec<_>>(),
+                    "description": format!(
+                        "{} | {}",
+                        itm.get_levels_description(),
+                        itm.get_conditions_description()
+                    ),
                     "expires_at": itm
                         .expires_at
                         .map(|micros| DateTimeAsMicroseconds::new(micros).to_rfc3339()),
+                    "suppressed_total": statistics.total,
+                    "suppressed_last_hour": statistics.last_hour,
+                    "suppressed_last_day": statistics.last_day,
+                    "last_matched": statistics
+                        .last_matched
+                        .map(|micros| DateTimeAsMicroseconds::new(micros).to_rfc3339()),
+                    "unused": statistics.unused,
                 })
             })
             .collect();
@@ -87,14 +222,32 @@ impl McpToolCall<GetIgnoreRulesInputData, GetIgnoreRulesResponse> for GetIgnoreR
 
 #[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
 pub struct AddIgnoreRuleInputData {
-    #[property(description: "Log level the rule applies to. One of: Info, Warning, Error, FatalError, Debug.")]
-    pub level: String,
-    #[property(description: "Application name the rule applies to. Use \"*\" to match any application.")]
-    pub application: String,
-    #[property(description: "Context marker (a context key present on the log record) the rule matches. Use \"*\" to match any record of the given level/application.")]
-    pub marker: String,
-    #[property(description: "Optional. Auto-remove this rule after the given number of minutes. Omit for a permanent rule. If a rule with the same level/application/marker already exists, its expiration is updated.")]
+    #[property(description: "Optional. Id of the rule to replace. Omit to create a new rule; a level/application/marker rule gets an id derived from them.")]
+    pub id: Option<String>,
+    #[property(description: "Optional. Simple rule: log level it applies to. One of: Info, Warning, Error, FatalError, Debug. Omit to use `levels` and `conditions_json`.")]
+    pub level: Option<String>,
+    #[property(description: "Optional. Simple rule: application name (exact 'Application' context value). Default \"*\", any application.")]
+    pub application: Option<String>,
+    #[property(description: "Optional. Simple rule: text searched in the process and the message of the record. Default \"*\", any record of the given level/application.")]
+    pub marker: Option<String>,
+    #[property(
+        enum: ["Contains", "Regex"],
+        description: "Optional. Simple rule: how the marker is matched against process and message. Default Contains. Regex patterns are validated and rejected if they match an empty string."
+    )]
+    pub match_mode: Option<String>,
+    #[property(description: "Optional. Simple rule: match the marker ignoring case. Default false.")]
+    pub case_insensitive: Option<bool>,
+    #[property(
+        enum: ["Info", "Warning", "Error", "FatalError", "Debug"],
+        description: "Optional. Condition rule: levels it applies to. Omit or leave empty for every level."
+    )]
+    pub levels: Option<Vec<String>>,
+    #[property(description: "Optional. Condition rule: JSON array string of conditions that all have to match. Each item: {field: Message|Process|ProcessOrMessage|Context, key: context key for the Context field, operator: Equals|Contains|Prefix|Regex (default Contains), value, case_insensitive}. Example: [{\"field\":\"Context\",\"key\":\"Env\",\"operator\":\"Equals\",\"value\":\"test\"}].")]
+    pub conditions_json: Option<String>,
+    #[property(description: "Optional. Auto-remove this rule after the given number of minutes. Omit for a permanent rule. If a rule with the same id already exists, it is replaced.")]
     pub expires_in_minutes: Option<i64>,
+    #[property(description: "Optional. Who makes the change, e.g. the client or user name. Recorded in the audit log.")]
+    pub actor: Option<String>,
 }
 
 #[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
@@ -115,7 +268,7 @@ impl AddIgnoreRuleHandler {
 
 impl ToolDefinition for AddIgnoreRuleHandler {
     const FUNC_NAME: &'static str = "add_ignore_rule";
-    const DESCRIPTION: &'static str = "Add an ignore (suppression) rule. Log records matching the given level + application + context marker are dropped before they are stored or alerted on. Use marker \"*\" to suppress every record of that level/application, and application \"*\" to match any application.";
+    const DESCRIPTION: &'static str = "Add an ignore (suppression) rule. Matching log records are dropped before they are stored or alerted on. Either a simple rule: `level` + `application` + `marker` searched in process and message (\"*\" matches anything), or a condition rule: `levels` + `conditions_json` over message, process or any context key, with Equals, Contains, Prefix or Regex operators.";
 }
 
 #[async_trait::async_trait]
@@ -124,30 +277,40 @@ impl McpToolCall<AddIgnoreRuleInputData, ManageIgnoreRuleResponse> for AddIgnore
         &self,
         model: AddIgnoreRuleInputData,
     ) -> Result<ManageIgnoreRuleResponse, String> {
-        let level = parse_level(&model.level)?;
-
         let expires_at = match model.expires_in_minutes {
             Some(minutes) => {
                 if minutes <= 0 {
                     return Err("`expires_in_minutes` must be a positive number.".to_string());
                 }
-                let dt = DateTimeAsMicroseconds::now()
-                    .add(Duration::from_secs(minutes as u64 * 60));
+                let dt =
+                    DateTimeAsMicroseconds::now().add(Duration::from_secs(minutes as u64 * 60));
                 Some(dt.unix_microseconds)
             }
             None => None,
         };
 
-        crate::flows::add_ignore_event(
+        let mut rule = build_rule(
+            model.level.as_deref(),
+            model.application.as_deref(),
+            model.marker.as_deref(),
+            model.match_mode.as_deref(),
+            model.case_insensitive,
+            model.levels.as_deref(),
+            model.conditions_json.as_deref(),
+        )?;
+
+        rule.expires_at = expires_at;
+
+        if let Some(id) = model.id {
+            rule.id = id;
+        }
+
+        let saved = crate::flows::add_ignore_event(
             &self.app,
-            IgnoreItemDto {
-                level: level.clone(),
-                application: model.application.clone(),
-                marker: model.marker.clone(),
-                expires_at,
-            },
+            rule,
+            &super::get_mcp_audit_actor(model.actor.as_deref()),
         )
-        .await;
+        .await?;
 
         let expiry_text = match expires_at {
             Some(micros) => format!(
@@ -159,8 +322,11 @@ impl McpToolCall<AddIgnoreRuleInputData, ManageIgnoreRuleResponse> for AddIgnore
 
         Ok(ManageIgnoreRuleResponse {
             message: format!(
-                "Ignore rule added: level={:?}, application='{}', marker='{}'{}.",
-                level, model.application, model.marker, expiry_text
+                "Ignore rule added: id='{}', levels={}, conditions={}{}.",
+                saved.id,
+                saved.get_levels_description(),
+                saved.get_conditions_description(),
+                expiry_text
             ),
         })
     }
@@ -170,12 +336,16 @@ impl McpToolCall<AddIgnoreRuleInputData, ManageIgnoreRuleResponse> for AddIgnore
 
 #[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
 pub struct DeleteIgnoreRuleInputData {
-    #[property(description: "Log level of the rule to delete. One of: Info, Warning, Error, FatalError, Debug.")]
-    pub level: String,
-    #[property(description: "Application name of the rule to delete (must match exactly, including \"*\").")]
-    pub application: String,
-    #[property(description: "Context marker of the rule to delete (must match exactly, including \"*\").")]
-    pub marker: String,
+    #[property(description: "Optional. Id of the rule to delete (see `get_ignore_rules`).")]
+    pub id: Option<String>,
+    #[property(description: "Optional. Instead of the id: log level of the simple rule to delete. One of: Info, Warning, Error, FatalError, Debug.")]
+    pub level: Option<String>,
+    #[property(description: "Optional. Instead of the id: application name of the simple rule to delete (must match exactly, including \"*\").")]
+    pub application: Option<String>,
+    #[property(description: "Optional. Instead of the id: marker of the simple rule to delete (must match exactly, including \"*\").")]
+    pub marker: Option<String>,
+    #[property(description: "Optional. Who makes the change, e.g. the client or user name. Recorded in the audit log.")]
+    pub actor: Option<String>,
 }
 
 pub struct DeleteIgnoreRuleHandler {
@@ -190,7 +360,7 @@ impl DeleteIgnoreRuleHandler {
 
 impl ToolDefinition for DeleteIgnoreRuleHandler {
     const FUNC_NAME: &'static str = "delete_ignore_rule";
-    const DESCRIPTION: &'static str = "Delete an existing ignore (suppression) rule. The level + application + marker must match an existing rule exactly (see `get_ignore_rules`). After deletion matching log records are stored and alerted on again.";
+    const DESCRIPTION: &'static str = "Delete an existing ignore (suppression) rule by its id, or by level + application + marker of a simple rule (see `get_ignore_rules`). After deletion matching log records are stored and alerted on again.";
 }
 
 #[async_trait::async_trait]
@@ -199,23 +369,146 @@ impl McpToolCall<DeleteIgnoreRuleInputData, ManageIgnoreRuleResponse> for Delete
         &self,
         model: DeleteIgnoreRuleInputData,
     ) -> Result<ManageIgnoreRuleResponse, String> {
-        let level = parse_level(&model.level)?;
+        let id = match (model.id, model.level, model.application, model.marker) {
+            (Some(id), _, _, _) => id,
+            (None, Some(level), Some(application), Some(marker)) => {
+                get_marker_rule_id(&parse_level(&level)?, &application, &marker)
+            }
+            _ => {
+                return Err(
+                    "Either `id` or `level`, `application` and `marker` are required.".to_string(),
+                )
+            }
+        };
 
-        crate::flows::remove_ignore_event(
+        let message = if crate::flows::remove_ignore_event(
             &self.app,
-            IgnoreWhereModel {
-                level: level.clone(),
-                application: model.application.clone(),
-                marker: model.marker.clone(),
-            },
+            &id,
+            &super::get_mcp_audit_actor(model.actor.as_deref()),
         )
-        .await;
+        .await
+        {
+            format!("Ignore rule '{}' removed.", id)
+        } else {
+            format!("No ignore rule with id '{}'.", id)
+        };
 
-        Ok(ManageIgnoreRuleResponse {
-            message: format!(
-                "Ignore rule removed (if it existed): level={:?}, application='{}', marker='{}'.",
-                level, model.application, model.marker
-            ),
+        Ok(ManageIgnoreRuleResponse { message })
+    }
+}
+
+// ====================== preview_ignore_rule ======================
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct PreviewIgnoreRuleInputData {
+    #[property(description: "Optional. Simple rule: log level of the candidate rule. One of: Info, Warning, Error, FatalError, Debug. Omit to use `levels` and `conditions_json`.")]
+    pub level: Option<String>,
+    #[property(description: "Optional. Simple rule: application name of the candidate rule. Default \"*\".")]
+    pub application: Option<String>,
+    #[property(description: "Optional. Simple rule: text searched in process and message. Default \"*\", every record of the given level/application.")]
+    pub marker: Option<String>,
+    #[property(
+        enum: ["Contains", "Regex"],
+        description: "Optional. Simple rule: how the marker is matched against process and message. Default Contains."
+    )]
+    pub match_mode: Option<String>,
+    #[property(description: "Optional. Simple rule: match the marker ignoring case. Default false.")]
+    pub case_insensitive: Option<bool>,
+    #[property(
+        enum: ["Info", "Warning", "Error", "FatalError", "Debug"],
+        description: "Optional. Condition rule: levels of the candidate rule. Omit or leave empty for every level."
+    )]
+    pub levels: Option<Vec<String>>,
+    #[property(description: "Optional. Condition rule: JSON array string of conditions, in the same shape as for `add_ignore_rule`.")]
+    pub conditions_json: Option<String>,
+    #[property(description: "Optional. How many last hours of stored records to check. Default 24, limited by the retention of the logs.")]
+    pub hours: Option<i64>,
+    #[property(description: "Optional. Amount of matched records to return as samples. Default 10.")]
+    pub samples: Option<i64>,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct PreviewIgnoreRuleResponse {
+    #[property(description: "Hours of stored records that were checked.")]
+    pub hours: i64,
+    #[property(description: "Records of the rule's levels and application that were checked.")]
+    pub scanned: i64,
+    #[property(description: "Records the rule would have suppressed.")]
+    pub matched: i64,
+    #[property(description: "True if the scan stopped at the newest 100000 records, so older records of the window were not checked.")]
+    pub truncated: bool,
+    #[property(description: "Matched records grouped by level and application as JSON array string. Each item: {level, application, count}.")]
+    pub groups_json: String,
+    #[property(description: "Newest matched records as JSON array string, in the same shape as `search_logs` records.")]
+    pub samples_json: String,
+}
+
+pub struct PreviewIgnoreRuleHandler {
+    app: Arc<AppContext>,
+}
+
+impl PreviewIgnoreRuleHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for PreviewIgnoreRuleHandler {
+    const FUNC_NAME: &'static str = "preview_ignore_rule";
+    const DESCRIPTION: &'static str = "Dry-run a candidate ignore rule against the stored logs without adding it. Returns how many records of the last hours it would have suppressed, grouped by level and application, plus sample records. Call this before `add_ignore_rule`, especially for a \"*\" marker or a Regex.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<PreviewIgnoreRuleInputData, PreviewIgnoreRuleResponse>
+    for PreviewIgnoreRuleHandler
+{
+    async fn execute_tool_call(
+        &self,
+        model: PreviewIgnoreRuleInputData,
+    ) -> Result<PreviewIgnoreRuleResponse, String> {
+        let rule = build_rule(
+            model.level.as_deref(),
+            model.application.as_deref(),
+            model.marker.as_deref(),
+            model.match_mode.as_deref(),
+            model.case_insensitive,
+            model.levels.as_deref(),
+            model.conditions_json.as_deref(),
+        )?;
+
+        let preview = crate::flows::preview_ignore_event(
+            &self.app,
+            rule,
+            model.hours.unwrap_or(24),
+            model.samples.unwrap_or(10).max(0) as usize,
+        )
+        .await?;
+
+        let groups: Vec<_> = preview
+            .groups
+            .iter()
+            .map(|group| {
+                serde_json::json!({
+                    "level": format!("{:?}", group.level),
+                    "application": group.application,
+                    "count": group.count,
+                })
+            })
+            .collect();
+
+        let samples: Vec<_> = preview
+            .samples
+            .into_iter()
+            .map(super::search_logs_tool_call::record_to_json)
+            .collect();
+
+        Ok(PreviewIgnoreRuleResponse {
+            hours: preview.hours,
+            scanned: preview.scanned as i64,
+            matched: preview.matched as i64,
+            truncated: preview.truncated,
+            groups_json: serde_json::to_string(&groups).unwrap_or_else(|_| "[]".to_string()),
+            samples_json: serde_json::to_string(&samples).unwrap_or_else(|_| "[]".to_string()),
         })
     }
 }
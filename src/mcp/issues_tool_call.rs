@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use my_ai_agent::{macros::ApplyJsonSchema, ToolDefinition};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::{Deserialize, Serialize};
+
+use mcp_server_middleware::McpToolCall;
+
+use crate::{
+    app::AppContext,
+    issues::{IssueItem, IssueStatus},
+};
+
+const DEFAULT_TAKE: i64 = 50;
+const MAX_TAKE: i64 = 500;
+
+fn parse_status(value: &str) -> Result<IssueStatus, String> {
+    match value.trim().to_lowercase().as_str() {
+        "open" => Ok(IssueStatus::Open),
+        "resolved" => Ok(IssueStatus::Resolved),
+        "ignored" => Ok(IssueStatus::Ignored),
+        _ => Err(format!(
+            "Unknown issue status '{}'. Expected one of: Open, Resolved, Ignored.",
+            value
+        )),
+    }
+}
+
+fn issue_to_json(item: &IssueItem) -> serde_json::Value {
+    serde_json::json!({
+        "id": item.id,
+        "application": item.application,
+        "template": item.template,
+        "level": format!("{:?}", item.level),
+        "status": format!("{:?}", item.status),
+        "first_seen": DateTimeAsMicroseconds::new(item.first_seen).to_rfc3339(),
+        "last_seen": DateTimeAsMicroseconds::new(item.last_seen).to_rfc3339(),
+        "count": item.count,
+        "hourly": item.hourly,
+        "versions": item.versions,
+        "sample_event_ids": item.sample_event_ids,
+    })
+}
+
+// ====================== get_issues ======================
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct GetIssuesInputData {
+    #[property(enum: ["Open", "Resolved", "Ignored"], description: "Optional. Only issues with this status. Omit for all.")]
+    pub status: Option<String>,
+    #[property(description: "Optional. Application name (exact 'Application' context value).")]
+    pub application: Option<String>,
+    #[property(description: "Optional. Max number of issues, most recently seen first. Default 50, max 500.")]
+    pub take: Option<i64>,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct GetIssuesResponse {
+    #[property(description: "Number of returned issues.")]
+    pub count: i64,
+    #[property(description: "Issues as JSON array string. Each item: {id, application, template, level, status, first_seen, last_seen, count, hourly (YYYYMMDDHH -> events), versions (Version -> events), sample_event_ids}.")]
+    pub issues_json: String,
+}
+
+pub struct GetIssuesHandler {
+    app: Arc<AppContext>,
+}
+
+impl GetIssuesHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for GetIssuesHandler {
+    const FUNC_NAME: &'static str = "get_issues";
+    const DESCRIPTION: &'static str = "List issues: Error and FatalError events of one application grouped by message template, with first/last seen, counts per hour and per version, and sample event ids.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<GetIssuesInputData, GetIssuesResponse> for GetIssuesHandler {
+    async fn execute_tool_call(
+        &self,
+        model: GetIssuesInputData,
+    ) -> Result<GetIssuesResponse, String> {
+        let status = match model.status.as_deref() {
+            Some(status) => Some(parse_status(status)?),
+            None => None,
+        };
+
+        let take = model.take.unwrap_or(DEFAULT_TAKE).clamp(1, MAX_TAKE) as usize;
+
+        let items =
+            self.app
+                .issues
+                .lock()
+                .await
+                .get_filtered(status, model.application.as_deref(), take);
+
+        let result: Vec<_> = items.iter().map(issue_to_json).collect();
+
+        Ok(GetIssuesResponse {
+            count: result.len() as i64,
+            issues_json: serde_json::to_string(&result).unwrap_or_else(|_| "[]".to_string()),
+        })
+    }
+}
+
+// ====================== set_issue_status ======================
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct SetIssueStatusInputData {
+    #[property(description: "Id of the issue (see `get_issues`).")]
+    pub id: String,
+    #[property(enum: ["Open", "Resolved", "Ignored"], description: "New status. Resolved issues reopen on the next event. Ignored adds ignore rules for the issue template.")]
+    pub status: String,
+    #[property(description: "Optional. Who makes the change, e.g. the client or user name. Recorded in the audit log.")]
+    pub actor: Option<String>,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct SetIssueStatusResponse {
+    #[property(description: "Issue as JSON object string.")]
+    pub issue_json: String,
+}
+
+pub struct SetIssueStatusHandler {
+    app: Arc<AppContext>,
+}
+
+impl SetIssueStatusHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for SetIssueStatusHandler {
+    const FUNC_NAME: &'static str = "set_issue_status";
+    const DESCRIPTION: &'static str = "Resolve, ignore or reopen an issue by id.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<SetIssueStatusInputData, SetIssueStatusResponse> for SetIssueStatusHandler {
+    async fn execute_tool_call(
+        &self,
+        model: SetIssueStatusInputData,
+    ) -> Result<SetIssueStatusResponse, String> {
+        let status = parse_status(&model.status)?;
+
+        let issue = crate::flows::issues::set_status(
+            &self.app,
+            &model.id,
+            status,
+            &super::get_mcp_audit_actor(model.actor.as_deref()),
+        )
+        .await?;
+
+        Ok(SetIssueStatusResponse {
+            issue_json: issue_to_json(&issue).to_string(),
+        })
+    }
+}
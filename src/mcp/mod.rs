@@ -1,11 +1,42 @@
+use crate::repo::dto::{AuditActorDto, AuditActorKindDto};
+
 mod search_logs_tool_call;
 pub use search_logs_tool_call::*;
 
 mod dashboard_statistics_tool_call;
 pub use dashboard_statistics_tool_call::*;
 
+mod dimension_statistics_tool_call;
+pub use dimension_statistics_tool_call::*;
+
 mod ignore_rules_tool_call;
 pub use ignore_rules_tool_call::*;
 
+mod saved_searches_tool_call;
+pub use saved_searches_tool_call::*;
+
+mod alert_rules_tool_call;
+pub use alert_rules_tool_call::*;
+
+mod issues_tool_call;
+pub use issues_tool_call::*;
+
+mod version_regression_tool_call;
+pub use version_regression_tool_call::*;
+
+mod audit_log_tool_call;
+pub use audit_log_tool_call::*;
+
 mod builder;
 pub use builder::*;
+
+/// Changes made by MCP tools are recorded in the audit log under this actor.
+/// Tool calls of the MCP middleware carry no session, so the client names itself with the
+/// `actor` argument of the changing tools, as HTTP clients do with the `X-Actor` header.
+fn get_mcp_audit_actor(actor: Option<&str>) -> AuditActorDto {
+    let name = actor
+        .map(|actor| actor.trim())
+        .filter(|actor| !actor.is_empty())
+        .map(|actor| actor.to_string());
+    AuditActorDto::new(AuditActorKindDto::Mcp, name)
+}
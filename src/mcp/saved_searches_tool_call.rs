@@ -0,0 +1,211 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use my_ai_agent::{macros::ApplyJsonSchema, ToolDefinition};
+use serde::{Deserialize, Serialize};
+
+use mcp_server_middleware::McpToolCall;
+
+use crate::{app::AppContext, repo::dto::SavedSearchDto};
+
+fn saved_search_to_json(item: &SavedSearchDto, url: Option<String>) -> serde_json::Value {
+    serde_json::json!({
+        "id": item.id,
+        "name": item.name,
+        "levels": item.levels.iter().map(|l| format!("{:?}", l)).collect::<Vec<_>>(),
+        "context": item.context,
+        "phrase": item.phrase,
+        "last_minutes": item.last_minutes,
+        "url": url,
+    })
+}
+
+// ====================== get_saved_searches ======================
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct GetSavedSearchesInputData {}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct GetSavedSearchesResponse {
+    #[property(description: "Number of saved searches.")]
+    pub count: i64,
+    #[property(description: "Saved searches as JSON array string. Each item: {id, name, levels, context, phrase, last_minutes, url}. `url` is a deep link into the UI or null if the UI url is not known yet.")]
+    pub saved_searches_json: String,
+}
+
+pub struct GetSavedSearchesHandler {
+    app: Arc<AppContext>,
+}
+
+impl GetSavedSearchesHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for GetSavedSearchesHandler {
+    const FUNC_NAME: &'static str = "get_saved_searches";
+    const DESCRIPTION: &'static str = "List named saved searches. Each one holds levels, context filters, an optional phrase and a relative time range (last N minutes). Re-run one by passing its filters to `search_logs` with the range `[now - last_minutes..now]`.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<GetSavedSearchesInputData, GetSavedSearchesResponse> for GetSavedSearchesHandler {
+    async fn execute_tool_call(
+        &self,
+        _model: GetSavedSearchesInputData,
+    ) -> Result<GetSavedSearchesResponse, String> {
+        let ui_url = self.app.get_ui_url().await;
+        let items = self.app.saved_searches_repo.get_all().await;
+
+        let result: Vec<_> = items
+            .iter()
+            .map(|itm| {
+                let url = crate::app::ui_links::compile_saved_search_url(&ui_url, &itm.id);
+                saved_search_to_json(itm, url)
+            })
+            .collect();
+
+        Ok(GetSavedSearchesResponse {
+            count: result.len() as i64,
+            saved_searches_json: serde_json::to_string(&result)
+                .unwrap_or_else(|_| "[]".to_string()),
+        })
+    }
+}
+
+// ====================== save_search ======================
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct SaveSearchInputData {
+    #[property(description: "Optional. Id of an existing saved search to update. Omit to create a new one.")]
+    pub id: Option<String>,
+    #[property(description: "Human-readable name, e.g. \"payment-api errors\".")]
+    pub name: String,
+    #[property(description: "Optional. Log levels. One of: Info, Warning, Error, FatalError, Debug. Empty means all levels.")]
+    pub levels: Option<Vec<String>>,
+    #[property(description: "Optional. Exact context filters as `key=value` entries, e.g. [\"Application=payment-api\"].")]
+    pub context_filters: Option<Vec<String>>,
+    #[property(description: "Optional. Full-text search phrase.")]
+    pub phrase: Option<String>,
+    #[property(description: "Relative time range: the search covers the last N minutes. Range 1..43200.")]
+    pub last_minutes: i64,
+    #[property(description: "Optional. Who makes the change, e.g. the client or user name. Recorded in the audit log.")]
+    pub actor: Option<String>,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct SaveSearchResponse {
+    #[property(description: "Saved search as JSON object string: {id, name, levels, context, phrase, last_minutes, url}.")]
+    pub saved_search_json: String,
+}
+
+pub struct SaveSearchHandler {
+    app: Arc<AppContext>,
+}
+
+impl SaveSearchHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for SaveSearchHandler {
+    const FUNC_NAME: &'static str = "save_search";
+    const DESCRIPTION: &'static str = "Create or update a named saved search. Returns its stable id and a UI deep link that can be shared.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<SaveSearchInputData, SaveSearchResponse> for SaveSearchHandler {
+    async fn execute_tool_call(
+        &self,
+        model: SaveSearchInputData,
+    ) -> Result<SaveSearchResponse, String> {
+        let mut levels = Vec::new();
+        for level in model.levels.unwrap_or_default() {
+            levels.push(super::ignore_rules_tool_call::parse_level(&level)?);
+        }
+
+        let mut context = BTreeMap::new();
+        for entry in model.context_filters.unwrap_or_default() {
+            let (key, value) = entry.split_once('=').ok_or_else(|| {
+                format!("Context filter '{}' must be in form key=value", entry)
+            })?;
+            context.insert(key.trim().to_string(), value.trim().to_string());
+        }
+
+        let saved = crate::flows::saved_searches::save(
+            &self.app,
+            SavedSearchDto {
+                id: model.id.unwrap_or_default(),
+                name: model.name,
+                levels,
+                context,
+                phrase: model.phrase,
+                last_minutes: model.last_minutes,
+            },
+            &super::get_mcp_audit_actor(model.actor.as_deref()),
+        )
+        .await?;
+
+        let url = self.app.get_saved_search_url(&saved.id).await;
+
+        Ok(SaveSearchResponse {
+            saved_search_json: saved_search_to_json(&saved, url).to_string(),
+        })
+    }
+}
+
+// ====================== delete_saved_search ======================
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct DeleteSavedSearchInputData {
+    #[property(description: "Id of the saved search to delete (see `get_saved_searches`).")]
+    pub id: String,
+    #[property(description: "Optional. Who makes the change, e.g. the client or user name. Recorded in the audit log.")]
+    pub actor: Option<String>,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct DeleteSavedSearchResponse {
+    #[property(description: "Human-readable result message.")]
+    pub message: String,
+}
+
+pub struct DeleteSavedSearchHandler {
+    app: Arc<AppContext>,
+}
+
+impl DeleteSavedSearchHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for DeleteSavedSearchHandler {
+    const FUNC_NAME: &'static str = "delete_saved_search";
+    const DESCRIPTION: &'static str = "Delete a saved search by id.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<DeleteSavedSearchInputData, DeleteSavedSearchResponse>
+    for DeleteSavedSearchHandler
+{
+    async fn execute_tool_call(
+        &self,
+        model: DeleteSavedSearchInputData,
+    ) -> Result<DeleteSavedSearchResponse, String> {
+        let deleted = crate::flows::saved_searches::delete(
+            &self.app,
+            &model.id,
+            &super::get_mcp_audit_actor(model.actor.as_deref()),
+        )
+        .await?;
+
+        let message = if deleted {
+            format!("Saved search '{}' deleted.", model.id)
+        } else {
+            format!("Saved search '{}' not found.", model.id)
+        };
+
+        Ok(DeleteSavedSearchResponse { message })
+    }
+}
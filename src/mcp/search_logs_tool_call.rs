@@ -7,12 +7,10 @@ use serde::{Deserialize, Serialize};
 use mcp_server_middleware::McpToolCall;
 
 use crate::{
-    app::{AppContext, PROCESS_CONTEXT_KEY},
-    repo::dto::{LogItemDto, LogLevelDto},
+    app::{AppContext, APPLICATION_KEY, PROCESS_CONTEXT_KEY, VERSION_KEY},
+    repo::dto::{LogItemDto, LogLevelDto, MatchModeDto, PhraseFilter, SearchPage, SortOrderDto},
 };
 
-const APPLICATION_KEY: &str = "Application";
-const VERSION_KEY: &str = "Version";
 const DEFAULT_LIMIT: i64 = 100;
 const MAX_LIMIT: i64 = 1000;
 
@@ -33,12 +31,30 @@ pub struct SearchLogsInputData {
     #[property(description: "Optional. Full-text search phrase across message and context (Tantivy QueryParser syntax). Empty or omitted means no phrase filter.")]
     pub phrase: Option<String>,
 
+    #[property(
+        enum: ["FullText", "Contains", "Regex"],
+        description: "Optional. How `phrase` is matched. FullText (default) uses the tokenized full-text index. Contains is a plain substring of the message. Regex is a regular expression over the message, e.g. `timeout after \\d+ms`."
+    )]
+    pub phrase_mode: Option<String>,
+
+    #[property(description: "Optional. Case-insensitive matching for Contains and Regex phrase modes. Default false.")]
+    pub case_insensitive: Option<bool>,
+
     #[property(
         enum: ["Info", "Warning", "Error", "FatalError", "Debug"],
         description: "Optional. Filter by log levels. If omitted or empty, all levels match. Pass any subset, e.g. [\"Error\", \"FatalError\"]."
     )]
     pub levels: Option<Vec<String>>,
 
+    #[property(
+        enum: ["Desc", "Asc"],
+        description: "Optional. Desc (default) returns the newest records first, Asc returns the oldest records first. `take` is applied after ordering across all levels."
+    )]
+    pub order: Option<String>,
+
+    #[property(description: "Optional. Number of records to skip in the requested order before `take` is applied. Default 0.")]
+    pub skip: Option<i64>,
+
     #[property(description: "Maximum number of records to return. Default 100. Range 1 to 1000.")]
     pub take: Option<i64>,
 }
@@ -51,7 +67,7 @@ pub struct SearchLogsResponse {
     #[property(description: "True if the result was truncated by `take`. Re-query with a smaller time range or stricter filters if so.")]
     pub truncated: bool,
 
-    #[property(description: "Matching log records as JSON array string sorted by timestamp ascending.")]
+    #[property(description: "Matching log records as JSON array string sorted by timestamp in the requested order.")]
     pub items_json: String,
 }
 
@@ -67,7 +83,7 @@ impl SearchLogsHandler {
 
 impl ToolDefinition for SearchLogsHandler {
     const FUNC_NAME: &'static str = "search_logs";
-    const DESCRIPTION: &'static str = "Search log records by time range with optional application, version, and full-text phrase filters. Returns matching records sorted by timestamp ascending, capped by `take`.";
+    const DESCRIPTION: &'static str = "Search log records by time range with optional application, version, and full-text phrase filters. Returns the newest (or with order=Asc the oldest) matching records sorted by timestamp, paged by `skip` and `take`.";
 }
 
 #[async_trait::async_trait]
@@ -84,6 +100,11 @@ impl McpToolCall<SearchLogsInputData, SearchLogsResponse> for SearchLogsHandler
         }
 
         let take = model.take.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize;
+        let page = SearchPage {
+            order: parse_order(model.order.as_deref())?,
+            skip: model.skip.unwrap_or(0).max(0) as usize,
+            take,
+        };
 
         let mut context = BTreeMap::new();
         if let Some(app) = trimmed(model.application.as_deref()) {
@@ -98,10 +119,11 @@ impl McpToolCall<SearchLogsInputData, SearchLogsResponse> for SearchLogsHandler
             Some(context)
         };
 
-        let phrase_owned = model
-            .phrase
-            .map(|p| p.trim().to_string())
-            .filter(|p| !p.is_empty());
+        let phrase = parse_phrase(
+            model.phrase.as_deref().unwrap_or_default(),
+            model.phrase_mode.as_deref(),
+            model.case_insensitive.unwrap_or(false),
+        )?;
 
         let levels = match model.levels {
             Some(v) if !v.is_empty() => {
@@ -112,19 +134,17 @@ impl McpToolCall<SearchLogsInputData, SearchLogsResponse> for SearchLogsHandler
             _ => None,
         };
 
-        let mut items = crate::flows::search_logs(
+        let items = crate::flows::search_logs(
             &self.app,
             from_dt,
             to_dt,
             levels,
             context,
-            phrase_owned.as_deref(),
-            take,
+            phrase.as_ref(),
+            page,
         )
         .await;
 
-        items.sort_by_key(|i| i.moment.unix_microseconds);
-
         let truncated = items.len() >= take;
         let count = items.len() as i64;
 
@@ -139,7 +159,7 @@ impl McpToolCall<SearchLogsInputData, SearchLogsResponse> for SearchLogsHandler
     }
 }
 
-fn parse_iso_date(value: &str, field: &str) -> Result<DateTimeAsMicroseconds, String> {
+pub(super) fn parse_iso_date(value: &str, field: &str) -> Result<DateTimeAsMicroseconds, String> {
     DateTimeAsMicroseconds::parse_iso_string(value).ok_or_else(|| {
         format!(
             "`{}` is not a valid ISO-8601 / RFC-3339 datetime: '{}'",
@@ -148,6 +168,30 @@ fn parse_iso_date(value: &str, field: &str) -> Result<DateTimeAsMicroseconds, St
     })
 }
 
+fn parse_phrase(
+    phrase: &str,
+    phrase_mode: Option<&str>,
+    case_insensitive: bool,
+) -> Result<Option<PhraseFilter>, String> {
+    match phrase_mode.unwrap_or("FullText") {
+        "FullText" => Ok(PhraseFilter::full_text(phrase)),
+        "Contains" => PhraseFilter::matcher(phrase, MatchModeDto::Contains, case_insensitive),
+        "Regex" => PhraseFilter::matcher(phrase, MatchModeDto::Regex, case_insensitive),
+        other => Err(format!(
+            "invalid phrase_mode '{}'. Allowed: FullText, Contains, Regex",
+            other
+        )),
+    }
+}
+
+fn parse_order(value: Option<&str>) -> Result<SortOrderDto, String> {
+    match value.unwrap_or("Desc") {
+        "Desc" => Ok(SortOrderDto::Desc),
+        "Asc" => Ok(SortOrderDto::Asc),
+        other => Err(format!("invalid order '{}'. Allowed: Desc, Asc", other)),
+    }
+}
+
 fn trimmed(value: Option<&str>) -> Option<&str> {
     value
         .map(str::trim)
@@ -178,7 +222,7 @@ fn level_to_str(level: &LogLevelDto) -> &'static str {
     }
 }
 
-fn record_to_json(mut item: LogItemDto) -> serde_json::Value {
+pub(super) fn record_to_json(mut item: LogItemDto) -> serde_json::Value {
     let application = item.context.remove(APPLICATION_KEY);
     let version = item.context.remove(VERSION_KEY);
     let process = item.context.remove(PROCESS_CONTEXT_KEY);
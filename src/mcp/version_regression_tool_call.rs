@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use my_ai_agent::{macros::ApplyJsonSchema, ToolDefinition};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::{Deserialize, Serialize};
+
+use mcp_server_middleware::McpToolCall;
+
+use crate::{app::AppContext, version_statistics::VersionSummary};
+
+const MAX_NEW_SIGNATURES: usize = 20;
+
+fn version_to_json(item: &VersionSummary) -> serde_json::Value {
+    serde_json::json!({
+        "version": item.version,
+        "first_seen": DateTimeAsMicroseconds::new(item.first_seen).to_rfc3339(),
+        "last_seen": DateTimeAsMicroseconds::new(item.last_seen).to_rfc3339(),
+        "total": item.get_total(),
+        "error": item.counts.error,
+        "fatal": item.counts.fatal_error,
+        "error_rate": item.get_error_rate(),
+        "fatal_rate": item.get_fatal_rate(),
+    })
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct VersionRegressionInputData {
+    #[property(description: "Optional. Application name (exact 'Application' context value). Every application with a 'Version' context if omitted.")]
+    pub application: Option<String>,
+
+    #[property(description: "Optional. Version to check, e.g. 0.1.18. The latest version of the application if omitted. Requires `application`.")]
+    pub version: Option<String>,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct VersionRegressionResponse {
+    #[property(description: "Number of returned reports.")]
+    pub count: i64,
+
+    #[property(description: "Reports as JSON array string. Each item: {application, verdict: NotEnoughData | Same | Better | Worse, current, previous (null for the first known version), new_signatures_count, new_signatures: [{id, template, level, count}]}. `current` and `previous`: {version, first_seen, last_seen, total, error, fatal, error_rate, fatal_rate}; rates are shares of all events of the version. At most 20 new signatures are listed, most events first.")]
+    pub reports_json: String,
+}
+
+pub struct VersionRegressionHandler {
+    app: Arc<AppContext>,
+}
+
+impl VersionRegressionHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for VersionRegressionHandler {
+    const FUNC_NAME: &'static str = "get_version_regression";
+    const DESCRIPTION: &'static str = "Compare a version of an application with the version first seen before it: error and fatal rates and error signatures (issues) which did not occur in the previous version. Use after a deploy to answer \"did this version make things worse?\", then call `get_issues` or `search_logs` for the new signatures.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<VersionRegressionInputData, VersionRegressionResponse>
+    for VersionRegressionHandler
+{
+    async fn execute_tool_call(
+        &self,
+        model: VersionRegressionInputData,
+    ) -> Result<VersionRegressionResponse, String> {
+        let reports = crate::flows::get_version_regression_report(
+            &self.app,
+            model.application.as_deref(),
+            model.version.as_deref(),
+        )
+        .await?;
+
+        let result: Vec<_> = reports
+            .into_iter()
+            .map(|report| {
+                let current_version = report.current.version.as_str();
+
+                let mut new_signatures: Vec<_> = report
+                    .new_signatures
+                    .iter()
+                    .map(|issue| {
+                        let count = issue.versions.get(current_version).copied().unwrap_or(0);
+                        (count, issue)
+                    })
+                    .collect();
+                new_signatures.sort_by(|a, b| b.0.cmp(&a.0));
+
+                let new_signatures_json: Vec<_> = new_signatures
+                    .iter()
+                    .take(MAX_NEW_SIGNATURES)
+                    .map(|(count, issue)| {
+                        serde_json::json!({
+                            "id": issue.id,
+                            "template": issue.template,
+                            "level": format!("{:?}", issue.level),
+                            "count": count,
+                        })
+                    })
+                    .collect();
+
+                serde_json::json!({
+                    "application": report.current.application,
+                    "verdict": format!("{:?}", report.verdict),
+                    "current": version_to_json(&report.current),
+                    "previous": report.previous.as_ref().map(version_to_json),
+                    "new_signatures_count": new_signatures.len(),
+                    "new_signatures": new_signatures_json,
+                })
+            })
+            .collect();
+
+        Ok(VersionRegressionResponse {
+            count: result.len() as i64,
+            reports_json: serde_json::to_string(&result).unwrap_or_else(|_| "[]".to_string()),
+        })
+    }
+}
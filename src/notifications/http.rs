@@ -0,0 +1,35 @@
+use std::collections::BTreeMap;
+
+use flurl::{FlUrl, FlUrlError, FlUrlResponse};
+
+pub async fn check_response(response: Result<FlUrlResponse, FlUrlError>) -> Result<(), String> {
+    let mut response = response.map_err(|err| format!("Request failed: {:?}", err))?;
+
+    let status = response.get_status_code();
+    if (200..300).contains(&status) {
+        return Ok(());
+    }
+
+    let body = response.get_body_as_slice().await;
+    let body_str = body
+        .as_ref()
+        .ok()
+        .and_then(|b| std::str::from_utf8(b).ok())
+        .unwrap_or("<binary>");
+
+    Err(format!("Non-2xx status {}, body: {}", status, body_str))
+}
+
+pub async fn post_json(
+    url: &str,
+    headers: &BTreeMap<String, String>,
+    body: &serde_json::Value,
+) -> Result<(), String> {
+    let mut fl_url = FlUrl::new(url).accept_invalid_certificate();
+
+    for (name, value) in headers {
+        fl_url = fl_url.with_header(name, value);
+    }
+
+    check_response(fl_url.post_json(body).await).await
+}
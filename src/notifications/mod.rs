@@ -0,0 +1,19 @@
+mod http;
+mod notification;
+mod notification_channel;
+mod notification_silences;
+mod notifications_dispatcher;
+mod slack_channel;
+mod teams_channel;
+mod telegram_channel;
+pub mod templates;
+mod webhook_channel;
+pub use http::*;
+pub use notification::*;
+pub use notification_channel::*;
+pub use notification_silences::*;
+pub use notifications_dispatcher::*;
+pub use slack_channel::*;
+pub use teams_channel::*;
+pub use telegram_channel::*;
+pub use webhook_channel::*;
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use crate::{
+    alerts::AlertNotification, app::LogItem, error_signatures::NewErrorNotification,
+    settings::NotificationKind, telegram::NotificationItem,
+};
+
+#[derive(Debug, Clone)]
+pub struct NotificationContext {
+    pub env_name: String,
+    pub ui_url: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Notification {
+    /// Per-minute Error/FatalError summary.
+    Summary(NotificationItem),
+    Alert(AlertNotification),
+    /// Single event, e.g. an ignore rule change.
+    Event(Arc<LogItem>),
+    /// First occurrence of an error signature, or its return after a long silence.
+    NewError(NewErrorNotification),
+}
+
+impl Notification {
+    pub fn get_kind(&self) -> NotificationKind {
+        match self {
+            Notification::Summary(_) => NotificationKind::Summary,
+            Notification::Alert(_) => NotificationKind::Alert,
+            Notification::Event(_) => NotificationKind::Event,
+            Notification::NewError(_) => NotificationKind::NewError,
+        }
+    }
+}
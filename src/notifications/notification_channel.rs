@@ -0,0 +1,113 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::settings::{NotificationChannelSettings, NotificationChannelType};
+
+use super::{
+    Notification, NotificationContext, SlackChannel, TeamsChannel, TelegramChannel, WebhookChannel,
+};
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+pub const FIRST_RETRY_DELAY: Duration = Duration::from_secs(1);
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+#[async_trait::async_trait]
+pub trait NotificationChannel: Send + Sync {
+    fn get_name(&self) -> &str;
+    fn get_max_retries(&self) -> u32;
+    async fn send(
+        &self,
+        notification: &Notification,
+        ctx: &NotificationContext,
+    ) -> Result<(), String>;
+}
+
+fn get_url(settings: &NotificationChannelSettings) -> Result<String, String> {
+    settings
+        .url
+        .clone()
+        .ok_or_else(|| format!("Channel '{}' has no url", settings.name))
+}
+
+pub fn create_channel(
+    settings: &NotificationChannelSettings,
+) -> Result<Arc<dyn NotificationChannel>, String> {
+    let name = settings.name.clone();
+    let headers = settings.headers.clone().unwrap_or_default();
+    let templates = settings.templates.clone().unwrap_or_default();
+    let max_retries = settings.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+
+    let result: Arc<dyn NotificationChannel> = match settings.channel_type {
+        NotificationChannelType::Telegram => Arc::new(TelegramChannel {
+            settings: settings
+                .telegram
+                .clone()
+                .ok_or_else(|| format!("Channel '{}' has no telegram settings", name))?,
+            name,
+            templates,
+            max_retries,
+        }),
+        NotificationChannelType::Slack => Arc::new(SlackChannel {
+            url: get_url(settings)?,
+            name,
+            headers,
+            templates,
+            max_retries,
+        }),
+        NotificationChannelType::Teams => Arc::new(TeamsChannel {
+            url: get_url(settings)?,
+            name,
+            headers,
+            templates,
+            max_retries,
+        }),
+        NotificationChannelType::Webhook => Arc::new(WebhookChannel {
+            url: get_url(settings)?,
+            name,
+            headers,
+            templates,
+            max_retries,
+        }),
+    };
+
+    Ok(result)
+}
+
+/// Retries with exponentially growing delays and drops the notification after `max_retries`.
+pub async fn send_with_retries(
+    channel: Arc<dyn NotificationChannel>,
+    notification: Notification,
+    ctx: NotificationContext,
+) {
+    let mut delay = FIRST_RETRY_DELAY;
+    let mut attempt = 0;
+
+    loop {
+        let err = match channel.send(&notification, &ctx).await {
+            Ok(()) => return,
+            Err(err) => err,
+        };
+
+        if attempt >= channel.get_max_retries() {
+            println!(
+                "{:?} notification to channel '{}' is dropped after {} attempts: {}",
+                notification.get_kind(),
+                channel.get_name(),
+                attempt + 1,
+                err
+            );
+            return;
+        }
+
+        println!(
+            "{:?} notification to channel '{}' failed: {}. Retrying in {:?}",
+            notification.get_kind(),
+            channel.get_name(),
+            err,
+            delay
+        );
+
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RETRY_DELAY);
+        attempt += 1;
+    }
+}
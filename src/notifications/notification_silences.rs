@@ -0,0 +1,165 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    alerts::AlertNotificationKind, app::APPLICATION_KEY, telegram::NotificationItem,
+    text_matching::is_glob_match,
+};
+
+use super::Notification;
+
+/// Mutes notifications of an application. Events are still stored.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotificationSilence {
+    /// Application glob.
+    pub application: String,
+    /// Unix microseconds.
+    pub until: i64,
+    /// Removed as soon as a minute passes without Error and FatalError of the application.
+    pub until_quiet: bool,
+    pub created_by: String,
+}
+
+/// Silences are kept between restarts, the applications of the latest summary are not.
+pub struct NotificationSilences {
+    items: Vec<NotificationSilence>,
+    last_summary_applications: Vec<String>,
+    has_changes: bool,
+}
+
+impl NotificationSilences {
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            last_summary_applications: Vec::new(),
+            has_changes: false,
+        }
+    }
+
+    pub fn restore(&mut self, items: Vec<NotificationSilence>) {
+        for item in items {
+            self.add(item);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Replaces the silence of the same application.
+    pub fn add(&mut self, silence: NotificationSilence) {
+        self.items.retain(|s| s.application != silence.application);
+        self.items.push(silence);
+        self.has_changes = true;
+    }
+
+    pub fn remove(&mut self, application: &str) -> bool {
+        let len = self.items.len();
+        self.items.retain(|s| s.application != application);
+        let removed = self.items.len() != len;
+        self.has_changes |= removed;
+        removed
+    }
+
+    pub fn get_active(&mut self, now: DateTimeAsMicroseconds) -> Vec<NotificationSilence> {
+        self.gc(now);
+        self.items.clone()
+    }
+
+    fn gc(&mut self, now: DateTimeAsMicroseconds) {
+        let len = self.items.len();
+        self.items.retain(|s| s.until > now.unix_microseconds);
+        self.has_changes |= self.items.len() != len;
+    }
+
+    fn is_silenced(&self, application: Option<&str>, now: DateTimeAsMicroseconds) -> bool {
+        let Some(application) = application else {
+            return false;
+        };
+
+        self.items
+            .iter()
+            .any(|s| s.until > now.unix_microseconds && is_glob_match(&s.application, application))
+    }
+
+    /// Called for every closed minute, even if the summary itself is not sent.
+    pub fn on_minute(&mut self, item: &NotificationItem, now: DateTimeAsMicroseconds) {
+        self.gc(now);
+
+        self.last_summary_applications = item
+            .by_application
+            .iter()
+            .filter(|(_, by_app)| by_app.fatal_errors > 0 || by_app.errors > 0)
+            .map(|(application, _)| application.to_string())
+            .collect();
+
+        let last_summary_applications = &self.last_summary_applications;
+        let len = self.items.len();
+        self.items
+            .retain(|s| !s.until_quiet || last_summary_applications.contains(&s.application));
+        self.has_changes |= self.items.len() != len;
+    }
+
+    /// `None` if nothing changed since the previous call.
+    pub fn get_snapshot_to_persist(&mut self) -> Option<Vec<NotificationSilence>> {
+        if !self.has_changes {
+            return None;
+        }
+
+        self.has_changes = false;
+        Some(self.items.clone())
+    }
+
+    /// Applications with Error or FatalError in the latest closed minute.
+    pub fn get_last_summary_applications(&self) -> &[String] {
+        &self.last_summary_applications
+    }
+
+    /// Drops silenced parts of the notification. Resolved alerts are always delivered.
+    pub fn filter(
+        &self,
+        notification: Notification,
+        now: DateTimeAsMicroseconds,
+    ) -> Option<Notification> {
+        match notification {
+            Notification::Summary(item) => {
+                let item = item
+                    .filter_applications(|application| !self.is_silenced(Some(application), now));
+
+                if item.fatal_errors == 0 && item.errors == 0 {
+                    return None;
+                }
+
+                Some(Notification::Summary(item))
+            }
+            Notification::Alert(alert) => {
+                if alert.kind == AlertNotificationKind::Firing
+                    && self.is_silenced(alert.get_application(), now)
+                {
+                    return None;
+                }
+
+                Some(Notification::Alert(alert))
+            }
+            Notification::Event(item) => {
+                let application = item.ctx.get(APPLICATION_KEY).map(|s| s.as_str());
+                if self.is_silenced(application, now) {
+                    return None;
+                }
+
+                Some(Notification::Event(item))
+            }
+            Notification::NewError(new_error) => {
+                if self.is_silenced(Some(&new_error.item.application), now) {
+                    return None;
+                }
+
+                Some(Notification::NewError(new_error))
+            }
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use crate::{
+    app::APPLICATION_KEY,
+    settings::{
+        NotificationChannelSettings, NotificationKind, NotificationRouteSettings, TelegramSettings,
+    },
+    telegram::NotificationItem,
+    text_matching::is_glob_match,
+};
+
+use super::{Notification, NotificationChannel, NotificationContext, TelegramChannel};
+
+/// Name of the channel created from the top level `TelegramSettings`.
+pub const DEFAULT_TELEGRAM_CHANNEL: &str = "telegram";
+const DEFAULT_TELEGRAM_MAX_RETRIES: u32 = 3;
+
+fn route_matches(
+    route: &NotificationRouteSettings,
+    application: Option<&str>,
+    kind: NotificationKind,
+) -> bool {
+    if let Some(kinds) = route.kinds.as_ref() {
+        if !kinds.contains(&kind) {
+            return false;
+        }
+    }
+
+    let Some(patterns) = route.applications.as_ref() else {
+        return true;
+    };
+
+    if patterns.is_empty() {
+        return true;
+    }
+
+    match application {
+        Some(application) => patterns
+            .iter()
+            .any(|pattern| is_glob_match(pattern, application)),
+        None => false,
+    }
+}
+
+pub struct NotificationsDispatcher {
+    channels: Vec<Arc<dyn NotificationChannel>>,
+    routes: Option<Vec<NotificationRouteSettings>>,
+}
+
+impl NotificationsDispatcher {
+    /// Without routes every channel receives every notification.
+    pub fn new(
+        channels: Vec<NotificationChannelSettings>,
+        telegram_settings: Option<TelegramSettings>,
+        routes: Option<Vec<NotificationRouteSettings>>,
+    ) -> Self {
+        let mut result = Vec::with_capacity(channels.len() + 1);
+
+        for settings in channels.iter() {
+            match super::create_channel(settings) {
+                Ok(channel) => result.push(channel),
+                Err(err) => println!("Skipping invalid notification channel: {}", err),
+            }
+        }
+
+        if let Some(telegram_settings) = telegram_settings {
+            if !result
+                .iter()
+                .any(|channel| channel.get_name() == DEFAULT_TELEGRAM_CHANNEL)
+            {
+                result.push(Arc::new(TelegramChannel {
+                    name: DEFAULT_TELEGRAM_CHANNEL.to_string(),
+                    settings: telegram_settings,
+                    templates: Default::default(),
+                    max_retries: DEFAULT_TELEGRAM_MAX_RETRIES,
+                }));
+            }
+        }
+
+        Self {
+            channels: result,
+            routes,
+        }
+    }
+
+    fn get_channel_indexes(
+        &self,
+        application: Option<&str>,
+        kind: NotificationKind,
+    ) -> BTreeSet<usize> {
+        let Some(routes) = self.routes.as_ref() else {
+            return (0..self.channels.len()).collect();
+        };
+
+        let mut names = BTreeSet::new();
+        for route in routes {
+            if route_matches(route, application, kind) {
+                names.extend(route.channels.iter().map(|name| name.as_str()));
+            }
+        }
+
+        self.get_indexes_by_names(names.into_iter())
+    }
+
+    fn get_indexes_by_names<'s>(&self, names: impl Iterator<Item = &'s str>) -> BTreeSet<usize> {
+        let mut result = BTreeSet::new();
+
+        for name in names {
+            match self.channels.iter().position(|c| c.get_name() == name) {
+                Some(index) => {
+                    result.insert(index);
+                }
+                None => println!("Notification channel '{}' is not configured", name),
+            }
+        }
+
+        result
+    }
+
+    fn send(&self, index: usize, notification: Notification, ctx: &NotificationContext) {
+        tokio::spawn(super::send_with_retries(
+            self.channels[index].clone(),
+            notification,
+            ctx.clone(),
+        ));
+    }
+
+    /// Sends in background tasks, so a slow channel does not delay the caller or other channels.
+    pub fn dispatch(&self, notification: Notification, ctx: &NotificationContext) {
+        let kind = notification.get_kind();
+
+        match &notification {
+            Notification::Summary(item) => {
+                for (index, item) in self.split_summary(item) {
+                    self.send(index, Notification::Summary(item), ctx);
+                }
+            }
+            Notification::Alert(alert) => {
+                let indexes = if alert.channels.is_empty() {
+                    self.get_channel_indexes(alert.get_application(), kind)
+                } else {
+                    self.get_indexes_by_names(alert.channels.iter().map(|c| c.as_str()))
+                };
+
+                for index in indexes {
+                    self.send(index, notification.clone(), ctx);
+                }
+            }
+            Notification::Event(item) => {
+                let application = item.ctx.get(APPLICATION_KEY).map(|s| s.as_str());
+                for index in self.get_channel_indexes(application, kind) {
+                    self.send(index, notification.clone(), ctx);
+                }
+            }
+            Notification::NewError(new_error) => {
+                let application = Some(new_error.item.application.as_str());
+                for index in self.get_channel_indexes(application, kind) {
+                    self.send(index, notification.clone(), ctx);
+                }
+            }
+        }
+    }
+
+    /// Every channel gets a summary of only the applications routed to it.
+    fn split_summary(&self, item: &NotificationItem) -> Vec<(usize, NotificationItem)> {
+        let mut result = Vec::new();
+
+        for index in 0..self.channels.len() {
+            let filtered = item.filter_applications(|application| {
+                self.get_channel_indexes(Some(application), NotificationKind::Summary)
+                    .contains(&index)
+            });
+
+            if filtered.fatal_errors > 0 || filtered.errors > 0 {
+                result.push((index, filtered));
+            }
+        }
+
+        result
+    }
+}
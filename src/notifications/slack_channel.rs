@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use crate::settings::NotificationTemplates;
+
+use super::{templates, Notification, NotificationChannel, NotificationContext};
+
+/// Slack incoming webhook.
+pub struct SlackChannel {
+    pub name: String,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    pub templates: NotificationTemplates,
+    pub max_retries: u32,
+}
+
+#[async_trait::async_trait]
+impl NotificationChannel for SlackChannel {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    async fn send(
+        &self,
+        notification: &Notification,
+        ctx: &NotificationContext,
+    ) -> Result<(), String> {
+        let text = match templates::get_template(&self.templates, notification.get_kind()) {
+            Some(template) => {
+                let fields = templates::get_template_fields(notification, ctx);
+                templates::render_template(template, &fields, templates::push_raw)
+            }
+            None => templates::compile_markdown_text(notification, ctx, &templates::SLACK_STYLE),
+        };
+
+        let body = serde_json::json!({ "text": text });
+
+        super::post_json(&self.url, &self.headers, &body).await
+    }
+}
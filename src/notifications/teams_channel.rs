@@ -0,0 +1,61 @@
+use std::collections::BTreeMap;
+
+use crate::{alerts::AlertNotificationKind, settings::NotificationTemplates};
+
+use super::{templates, Notification, NotificationChannel, NotificationContext};
+
+/// Microsoft Teams incoming webhook. Sends a legacy `MessageCard`.
+pub struct TeamsChannel {
+    pub name: String,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    pub templates: NotificationTemplates,
+    pub max_retries: u32,
+}
+
+fn get_theme_color(notification: &Notification) -> &'static str {
+    match notification {
+        Notification::Summary(_) => "F57C00",
+        Notification::Alert(alert) => match alert.kind {
+            AlertNotificationKind::Firing => "D32F2F",
+            AlertNotificationKind::Resolved => "2E7D32",
+        },
+        Notification::Event(_) => "0078D7",
+        Notification::NewError(_) => "7B1FA2",
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationChannel for TeamsChannel {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    async fn send(
+        &self,
+        notification: &Notification,
+        ctx: &NotificationContext,
+    ) -> Result<(), String> {
+        let text = match templates::get_template(&self.templates, notification.get_kind()) {
+            Some(template) => {
+                let fields = templates::get_template_fields(notification, ctx);
+                templates::render_template(template, &fields, templates::push_raw)
+            }
+            None => templates::compile_markdown_text(notification, ctx, &templates::TEAMS_STYLE),
+        };
+
+        let body = serde_json::json!({
+            "@type": "MessageCard",
+            "@context": "https://schema.org/extensions",
+            "summary": format!("{} {:?}", ctx.env_name, notification.get_kind()),
+            "themeColor": get_theme_color(notification),
+            "text": text,
+        });
+
+        super::post_json(&self.url, &self.headers, &body).await
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{
+    app::APPLICATION_KEY,
+    settings::{NotificationTemplates, TelegramSettings},
+    telegram::{api, get_telegram_chat, TelegramChat},
+};
+
+use super::{
+    templates, Notification, NotificationChannel, NotificationContext, FIRST_RETRY_DELAY,
+    MAX_RETRY_DELAY,
+};
+
+/// Every summary, alert and event goes to the chat of its route, see [`get_telegram_chat`].
+pub struct TelegramChannel {
+    pub name: String,
+    pub settings: TelegramSettings,
+    pub templates: NotificationTemplates,
+    pub max_retries: u32,
+}
+
+#[async_trait::async_trait]
+impl NotificationChannel for TelegramChannel {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    async fn send(
+        &self,
+        notification: &Notification,
+        ctx: &NotificationContext,
+    ) -> Result<(), String> {
+        let template = templates::get_template(&self.templates, notification.get_kind());
+
+        let text_to_send = |notification: &Notification| match template {
+            Some(template) => {
+                let fields = templates::get_template_fields(notification, ctx);
+                let text = templates::render_template(template, &fields, api::append_html_escaped);
+                ("HTML", text)
+            }
+            None => match notification {
+                Notification::Summary(item) => (
+                    "HTML",
+                    api::compile_notification_text(item, &ctx.env_name, &ctx.ui_url),
+                ),
+                Notification::Alert(alert) => (
+                    "HTML",
+                    api::compile_alert_text(alert, &ctx.env_name, &ctx.ui_url),
+                ),
+                Notification::Event(item) => {
+                    ("Markdown", api::compile_log_item_text(item, &ctx.env_name))
+                }
+                Notification::NewError(new_error) => (
+                    "HTML",
+                    api::compile_new_error_text(new_error, &ctx.env_name, &ctx.ui_url),
+                ),
+            },
+        };
+
+        match notification {
+            Notification::Summary(item) => {
+                let by_chat = item.split_by(|application, level| {
+                    get_telegram_chat(&self.settings, Some(application), level)
+                });
+
+                // Retried per chat, so the chats which got the summary do not get it twice.
+                for (chat, item) in by_chat {
+                    if item.fatal_errors == 0 && item.errors == 0 {
+                        continue;
+                    }
+
+                    let (parse_mode, text) = text_to_send(&Notification::Summary(item));
+                    self.send_to_chat_with_retries(chat, parse_mode, &text)
+                        .await;
+                }
+
+                Ok(())
+            }
+            Notification::Alert(alert) => {
+                let chat =
+                    get_telegram_chat(&self.settings, alert.get_application(), &alert.min_level);
+                let (parse_mode, text) = text_to_send(notification);
+                api::send_message(&self.settings, chat, parse_mode, &text).await
+            }
+            Notification::Event(item) => {
+                let application = item.ctx.get(APPLICATION_KEY).map(|s| s.as_str());
+                let chat = get_telegram_chat(&self.settings, application, &(&item.level).into());
+                let (parse_mode, text) = text_to_send(notification);
+                api::send_message(&self.settings, chat, parse_mode, &text).await
+            }
+            Notification::NewError(new_error) => {
+                let chat = get_telegram_chat(
+                    &self.settings,
+                    Some(&new_error.item.application),
+                    &new_error.item.level,
+                );
+                let (parse_mode, text) = text_to_send(notification);
+                api::send_message(&self.settings, chat, parse_mode, &text).await
+            }
+        }
+    }
+}
+
+impl TelegramChannel {
+    async fn send_to_chat_with_retries(&self, chat: TelegramChat, parse_mode: &str, text: &str) {
+        let mut delay = FIRST_RETRY_DELAY;
+        let mut attempt = 0;
+
+        loop {
+            let err = match api::send_message(&self.settings, chat, parse_mode, text).await {
+                Ok(()) => return,
+                Err(err) => err,
+            };
+
+            if attempt >= self.max_retries {
+                println!(
+                    "Summary to telegram chat {} of channel '{}' is dropped after {} attempts: {}",
+                    chat.chat_id,
+                    self.name,
+                    attempt + 1,
+                    err
+                );
+                return;
+            }
+
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(MAX_RETRY_DELAY);
+            attempt += 1;
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use std::fmt::Write;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    alerts::AlertNotificationKind,
+    app::APPLICATION_KEY,
+    settings::{NotificationKind, NotificationTemplates},
+};
+
+use super::{Notification, NotificationContext};
+
+const MAX_SUMMARY_APPLICATIONS: usize = 20;
+
+pub fn get_template(templates: &NotificationTemplates, kind: NotificationKind) -> Option<&str> {
+    match kind {
+        NotificationKind::Summary => templates.summary.as_deref(),
+        NotificationKind::Alert => templates.alert.as_deref(),
+        NotificationKind::Event => templates.event.as_deref(),
+        NotificationKind::NewError => templates.new_error.as_deref(),
+    }
+}
+
+/// Values available as `{placeholder}` in templates. Also sent as `data` by the webhook channel.
+pub fn get_template_fields(
+    notification: &Notification,
+    ctx: &NotificationContext,
+) -> Vec<(&'static str, String)> {
+    let mut result = vec![
+        ("env", ctx.env_name.clone()),
+        ("kind", format!("{:?}", notification.get_kind())),
+        ("ui_url", ctx.ui_url.clone()),
+    ];
+
+    match notification {
+        Notification::Summary(item) => {
+            let minute: DateTimeAsMicroseconds = item.key.try_into().unwrap();
+
+            let mut applications = String::new();
+            for (application, by_app) in item.get_applications_by_severity() {
+                if !applications.is_empty() {
+                    applications.push('\n');
+                }
+                let _ = write!(
+                    applications,
+                    "{}: fatal {}, errors {}, warnings {}",
+                    application, by_app.fatal_errors, by_app.errors, by_app.warnings
+                );
+            }
+
+            result.push(("minute", minute.to_rfc3339()));
+            result.push(("fatal_errors", item.fatal_errors.to_string()));
+            result.push(("errors", item.errors.to_string()));
+            result.push(("warnings", item.warnings.to_string()));
+            result.push(("applications", applications));
+        }
+        Notification::Alert(alert) => {
+            result.push(("state", format!("{:?}", alert.kind)));
+            result.push(("rule", alert.rule_name.clone()));
+            result.push(("application", alert.application.clone().unwrap_or_default()));
+            result.push(("group_by", alert.group_by.clone().unwrap_or_default()));
+            result.push(("group", alert.group.clone()));
+            result.push(("count", alert.count.to_string()));
+            result.push(("threshold", alert.threshold.to_string()));
+            result.push(("window_sec", alert.window_sec.to_string()));
+            result.push(("message", alert.sample_message.clone().unwrap_or_default()));
+            result.push(("moment", alert.moment.to_rfc3339()));
+            result.push((
+                "saved_search_url",
+                alert
+                    .saved_search_id
+                    .as_deref()
+                    .and_then(|id| crate::app::ui_links::compile_saved_search_url(&ctx.ui_url, id))
+                    .unwrap_or_default(),
+            ));
+        }
+        Notification::Event(item) => {
+            let context: Vec<_> = item
+                .ctx
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+
+            result.push(("level", format!("{:?}", item.level)));
+            result.push((
+                "application",
+                item.ctx.get(APPLICATION_KEY).cloned().unwrap_or_default(),
+            ));
+            result.push(("process", item.process.clone().unwrap_or_default()));
+            result.push(("message", item.message.clone()));
+            result.push(("moment", item.timestamp.to_rfc3339()));
+            result.push(("context", context.join(", ")));
+        }
+        Notification::NewError(new_error) => {
+            let item = &new_error.item;
+            result.push(("level", format!("{:?}", item.level)));
+            result.push(("application", item.application.clone()));
+            result.push(("signature", item.signature.clone()));
+            result.push(("template", item.template.clone()));
+            result.push(("message", item.sample_message.clone()));
+            result.push(("count", item.count.to_string()));
+            result.push((
+                "first_seen",
+                DateTimeAsMicroseconds::new(item.first_seen).to_rfc3339(),
+            ));
+            result.push((
+                "reappeared_after_sec",
+                new_error
+                    .reappeared_after_sec
+                    .map(|sec| sec.to_string())
+                    .unwrap_or_default(),
+            ));
+        }
+    }
+
+    result
+}
+
+/// Replaces known `{placeholder}`s with escaped values. Unknown ones are kept as is.
+pub fn render_template(
+    template: &str,
+    fields: &[(&str, String)],
+    escape: impl Fn(&mut String, &str),
+) -> String {
+    let mut result = String::with_capacity(template.len() * 2);
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let value = rest.find('}').and_then(|end| {
+            let name = &rest[1..end];
+            fields
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (end, value))
+        });
+
+        match value {
+            Some((end, value)) => {
+                escape(&mut result, value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+pub fn push_raw(dst: &mut String, src: &str) {
+    dst.push_str(src);
+}
+
+/// Markup differences between the chat tools rendering the built-in text.
+pub struct MarkdownStyle {
+    pub bold: &'static str,
+    pub line_break: &'static str,
+}
+
+pub const SLACK_STYLE: MarkdownStyle = MarkdownStyle {
+    bold: "*",
+    line_break: "\n",
+};
+
+pub const TEAMS_STYLE: MarkdownStyle = MarkdownStyle {
+    bold: "**",
+    line_break: "\n\n",
+};
+
+pub const PLAIN_STYLE: MarkdownStyle = MarkdownStyle {
+    bold: "",
+    line_break: "\n",
+};
+
+/// Built-in text used when the channel has no template for the notification kind.
+pub fn compile_markdown_text(
+    notification: &Notification,
+    ctx: &NotificationContext,
+    style: &MarkdownStyle,
+) -> String {
+    let b = style.bold;
+    let br = style.line_break;
+
+    let mut result = String::with_capacity(512);
+
+    match notification {
+        Notification::Summary(item) => {
+            let minute: DateTimeAsMicroseconds = item.key.try_into().unwrap();
+            let _ = write!(
+                result,
+                "📊 {b}{}{b} minute {}: ☠️ FatalErrors {}, 🟥 Errors {}, ⚠️ Warnings {}",
+                ctx.env_name,
+                minute.to_rfc3339(),
+                item.fatal_errors,
+                item.errors,
+                item.warnings,
+            );
+
+            let applications = item.get_applications_by_severity();
+            for (application, by_app) in applications.iter().take(MAX_SUMMARY_APPLICATIONS) {
+                let _ = write!(
+                    result,
+                    "{br}• {b}{}{b} ☠️{} 🟥{} ⚠️{}",
+                    application, by_app.fatal_errors, by_app.errors, by_app.warnings
+                );
+                if let Some((message, count)) = by_app.get_top_messages(1).first() {
+                    let _ = write!(result, " — {}× {}", count, message);
+                }
+            }
+
+            if applications.len() > MAX_SUMMARY_APPLICATIONS {
+                let _ = write!(
+                    result,
+                    "{br}…and {} more applications",
+                    applications.len() - MAX_SUMMARY_APPLICATIONS
+                );
+            }
+        }
+        Notification::Alert(alert) => {
+            match alert.kind {
+                AlertNotificationKind::Firing => {
+                    let _ = write!(result, "🚨 {b}Alert{b}: {}", alert.rule_name);
+                }
+                AlertNotificationKind::Resolved => {
+                    let _ = write!(result, "✅ {b}Resolved{b}: {}", alert.rule_name);
+                }
+            }
+
+            let _ = write!(result, "{br}Env: {}", ctx.env_name);
+            if let Some(application) = alert.application.as_deref() {
+                let _ = write!(result, "{br}Application: {}", application);
+            }
+            if let Some(group_by) = alert.group_by.as_deref() {
+                let _ = write!(result, "{br}{}: {}", group_by, alert.group);
+            }
+            let _ = write!(
+                result,
+                "{br}Events: {} in {}s (threshold {}){br}At: {}",
+                alert.count,
+                alert.window_sec,
+                alert.threshold,
+                alert.moment.to_rfc3339()
+            );
+            if let Some(sample_message) = alert.sample_message.as_deref() {
+                let _ = write!(result, "{br}Msg: {}", sample_message);
+            }
+        }
+        Notification::Event(item) => {
+            let _ = write!(
+                result,
+                "{b}{:?}{b}: {}{br}Env: {}{br}At: {}",
+                item.level,
+                item.message,
+                ctx.env_name,
+                item.timestamp.to_rfc3339()
+            );
+            if let Some(process) = item.process.as_deref() {
+                let _ = write!(result, "{br}Process: {}", process);
+            }
+            for (key, value) in item.ctx.iter() {
+                let _ = write!(result, "{br}{}: {}", key, value);
+            }
+        }
+        Notification::NewError(new_error) => {
+            let item = &new_error.item;
+            match new_error.reappeared_after_sec {
+                Some(sec) => {
+                    let _ = write!(
+                        result,
+                        "🔁 {b}Error is back after {} days{b}: {}",
+                        sec / 86400,
+                        item.application
+                    );
+                }
+                None => {
+                    let _ = write!(result, "🆕 {b}New error{b}: {}", item.application);
+                }
+            }
+            let _ = write!(
+                result,
+                "{br}Env: {}{br}Level: {:?}{br}Msg: {}{br}Signature: {} (seen {} times since {})",
+                ctx.env_name,
+                item.level,
+                item.sample_message,
+                item.signature,
+                item.count,
+                DateTimeAsMicroseconds::new(item.first_seen).to_rfc3339()
+            );
+        }
+    }
+
+    if !ctx.ui_url.is_empty() {
+        let _ = write!(result, "{br}{}", ctx.ui_url);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{push_raw, render_template};
+
+    #[test]
+    fn test_render_template() {
+        let fields = vec![
+            ("rule", "Payments <errors>".to_string()),
+            ("count", "21".to_string()),
+        ];
+
+        assert_eq!(
+            render_template("{rule}: {count} {unknown} {", &fields, push_raw),
+            "Payments <errors>: 21 {unknown} {"
+        );
+
+        assert_eq!(
+            render_template("{rule}", &fields, crate::telegram::api::append_html_escaped),
+            "Payments &lt;errors&gt;"
+        );
+    }
+}
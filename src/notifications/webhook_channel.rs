@@ -0,0 +1,52 @@
+use std::collections::BTreeMap;
+
+use crate::settings::NotificationTemplates;
+
+use super::{templates, Notification, NotificationChannel, NotificationContext};
+
+/// Generic JSON webhook: `{kind, env, text, data}` where `data` holds the template fields.
+pub struct WebhookChannel {
+    pub name: String,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    pub templates: NotificationTemplates,
+    pub max_retries: u32,
+}
+
+#[async_trait::async_trait]
+impl NotificationChannel for WebhookChannel {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    async fn send(
+        &self,
+        notification: &Notification,
+        ctx: &NotificationContext,
+    ) -> Result<(), String> {
+        let fields = templates::get_template_fields(notification, ctx);
+
+        let text = match templates::get_template(&self.templates, notification.get_kind()) {
+            Some(template) => templates::render_template(template, &fields, templates::push_raw),
+            None => templates::compile_markdown_text(notification, ctx, &templates::PLAIN_STYLE),
+        };
+
+        let mut data = serde_json::Map::new();
+        for (key, value) in fields {
+            data.insert(key.to_string(), serde_json::Value::String(value));
+        }
+
+        let body = serde_json::json!({
+            "kind": format!("{:?}", notification.get_kind()),
+            "env": ctx.env_name,
+            "text": text,
+            "data": data,
+        });
+
+        super::post_json(&self.url, &self.headers, &body).await
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+
+use crate::{
+    settings::{RedactionAction, RedactionDetector},
+    text_matching::compile_regex,
+};
+
+/// Name of the group which narrows the redacted part of a match. Whole match is redacted if absent.
+pub const SECRET_GROUP: &str = "secret";
+
+const JWT_PATTERN: &str = r"\beyJ[A-Za-z0-9_-]{5,}\.[A-Za-z0-9_-]{5,}\.[A-Za-z0-9_-]{10,}";
+const BEARER_PATTERN: &str = r"(?i:\bbearer)\s+(?P<secret>[A-Za-z0-9\-._~+/]{8,}=*)";
+const PAN_PATTERN: &str = r"\b\d(?:[ -]?\d){12,18}\b";
+const EMAIL_PATTERN: &str =
+    r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b";
+const IBAN_PATTERN: &str = r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]){11,30}\b";
+
+pub struct Detector {
+    pub name: String,
+    regex: Regex,
+    validate: Option<fn(&str) -> bool>,
+    action: RedactionAction,
+    context_keys: BTreeMap<String, RedactionAction>,
+}
+
+impl Detector {
+    pub fn builtin(
+        detector: RedactionDetector,
+        action: RedactionAction,
+        context_keys: BTreeMap<String, RedactionAction>,
+    ) -> Self {
+        let (pattern, validate): (&str, Option<fn(&str) -> bool>) = match detector {
+            RedactionDetector::Jwt => (JWT_PATTERN, None),
+            RedactionDetector::Bearer => (BEARER_PATTERN, None),
+            RedactionDetector::Pan => (PAN_PATTERN, Some(is_valid_pan)),
+            RedactionDetector::Email => (EMAIL_PATTERN, None),
+            RedactionDetector::Iban => (IBAN_PATTERN, Some(is_valid_iban)),
+        };
+
+        Self {
+            name: format!("{:?}", detector),
+            regex: Regex::new(pattern).unwrap(),
+            validate,
+            action,
+            context_keys,
+        }
+    }
+
+    pub fn custom(
+        name: &str,
+        pattern: &str,
+        action: RedactionAction,
+        context_keys: BTreeMap<String, RedactionAction>,
+    ) -> Result<Self, String> {
+        let regex = compile_regex(pattern, false)?;
+
+        if regex.is_match("") {
+            return Err(format!(
+                "Redaction pattern '{}' matches an empty string",
+                pattern
+            ));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            regex,
+            validate: None,
+            action,
+            context_keys,
+        })
+    }
+
+    pub fn get_action(&self, context_key: Option<&str>) -> RedactionAction {
+        match context_key.and_then(|key| self.context_keys.get(key)) {
+            Some(action) => *action,
+            None => self.action,
+        }
+    }
+
+    /// Returns the redacted text and the number of replaced values. `None` if nothing matched.
+    pub fn redact(
+        &self,
+        src: &str,
+        replace: impl Fn(&str, &str) -> String,
+    ) -> Option<(String, usize)> {
+        let mut result = String::new();
+        let mut last_end = 0;
+        let mut replaced = 0;
+
+        for captures in self.regex.captures_iter(src) {
+            let found = match captures.name(SECRET_GROUP) {
+                Some(secret) => secret,
+                None => captures.get(0).unwrap(),
+            };
+
+            if let Some(validate) = self.validate {
+                if !validate(found.as_str()) {
+                    continue;
+                }
+            }
+
+            result.push_str(&src[last_end..found.start()]);
+            result.push_str(&replace(&self.name, found.as_str()));
+            last_end = found.end();
+            replaced += 1;
+        }
+
+        if replaced == 0 {
+            return None;
+        }
+
+        result.push_str(&src[last_end..]);
+        Some((result, replaced))
+    }
+}
+
+/// Card number length, major network prefix and Luhn checksum.
+/// Prefix check keeps unix timestamps and ids which start with `1` or `9` out.
+pub fn is_valid_pan(src: &str) -> bool {
+    let digits: Vec<u32> = src.chars().filter_map(|c| c.to_digit(10)).collect();
+
+    if digits.len() < 13 || digits.len() > 19 {
+        return false;
+    }
+
+    if !matches!(digits[0], 2..=6) {
+        return false;
+    }
+
+    let mut sum = 0;
+    for (i, digit) in digits.iter().rev().enumerate() {
+        if i % 2 == 1 {
+            let doubled = digit * 2;
+            sum += if doubled > 9 { doubled - 9 } else { doubled };
+        } else {
+            sum += digit;
+        }
+    }
+
+    sum % 10 == 0
+}
+
+/// ISO 13616 mod-97 checksum.
+pub fn is_valid_iban(src: &str) -> bool {
+    let iban: Vec<char> = src.chars().filter(|c| !c.is_whitespace()).collect();
+
+    if iban.len() < 15 || iban.len() > 34 {
+        return false;
+    }
+
+    let mut remainder: u32 = 0;
+    for c in iban[4..].iter().chain(iban[..4].iter()) {
+        let value = match c.to_digit(36) {
+            Some(value) => value,
+            None => return false,
+        };
+
+        remainder = if value < 10 {
+            (remainder * 10 + value) % 97
+        } else {
+            (remainder * 100 + value) % 97
+        };
+    }
+
+    remainder == 1
+}
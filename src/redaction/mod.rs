@@ -0,0 +1,6 @@
+mod detectors;
+pub use detectors::*;
+mod redactor;
+pub use redactor::*;
+mod redaction_statistics;
+pub use redaction_statistics::*;
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+pub struct RedactionStatisticsItem {
+    pub application: String,
+    pub by_detector: BTreeMap<String, u64>,
+    pub last_redacted: Option<DateTimeAsMicroseconds>,
+}
+
+/// Redaction counters per application since the server start.
+pub struct RedactionStatistics {
+    by_application: BTreeMap<String, BTreeMap<String, u64>>,
+    last_redacted: BTreeMap<String, DateTimeAsMicroseconds>,
+}
+
+impl RedactionStatistics {
+    pub fn new() -> Self {
+        Self {
+            by_application: BTreeMap::new(),
+            last_redacted: BTreeMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, application: &str, detector: &str, count: usize) {
+        if count == 0 {
+            return;
+        }
+
+        if !self.by_application.contains_key(application) {
+            self.by_application
+                .insert(application.to_string(), BTreeMap::new());
+        }
+
+        let by_detector = self.by_application.get_mut(application).unwrap();
+
+        match by_detector.get_mut(detector) {
+            Some(value) => *value += count as u64,
+            None => {
+                by_detector.insert(detector.to_string(), count as u64);
+            }
+        }
+
+        self.last_redacted
+            .insert(application.to_string(), DateTimeAsMicroseconds::now());
+    }
+
+    pub fn get_all(&self) -> Vec<RedactionStatisticsItem> {
+        self.by_application
+            .iter()
+            .map(|(application, by_detector)| RedactionStatisticsItem {
+                application: application.to_string(),
+                by_detector: by_detector.clone(),
+                last_redacted: self.last_redacted.get(application).copied(),
+            })
+            .collect()
+    }
+}
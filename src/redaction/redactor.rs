@@ -0,0 +1,327 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::{
+    app::{LogItem, APPLICATION_KEY, NO_APPLICATION},
+    settings::{RedactionAction, RedactionDetector, RedactionSettings},
+};
+
+use super::{Detector, RedactionStatistics};
+
+/// Bytes of the HMAC kept in the `Hash` token.
+const HASH_BYTES: usize = 8;
+
+const ALL_DETECTORS: [RedactionDetector; 5] = [
+    RedactionDetector::Jwt,
+    RedactionDetector::Bearer,
+    RedactionDetector::Pan,
+    RedactionDetector::Iban,
+    RedactionDetector::Email,
+];
+
+/// Redaction stage of the ingest pipeline. Applied to message, process and context values.
+pub struct Redactor {
+    detectors: Vec<Detector>,
+    /// Keyed with `hash_salt`. `None` without it: the `Hash` action is replaced with `Mask` then.
+    hasher: Option<Hmac<Sha256>>,
+}
+
+impl Redactor {
+    /// No detectors without the settings section. With the section, every built-in detector masks unless `detectors` are set.
+    pub fn new(settings: Option<RedactionSettings>) -> Self {
+        let settings = match settings {
+            Some(settings) if !settings.disabled => settings,
+            _ => {
+                return Self {
+                    detectors: Vec::new(),
+                    hasher: None,
+                };
+            }
+        };
+
+        let hasher = settings
+            .hash_salt
+            .as_deref()
+            .filter(|salt| !salt.is_empty())
+            .map(|salt| Hmac::<Sha256>::new_from_slice(salt.as_bytes()).unwrap());
+
+        let mut detectors = Vec::new();
+
+        match settings.detectors {
+            Some(configured) => {
+                for itm in configured {
+                    let name = format!("{:?}", itm.detector);
+                    let (action, context_keys) = get_actions(
+                        &name,
+                        itm.action,
+                        itm.context_keys.unwrap_or_default(),
+                        hasher.is_some(),
+                    );
+                    detectors.push(Detector::builtin(itm.detector, action, context_keys));
+                }
+            }
+            None => {
+                for detector in ALL_DETECTORS {
+                    detectors.push(Detector::builtin(
+                        detector,
+                        RedactionAction::Mask,
+                        BTreeMap::new(),
+                    ));
+                }
+            }
+        }
+
+        for itm in settings.custom.unwrap_or_default() {
+            let (action, context_keys) = get_actions(
+                &itm.name,
+                itm.action,
+                itm.context_keys.unwrap_or_default(),
+                hasher.is_some(),
+            );
+            match Detector::custom(&itm.name, &itm.pattern, action, context_keys) {
+                Ok(detector) => detectors.push(detector),
+                Err(err) => println!("Skipping redaction rule {}: {}", itm.name, err),
+            }
+        }
+
+        Self { detectors, hasher }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.detectors.is_empty()
+    }
+
+    pub fn apply(&self, item: &mut LogItem, statistics: &mut RedactionStatistics) {
+        let application = item
+            .ctx
+            .get(APPLICATION_KEY)
+            .cloned()
+            .unwrap_or_else(|| NO_APPLICATION.to_string());
+
+        for detector in self.detectors.iter() {
+            let mut count = self.redact_value(detector, None, &mut item.message);
+
+            if let Some(process) = item.process.as_mut() {
+                count += self.redact_value(detector, None, process);
+            }
+
+            for (key, value) in item.ctx.iter_mut() {
+                count += self.redact_value(detector, Some(key), value);
+            }
+
+            statistics.add(&application, &detector.name, count);
+        }
+    }
+
+    fn redact_value(
+        &self,
+        detector: &Detector,
+        context_key: Option<&str>,
+        value: &mut String,
+    ) -> usize {
+        let action = detector.get_action(context_key);
+
+        let redacted = match action {
+            RedactionAction::Keep => return 0,
+            RedactionAction::Mask => {
+                detector.redact(value, |name, _| format!("[REDACTED:{}]", name))
+            }
+            RedactionAction::Hash => {
+                let Some(hasher) = self.hasher.as_ref() else {
+                    return 0;
+                };
+                detector.redact(value, |name, found| {
+                    format!("[{}:{}]", name, compile_hash(hasher, found))
+                })
+            }
+        };
+
+        match redacted {
+            Some((redacted, count)) => {
+                *value = redacted;
+                count
+            }
+            None => 0,
+        }
+    }
+}
+
+/// `Hash` can only be used with a `hash_salt`: unkeyed hashes of card numbers or emails can be
+/// brute-forced. Without it the action is rejected and the values are masked.
+fn get_actions(
+    name: &str,
+    action: RedactionAction,
+    mut context_keys: BTreeMap<String, RedactionAction>,
+    can_hash: bool,
+) -> (RedactionAction, BTreeMap<String, RedactionAction>) {
+    if can_hash {
+        return (action, context_keys);
+    }
+
+    let mut rejected = false;
+    let mut reject = |action: &mut RedactionAction| {
+        if *action == RedactionAction::Hash {
+            *action = RedactionAction::Mask;
+            rejected = true;
+        }
+    };
+
+    let mut action = action;
+    reject(&mut action);
+    context_keys.values_mut().for_each(reject);
+
+    if rejected {
+        println!(
+            "Redaction {}: Hash action requires hash_salt. Values are masked instead",
+            name
+        );
+    }
+
+    (action, context_keys)
+}
+
+/// HMAC-SHA256 keyed with `hash_salt`, so the same secret gives the same token across events
+/// and restarts, but the token can not be reversed without the key.
+fn compile_hash(hasher: &Hmac<Sha256>, value: &str) -> String {
+    let mut mac = hasher.clone();
+    mac.update(value.as_bytes());
+    let bytes = mac.finalize().into_bytes();
+
+    let mut result = String::with_capacity(HASH_BYTES * 2);
+    for byte in &bytes[..HASH_BYTES] {
+        let _ = write!(result, "{:02x}", byte);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::Redactor;
+    use crate::{
+        app::LogItem,
+        redaction::{is_valid_iban, is_valid_pan, RedactionStatistics},
+        settings::{
+            RedactionAction, RedactionDetector, RedactionDetectorSettings, RedactionSettings,
+        },
+    };
+
+    #[test]
+    fn test_checksums() {
+        assert!(is_valid_pan("4111 1111 1111 1111"));
+        assert!(!is_valid_pan("4111 1111 1111 1112"));
+        assert!(!is_valid_pan("1700000000000005"));
+        assert!(is_valid_iban("GB82 WEST 1234 5698 7654 32"));
+        assert!(!is_valid_iban("GB82 WEST 1234 5698 7654 33"));
+    }
+
+    #[test]
+    fn test_redaction_with_context_key_override() {
+        let mut context_keys = BTreeMap::new();
+        context_keys.insert("CustomerEmail".to_string(), RedactionAction::Hash);
+
+        let redactor = Redactor::new(Some(RedactionSettings {
+            disabled: false,
+            detectors: Some(vec![
+                RedactionDetectorSettings {
+                    detector: RedactionDetector::Email,
+                    action: RedactionAction::Mask,
+                    context_keys: Some(context_keys),
+                },
+                RedactionDetectorSettings {
+                    detector: RedactionDetector::Pan,
+                    action: RedactionAction::Mask,
+                    context_keys: None,
+                },
+            ]),
+            custom: None,
+            hash_salt: Some("test-salt".to_string()),
+        }));
+
+        let mut ctx = BTreeMap::new();
+        ctx.insert("Application".to_string(), "billing".to_string());
+        ctx.insert("CustomerEmail".to_string(), "john@example.com".to_string());
+
+        let mut item = LogItem {
+            id: "1".to_string(),
+            level: my_logger::LogLevel::Error,
+            process: None,
+            message: "Payment of john@example.com with card 4111-1111-1111-1111 failed".to_string(),
+            timestamp: DateTimeAsMicroseconds::now(),
+            ctx,
+        };
+
+        let mut statistics = RedactionStatistics::new();
+        redactor.apply(&mut item, &mut statistics);
+
+        assert_eq!(
+            item.message,
+            "Payment of [REDACTED:Email] with card [REDACTED:Pan] failed"
+        );
+        assert!(item
+            .ctx
+            .get("CustomerEmail")
+            .unwrap()
+            .starts_with("[Email:"));
+
+        let all = statistics.get_all();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].application, "billing");
+        assert_eq!(all[0].by_detector.get("Email"), Some(&2));
+        assert_eq!(all[0].by_detector.get("Pan"), Some(&1));
+    }
+    #[test]
+    fn test_hash_without_salt_is_masked() {
+        let mut context_keys = BTreeMap::new();
+        context_keys.insert("CustomerEmail".to_string(), RedactionAction::Hash);
+
+        let settings = |hash_salt: Option<&str>| RedactionSettings {
+            disabled: false,
+            detectors: Some(vec![RedactionDetectorSettings {
+                detector: RedactionDetector::Email,
+                action: RedactionAction::Hash,
+                context_keys: Some(context_keys.clone()),
+            }]),
+            custom: None,
+            hash_salt: hash_salt.map(|salt| salt.to_string()),
+        };
+
+        let redact = |redactor: &Redactor| {
+            let mut ctx = BTreeMap::new();
+            ctx.insert("CustomerEmail".to_string(), "john@example.com".to_string());
+            let mut item = LogItem {
+                id: "1".to_string(),
+                level: my_logger::LogLevel::Error,
+                process: None,
+                message: "Sent to john@example.com".to_string(),
+                timestamp: DateTimeAsMicroseconds::now(),
+                ctx,
+            };
+            redactor.apply(&mut item, &mut RedactionStatistics::new());
+            item
+        };
+
+        let item = redact(&Redactor::new(Some(settings(None))));
+        assert_eq!(item.message, "Sent to [REDACTED:Email]");
+        assert_eq!(item.ctx.get("CustomerEmail").unwrap(), "[REDACTED:Email]");
+
+        let item = redact(&Redactor::new(Some(settings(Some("salt-1")))));
+        let hashed = item.ctx.get("CustomerEmail").unwrap();
+        assert_eq!(item.message, format!("Sent to {}", hashed));
+        assert_eq!(hashed.len(), "[Email:]".len() + 16);
+
+        let item = redact(&Redactor::new(Some(settings(Some("salt-2")))));
+        assert_ne!(item.ctx.get("CustomerEmail").unwrap(), hashed);
+    }
+
+    #[test]
+    fn test_no_detectors_without_settings() {
+        assert!(Redactor::new(None).is_empty());
+        assert!(!Redactor::new(Some(RedactionSettings::default())).is_empty());
+    }
+}
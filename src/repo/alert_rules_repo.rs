@@ -0,0 +1,72 @@
+use tokio::sync::Mutex;
+
+use super::dto::*;
+
+pub struct AlertRulesRepo {
+    items: Mutex<Vec<AlertRuleDto>>,
+    path: String,
+}
+
+impl AlertRulesRepo {
+    pub async fn new(path: String) -> Self {
+        let items = match tokio::fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice::<Vec<AlertRuleDto>>(&bytes).unwrap_or_default(),
+            Err(_) => Vec::new(),
+        };
+        Self {
+            items: Mutex::new(items),
+            path,
+        }
+    }
+
+    pub async fn get_all(&self) -> Vec<AlertRuleDto> {
+        let read_access = self.items.lock().await;
+        read_access.clone()
+    }
+
+    pub async fn get(&self, id: &str) -> Option<AlertRuleDto> {
+        let read_access = self.items.lock().await;
+        read_access.iter().find(|itm| itm.id == id).cloned()
+    }
+
+    /// Inserts a new alert rule or replaces the one with the same id. Returns the replaced one.
+    pub async fn upsert(&self, item: AlertRuleDto) -> Option<AlertRuleDto> {
+        let mut write_access = self.items.lock().await;
+        let before = match write_access.iter_mut().find(|x| x.id == item.id) {
+            Some(existing) => {
+                if *existing == item {
+                    return Some(item);
+                }
+                Some(std::mem::replace(existing, item))
+            }
+            None => {
+                write_access.push(item);
+                None
+            }
+        };
+        persist(&self.path, &write_access).await;
+        before
+    }
+
+    /// Returns the deleted alert rule, `None` if there was no alert rule with the given id.
+    pub async fn delete(&self, id: &str) -> Option<AlertRuleDto> {
+        let mut write_access = self.items.lock().await;
+        let index = write_access.iter().position(|itm| itm.id == id)?;
+        let before = write_access.remove(index);
+        persist(&self.path, &write_access).await;
+        Some(before)
+    }
+}
+
+async fn persist(path: &str, items: &[AlertRuleDto]) {
+    let bytes = match serde_json::to_vec_pretty(items) {
+        Ok(b) => b,
+        Err(e) => {
+            println!("Failed to serialize alert rules: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = tokio::fs::write(path, bytes).await {
+        println!("Failed to persist alert rules to {}: {}", path, e);
+    }
+}
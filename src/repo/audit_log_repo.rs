@@ -0,0 +1,279 @@
+use std::{io::SeekFrom, path::Path};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+    sync::Mutex,
+};
+
+use super::dto::*;
+
+/// Once the file grows above this size it is closed as the next numbered segment `<path>.<n>`.
+/// Segments are never deleted.
+const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+const READ_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// Append-only JSON lines file. Every line is one `AuditLogEntryDto`.
+pub struct AuditLogRepo {
+    path: String,
+    write_lock: Mutex<()>,
+}
+
+impl AuditLogRepo {
+    pub fn new(path: String) -> Self {
+        Self {
+            path,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    fn get_segment_path(&self, no: u64) -> String {
+        format!("{}.{}", self.path, no)
+    }
+
+    /// Numbers of the closed segments, oldest first.
+    async fn get_segment_numbers(&self) -> Vec<u64> {
+        let path = Path::new(&self.path);
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            return vec![];
+        };
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+
+        let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+            return vec![];
+        };
+
+        let mut result = Vec::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name();
+            let Some(no) = name
+                .to_str()
+                .and_then(|name| name.strip_prefix(file_name))
+                .and_then(|suffix| suffix.strip_prefix('.'))
+                .and_then(|no| no.parse::<u64>().ok())
+            else {
+                continue;
+            };
+            result.push(no);
+        }
+
+        result.sort();
+        result
+    }
+
+    pub async fn append(&self, entry: &AuditLogEntryDto) {
+        let mut line = match serde_json::to_string(entry) {
+            Ok(line) => line,
+            Err(e) => {
+                println!("Failed to serialize audit log entry: {}", e);
+                return;
+            }
+        };
+        line.push('\n');
+
+        let _write_access = self.write_lock.lock().await;
+
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await;
+
+        let mut file = match file {
+            Ok(file) => file,
+            Err(e) => {
+                println!("Failed to open audit log {}: {}", self.path, e);
+                return;
+            }
+        };
+
+        if let Err(e) = file.write_all(line.as_bytes()).await {
+            println!("Failed to append audit log entry to {}: {}", self.path, e);
+            return;
+        }
+
+        let size = match file.metadata().await {
+            Ok(metadata) => metadata.len(),
+            Err(_) => return,
+        };
+
+        if size > MAX_FILE_SIZE {
+            let segments = self.get_segment_numbers().await;
+            let next_no = segments.last().map_or(1, |no| no + 1);
+            let rotated_path = self.get_segment_path(next_no);
+            if let Err(e) = tokio::fs::rename(&self.path, &rotated_path).await {
+                println!(
+                    "Failed to rotate audit log {} to {}: {}",
+                    self.path, rotated_path, e
+                );
+            }
+        }
+    }
+
+    /// Newest entries first. The current file and then the segments from the newest are read
+    /// from the end without blocking appends: a line being written at the moment is skipped.
+    pub async fn query(&self, filter: &AuditLogFilterDto, take: usize) -> Vec<AuditLogEntryDto> {
+        let mut result = Vec::new();
+
+        read_from_end(&self.path, filter, take, &mut result).await;
+
+        if result.len() < take {
+            for no in self.get_segment_numbers().await.into_iter().rev() {
+                read_from_end(&self.get_segment_path(no), filter, take, &mut result).await;
+                if result.len() >= take {
+                    break;
+                }
+            }
+        }
+
+        result
+    }
+}
+
+async fn read_from_end(
+    path: &str,
+    filter: &AuditLogFilterDto,
+    take: usize,
+    result: &mut Vec<AuditLogEntryDto>,
+) {
+    let Ok(mut file) = tokio::fs::File::open(path).await else {
+        return;
+    };
+
+    let mut position = match file.metadata().await {
+        Ok(metadata) => metadata.len(),
+        Err(_) => return,
+    };
+
+    // Start of the line the previous chunk began in the middle of.
+    let mut line_end: Vec<u8> = Vec::new();
+
+    while position > 0 && result.len() < take {
+        let chunk_size = READ_CHUNK_SIZE.min(position);
+        position -= chunk_size;
+
+        let mut chunk = vec![0u8; chunk_size as usize];
+        let read = match file.seek(SeekFrom::Start(position)).await {
+            Ok(_) => file.read_exact(&mut chunk).await,
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = read {
+            println!("Failed to read audit log {}: {}", path, e);
+            return;
+        }
+
+        chunk.extend_from_slice(&line_end);
+
+        let first_line_len = if position > 0 {
+            match chunk.iter().position(|b| *b == b'\n') {
+                Some(index) => index,
+                None => {
+                    line_end = chunk;
+                    continue;
+                }
+            }
+        } else {
+            0
+        };
+
+        let complete_lines = if position > 0 {
+            &chunk[first_line_len + 1..]
+        } else {
+            &chunk[..]
+        };
+
+        for line in complete_lines.split(|b| *b == b'\n').rev() {
+            let Ok(entry) = serde_json::from_slice::<AuditLogEntryDto>(line) else {
+                continue;
+            };
+
+            if filter.matches(&entry) {
+                result.push(entry);
+                if result.len() >= take {
+                    return;
+                }
+            }
+        }
+
+        chunk.truncate(first_line_len);
+        line_end = chunk;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AuditLogRepo;
+    use crate::repo::dto::{
+        AuditActionDto, AuditActorDto, AuditActorKindDto, AuditLogEntryDto, AuditLogFilterDto,
+        AuditTargetDto,
+    };
+
+    #[tokio::test]
+    async fn test_query_reads_newest_entries_across_chunks() {
+        let path =
+            std::env::temp_dir().join(format!("audit-log-test-{}.jsonl", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let repo = AuditLogRepo::new(path.clone());
+
+        for i in 0..2000 {
+            repo.append(&AuditLogEntryDto {
+                moment: i,
+                actor: AuditActorDto::new(AuditActorKindDto::Http, Some("tests".to_string())),
+                target: AuditTargetDto::SavedSearch,
+                action: AuditActionDto::Created,
+                id: i.to_string(),
+                before: None,
+                after: Some(serde_json::json!({ "name": "x".repeat(100) })),
+            })
+            .await;
+        }
+
+        let entries = repo.query(&AuditLogFilterDto::default(), 1500).await;
+        let _ = tokio::fs::remove_file(&path).await;
+
+        assert_eq!(entries.len(), 1500);
+        assert!(entries
+            .iter()
+            .zip((500..2000).rev())
+            .all(|(entry, moment)| entry.moment == moment));
+    }
+
+    #[tokio::test]
+    async fn test_query_reads_every_segment_newest_first() {
+        let dir = std::env::temp_dir().join(format!("audit-log-segments-{}", std::process::id()));
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let path = dir.join("audit-log.jsonl").to_string_lossy().to_string();
+        let entry = |moment: i64| AuditLogEntryDto {
+            moment,
+            actor: AuditActorDto::new(AuditActorKindDto::Http, None),
+            target: AuditTargetDto::AlertRule,
+            action: AuditActionDto::Updated,
+            id: moment.to_string(),
+            before: None,
+            after: None,
+        };
+
+        // Segments 1, 2 and 10 hold the moments 1, 2 and 10, the current file 11.
+        for no in [1, 2, 10] {
+            let segment = AuditLogRepo::new(format!("{}.{}", path, no));
+            segment.append(&entry(no)).await;
+        }
+        let repo = AuditLogRepo::new(path.clone());
+        repo.append(&entry(11)).await;
+
+        let moments: Vec<i64> = repo
+            .query(&AuditLogFilterDto::default(), 10)
+            .await
+            .iter()
+            .map(|entry| entry.moment)
+            .collect();
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+
+        assert_eq!(moments, vec![11, 10, 2, 1]);
+    }
+}
@@ -4,7 +4,10 @@ use my_logger::LogLevel;
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 use serde::{Deserialize, Serialize};
 
-use crate::app::LogItem;
+use crate::{
+    app::APPLICATION_KEY,
+    text_matching::{ConditionMatcher, TextMatcher},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum LogLevelDto {
@@ -15,25 +18,25 @@ pub enum LogLevelDto {
     Debug,
 }
 
-impl LogLevelDto {
-    pub fn is_info(&self) -> bool {
-        matches!(self, LogLevelDto::Info)
-    }
-
-    pub fn is_warning(&self) -> bool {
-        matches!(self, LogLevelDto::Warning)
-    }
+/// Every level in the order of declaration.
+pub const ALL_LOG_LEVELS: [LogLevelDto; 5] = [
+    LogLevelDto::Info,
+    LogLevelDto::Warning,
+    LogLevelDto::Error,
+    LogLevelDto::FatalError,
+    LogLevelDto::Debug,
+];
 
-    pub fn is_error(&self) -> bool {
-        matches!(self, LogLevelDto::Error)
-    }
-
-    pub fn is_fatal_error(&self) -> bool {
-        matches!(self, LogLevelDto::FatalError)
-    }
-
-    pub fn is_debug(&self) -> bool {
-        matches!(self, LogLevelDto::Debug)
+impl LogLevelDto {
+    /// Rank by severity: Debug is the lowest, FatalError is the highest.
+    pub fn severity(&self) -> u8 {
+        match self {
+            LogLevelDto::Debug => 0,
+            LogLevelDto::Info => 1,
+            LogLevelDto::Warning => 2,
+            LogLevelDto::Error => 3,
+            LogLevelDto::FatalError => 4,
+        }
     }
 }
 
@@ -49,6 +52,38 @@ impl<'s> Into<LogLevelDto> for &'s LogLevel {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrderDto {
+    /// Newest first.
+    #[default]
+    Desc,
+    /// Oldest first.
+    Asc,
+}
+
+/// Which slice of the time-ordered result a search returns.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchPage {
+    pub order: SortOrderDto,
+    pub skip: usize,
+    pub take: usize,
+}
+
+impl SearchPage {
+    pub fn newest(take: usize) -> Self {
+        Self {
+            order: SortOrderDto::Desc,
+            skip: 0,
+            take,
+        }
+    }
+
+    /// Amount of records every store has to return so that `skip + take` are guaranteed after merge.
+    pub fn limit(&self) -> usize {
+        self.skip.saturating_add(self.take)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LogItemDto {
     pub moment: DateTimeAsMicroseconds,
@@ -64,17 +99,313 @@ pub struct StatisticsModel {
     pub count: i64,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatchModeDto {
+    #[default]
+    Contains,
+    Regex,
+}
+
+impl MatchModeDto {
+    pub fn is_default(&self) -> bool {
+        matches!(self, MatchModeDto::Contains)
+    }
+}
+
+/// Phrase part of a search request.
+#[derive(Debug, Clone)]
+pub enum PhraseFilter {
+    /// Tantivy tokenized full-text query or SQL `LIKE` for the turso tier.
+    FullText(String),
+    /// Exact substring or regex, evaluated against the message of every candidate record.
+    Matcher(TextMatcher),
+}
+
+impl PhraseFilter {
+    pub fn full_text(phrase: &str) -> Option<Self> {
+        let phrase = phrase.trim();
+        if phrase.is_empty() {
+            return None;
+        }
+        Some(Self::FullText(phrase.to_string()))
+    }
+
+    pub fn matcher(
+        phrase: &str,
+        mode: MatchModeDto,
+        case_insensitive: bool,
+    ) -> Result<Option<Self>, String> {
+        if phrase.is_empty() {
+            return Ok(None);
+        }
+        let matcher = TextMatcher::new(phrase, mode, case_insensitive)?;
+        Ok(Some(Self::Matcher(matcher)))
+    }
+}
+
+const HOUR_US: i64 = 3_600_000_000;
+const RULE_STATISTICS_HOURS_TO_KEEP: i64 = 7 * 24;
+
+fn to_hour_key(moment: i64) -> u64 {
+    let hour: crate::hourly_statistics::StatisticsHour = DateTimeAsMicroseconds::new(moment).into();
+    hour.get_value()
+}
+
+/// Events suppressed by an ignore or single event rule.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IgnoreRuleStatisticsDto {
+    #[serde(default)]
+    pub total: u64,
+    /// Hour key (YYYYMMDDHH) -> events, for the last 7 days.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hourly: BTreeMap<u64, u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_matched: Option<i64>,
+    /// Moment the counting started. Rules without matches are unused relative to it.
+    #[serde(default)]
+    pub since: i64,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct IgnoreRuleStatisticsSummary {
+    pub total: u64,
+    pub last_hour: u64,
+    pub last_day: u64,
+    pub last_matched: Option<i64>,
+    pub unused: bool,
+}
+
+impl IgnoreRuleStatisticsDto {
+    pub fn add(&mut self, hits: &IgnoreRuleStatisticsDto) {
+        self.total += hits.total;
+
+        for (hour, count) in hits.hourly.iter() {
+            *self.hourly.entry(*hour).or_default() += count;
+        }
+
+        if hits.last_matched > self.last_matched {
+            self.last_matched = hits.last_matched;
+        }
+
+        self.trim_hourly();
+    }
+
+    /// Drops the hours older than the retention window before the last match.
+    fn trim_hourly(&mut self) {
+        if let Some(last_matched) = self.last_matched {
+            let oldest = to_hour_key(last_matched - RULE_STATISTICS_HOURS_TO_KEEP * HOUR_US);
+            self.hourly.retain(|hour, _| *hour >= oldest);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn add_hit(&mut self, moment: DateTimeAsMicroseconds) {
+        self.total += 1;
+
+        if self.last_matched < Some(moment.unix_microseconds) {
+            self.last_matched = Some(moment.unix_microseconds);
+        }
+
+        let hour = to_hour_key(moment.unix_microseconds);
+        match self.hourly.get_mut(&hour) {
+            Some(count) => *count += 1,
+            None => {
+                // A new hour starts, so older hours may leave the window
+                self.hourly.insert(hour, 1);
+                self.trim_hourly();
+            }
+        }
+    }
+
+    /// Events within the hour buckets starting with the one of `from`.
+    pub fn get_since(&self, from: DateTimeAsMicroseconds) -> u64 {
+        let from = to_hour_key(from.unix_microseconds);
+        self.hourly.range(from..).map(|(_, count)| *count).sum()
+    }
+
+    pub fn get_summary(
+        &self,
+        now: DateTimeAsMicroseconds,
+        unused_after_days: u32,
+    ) -> IgnoreRuleStatisticsSummary {
+        let last_activity = self.last_matched.unwrap_or(self.since).max(self.since);
+
+        IgnoreRuleStatisticsSummary {
+            total: self.total,
+            last_hour: self.get_since(DateTimeAsMicroseconds::new(now.unix_microseconds - HOUR_US)),
+            last_day: self.get_since(DateTimeAsMicroseconds::new(
+                now.unix_microseconds - 24 * HOUR_US,
+            )),
+            last_matched: self.last_matched,
+            unused: unused_after_days > 0
+                && now.unix_microseconds - last_activity > unused_after_days as i64 * 24 * HOUR_US,
+        }
+    }
+}
+
+/// Part of the event a condition is checked against.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum IgnoreConditionFieldDto {
+    Message,
+    Process,
+    /// Matches if either the process or the message matches. The `marker` of the level/application/marker rules.
+    ProcessOrMessage,
+    /// Value of the context key. The condition fails if the event has no such key.
+    Context(String),
+}
+
+impl IgnoreConditionFieldDto {
+    pub fn parse(field: &str, key: Option<String>) -> Result<Self, String> {
+        match field.trim().to_lowercase().as_str() {
+            "message" => Ok(Self::Message),
+            "process" => Ok(Self::Process),
+            "processormessage" => Ok(Self::ProcessOrMessage),
+            "context" => match key {
+                Some(key) if !key.trim().is_empty() => Ok(Self::Context(key.trim().to_string())),
+                _ => Err("Context condition needs a context key".to_string()),
+            },
+            _ => Err(format!(
+                "Unknown condition field '{}'. Expected one of: Message, Process, ProcessOrMessage, Context.",
+                field
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for IgnoreConditionFieldDto {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Message => write!(f, "Message"),
+            Self::Process => write!(f, "Process"),
+            Self::ProcessOrMessage => write!(f, "ProcessOrMessage"),
+            Self::Context(key) => write!(f, "Context[{}]", key),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConditionOperatorDto {
+    Equals,
+    #[default]
+    Contains,
+    Prefix,
+    Regex,
+}
+
+impl ConditionOperatorDto {
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        let Some(value) = value else {
+            return Ok(Self::Contains);
+        };
+
+        match value.trim().to_lowercase().as_str() {
+            "" | "contains" => Ok(Self::Contains),
+            "equals" => Ok(Self::Equals),
+            "prefix" => Ok(Self::Prefix),
+            "regex" => Ok(Self::Regex),
+            _ => Err(format!(
+                "Unknown condition operator '{}'. Expected one of: Equals, Contains, Prefix, Regex.",
+                value
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IgnoreConditionDto {
+    pub field: IgnoreConditionFieldDto,
+    #[serde(default)]
+    pub operator: ConditionOperatorDto,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub case_insensitive: bool,
+}
+
+impl IgnoreConditionDto {
+    pub fn compile(&self) -> Result<ConditionMatcher, String> {
+        ConditionMatcher::new(&self.value, self.operator, self.case_insensitive)
+    }
+}
+
+impl std::fmt::Display for IgnoreConditionDto {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {:?} '{}'", self.field, self.operator, self.value)?;
+        if self.case_insensitive {
+            write!(f, " (ignore case)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Events of one of `levels` matching every condition are dropped.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct IgnoreItemDto {
-    pub level: LogLevelDto,
-    pub application: String,
-    pub marker: String,
+    pub id: String,
+    /// Every level if empty.
+    #[serde(default)]
+    pub levels: Vec<LogLevelDto>,
+    pub conditions: Vec<IgnoreConditionDto>,
     /// Optional expiration moment as unix microseconds. `None` means the rule never expires.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub expires_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "IgnoreRuleStatisticsDto::is_empty")]
+    pub statistics: IgnoreRuleStatisticsDto,
 }
 
 impl IgnoreItemDto {
+    /// Rule of the level/application/marker shape. `*` application matches any application, `*` marker any event.
+    /// Rules of `settings.json` written before conditions are migrated differently, see `LegacyIgnoreItem`.
+    pub fn from_marker(
+        level: LogLevelDto,
+        application: &str,
+        marker: &str,
+        match_mode: MatchModeDto,
+        case_insensitive: bool,
+        expires_at: Option<i64>,
+    ) -> Self {
+        let mut conditions = Vec::new();
+
+        if application != "*" {
+            conditions.push(IgnoreConditionDto {
+                field: IgnoreConditionFieldDto::Context(APPLICATION_KEY.to_string()),
+                operator: ConditionOperatorDto::Equals,
+                value: application.to_string(),
+                case_insensitive: false,
+            });
+        }
+
+        if !(marker == "*" && match_mode == MatchModeDto::Contains) {
+            conditions.push(IgnoreConditionDto {
+                field: IgnoreConditionFieldDto::ProcessOrMessage,
+                operator: match match_mode {
+                    MatchModeDto::Contains => ConditionOperatorDto::Contains,
+                    MatchModeDto::Regex => ConditionOperatorDto::Regex,
+                },
+                value: marker.to_string(),
+                case_insensitive,
+            });
+        }
+
+        Self {
+            id: get_marker_rule_id(&level, application, marker),
+            levels: vec![level],
+            conditions,
+            expires_at,
+            statistics: Default::default(),
+        }
+    }
+
+    /// Same rule, not taking the statistics into account.
+    pub fn is_same_rule(&self, other: &IgnoreItemDto) -> bool {
+        self.id == other.id
+            && self.levels == other.levels
+            && self.conditions == other.conditions
+            && self.expires_at == other.expires_at
+    }
+
     pub fn is_expired(&self, now: DateTimeAsMicroseconds) -> bool {
         match self.expires_at {
             Some(expires_at) => expires_at <= now.unix_microseconds,
@@ -82,38 +413,344 @@ impl IgnoreItemDto {
         }
     }
 
-    pub fn matches_ignore_filter(&self, log_event: &LogItem) -> bool {
-        if self.is_expired(DateTimeAsMicroseconds::now()) {
+    /// Value of the case-sensitive `Application` equals condition, if the rule has one.
+    pub fn get_application(&self) -> Option<&str> {
+        self.conditions
+            .iter()
+            .find(|condition| {
+                condition.field == IgnoreConditionFieldDto::Context(APPLICATION_KEY.to_string())
+                    && condition.operator == ConditionOperatorDto::Equals
+                    && !condition.case_insensitive
+            })
+            .map(|condition| condition.value.as_str())
+    }
+
+    pub fn get_levels_description(&self) -> String {
+        if self.levels.is_empty() {
+            return "Any".to_string();
+        }
+
+        self.levels
+            .iter()
+            .map(|level| format!("{:?}", level))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn get_conditions_description(&self) -> String {
+        if self.conditions.is_empty() {
+            return "*".to_string();
+        }
+
+        self.conditions
+            .iter()
+            .map(|condition| condition.to_string())
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    }
+
+    /// Checks that the rule does not match every event and compiles its conditions.
+    pub fn compile_conditions(
+        &self,
+    ) -> Result<Vec<(IgnoreConditionFieldDto, ConditionMatcher)>, String> {
+        if self.levels.is_empty() && self.conditions.is_empty() {
+            return Err("Ignore rule needs at least one level or condition".to_string());
+        }
+
+        self.conditions
+            .iter()
+            .map(|condition| {
+                if condition.field == IgnoreConditionFieldDto::Context(String::new()) {
+                    return Err("Context condition needs a context key".to_string());
+                }
+                Ok((condition.field.clone(), condition.compile()?))
+            })
+            .collect()
+    }
+}
+
+/// Id of the rule created from level, application and marker, so such rules can be updated and deleted by them.
+pub fn get_marker_rule_id(level: &LogLevelDto, application: &str, marker: &str) -> String {
+    format!("{:?}:{}:{}", level, application, marker)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SavedSearchDto {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub levels: Vec<LogLevelDto>,
+    #[serde(default)]
+    pub context: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phrase: Option<String>,
+    /// Relative time range: the search covers `[now - last_minutes..now]`.
+    pub last_minutes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AlertRuleDto {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+    /// Value of the `Application` context key. Any application if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub application: Option<String>,
+    /// Events of this level and more severe are counted.
+    pub min_level: LogLevelDto,
+    /// Exact context matches, all of them have to match.
+    #[serde(default)]
+    pub context: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phrase: Option<String>,
+    #[serde(default, skip_serializing_if = "MatchModeDto::is_default")]
+    pub match_mode: MatchModeDto,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub case_insensitive: bool,
+    /// Fires when more than `threshold` events are seen within the window. 0 - on the first event.
+    pub threshold: u32,
+    pub window_sec: u64,
+    /// Context key. Every distinct value is counted and alerted separately.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_by: Option<String>,
+    /// Minimal interval between two firing notifications of the same group.
+    pub cooldown_sec: u64,
+    /// Alert resolves once the count stays at or below the threshold for this long.
+    pub resolve_after_sec: u64,
+    /// Notification channel names. Routed by application if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<String>,
+    /// Saved search the notifications link to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saved_search_id: Option<String>,
+}
+
+impl AlertRuleDto {
+    pub fn compile_phrase(&self) -> Result<Option<TextMatcher>, String> {
+        match self.phrase.as_deref() {
+            Some(phrase) if !phrase.is_empty() => {
+                TextMatcher::new(phrase, self.match_mode, self.case_insensitive).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditActorKindDto {
+    Http,
+    Grpc,
+    Mcp,
+    Telegram,
+}
+
+impl AuditActorKindDto {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "http" => Ok(Self::Http),
+            "grpc" => Ok(Self::Grpc),
+            "mcp" => Ok(Self::Mcp),
+            "telegram" => Ok(Self::Telegram),
+            _ => Err(format!(
+                "Unknown actor kind '{}'. Expected one of: Http, Grpc, Mcp, Telegram.",
+                value
+            )),
+        }
+    }
+}
+
+/// Who changed the configuration. `name` is what the client told about itself: the `X-Actor` header, the UI url or the Telegram user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditActorDto {
+    pub kind: AuditActorKindDto,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl AuditActorDto {
+    pub fn new(kind: AuditActorKindDto, name: Option<String>) -> Self {
+        Self {
+            kind,
+            name: name
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty()),
+        }
+    }
+}
+
+impl std::fmt::Display for AuditActorDto {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{:?}({})", self.kind, name),
+            None => write!(f, "{:?}", self.kind),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditTargetDto {
+    IgnoreRule,
+    SingleEventRule,
+    SavedSearch,
+    AlertRule,
+}
+
+impl AuditTargetDto {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "ignorerule" => Ok(Self::IgnoreRule),
+            "singleeventrule" => Ok(Self::SingleEventRule),
+            "savedsearch" => Ok(Self::SavedSearch),
+            "alertrule" => Ok(Self::AlertRule),
+            _ => Err(format!(
+                "Unknown audit target '{}'. Expected one of: IgnoreRule, SingleEventRule, SavedSearch, AlertRule.",
+                value
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditActionDto {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// One line of the audit trail.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditLogEntryDto {
+    /// Unix microseconds.
+    pub moment: i64,
+    pub actor: AuditActorDto,
+    pub target: AuditTargetDto,
+    pub action: AuditActionDto,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilterDto {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub target: Option<AuditTargetDto>,
+    pub id: Option<String>,
+    pub actor_kind: Option<AuditActorKindDto>,
+}
+
+impl AuditLogFilterDto {
+    pub fn matches(&self, entry: &AuditLogEntryDto) -> bool {
+        if self.from.is_some_and(|from| entry.moment < from) {
             return false;
         }
 
-        if !log_event.is_level(&self.level) {
+        if self.to.is_some_and(|to| entry.moment > to) {
             return false;
         }
 
-        if !log_event.is_application(&self.application) {
+        if self.target.is_some_and(|target| entry.target != target) {
             return false;
         }
 
-        if self.marker.as_str() == "*" {
-            return true;
+        if self.actor_kind.is_some_and(|kind| entry.actor.kind != kind) {
+            return false;
         }
 
-        log_event.has_entry(self.marker.as_str())
+        match &self.id {
+            Some(id) => &entry.id == id,
+            None => true,
+        }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct IgnoreWhereModel {
-    pub level: LogLevelDto,
-    pub application: String,
-    pub marker: String,
-}
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{
+        AuditActionDto, AuditActorDto, AuditActorKindDto, AuditLogEntryDto, AuditLogFilterDto,
+        AuditTargetDto, IgnoreRuleStatisticsDto, HOUR_US, RULE_STATISTICS_HOURS_TO_KEEP,
+    };
+
+    #[test]
+    fn test_ignore_rule_statistics_summary() {
+        let now = DateTimeAsMicroseconds::now();
+        let mut pending = IgnoreRuleStatisticsDto::default();
+        pending.add_hit(DateTimeAsMicroseconds::new(
+            now.unix_microseconds - 3 * HOUR_US,
+        ));
+        pending.add_hit(now);
+
+        let mut statistics = IgnoreRuleStatisticsDto {
+            since: now.unix_microseconds - 40 * 24 * HOUR_US,
+            ..Default::default()
+        };
+        assert!(statistics.get_summary(now, 30).unused);
 
-impl IgnoreWhereModel {
-    pub fn matches(&self, item: &IgnoreItemDto) -> bool {
-        item.level == self.level
-            && item.application == self.application
-            && item.marker == self.marker
+        statistics.add(&pending);
+        let summary = statistics.get_summary(now, 30);
+
+        assert_eq!(summary.total, 2);
+        assert_eq!(summary.last_hour, 1);
+        assert_eq!(summary.last_day, 2);
+        assert_eq!(summary.last_matched, Some(now.unix_microseconds));
+        assert!(!summary.unused);
+    }
+
+    #[test]
+    fn test_hits_outside_retention_are_trimmed() {
+        let now = DateTimeAsMicroseconds::now();
+        let mut statistics = IgnoreRuleStatisticsDto::default();
+
+        statistics.add_hit(DateTimeAsMicroseconds::new(
+            now.unix_microseconds - (RULE_STATISTICS_HOURS_TO_KEEP + 2) * HOUR_US,
+        ));
+        statistics.add_hit(now);
+
+        assert_eq!(statistics.total, 2);
+        assert_eq!(statistics.hourly.len(), 1);
+    }
+
+    #[test]
+    fn test_audit_log_filter() {
+        let entry = AuditLogEntryDto {
+            moment: 100,
+            actor: AuditActorDto::new(AuditActorKindDto::Mcp, Some(" ".to_string())),
+            target: AuditTargetDto::IgnoreRule,
+            action: AuditActionDto::Created,
+            id: "rule".to_string(),
+            before: None,
+            after: Some(serde_json::json!({"id": "rule"})),
+        };
+        assert_eq!(entry.actor.name, None);
+
+        assert!(AuditLogFilterDto::default().matches(&entry));
+        assert!(AuditLogFilterDto {
+            from: Some(100),
+            to: Some(100),
+            target: Some(AuditTargetDto::IgnoreRule),
+            id: Some("rule".to_string()),
+            actor_kind: Some(AuditActorKindDto::Mcp),
+        }
+        .matches(&entry));
+        assert!(!AuditLogFilterDto {
+            from: Some(101),
+            ..Default::default()
+        }
+        .matches(&entry));
+        assert!(!AuditLogFilterDto {
+            target: Some(AuditTargetDto::AlertRule),
+            ..Default::default()
+        }
+        .matches(&entry));
+        assert!(!AuditLogFilterDto {
+            actor_kind: Some(AuditActorKindDto::Http),
+            ..Default::default()
+        }
+        .matches(&entry));
     }
 }
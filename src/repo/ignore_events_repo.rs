@@ -1,23 +1,131 @@
+use std::collections::BTreeMap;
+
 use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::Deserialize;
 use tokio::sync::Mutex;
 
+use crate::app::APPLICATION_KEY;
+
 use super::dto::*;
 
+/// Rule of `settings.json` before conditions were introduced.
+#[derive(Deserialize)]
+struct LegacyIgnoreItem {
+    level: LogLevelDto,
+    application: String,
+    marker: String,
+    #[serde(default)]
+    expires_at: Option<i64>,
+    #[serde(default)]
+    match_mode: MatchModeDto,
+    #[serde(default)]
+    case_insensitive: bool,
+    #[serde(default)]
+    statistics: IgnoreRuleStatisticsDto,
+}
+
+impl LegacyIgnoreItem {
+    /// Keeps the matching of the legacy rules: their level was never checked
+    /// and `*` was a wildcard only for the marker, not for the application.
+    fn into_dto(self) -> IgnoreItemDto {
+        let mut result = IgnoreItemDto::from_marker(
+            self.level,
+            &self.application,
+            &self.marker,
+            self.match_mode,
+            self.case_insensitive,
+            self.expires_at,
+        );
+
+        result.levels = ALL_LOG_LEVELS.to_vec();
+
+        if self.application == "*" {
+            result.conditions.insert(
+                0,
+                IgnoreConditionDto {
+                    field: IgnoreConditionFieldDto::Context(APPLICATION_KEY.to_string()),
+                    operator: ConditionOperatorDto::Equals,
+                    value: self.application,
+                    case_insensitive: false,
+                },
+            );
+        }
+
+        result.statistics = self.statistics;
+        result
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IgnoreItemFileModel {
+    Current(IgnoreItemDto),
+    Legacy(LegacyIgnoreItem),
+}
+
 pub struct SettingsRepo {
     items: Mutex<Vec<IgnoreItemDto>>,
     path: String,
+    /// Set if the file could not be parsed, so it is never overwritten.
+    read_only: bool,
 }
 
 impl SettingsRepo {
     pub async fn new(path: String) -> Self {
-        let items = match tokio::fs::read(&path).await {
-            Ok(bytes) => serde_json::from_slice::<Vec<IgnoreItemDto>>(&bytes).unwrap_or_default(),
+        let mut read_only = false;
+
+        let file_items = match tokio::fs::read(&path).await {
+            Ok(bytes) => match serde_json::from_slice::<Vec<IgnoreItemFileModel>>(&bytes) {
+                Ok(items) => items,
+                Err(e) => {
+                    println!(
+                        "Failed to parse ignore events at {}: {}. Changes will not be saved to the file",
+                        path, e
+                    );
+                    read_only = true;
+                    Vec::new()
+                }
+            },
             Err(_) => Vec::new(),
         };
-        Self {
+
+        let mut has_changes = false;
+        let mut items = Vec::with_capacity(file_items.len());
+
+        for file_item in file_items {
+            match file_item {
+                IgnoreItemFileModel::Current(item) => items.push(item),
+                IgnoreItemFileModel::Legacy(legacy) => {
+                    let item = legacy.into_dto();
+                    println!(
+                        "Migrated ignore rule {} to conditions, it matches every level as before",
+                        item.id
+                    );
+                    items.push(item);
+                    has_changes = true;
+                }
+            }
+        }
+
+        let now = DateTimeAsMicroseconds::now().unix_microseconds;
+        for item in items.iter_mut() {
+            if item.statistics.since == 0 {
+                item.statistics.since = now;
+                has_changes = true;
+            }
+        }
+
+        let result = Self {
             items: Mutex::new(items),
             path,
+            read_only,
+        };
+
+        if has_changes {
+            result.persist(&result.items.lock().await).await;
         }
+
+        result
     }
 
     pub async fn get_ignore_events(&self) -> Vec<IgnoreItemDto> {
@@ -26,7 +134,7 @@ impl SettingsRepo {
         let before = write_access.len();
         write_access.retain(|item| !item.is_expired(now));
         if write_access.len() != before {
-            persist(&self.path, &write_access).await;
+            self.persist(&write_access).await;
         }
         write_access.clone()
     }
@@ -40,44 +148,136 @@ impl SettingsRepo {
         if write_access.len() == before {
             return false;
         }
-        persist(&self.path, &write_access).await;
+        self.persist(&write_access).await;
         true
     }
 
-    pub async fn add_ignore_event(&self, item: &IgnoreItemDto) {
+    /// Inserts a new rule or replaces the one with the same id, keeping its statistics.
+    /// Returns the replaced rule.
+    pub async fn add_ignore_event(&self, item: &IgnoreItemDto) -> Option<IgnoreItemDto> {
         let mut write_access = self.items.lock().await;
-        if let Some(existing) = write_access.iter_mut().find(|x| {
-            x.level == item.level && x.application == item.application && x.marker == item.marker
-        }) {
-            if existing.expires_at == item.expires_at {
-                return;
+        let before = if let Some(existing) = write_access.iter_mut().find(|x| x.id == item.id) {
+            if existing.is_same_rule(item) {
+                return Some(existing.clone());
             }
-            existing.expires_at = item.expires_at;
+            let before = existing.clone();
+            let statistics = std::mem::take(&mut existing.statistics);
+            *existing = item.clone();
+            existing.statistics = statistics;
+            Some(before)
         } else {
-            write_access.push(item.clone());
-        }
-        persist(&self.path, &write_access).await;
+            let mut item = item.clone();
+            item.statistics = IgnoreRuleStatisticsDto {
+                since: DateTimeAsMicroseconds::now().unix_microseconds,
+                ..Default::default()
+            };
+            write_access.push(item);
+            None
+        };
+        self.persist(&write_access).await;
+        before
     }
 
-    pub async fn delete_ignore_event(&self, model: &IgnoreWhereModel) {
+    /// Adds hits collected on ingestion to the statistics of the rules.
+    pub async fn add_hits(&self, hits: BTreeMap<String, IgnoreRuleStatisticsDto>) {
+        if hits.is_empty() {
+            return;
+        }
+
         let mut write_access = self.items.lock().await;
-        let before = write_access.len();
-        write_access.retain(|item| !model.matches(item));
-        if write_access.len() != before {
-            persist(&self.path, &write_access).await;
+        let mut updated = false;
+
+        for item in write_access.iter_mut() {
+            if let Some(hits) = hits.get(&item.id) {
+                item.statistics.add(hits);
+                updated = true;
+            }
+        }
+
+        if updated {
+            self.persist(&write_access).await;
         }
     }
-}
 
-async fn persist(path: &str, items: &[IgnoreItemDto]) {
-    let bytes = match serde_json::to_vec_pretty(items) {
-        Ok(b) => b,
-        Err(e) => {
-            println!("Failed to serialize ignore events: {}", e);
+    /// Returns the deleted rule, `None` if there was no rule with the given id.
+    pub async fn delete_ignore_event(&self, id: &str) -> Option<IgnoreItemDto> {
+        let mut write_access = self.items.lock().await;
+        let index = write_access.iter().position(|item| item.id == id)?;
+        let before = write_access.remove(index);
+        self.persist(&write_access).await;
+        Some(before)
+    }
+
+    async fn persist(&self, items: &[IgnoreItemDto]) {
+        if self.read_only {
+            println!(
+                "Ignore events are not saved: {} could not be parsed on start",
+                self.path
+            );
             return;
         }
-    };
-    if let Err(e) = tokio::fs::write(path, bytes).await {
-        println!("Failed to persist ignore events to {}: {}", path, e);
+
+        let bytes = match serde_json::to_vec_pretty(items) {
+            Ok(b) => b,
+            Err(e) => {
+                println!("Failed to serialize ignore events: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = tokio::fs::write(&self.path, bytes).await {
+            println!("Failed to persist ignore events to {}: {}", self.path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IgnoreItemFileModel;
+    use crate::repo::dto::{ConditionOperatorDto, IgnoreConditionFieldDto, ALL_LOG_LEVELS};
+
+    #[test]
+    fn test_legacy_rule_is_migrated_to_conditions() {
+        let json = r#"[
+            {"level":"Warning","application":"billing","marker":"timeout","case_insensitive":true,"statistics":{"total":5,"since":1}},
+            {"id":"env","levels":[],"conditions":[{"field":{"Context":"Env"},"operator":"Equals","value":"test"}]}
+        ]"#;
+
+        let items: Vec<IgnoreItemFileModel> = serde_json::from_str(json).unwrap();
+
+        let IgnoreItemFileModel::Legacy(legacy) = items.into_iter().next().unwrap() else {
+            panic!("legacy rule is expected first");
+        };
+        let migrated = legacy.into_dto();
+
+        assert_eq!(migrated.id, "Warning:billing:timeout");
+        assert_eq!(migrated.levels, ALL_LOG_LEVELS.to_vec());
+        assert_eq!(migrated.get_application(), Some("billing"));
+        assert_eq!(migrated.conditions.len(), 2);
+        assert_eq!(
+            migrated.conditions[1].field,
+            IgnoreConditionFieldDto::ProcessOrMessage
+        );
+        assert_eq!(
+            migrated.conditions[1].operator,
+            ConditionOperatorDto::Contains
+        );
+        assert!(migrated.conditions[1].case_insensitive);
+        assert_eq!(migrated.statistics.total, 5);
+    }
+
+    #[test]
+    fn test_legacy_star_application_stays_exact() {
+        let json = r#"[{"level":"Error","application":"*","marker":"*"}]"#;
+
+        let items: Vec<IgnoreItemFileModel> = serde_json::from_str(json).unwrap();
+
+        let IgnoreItemFileModel::Legacy(legacy) = items.into_iter().next().unwrap() else {
+            panic!("legacy rule is expected");
+        };
+        let migrated = legacy.into_dto();
+
+        assert_eq!(migrated.get_application(), Some("*"));
+        assert_eq!(migrated.conditions.len(), 1);
+        assert!(migrated.compile_conditions().is_ok());
     }
 }
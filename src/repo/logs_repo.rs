@@ -13,9 +13,9 @@ use tantivy::{
 };
 use tokio::sync::Mutex;
 
-use crate::app::PROCESS_CONTEXT_KEY;
+use crate::{app::PROCESS_CONTEXT_KEY, text_matching::MAX_MATCHER_SCAN_ITEMS};
 
-use super::{dto::*, DateHourKey};
+use super::{dto::*, merge_sorted, DateHourKey};
 
 pub const LOG_FILE_PREFIX: &str = "logs-";
 const WRITER_HEAP: usize = 50_000_000;
@@ -313,13 +313,20 @@ impl LogsRepo {
         to_date: DateTimeAsMicroseconds,
         levels: Option<Vec<LogLevelDto>>,
         context: Option<BTreeMap<String, String>>,
-        phrase: Option<&str>,
+        phrase: Option<&PhraseFilter>,
+        order: SortOrderDto,
         limit: usize,
     ) -> Vec<LogItemDto> {
         let keys = DateHourKey::get_keys_to_request(from_date, to_date);
-        let mut result: Vec<LogItemDto> = Vec::new();
-        for date_key in keys.keys().rev() {
-            let hour = match self.get_hour(*date_key).await {
+        let date_keys: Vec<DateHourKey> = match order {
+            SortOrderDto::Desc => keys.keys().rev().copied().collect(),
+            SortOrderDto::Asc => keys.keys().copied().collect(),
+        };
+
+        let mut by_hours: Vec<Vec<LogItemDto>> = Vec::new();
+        let mut found = 0;
+        for date_key in date_keys {
+            let hour = match self.get_hour(date_key).await {
                 Some(h) => h,
                 None => continue,
             };
@@ -330,19 +337,24 @@ impl LogsRepo {
                 levels.as_deref(),
                 context.as_ref(),
                 phrase,
+                order,
                 limit,
             )
             .await
             {
-                Ok(items) => result.extend(items),
+                Ok(items) => {
+                    found += items.len();
+                    by_hours.push(items);
+                }
                 Err(e) => println!("Error: {:?}", e),
             }
-            if result.len() >= limit {
+            // Hour shards do not overlap in time, so the hours after this one can not contribute.
+            if found >= limit {
                 break;
             }
         }
-        result.truncate(limit);
-        result
+
+        merge_sorted(by_hours, order, limit)
     }
 
     pub async fn prepare_to_delete(&self, date_key: DateHourKey) {
@@ -456,7 +468,8 @@ async fn search_hour(
     to_ts: Option<i64>,
     levels: Option<&[LogLevelDto]>,
     context: Option<&BTreeMap<String, String>>,
-    phrase: Option<&str>,
+    phrase: Option<&PhraseFilter>,
+    order: SortOrderDto,
     take: usize,
 ) -> tantivy::Result<Vec<LogItemDto>> {
     let take = if take == 0 { 1000 } else { take };
@@ -470,7 +483,7 @@ async fn search_hour(
     let ctx_owned: Vec<String> = context
         .map(|c| c.iter().map(|(k, v)| ctx_token(k, v)).collect())
         .unwrap_or_default();
-    let phrase_owned = phrase.map(|s| s.to_string());
+    let phrase_owned = phrase.cloned();
 
     tokio::task::spawn_blocking(move || -> tantivy::Result<Vec<LogItemDto>> {
         let searcher = reader.searcher();
@@ -516,13 +529,18 @@ async fn search_hour(
             ));
         }
 
-        if let Some(p) = &phrase_owned {
-            if !p.trim().is_empty() {
-                let qp = QueryParser::for_index(&index, vec![fields.text_search]);
-                if let Ok(q) = qp.parse_query(p) {
-                    clauses.push((Occur::Must, q));
+        let mut matcher = None;
+        match &phrase_owned {
+            Some(PhraseFilter::FullText(p)) => {
+                if !p.trim().is_empty() {
+                    let qp = QueryParser::for_index(&index, vec![fields.text_search]);
+                    if let Ok(q) = qp.parse_query(p) {
+                        clauses.push((Occur::Must, q));
+                    }
                 }
             }
+            Some(PhraseFilter::Matcher(m)) => matcher = Some(m),
+            None => {}
         }
 
         let query: Box<dyn Query> = if clauses.is_empty() {
@@ -531,14 +549,35 @@ async fn search_hour(
             Box::new(BooleanQuery::new(clauses))
         };
 
-        let collector = TopDocs::with_limit(take)
-            .order_by_fast_field::<i64>(F_TIMESTAMP, tantivy::Order::Desc);
+        // Matcher is evaluated on the stored message, so more candidates have to be scanned.
+        let limit = if matcher.is_some() {
+            MAX_MATCHER_SCAN_ITEMS
+        } else {
+            take
+        };
+
+        let tantivy_order = match order {
+            SortOrderDto::Desc => tantivy::Order::Desc,
+            SortOrderDto::Asc => tantivy::Order::Asc,
+        };
+
+        let collector =
+            TopDocs::with_limit(limit).order_by_fast_field::<i64>(F_TIMESTAMP, tantivy_order);
         let docs = searcher.search(&*query, &collector)?;
 
-        let mut out = Vec::with_capacity(docs.len());
+        let mut out = Vec::with_capacity(docs.len().min(take));
         for (_, addr) in docs {
             let retrieved = searcher.doc::<TantivyDocument>(addr)?;
-            out.push(doc_to_dto(&retrieved, &fields));
+            let dto = doc_to_dto(&retrieved, &fields);
+            if let Some(matcher) = matcher {
+                if !matcher.is_match(&dto.message) {
+                    continue;
+                }
+            }
+            out.push(dto);
+            if out.len() >= take {
+                break;
+            }
         }
         Ok(out)
     })
@@ -0,0 +1,137 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use super::dto::{LogItemDto, SortOrderDto};
+
+struct HeapItem {
+    item: LogItemDto,
+    list_no: usize,
+    order: SortOrderDto,
+}
+
+impl HeapItem {
+    fn cmp_by_order(&self, other: &Self) -> Ordering {
+        let by_time = self
+            .item
+            .moment
+            .unix_microseconds
+            .cmp(&other.item.moment.unix_microseconds);
+
+        // BinaryHeap is a max-heap: the item which has to go out first must be the greatest.
+        match self.order {
+            SortOrderDto::Desc => by_time,
+            SortOrderDto::Asc => by_time.reverse(),
+        }
+    }
+}
+
+impl PartialEq for HeapItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HeapItem {}
+
+impl PartialOrd for HeapItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_by_order(other)
+    }
+}
+
+/// K-way merge of lists that are each already sorted by timestamp in `order`.
+/// Returns at most `limit` items sorted by timestamp in `order`.
+pub fn merge_sorted(
+    lists: Vec<Vec<LogItemDto>>,
+    order: SortOrderDto,
+    limit: usize,
+) -> Vec<LogItemDto> {
+    let mut iterators: Vec<_> = lists.into_iter().map(|list| list.into_iter()).collect();
+
+    let mut heap = BinaryHeap::with_capacity(iterators.len());
+
+    for (list_no, iterator) in iterators.iter_mut().enumerate() {
+        if let Some(item) = iterator.next() {
+            heap.push(HeapItem {
+                item,
+                list_no,
+                order,
+            });
+        }
+    }
+
+    let mut result = Vec::with_capacity(limit.min(1024));
+
+    while result.len() < limit {
+        let Some(next) = heap.pop() else {
+            break;
+        };
+
+        if let Some(item) = iterators[next.list_no].next() {
+            heap.push(HeapItem {
+                item,
+                list_no: next.list_no,
+                order,
+            });
+        }
+
+        result.push(next.item);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::merge_sorted;
+    use crate::repo::dto::{LogItemDto, LogLevelDto, SortOrderDto};
+
+    fn item(ts: i64) -> LogItemDto {
+        LogItemDto {
+            moment: DateTimeAsMicroseconds::new(ts),
+            id: ts.to_string(),
+            level: LogLevelDto::Error,
+            message: String::new(),
+            context: BTreeMap::new(),
+        }
+    }
+
+    fn timestamps(items: &[LogItemDto]) -> Vec<i64> {
+        items.iter().map(|itm| itm.moment.unix_microseconds).collect()
+    }
+
+    #[test]
+    fn test_merge_desc() {
+        let result = merge_sorted(
+            vec![
+                vec![item(9), item(5), item(1)],
+                vec![item(8), item(7), item(2)],
+                vec![],
+            ],
+            SortOrderDto::Desc,
+            4,
+        );
+
+        assert_eq!(timestamps(&result), vec![9, 8, 7, 5]);
+    }
+
+    #[test]
+    fn test_merge_asc() {
+        let result = merge_sorted(
+            vec![vec![item(1), item(5), item(9)], vec![item(2), item(7)]],
+            SortOrderDto::Asc,
+            10,
+        );
+
+        assert_eq!(timestamps(&result), vec![1, 2, 5, 7, 9]);
+    }
+}
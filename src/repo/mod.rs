@@ -7,3 +7,11 @@ mod date_key;
 pub use date_key::*;
 mod sqlite_logs_repo;
 pub use sqlite_logs_repo::*;
+mod saved_searches_repo;
+pub use saved_searches_repo::*;
+mod merge_sorted;
+pub use merge_sorted::*;
+mod alert_rules_repo;
+pub use alert_rules_repo::*;
+mod audit_log_repo;
+pub use audit_log_repo::*;
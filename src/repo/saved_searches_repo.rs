@@ -0,0 +1,120 @@
+use tokio::sync::Mutex;
+
+use super::dto::*;
+
+pub struct SavedSearchesRepo {
+    items: Mutex<Vec<SavedSearchDto>>,
+    path: String,
+}
+
+impl SavedSearchesRepo {
+    pub async fn new(path: String) -> Self {
+        let items = match tokio::fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice::<Vec<SavedSearchDto>>(&bytes).unwrap_or_default(),
+            Err(_) => Vec::new(),
+        };
+        Self {
+            items: Mutex::new(items),
+            path,
+        }
+    }
+
+    pub async fn get_all(&self) -> Vec<SavedSearchDto> {
+        let read_access = self.items.lock().await;
+        read_access.clone()
+    }
+
+    pub async fn get(&self, id: &str) -> Option<SavedSearchDto> {
+        let read_access = self.items.lock().await;
+        read_access.iter().find(|itm| itm.id == id).cloned()
+    }
+
+    /// Inserts a new saved search or replaces the one with the same id. Returns the replaced one.
+    pub async fn upsert(&self, item: SavedSearchDto) -> Option<SavedSearchDto> {
+        let mut write_access = self.items.lock().await;
+        let before = match write_access.iter_mut().find(|x| x.id == item.id) {
+            Some(existing) => {
+                if *existing == item {
+                    return Some(item);
+                }
+                Some(std::mem::replace(existing, item))
+            }
+            None => {
+                write_access.push(item);
+                None
+            }
+        };
+        persist(&self.path, &write_access).await;
+        before
+    }
+
+    /// Returns the deleted saved search, `None` if there was no saved search with the given id.
+    pub async fn delete(&self, id: &str) -> Option<SavedSearchDto> {
+        let mut write_access = self.items.lock().await;
+        let index = write_access.iter().position(|itm| itm.id == id)?;
+        let before = write_access.remove(index);
+        persist(&self.path, &write_access).await;
+        Some(before)
+    }
+}
+
+async fn persist(path: &str, items: &[SavedSearchDto]) {
+    let bytes = match serde_json::to_vec_pretty(items) {
+        Ok(b) => b,
+        Err(e) => {
+            println!("Failed to serialize saved searches: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = tokio::fs::write(path, bytes).await {
+        println!("Failed to persist saved searches to {}: {}", path, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::SavedSearchesRepo;
+    use crate::repo::dto::{LogLevelDto, SavedSearchDto};
+
+    fn saved_search(id: &str, name: &str) -> SavedSearchDto {
+        SavedSearchDto {
+            id: id.to_string(),
+            name: name.to_string(),
+            levels: vec![LogLevelDto::Error],
+            context: BTreeMap::new(),
+            phrase: None,
+            last_minutes: 60,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_update_delete_are_persisted() {
+        let path =
+            std::env::temp_dir().join(format!("saved-searches-test-{}.json", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let _ = tokio::fs::remove_file(&path).await;
+
+        let repo = SavedSearchesRepo::new(path.clone()).await;
+
+        assert!(repo.upsert(saved_search("1", "Payments")).await.is_none());
+        assert!(repo.upsert(saved_search("2", "Gateway")).await.is_none());
+
+        let before = repo.upsert(saved_search("1", "Payments errors")).await;
+        assert_eq!(before.unwrap().name, "Payments");
+
+        let restored = SavedSearchesRepo::new(path.clone()).await;
+        assert_eq!(restored.get("1").await.unwrap().name, "Payments errors");
+        assert_eq!(restored.get_all().await.len(), 2);
+
+        assert_eq!(repo.delete("2").await.unwrap().name, "Gateway");
+        assert!(repo.delete("2").await.is_none());
+
+        let restored = SavedSearchesRepo::new(path.clone()).await;
+        let _ = tokio::fs::remove_file(&path).await;
+
+        assert!(restored.get("2").await.is_none());
+        assert_eq!(restored.get_all().await.len(), 1);
+    }
+}
@@ -4,7 +4,9 @@ use rust_extensions::date_time::DateTimeAsMicroseconds;
 use tokio::sync::Mutex;
 use turso::{params::Params, Builder, Connection, Database, Value};
 
-use super::{dto::*, DateHourKey};
+use crate::text_matching::{TextMatcher, MAX_MATCHER_SCAN_ITEMS};
+
+use super::{dto::*, merge_sorted, DateHourKey};
 
 pub const SQLITE_FILE_PREFIX: &str = "logs-";
 pub const SQLITE_FILE_SUFFIX: &str = ".db";
@@ -233,7 +235,8 @@ impl SqliteLogsRepo {
         to_date: DateTimeAsMicroseconds,
         levels: Option<Vec<LogLevelDto>>,
         context: Option<BTreeMap<String, String>>,
-        phrase: Option<&str>,
+        phrase: Option<&PhraseFilter>,
+        order: SortOrderDto,
         limit: usize,
     ) -> Vec<LogItemDto> {
         let levels_to_query = filter_sqlite_levels(levels);
@@ -255,19 +258,33 @@ impl SqliteLogsRepo {
             }
         }
 
-        let phrase_lower = phrase
-            .map(|p| p.trim().to_string())
-            .filter(|p| !p.is_empty())
-            .map(|p| p.to_lowercase());
+        let mut phrase_lower = None;
+        let mut matcher = None;
+        match phrase {
+            Some(PhraseFilter::FullText(p)) => {
+                let p = p.trim();
+                if !p.is_empty() {
+                    phrase_lower = Some(p.to_lowercase());
+                }
+            }
+            Some(PhraseFilter::Matcher(m)) => matcher = Some(m),
+            None => {}
+        }
 
         let keys = DateHourKey::get_keys_to_request(from_date, to_date);
+        let date_keys: Vec<DateHourKey> = match order {
+            SortOrderDto::Desc => keys.keys().rev().copied().collect(),
+            SortOrderDto::Asc => keys.keys().copied().collect(),
+        };
         let from_ts = from_date.unix_microseconds;
         let to_ts = to_date.unix_microseconds;
 
-        let mut result: Vec<LogItemDto> = Vec::new();
+        let mut by_hours: Vec<Vec<LogItemDto>> = Vec::new();
 
-        'outer: for level in &levels_to_query {
-            for date_key in keys.keys().rev() {
+        for level in &levels_to_query {
+            // Each level lives in its own set of files, so every level is scanned up to `take`.
+            let mut found = 0;
+            for date_key in date_keys.iter() {
                 let hour = match self.get_hour(level.clone(), *date_key).await {
                     Some(h) => h,
                     None => continue,
@@ -280,25 +297,28 @@ impl SqliteLogsRepo {
                     to_ts,
                     application_filter.as_deref(),
                     phrase_lower.as_deref(),
+                    matcher,
                     &other_ctx,
+                    order,
                     take,
                 )
                 .await;
 
                 match rows {
-                    Ok(items) => result.extend(items),
+                    Ok(items) => {
+                        found += items.len();
+                        by_hours.push(items);
+                    }
                     Err(e) => println!("sqlite query failed: {:?}", e),
                 }
 
-                if result.len() >= take {
-                    break 'outer;
+                if found >= take {
+                    break;
                 }
             }
         }
 
-        result.sort_by(|a, b| b.moment.unix_microseconds.cmp(&a.moment.unix_microseconds));
-        result.truncate(take);
-        result
+        merge_sorted(by_hours, order, take)
     }
 
     pub async fn get_statistics(&self) -> Vec<StatisticsModel> {
@@ -399,7 +419,9 @@ async fn query_hour(
     to_ts: i64,
     application: Option<&str>,
     phrase_lower: Option<&str>,
+    matcher: Option<&TextMatcher>,
     other_ctx: &BTreeMap<String, String>,
+    order: SortOrderDto,
     take: usize,
 ) -> turso::Result<Vec<LogItemDto>> {
     let mut sql = String::from(
@@ -420,8 +442,17 @@ async fn query_hour(
         pat.push('%');
         params.push(Value::Text(pat));
     }
-    sql.push_str(" ORDER BY timestamp DESC LIMIT ?");
-    params.push(Value::Integer(take as i64));
+    match order {
+        SortOrderDto::Desc => sql.push_str(" ORDER BY timestamp DESC LIMIT ?"),
+        SortOrderDto::Asc => sql.push_str(" ORDER BY timestamp ASC LIMIT ?"),
+    }
+    // Matcher is evaluated on the message of every row, so more candidates have to be scanned.
+    let limit = if matcher.is_some() {
+        MAX_MATCHER_SCAN_ITEMS
+    } else {
+        take
+    };
+    params.push(Value::Integer(limit as i64));
 
     let conn = hour.connect()?;
     let mut rows = conn.query(&sql, Params::Positional(params)).await?;
@@ -459,6 +490,12 @@ async fn query_hour(
             continue;
         }
 
+        if let Some(matcher) = matcher {
+            if !matcher.is_match(&message) {
+                continue;
+            }
+        }
+
         out.push(LogItemDto {
             moment: DateTimeAsMicroseconds::new(ts),
             id,
@@ -466,6 +503,10 @@ async fn query_hour(
             message,
             context,
         });
+
+        if out.len() >= take {
+            break;
+        }
     }
     Ok(out)
 }
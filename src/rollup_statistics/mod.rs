@@ -0,0 +1,2 @@
+mod rollup_statistics;
+pub use rollup_statistics::*;
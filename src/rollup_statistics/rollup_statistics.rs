@@ -0,0 +1,555 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use my_logger::LogLevel;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::{LogItem, APPLICATION_KEY, NO_APPLICATION},
+    hourly_statistics::{
+        add_statistics, HourlyStatisticsItem, PersistedDay, PersistedHour, StatisticsHour,
+    },
+};
+
+const MINUTE_SEC: i64 = 60;
+const HOUR_SEC: i64 = 60 * MINUTE_SEC;
+const DAY_SEC: i64 = 24 * HOUR_SEC;
+
+/// An automatically selected resolution returns at most this many buckets unless there is no coarser one.
+pub const MAX_AUTO_BUCKETS: i64 = 1500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollupResolution {
+    Minute,
+    Hour,
+    Day,
+}
+
+/// From the finest to the coarsest.
+pub const ROLLUP_RESOLUTIONS: [RollupResolution; 3] = [
+    RollupResolution::Minute,
+    RollupResolution::Hour,
+    RollupResolution::Day,
+];
+
+impl RollupResolution {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "minute" => Ok(Self::Minute),
+            "hour" => Ok(Self::Hour),
+            "day" => Ok(Self::Day),
+            _ => Err(format!(
+                "Unknown resolution '{}'. Expected one of: Minute, Hour, Day.",
+                value
+            )),
+        }
+    }
+
+    pub fn get_bucket_sec(&self) -> i64 {
+        match self {
+            Self::Minute => MINUTE_SEC,
+            Self::Hour => HOUR_SEC,
+            Self::Day => DAY_SEC,
+        }
+    }
+
+    pub fn get_retention_sec(&self) -> i64 {
+        match self {
+            Self::Minute => DAY_SEC,
+            Self::Hour => 30 * DAY_SEC,
+            Self::Day => 365 * DAY_SEC,
+        }
+    }
+
+    /// Buckets are aligned to UTC.
+    pub fn get_bucket_start(&self, unix_sec: i64) -> i64 {
+        let bucket_sec = self.get_bucket_sec();
+        unix_sec.div_euclid(bucket_sec) * bucket_sec
+    }
+
+    /// The finest resolution which still keeps `from` and does not split the range into too many buckets.
+    pub fn select(from_sec: i64, to_sec: i64, now_sec: i64) -> Self {
+        for resolution in ROLLUP_RESOLUTIONS {
+            if from_sec < now_sec - resolution.get_retention_sec() {
+                continue;
+            }
+
+            if (to_sec - from_sec) / resolution.get_bucket_sec() > MAX_AUTO_BUCKETS {
+                continue;
+            }
+
+            return resolution;
+        }
+
+        Self::Day
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RollupBucket {
+    /// Unix seconds.
+    pub start: i64,
+    pub apps: BTreeMap<String, HourlyStatisticsItem>,
+}
+
+/// Buckets of one UTC day. Applications are stored once, every row is
+/// `[bucket start as unix seconds, application index, info, warning, error, fatal_error, debug, sampled]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PersistedRollupStatistics {
+    pub applications: Vec<String>,
+    #[serde(default)]
+    pub minutes: Vec<[i64; 8]>,
+    #[serde(default)]
+    pub hours: Vec<[i64; 8]>,
+    #[serde(default)]
+    pub days: Vec<[i64; 8]>,
+}
+
+type Buckets = BTreeMap<i64, BTreeMap<String, HourlyStatisticsItem>>;
+
+/// Per-application counts by minute for a day, by hour for a month and by day for a year.
+/// Persisted by UTC day, so only the days with changed buckets are written.
+pub struct RollupStatistics {
+    minutes: Buckets,
+    hours: Buckets,
+    days: Buckets,
+    /// Starts of the days with changed buckets, unix seconds.
+    changed_days: BTreeSet<i64>,
+}
+
+impl RollupStatistics {
+    pub fn new() -> Self {
+        Self {
+            minutes: BTreeMap::new(),
+            hours: BTreeMap::new(),
+            days: BTreeMap::new(),
+            changed_days: BTreeSet::new(),
+        }
+    }
+
+    fn get_buckets(&self, resolution: RollupResolution) -> &Buckets {
+        match resolution {
+            RollupResolution::Minute => &self.minutes,
+            RollupResolution::Hour => &self.hours,
+            RollupResolution::Day => &self.days,
+        }
+    }
+
+    fn get_buckets_mut(&mut self, resolution: RollupResolution) -> &mut Buckets {
+        match resolution {
+            RollupResolution::Minute => &mut self.minutes,
+            RollupResolution::Hour => &mut self.hours,
+            RollupResolution::Day => &mut self.days,
+        }
+    }
+
+    pub fn update(&mut self, itm: &LogItem) {
+        self.add(itm, |counts| match itm.level {
+            LogLevel::Info => counts.info += 1,
+            LogLevel::Warning => counts.warning += 1,
+            LogLevel::Error => counts.error += 1,
+            LogLevel::FatalError => counts.fatal_error += 1,
+            LogLevel::Debug => counts.debug += 1,
+        });
+    }
+
+    pub fn add_sampled(&mut self, itm: &LogItem) {
+        self.add(itm, |counts| counts.sampled += 1);
+    }
+
+    /// Events without an `Application` are counted as [`NO_APPLICATION`].
+    fn add(&mut self, itm: &LogItem, apply: impl Fn(&mut HourlyStatisticsItem)) {
+        let application = itm
+            .ctx
+            .get(APPLICATION_KEY)
+            .map(|application| application.as_str())
+            .unwrap_or(NO_APPLICATION);
+
+        let moment = itm.timestamp.unix_microseconds.div_euclid(1_000_000);
+
+        for resolution in ROLLUP_RESOLUTIONS {
+            let apps = self
+                .get_buckets_mut(resolution)
+                .entry(resolution.get_bucket_start(moment))
+                .or_default();
+
+            match apps.get_mut(application) {
+                Some(counts) => apply(counts),
+                None => apply(apps.entry(application.to_string()).or_default()),
+            }
+        }
+
+        self.changed_days
+            .insert(RollupResolution::Day.get_bucket_start(moment));
+    }
+
+    /// Buckets overlapping `[from_sec..to_sec]`, oldest first. Only the given application if set.
+    pub fn query(
+        &self,
+        resolution: RollupResolution,
+        from_sec: i64,
+        to_sec: i64,
+        application: Option<&str>,
+    ) -> Vec<RollupBucket> {
+        let from = resolution.get_bucket_start(from_sec);
+        if to_sec < from {
+            return vec![];
+        }
+
+        self.get_buckets(resolution)
+            .range(from..=to_sec)
+            .filter_map(|(start, apps)| {
+                let apps: BTreeMap<String, HourlyStatisticsItem> = match application {
+                    Some(application) => apps
+                        .get_key_value(application)
+                        .map(|(name, counts)| (name.clone(), *counts))
+                        .into_iter()
+                        .collect(),
+                    None => apps.clone(),
+                };
+
+                if apps.is_empty() {
+                    return None;
+                }
+
+                Some(RollupBucket {
+                    start: *start,
+                    apps,
+                })
+            })
+            .collect()
+    }
+
+    /// Sum of the buckets overlapping `[from_sec..to_sec]` per application.
+    pub fn get_sum(
+        &self,
+        resolution: RollupResolution,
+        from_sec: i64,
+        to_sec: i64,
+    ) -> BTreeMap<String, HourlyStatisticsItem> {
+        let mut result = BTreeMap::new();
+
+        let from = resolution.get_bucket_start(from_sec);
+        if to_sec < from {
+            return result;
+        }
+
+        for (_, apps) in self.get_buckets(resolution).range(from..=to_sec) {
+            add_statistics(&mut result, apps);
+        }
+
+        result
+    }
+
+    /// The latest `max_hours` hour buckets with events, newest first.
+    pub fn get_max_hours(
+        &self,
+        max_hours: usize,
+    ) -> Vec<(StatisticsHour, BTreeMap<String, HourlyStatisticsItem>)> {
+        self.hours
+            .iter()
+            .rev()
+            .take(max_hours)
+            .map(|(start, apps)| {
+                let hour: StatisticsHour = DateTimeAsMicroseconds::new(start * 1_000_000).into();
+                (hour, apps.clone())
+            })
+            .collect()
+    }
+
+    pub fn gc(&mut self, now_sec: i64) {
+        for resolution in ROLLUP_RESOLUTIONS {
+            let oldest = resolution.get_bucket_start(now_sec - resolution.get_retention_sec());
+            let buckets = self.get_buckets_mut(resolution);
+            let kept = buckets.split_off(&oldest);
+            let removed = std::mem::replace(buckets, kept);
+
+            for start in removed.keys() {
+                self.changed_days
+                    .insert(RollupResolution::Day.get_bucket_start(*start));
+            }
+        }
+    }
+
+    pub fn restore(&mut self, persisted: PersistedRollupStatistics) {
+        for (resolution, rows) in [
+            (RollupResolution::Minute, persisted.minutes),
+            (RollupResolution::Hour, persisted.hours),
+            (RollupResolution::Day, persisted.days),
+        ] {
+            let buckets = self.get_buckets_mut(resolution);
+
+            for row in rows {
+                let Some(application) = persisted.applications.get(row[1] as usize) else {
+                    continue;
+                };
+
+                buckets.entry(row[0]).or_default().insert(
+                    application.to_string(),
+                    HourlyStatisticsItem {
+                        info: row[2] as u32,
+                        warning: row[3] as u32,
+                        error: row[4] as u32,
+                        fatal_error: row[5] as u32,
+                        debug: row[6] as u32,
+                        sampled: row[7] as u32,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Fills the hour buckets missing in the rollups from the former hourly statistics.
+    pub fn restore_legacy_hours(&mut self, items: Vec<PersistedHour>) {
+        for item in items {
+            let hour: StatisticsHour = item.hour.into();
+            if let Some(start) = hour.get_start() {
+                self.restore_legacy_bucket(RollupResolution::Hour, start, item.apps);
+            }
+        }
+    }
+
+    /// Fills the day buckets missing in the rollups from the former daily totals.
+    pub fn restore_legacy_days(&mut self, items: Vec<PersistedDay>) {
+        for item in items {
+            let hour: StatisticsHour = (item.day * 100).into();
+            if let Some(start) = hour.get_start() {
+                self.restore_legacy_bucket(RollupResolution::Day, start, item.apps);
+            }
+        }
+    }
+
+    fn restore_legacy_bucket(
+        &mut self,
+        resolution: RollupResolution,
+        start: DateTimeAsMicroseconds,
+        apps: BTreeMap<String, HourlyStatisticsItem>,
+    ) {
+        let start = start.unix_microseconds / 1_000_000;
+
+        if apps.is_empty() || self.get_buckets(resolution).contains_key(&start) {
+            return;
+        }
+
+        self.get_buckets_mut(resolution).insert(start, apps);
+        self.changed_days
+            .insert(RollupResolution::Day.get_bucket_start(start));
+    }
+
+    /// Number of minute, hour and day buckets.
+    pub fn len(&self) -> usize {
+        self.minutes.len() + self.hours.len() + self.days.len()
+    }
+
+    /// Changed days with their start as unix seconds. An empty day has no buckets left.
+    pub fn get_days_to_persist(&mut self) -> Vec<(i64, PersistedRollupStatistics)> {
+        let changed_days = std::mem::take(&mut self.changed_days);
+
+        changed_days
+            .into_iter()
+            .map(|day| (day, self.get_day_snapshot(day)))
+            .collect()
+    }
+
+    fn get_day_snapshot(&self, day: i64) -> PersistedRollupStatistics {
+        let mut result = PersistedRollupStatistics::default();
+        let mut app_indexes: BTreeMap<&str, i64> = BTreeMap::new();
+
+        for resolution in ROLLUP_RESOLUTIONS {
+            let mut rows = Vec::new();
+
+            for (start, apps) in self.get_buckets(resolution).range(day..day + DAY_SEC) {
+                for (application, counts) in apps {
+                    let index = *app_indexes.entry(application.as_str()).or_insert_with(|| {
+                        result.applications.push(application.to_string());
+                        result.applications.len() as i64 - 1
+                    });
+
+                    rows.push([
+                        *start,
+                        index,
+                        counts.info as i64,
+                        counts.warning as i64,
+                        counts.error as i64,
+                        counts.fatal_error as i64,
+                        counts.debug as i64,
+                        counts.sampled as i64,
+                    ]);
+                }
+            }
+
+            match resolution {
+                RollupResolution::Minute => result.minutes = rows,
+                RollupResolution::Hour => result.hours = rows,
+                RollupResolution::Day => result.days = rows,
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::app::LogItem;
+
+    use super::{RollupResolution, RollupStatistics, DAY_SEC, HOUR_SEC};
+
+    fn log_item(unix_sec: i64, application: &str, level: my_logger::LogLevel) -> LogItem {
+        let mut ctx = BTreeMap::new();
+        ctx.insert("Application".to_string(), application.to_string());
+
+        LogItem {
+            id: "Test".to_string(),
+            level,
+            process: None,
+            message: "Test".to_string(),
+            timestamp: DateTimeAsMicroseconds::new(unix_sec * 1_000_000),
+            ctx,
+        }
+    }
+
+    #[test]
+    fn test_rollup_buckets_and_persistence() {
+        let now = 1_700_000_000 - 1_700_000_000 % DAY_SEC + 12 * HOUR_SEC;
+
+        let mut statistics = RollupStatistics::new();
+        statistics.update(&log_item(now, "a", my_logger::LogLevel::Error));
+        statistics.update(&log_item(now + 30, "a", my_logger::LogLevel::Error));
+        statistics.update(&log_item(now + 90, "b", my_logger::LogLevel::Info));
+        statistics.add_sampled(&log_item(now + 90, "b", my_logger::LogLevel::Info));
+        statistics.update(&log_item(
+            now - 40 * DAY_SEC,
+            "a",
+            my_logger::LogLevel::Warning,
+        ));
+
+        let minutes = statistics.query(RollupResolution::Minute, now, now + 120, None);
+        assert_eq!(minutes.len(), 2);
+        assert_eq!(minutes[0].apps["a"].error, 2);
+        assert_eq!(minutes[1].apps["b"].sampled, 1);
+
+        let hours = statistics.query(RollupResolution::Hour, now, now, Some("a"));
+        assert_eq!(hours.len(), 1);
+        assert_eq!(hours[0].start, now);
+        assert!(!hours[0].apps.contains_key("b"));
+
+        statistics.gc(now);
+        assert!(statistics
+            .query(RollupResolution::Hour, now - 41 * DAY_SEC, now, Some("a"))
+            .iter()
+            .all(|bucket| bucket.apps["a"].warning == 0));
+        let days = statistics.query(RollupResolution::Day, now - 41 * DAY_SEC, now, None);
+        assert_eq!(days.len(), 2);
+
+        let days_to_persist = statistics.get_days_to_persist();
+        assert!(statistics.get_days_to_persist().is_empty());
+        assert_eq!(days_to_persist.len(), 2);
+        assert_eq!(days_to_persist[0].0, now - 40 * DAY_SEC - 12 * HOUR_SEC);
+        assert_eq!(
+            days_to_persist[1].1.applications,
+            vec!["a".to_string(), "b".to_string()]
+        );
+
+        let mut restored = RollupStatistics::new();
+        for (_, snapshot) in days_to_persist {
+            restored
+                .restore(serde_json::from_slice(&serde_json::to_vec(&snapshot).unwrap()).unwrap());
+        }
+        assert_eq!(restored.len(), statistics.len());
+        let days = restored.query(RollupResolution::Day, now - 41 * DAY_SEC, now, Some("a"));
+        assert_eq!(days[0].apps["a"].warning, 1);
+        assert_eq!(days[1].apps["a"].error, 2);
+
+        let sum = restored.get_sum(RollupResolution::Hour, now - DAY_SEC, now);
+        assert_eq!(sum["a"].error, 2);
+        assert_eq!(sum["b"].info, 1);
+
+        let hours = restored.get_max_hours(48);
+        assert_eq!(hours.len(), 1);
+        assert_eq!(
+            hours[0].0.get_start().unwrap().unix_microseconds,
+            now * 1_000_000
+        );
+    }
+
+    #[test]
+    fn test_events_without_application() {
+        let now = 1_700_000_000;
+
+        let mut itm = log_item(now, "a", my_logger::LogLevel::Error);
+        itm.ctx.clear();
+
+        let mut statistics = RollupStatistics::new();
+        statistics.update(&itm);
+
+        let hours = statistics.query(RollupResolution::Hour, now, now, None);
+        assert_eq!(hours[0].apps[crate::app::NO_APPLICATION].error, 1);
+    }
+
+    #[test]
+    fn test_legacy_restore_fills_missing_buckets() {
+        let now = 1_700_000_000 - 1_700_000_000 % DAY_SEC + 12 * HOUR_SEC;
+        let hour: crate::hourly_statistics::StatisticsHour =
+            DateTimeAsMicroseconds::new(now * 1_000_000).into();
+
+        let mut statistics = RollupStatistics::new();
+        statistics.update(&log_item(now, "a", my_logger::LogLevel::Error));
+        statistics.get_days_to_persist();
+
+        let mut apps = BTreeMap::new();
+        apps.insert(
+            "a".to_string(),
+            crate::hourly_statistics::HourlyStatisticsItem {
+                error: 10,
+                ..Default::default()
+            },
+        );
+
+        statistics.restore_legacy_hours(vec![
+            crate::hourly_statistics::PersistedHour {
+                hour: hour.get_value(),
+                apps: apps.clone(),
+            },
+            crate::hourly_statistics::PersistedHour {
+                hour: hour.get_value() - 1,
+                apps: apps.clone(),
+            },
+        ]);
+        statistics.restore_legacy_days(vec![crate::hourly_statistics::PersistedDay {
+            day: hour.get_value() / 100 - 1,
+            apps,
+        }]);
+
+        let hours = statistics.query(RollupResolution::Hour, now - HOUR_SEC, now, None);
+        assert_eq!(hours.len(), 2);
+        assert_eq!(hours[0].apps["a"].error, 10);
+        assert_eq!(hours[1].apps["a"].error, 1);
+
+        let days = statistics.query(RollupResolution::Day, now - DAY_SEC, now, None);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].apps["a"].error, 10);
+        assert_eq!(statistics.get_days_to_persist().len(), 2);
+    }
+
+    #[test]
+    fn test_resolution_select() {
+        let now = 1_700_000_000;
+        assert_eq!(
+            RollupResolution::select(now - HOUR_SEC, now, now),
+            RollupResolution::Minute
+        );
+        assert_eq!(
+            RollupResolution::select(now - 2 * DAY_SEC, now - DAY_SEC + 1, now),
+            RollupResolution::Hour
+        );
+        assert_eq!(
+            RollupResolution::select(now - 90 * DAY_SEC, now, now),
+            RollupResolution::Day
+        );
+    }
+}
@@ -0,0 +1,2 @@
+mod sampler;
+pub use sampler::*;
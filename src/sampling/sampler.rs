@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::{LogItem, APPLICATION_KEY},
+    error_signatures::{get_error_signature, to_message_template},
+    repo::dto::LogLevelDto,
+    settings::SamplingRuleSettings,
+    text_matching::is_glob_match,
+};
+
+/// Counters of a rule are reset once there are more keys, so a burst of unique signatures can not grow memory.
+const MAX_COUNTERS_PER_RULE: usize = 10_000;
+
+#[derive(Default)]
+struct SamplingCounter {
+    seen: u64,
+    second: i64,
+    kept_in_second: u64,
+}
+
+pub struct SamplingStatisticsItem {
+    pub rule: String,
+    pub kept: u64,
+    pub dropped: u64,
+    pub last_dropped: Option<DateTimeAsMicroseconds>,
+}
+
+struct SamplingRule {
+    name: String,
+    applications: Option<Vec<String>>,
+    levels: Option<Vec<LogLevelDto>>,
+    keep_one_of: u64,
+    max_per_second: u64,
+    by_signature: bool,
+    counters: BTreeMap<(String, LogLevelDto, String), SamplingCounter>,
+    kept: u64,
+    dropped: u64,
+    last_dropped: Option<DateTimeAsMicroseconds>,
+}
+
+impl SamplingRule {
+    fn new(index: usize, settings: &SamplingRuleSettings) -> Result<Self, String> {
+        let name = settings.get_name(index);
+        let keep_one_of = settings.keep_one_of.unwrap_or(0);
+        let max_per_second = settings.max_per_second.unwrap_or(0);
+
+        if keep_one_of <= 1 && max_per_second == 0 {
+            return Err(format!(
+                "Sampling rule '{}' has neither keep_one_of > 1 nor max_per_second > 0",
+                name
+            ));
+        }
+
+        Ok(Self {
+            name,
+            applications: settings.applications.clone(),
+            levels: settings.levels.clone(),
+            keep_one_of,
+            max_per_second,
+            by_signature: settings.by_signature,
+            counters: BTreeMap::new(),
+            kept: 0,
+            dropped: 0,
+            last_dropped: None,
+        })
+    }
+
+    fn is_for(&self, application: &str, level: &LogLevelDto) -> bool {
+        if let Some(levels) = &self.levels {
+            if !levels.contains(level) {
+                return false;
+            }
+        }
+
+        match &self.applications {
+            Some(applications) => applications
+                .iter()
+                .any(|pattern| is_glob_match(pattern, application)),
+            None => true,
+        }
+    }
+
+    fn keep(&mut self, itm: &LogItem, application: &str, level: LogLevelDto) -> bool {
+        let signature = if self.by_signature {
+            get_error_signature(application, &to_message_template(&itm.message))
+        } else {
+            String::new()
+        };
+
+        if self.counters.len() >= MAX_COUNTERS_PER_RULE {
+            self.counters.clear();
+        }
+
+        let counter = self
+            .counters
+            .entry((application.to_string(), level, signature))
+            .or_default();
+
+        counter.seen += 1;
+
+        let mut keep = self.keep_one_of <= 1 || (counter.seen - 1) % self.keep_one_of == 0;
+
+        if keep && self.max_per_second > 0 {
+            let second = itm.timestamp.unix_microseconds / 1_000_000;
+            if counter.second != second {
+                counter.second = second;
+                counter.kept_in_second = 0;
+            }
+
+            if counter.kept_in_second >= self.max_per_second {
+                keep = false;
+            } else {
+                counter.kept_in_second += 1;
+            }
+        }
+
+        if keep {
+            self.kept += 1;
+        } else {
+            self.dropped += 1;
+            self.last_dropped = Some(itm.timestamp);
+        }
+
+        keep
+    }
+}
+
+/// Sampling rules compiled once from settings. The first matching rule decides.
+pub struct Sampler {
+    rules: Vec<SamplingRule>,
+}
+
+impl Sampler {
+    pub fn new(settings: Vec<SamplingRuleSettings>) -> Self {
+        let mut rules = Vec::with_capacity(settings.len());
+
+        for (index, rule) in settings.iter().enumerate() {
+            match SamplingRule::new(index, rule) {
+                Ok(rule) => rules.push(rule),
+                Err(err) => println!("Skipping invalid sampling rule: {}", err),
+            }
+        }
+
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns `false` if the event is sampled out and must not be stored.
+    pub fn keep(&mut self, itm: &LogItem) -> bool {
+        let application = itm
+            .ctx
+            .get(APPLICATION_KEY)
+            .map(|value| value.as_str())
+            .unwrap_or_default();
+        let level: LogLevelDto = (&itm.level).into();
+
+        for rule in self.rules.iter_mut() {
+            if rule.is_for(application, &level) {
+                return rule.keep(itm, application, level);
+            }
+        }
+
+        true
+    }
+
+    pub fn get_statistics(&self) -> Vec<SamplingStatisticsItem> {
+        self.rules
+            .iter()
+            .map(|rule| SamplingStatisticsItem {
+                rule: rule.name.to_string(),
+                kept: rule.kept,
+                dropped: rule.dropped,
+                last_dropped: rule.last_dropped,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::{app::LogItem, repo::dto::LogLevelDto, settings::SamplingRuleSettings};
+
+    use super::Sampler;
+
+    fn log_item(application: &str, timestamp: i64) -> LogItem {
+        let mut ctx = BTreeMap::new();
+        ctx.insert("Application".to_string(), application.to_string());
+
+        LogItem {
+            id: timestamp.to_string(),
+            level: my_logger::LogLevel::Info,
+            process: None,
+            message: "Request handled in 15ms".to_string(),
+            timestamp: DateTimeAsMicroseconds::new(timestamp),
+            ctx,
+        }
+    }
+
+    #[test]
+    fn test_keep_one_of_and_max_per_second() {
+        let mut sampler = Sampler::new(vec![
+            SamplingRuleSettings {
+                name: Some("chatty".to_string()),
+                applications: Some(vec!["chatty*".to_string()]),
+                levels: Some(vec![LogLevelDto::Info]),
+                keep_one_of: Some(3),
+                max_per_second: None,
+                by_signature: false,
+            },
+            SamplingRuleSettings {
+                name: None,
+                applications: None,
+                levels: None,
+                keep_one_of: None,
+                max_per_second: Some(2),
+                by_signature: true,
+            },
+        ]);
+
+        let kept: Vec<_> = (0..7)
+            .map(|i| sampler.keep(&log_item("chatty-api", i)))
+            .collect();
+        assert_eq!(kept, vec![true, false, false, true, false, false, true]);
+
+        let kept: Vec<_> = (0..4)
+            .map(|i| sampler.keep(&log_item("payments", 1_000_000 + i)))
+            .collect();
+        assert_eq!(kept, vec![true, true, false, false]);
+        assert!(sampler.keep(&log_item("payments", 2_000_000)));
+
+        let statistics = sampler.get_statistics();
+        assert_eq!(statistics[0].rule, "chatty");
+        assert_eq!(statistics[0].dropped, 4);
+        assert_eq!(statistics[1].rule, "rule-1");
+        assert_eq!(statistics[1].kept, 3);
+        assert_eq!(statistics[1].dropped, 2);
+    }
+}
@@ -1,12 +1,30 @@
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 
 use serde::{Deserialize, Serialize};
 
+use crate::repo::dto::LogLevelDto;
+
+/// Sends notifications of matching applications and levels to a dedicated chat.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TelegramRouteSettings {
+    /// Application globs (`payments*`). Route matches every application if not set.
+    pub applications: Option<Vec<String>>,
+    /// Route matches every level if not set.
+    pub levels: Option<Vec<LogLevelDto>>,
+    pub chat_id: i64,
+    pub message_thread_id: Option<i32>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TelegramSettings {
     pub api_key: String,
+    /// Default chat for notifications no route matches.
     pub chat_id: i64,
     pub message_thread_id: i32,
+    /// The first matching route wins.
+    pub routes: Option<Vec<TelegramRouteSettings>>,
+    /// Polls `getUpdates` and answers bot commands sent to the configured chats.
+    pub bot_commands: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -16,6 +34,315 @@ pub struct ElasticSettings {
     pub env_source: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum FieldExtractionFormat {
+    Json,
+    Logfmt,
+    Regex,
+}
+
+/// Rule which promotes values found in the message to the context of the event.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FieldExtractionSettings {
+    /// Value of the `Application` context key. Rule applies to every application if not set.
+    pub application: Option<String>,
+    pub format: FieldExtractionFormat,
+    /// Context key -> dot separated JSON path (`user.id`, `items.0.sku`) or logfmt key.
+    /// If not set - every top level scalar field is extracted with its own name.
+    pub fields: Option<BTreeMap<String, String>>,
+    /// Regex with named groups. Required for the `Regex` format.
+    pub pattern: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RedactionDetector {
+    Jwt,
+    Bearer,
+    Pan,
+    Email,
+    Iban,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RedactionAction {
+    #[default]
+    Mask,
+    Hash,
+    Keep,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RedactionDetectorSettings {
+    pub detector: RedactionDetector,
+    #[serde(default)]
+    pub action: RedactionAction,
+    /// Context key -> action. Overrides `action` for values of these keys.
+    pub context_keys: Option<BTreeMap<String, RedactionAction>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CustomRedactionSettings {
+    pub name: String,
+    pub pattern: String,
+    #[serde(default)]
+    pub action: RedactionAction,
+    pub context_keys: Option<BTreeMap<String, RedactionAction>>,
+}
+
+/// Nothing is redacted without the section. With the section but without `detectors`,
+/// every built-in detector is enabled with `Mask`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RedactionSettings {
+    #[serde(default)]
+    pub disabled: bool,
+    pub detectors: Option<Vec<RedactionDetectorSettings>>,
+    pub custom: Option<Vec<CustomRedactionSettings>>,
+    /// HMAC key of the `Hash` action. `Hash` is replaced with `Mask` without it.
+    pub hash_salt: Option<String>,
+}
+
+/// Keeps only part of the matching events in storage. Statistics, alerts and issues still see every event.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SamplingRuleSettings {
+    /// Name used in the sampling statistics. `rule-<index>` if not set.
+    pub name: Option<String>,
+    /// Application globs. Rule matches every application if not set.
+    pub applications: Option<Vec<String>>,
+    /// Rule matches every level if not set.
+    pub levels: Option<Vec<LogLevelDto>>,
+    /// Keeps the first of every N events.
+    pub keep_one_of: Option<u64>,
+    /// Keeps at most N events per second.
+    pub max_per_second: Option<u64>,
+    /// Counts per message signature instead of per application and level.
+    #[serde(default)]
+    pub by_signature: bool,
+}
+
+impl SamplingRuleSettings {
+    pub fn get_name(&self, index: usize) -> String {
+        match &self.name {
+            Some(name) => name.to_string(),
+            None => format!("rule-{}", index),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationChannelType {
+    Telegram,
+    Slack,
+    Teams,
+    Webhook,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    Summary,
+    Alert,
+    Event,
+    NewError,
+}
+
+/// Text templates with `{placeholder}` substitution. Built-in formatting is used for omitted ones.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NotificationTemplates {
+    pub summary: Option<String>,
+    pub alert: Option<String>,
+    pub event: Option<String>,
+    pub new_error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotificationChannelSettings {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub channel_type: NotificationChannelType,
+    /// Incoming webhook url. Required for every type except `Telegram`.
+    pub url: Option<String>,
+    /// Extra HTTP headers, e.g. `Authorization` of the paging tool.
+    pub headers: Option<BTreeMap<String, String>>,
+    /// Required for the `Telegram` type.
+    pub telegram: Option<TelegramSettings>,
+    pub templates: Option<NotificationTemplates>,
+    /// Retries after the first failed attempt. Default 3.
+    pub max_retries: Option<u32>,
+}
+
+/// Sends notifications of matching applications to the listed channels.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotificationRouteSettings {
+    /// Application globs (`payments*`). Route matches every application if not set.
+    pub applications: Option<Vec<String>>,
+    /// Route matches every kind if not set.
+    pub kinds: Option<Vec<NotificationKind>>,
+    pub channels: Vec<String>,
+}
+
+/// Counts events by the values of a context key.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StatisticDimensionSettings {
+    pub key: String,
+    /// Distinct values counted separately, the rest is counted as `(other)`. Default 100.
+    pub max_values: Option<usize>,
+}
+
+impl StatisticDimensionSettings {
+    pub fn get_max_values(&self) -> usize {
+        self.max_values.unwrap_or(100).max(1)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NewErrorDetectionSettings {
+    #[serde(default)]
+    pub disabled: bool,
+    /// Report a known signature again when it was not seen for this many days. Default 7, 0 - never.
+    pub reappear_after_days: Option<u32>,
+    /// Signatures are only recorded for this long after starting with an empty store. Default 10.
+    pub learning_minutes: Option<u32>,
+}
+
+impl NewErrorDetectionSettings {
+    pub fn get_reappear_after_sec(&self) -> i64 {
+        self.reappear_after_days.unwrap_or(7) as i64 * 86400
+    }
+
+    pub fn get_learning_sec(&self) -> i64 {
+        self.learning_minutes.unwrap_or(10) as i64 * 60
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AnomalyDetectionSettings {
+    #[serde(default)]
+    pub disabled: bool,
+    /// Spike is raised when the current hour is this many standard deviations above the baseline. Default 4.
+    pub z_score: Option<f64>,
+    /// Spike needs at least this many events in the current hour. Default 20.
+    pub min_events: Option<u32>,
+    /// Number of previous hours used as the baseline. Default 24, at most 47.
+    pub baseline_hours: Option<u32>,
+    /// Silence is only raised for applications logging at least this many events per hour. Default 60.
+    pub silence_min_hourly_events: Option<u32>,
+    /// Minutes of the current hour to wait before comparing. Default 10.
+    pub min_elapsed_minutes: Option<u32>,
+    /// Notification channels. Routed by application if omitted.
+    pub channels: Option<Vec<String>>,
+}
+
+impl AnomalyDetectionSettings {
+    pub fn get_z_score(&self) -> f64 {
+        self.z_score.unwrap_or(4.0)
+    }
+
+    pub fn get_min_events(&self) -> u32 {
+        self.min_events.unwrap_or(20)
+    }
+
+    pub fn get_baseline_hours(&self) -> usize {
+        self.baseline_hours.unwrap_or(24).min(47) as usize
+    }
+
+    pub fn get_silence_min_hourly_events(&self) -> u32 {
+        self.silence_min_hourly_events.unwrap_or(60)
+    }
+
+    pub fn get_min_elapsed_sec(&self) -> i64 {
+        self.min_elapsed_minutes.unwrap_or(10).min(59) as i64 * 60
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HeartbeatApplicationSettings {
+    pub application: String,
+    /// Overrides the default limit. 0 - not monitored.
+    pub max_silence_minutes: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct HeartbeatSettings {
+    #[serde(default)]
+    pub disabled: bool,
+    /// Monitor every application seen in the `Application` context. Default false.
+    pub auto_learn: Option<bool>,
+    /// Default limit of silence. Default 30.
+    pub max_silence_minutes: Option<u32>,
+    /// Learned applications silent for this long are removed. Default 7.
+    pub forget_after_days: Option<u32>,
+    /// Applications expected even if they were never seen.
+    pub applications: Option<Vec<HeartbeatApplicationSettings>>,
+    /// Notification channels. Routed by application if omitted.
+    pub channels: Option<Vec<String>>,
+}
+
+impl HeartbeatSettings {
+    pub fn is_declared(&self, application: &str) -> bool {
+        self.applications
+            .iter()
+            .flatten()
+            .any(|itm| itm.application == application)
+    }
+
+    /// `None` if the application is not monitored.
+    pub fn get_max_silence_sec(&self, application: &str) -> Option<i64> {
+        let default_minutes = self.max_silence_minutes.unwrap_or(30);
+
+        let declared = self
+            .applications
+            .iter()
+            .flatten()
+            .find(|itm| itm.application == application);
+
+        let minutes = match declared {
+            Some(declared) => declared.max_silence_minutes.unwrap_or(default_minutes),
+            None if self.auto_learn.unwrap_or(false) => default_minutes,
+            None => return None,
+        };
+
+        if minutes == 0 {
+            return None;
+        }
+
+        Some(minutes as i64 * 60)
+    }
+
+    pub fn get_forget_after_sec(&self) -> i64 {
+        self.forget_after_days.unwrap_or(7) as i64 * 86400
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DigestSettings {
+    /// Default true.
+    pub daily: Option<bool>,
+    /// Default true.
+    pub weekly: Option<bool>,
+    /// UTC hour the digests are sent at. Default 8.
+    pub hour_utc: Option<u8>,
+    /// Day of the weekly digest, 1 - Monday .. 7 - Sunday. Default 1.
+    pub weekday: Option<u8>,
+    /// Items per section. Default 10.
+    pub top: Option<usize>,
+    /// Overrides the chat of TelegramSettings.
+    pub chat_id: Option<i64>,
+    pub message_thread_id: Option<i32>,
+}
+
+impl DigestSettings {
+    pub fn get_hour_utc(&self) -> i64 {
+        self.hour_utc.unwrap_or(8).min(23) as i64
+    }
+
+    pub fn get_weekday(&self) -> i64 {
+        self.weekday.unwrap_or(1).clamp(1, 7) as i64
+    }
+
+    pub fn get_top(&self) -> usize {
+        self.top.unwrap_or(10)
+    }
+}
+
 #[derive(my_settings_reader::SettingsModel, Serialize, Deserialize, Debug, Clone)]
 pub struct SettingsModel {
     #[serde(rename = "ApiKey")]
@@ -43,6 +370,40 @@ pub struct SettingsModel {
 
     #[serde(rename = "InsightsKeys")]
     pub insights_keys: Option<Vec<String>>,
+
+    #[serde(rename = "FieldExtraction")]
+    pub field_extraction: Option<Vec<FieldExtractionSettings>>,
+
+    #[serde(rename = "Redaction")]
+    pub redaction: Option<RedactionSettings>,
+
+    #[serde(rename = "Sampling")]
+    pub sampling: Option<Vec<SamplingRuleSettings>>,
+
+    #[serde(rename = "StatisticDimensions")]
+    pub statistic_dimensions: Option<Vec<StatisticDimensionSettings>>,
+
+    #[serde(rename = "NewErrorDetection")]
+    pub new_error_detection: Option<NewErrorDetectionSettings>,
+
+    #[serde(rename = "AnomalyDetection")]
+    pub anomaly_detection: Option<AnomalyDetectionSettings>,
+
+    #[serde(rename = "Heartbeats")]
+    pub heartbeats: Option<HeartbeatSettings>,
+
+    #[serde(rename = "Digest")]
+    pub digest: Option<DigestSettings>,
+
+    #[serde(rename = "NotificationChannels")]
+    pub notification_channels: Option<Vec<NotificationChannelSettings>>,
+
+    #[serde(rename = "NotificationRoutes")]
+    pub notification_routes: Option<Vec<NotificationRouteSettings>>,
+
+    /// Ignore rules without hits for this number of days are reported as unused. Default: 30.
+    #[serde(rename = "IgnoreRuleUnusedDays")]
+    pub ignore_rule_unused_days: Option<u32>,
 }
 
 impl SettingsReader {
@@ -61,6 +422,70 @@ impl SettingsReader {
         read_access.insights_keys.clone().unwrap_or_default()
     }
 
+    pub async fn get_field_extraction(&self) -> Vec<FieldExtractionSettings> {
+        let read_access = self.settings.read().await;
+        read_access.field_extraction.clone().unwrap_or_default()
+    }
+
+    pub async fn get_redaction_settings(&self) -> Option<RedactionSettings> {
+        let read_access = self.settings.read().await;
+        read_access.redaction.clone()
+    }
+
+    pub async fn get_sampling(&self) -> Vec<SamplingRuleSettings> {
+        let read_access = self.settings.read().await;
+        read_access.sampling.clone().unwrap_or_default()
+    }
+
+    pub async fn get_statistic_dimensions(&self) -> Vec<StatisticDimensionSettings> {
+        let read_access = self.settings.read().await;
+        read_access.statistic_dimensions.clone().unwrap_or_default()
+    }
+
+    /// `None` if the section is absent or disabled: signatures are not recorded then.
+    pub async fn get_new_error_detection(&self) -> Option<NewErrorDetectionSettings> {
+        let read_access = self.settings.read().await;
+        read_access
+            .new_error_detection
+            .clone()
+            .filter(|settings| !settings.disabled)
+    }
+
+    /// `None` if the section is absent or disabled, so no anomalies are reported.
+    pub async fn get_anomaly_detection(&self) -> Option<AnomalyDetectionSettings> {
+        let read_access = self.settings.read().await;
+        read_access
+            .anomaly_detection
+            .clone()
+            .filter(|settings| !settings.disabled)
+    }
+
+    /// `None` if heartbeats are not configured, so nothing is monitored.
+    pub async fn get_heartbeat_settings(&self) -> Option<HeartbeatSettings> {
+        let read_access = self.settings.read().await;
+        read_access.heartbeats.clone()
+    }
+
+    pub async fn get_digest_settings(&self) -> Option<DigestSettings> {
+        let read_access = self.settings.read().await;
+        read_access.digest.clone()
+    }
+
+    pub async fn get_notification_channels(&self) -> Vec<NotificationChannelSettings> {
+        let read_access = self.settings.read().await;
+        read_access.notification_channels.clone().unwrap_or_default()
+    }
+
+    pub async fn get_notification_routes(&self) -> Option<Vec<NotificationRouteSettings>> {
+        let read_access = self.settings.read().await;
+        read_access.notification_routes.clone()
+    }
+
+    pub async fn get_ignore_rule_unused_days(&self) -> u32 {
+        let read_access = self.settings.read().await;
+        read_access.ignore_rule_unused_days.unwrap_or(30)
+    }
+
     pub async fn get_elastic_settings(&self) -> Option<ElasticSettings> {
         let read_access = self.settings.read().await;
         read_access.elastic.clone()
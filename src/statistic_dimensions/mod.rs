@@ -0,0 +1,2 @@
+mod statistic_dimensions;
+pub use statistic_dimensions::*;
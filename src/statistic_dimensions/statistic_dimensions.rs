@@ -0,0 +1,400 @@
+use std::collections::BTreeMap;
+
+use my_logger::LogLevel;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::LogItem,
+    hourly_statistics::{add_statistics, HourlyStatisticsItem, StatisticsHour},
+    settings::StatisticDimensionSettings,
+};
+
+/// Events without the key or with an empty value.
+pub const NONE_VALUE: &str = "(none)";
+/// Values above the cardinality limit of the dimension.
+pub const OTHER_VALUE: &str = "(other)";
+
+pub const DIMENSION_HOURS_TO_KEEP: i64 = 7 * 24;
+const MAX_VALUE_LEN: usize = 128;
+const HOUR_US: i64 = 60 * 60 * 1_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DimensionOrder {
+    Total,
+    /// Error and FatalError events.
+    Errors,
+}
+
+impl DimensionOrder {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "total" => Ok(Self::Total),
+            "errors" => Ok(Self::Errors),
+            _ => Err(format!(
+                "Unknown order '{}'. Expected one of: Total, Errors.",
+                value
+            )),
+        }
+    }
+
+    fn get_value(&self, counts: &HourlyStatisticsItem) -> u64 {
+        match self {
+            Self::Total => {
+                counts.info as u64
+                    + counts.warning as u64
+                    + counts.error as u64
+                    + counts.fatal_error as u64
+                    + counts.debug as u64
+            }
+            Self::Errors => counts.error as u64 + counts.fatal_error as u64,
+        }
+    }
+}
+
+/// Unix microseconds of the first and the last event with the value within the kept hours.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DimensionValueSeen {
+    pub first_seen: i64,
+    pub last_seen: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DimensionValueStatistics {
+    pub value: String,
+    pub counts: HourlyStatisticsItem,
+    pub seen: DimensionValueSeen,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistedDimension {
+    pub key: String,
+    /// Hour key (YYYYMMDDHH) -> value -> counts.
+    pub hours: BTreeMap<u64, BTreeMap<String, HourlyStatisticsItem>>,
+    pub values: BTreeMap<String, DimensionValueSeen>,
+}
+
+struct Dimension {
+    key: String,
+    max_values: usize,
+    hours: BTreeMap<u64, BTreeMap<String, HourlyStatisticsItem>>,
+    values: BTreeMap<String, DimensionValueSeen>,
+}
+
+impl Dimension {
+    fn get_value<'s>(&self, itm: &'s LogItem) -> &'s str {
+        let value = match itm.ctx.get(&self.key).map(|value| value.trim()) {
+            Some(value) if !value.is_empty() => value,
+            _ => return NONE_VALUE,
+        };
+
+        let value = match value.char_indices().nth(MAX_VALUE_LEN) {
+            Some((end, _)) => &value[..end],
+            None => value,
+        };
+
+        if self.values.contains_key(value) {
+            return value;
+        }
+
+        let tracked = self
+            .values
+            .keys()
+            .filter(|value| value.as_str() != NONE_VALUE && value.as_str() != OTHER_VALUE)
+            .count();
+
+        if tracked >= self.max_values {
+            return OTHER_VALUE;
+        }
+
+        value
+    }
+
+    fn add(&mut self, itm: &LogItem, apply: &impl Fn(&mut HourlyStatisticsItem)) {
+        let value = self.get_value(itm);
+        let moment = itm.timestamp.unix_microseconds;
+
+        match self.values.get_mut(value) {
+            Some(seen) => {
+                seen.first_seen = seen.first_seen.min(moment);
+                seen.last_seen = seen.last_seen.max(moment);
+            }
+            None => {
+                self.values.insert(
+                    value.to_string(),
+                    DimensionValueSeen {
+                        first_seen: moment,
+                        last_seen: moment,
+                    },
+                );
+            }
+        }
+
+        let hour: StatisticsHour = itm.timestamp.into();
+        let values = self.hours.entry(hour.get_value()).or_default();
+
+        match values.get_mut(value) {
+            Some(counts) => apply(counts),
+            None => apply(values.entry(value.to_string()).or_default()),
+        }
+    }
+}
+
+/// Hourly counts per value of the configured context keys.
+pub struct StatisticDimensions {
+    dimensions: Vec<Dimension>,
+    has_changes: bool,
+}
+
+impl StatisticDimensions {
+    pub fn new(settings: Vec<StatisticDimensionSettings>) -> Self {
+        let mut dimensions: Vec<Dimension> = Vec::with_capacity(settings.len());
+
+        for item in settings {
+            let key = item.key.trim().to_string();
+            if key.is_empty() || dimensions.iter().any(|d| d.key == key) {
+                println!("Skipping empty or duplicated statistic dimension '{}'", key);
+                continue;
+            }
+
+            dimensions.push(Dimension {
+                max_values: item.get_max_values(),
+                key,
+                hours: BTreeMap::new(),
+                values: BTreeMap::new(),
+            });
+        }
+
+        Self {
+            dimensions,
+            has_changes: false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dimensions.is_empty()
+    }
+
+    pub fn get_keys(&self) -> Vec<String> {
+        self.dimensions.iter().map(|d| d.key.to_string()).collect()
+    }
+
+    pub fn update(&mut self, itm: &LogItem) {
+        self.add(itm, |counts| match itm.level {
+            LogLevel::Info => counts.info += 1,
+            LogLevel::Warning => counts.warning += 1,
+            LogLevel::Error => counts.error += 1,
+            LogLevel::FatalError => counts.fatal_error += 1,
+            LogLevel::Debug => counts.debug += 1,
+        });
+    }
+
+    pub fn add_sampled(&mut self, itm: &LogItem) {
+        self.add(itm, |counts| counts.sampled += 1);
+    }
+
+    fn add(&mut self, itm: &LogItem, apply: impl Fn(&mut HourlyStatisticsItem)) {
+        for dimension in self.dimensions.iter_mut() {
+            dimension.add(itm, &apply);
+        }
+
+        if !self.dimensions.is_empty() {
+            self.has_changes = true;
+        }
+    }
+
+    /// Top `take` values of the dimension within the hours of `[from..to]`. `None` if the key is not configured.
+    /// A value without its first and last moment reports the requested range as seen.
+    pub fn query(
+        &self,
+        key: &str,
+        from: DateTimeAsMicroseconds,
+        to: DateTimeAsMicroseconds,
+        order: DimensionOrder,
+        take: usize,
+    ) -> Option<Vec<DimensionValueStatistics>> {
+        let dimension = self.dimensions.iter().find(|d| d.key == key)?;
+
+        let from_moment = from.unix_microseconds;
+        let to_moment = to.unix_microseconds;
+        let from: StatisticsHour = from.into();
+        let to: StatisticsHour = to.into();
+
+        let mut counts = BTreeMap::new();
+        if from <= to {
+            for (_, values) in dimension.hours.range(from.get_value()..=to.get_value()) {
+                add_statistics(&mut counts, values);
+            }
+        }
+
+        let requested = DimensionValueSeen {
+            first_seen: from_moment,
+            last_seen: to_moment,
+        };
+
+        let mut result: Vec<DimensionValueStatistics> = counts
+            .into_iter()
+            .map(|(value, counts)| {
+                let seen = dimension.values.get(&value).copied().unwrap_or(requested);
+                DimensionValueStatistics {
+                    value,
+                    counts,
+                    seen,
+                }
+            })
+            .collect();
+
+        result.sort_by(|a, b| {
+            order
+                .get_value(&b.counts)
+                .cmp(&order.get_value(&a.counts))
+                .then_with(|| a.value.cmp(&b.value))
+        });
+        result.truncate(take);
+
+        Some(result)
+    }
+
+    /// Values are kept while they have events in the kept hours.
+    pub fn gc(&mut self, now: DateTimeAsMicroseconds) {
+        let cutoff = now.unix_microseconds - DIMENSION_HOURS_TO_KEEP * HOUR_US;
+        let cutoff = cutoff - cutoff.rem_euclid(HOUR_US);
+        let oldest_hour: StatisticsHour = DateTimeAsMicroseconds::new(cutoff).into();
+
+        for dimension in self.dimensions.iter_mut() {
+            let before = dimension.hours.len() + dimension.values.len();
+
+            dimension.hours = dimension.hours.split_off(&oldest_hour.get_value());
+            dimension.values.retain(|_, seen| seen.last_seen >= cutoff);
+
+            if dimension.hours.len() + dimension.values.len() != before {
+                self.has_changes = true;
+            }
+        }
+    }
+
+    /// Dimensions which are not configured anymore are skipped.
+    pub fn restore(&mut self, items: Vec<PersistedDimension>) {
+        for item in items {
+            if let Some(dimension) = self.dimensions.iter_mut().find(|d| d.key == item.key) {
+                dimension.hours = item.hours;
+                dimension.values = item.values;
+            }
+        }
+    }
+
+    /// `None` if nothing changed since the previous call.
+    pub fn get_snapshot_to_persist(&mut self) -> Option<Vec<PersistedDimension>> {
+        if !self.has_changes {
+            return None;
+        }
+
+        self.has_changes = false;
+
+        Some(
+            self.dimensions
+                .iter()
+                .map(|d| PersistedDimension {
+                    key: d.key.to_string(),
+                    hours: d.hours.clone(),
+                    values: d.values.clone(),
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::{app::LogItem, settings::StatisticDimensionSettings};
+
+    use super::{
+        DimensionOrder, StatisticDimensions, DIMENSION_HOURS_TO_KEEP, HOUR_US, NONE_VALUE,
+        OTHER_VALUE,
+    };
+
+    fn log_item(level: my_logger::LogLevel, version: Option<&str>) -> LogItem {
+        let mut ctx = BTreeMap::new();
+        if let Some(version) = version {
+            ctx.insert("Version".to_string(), version.to_string());
+        }
+
+        LogItem {
+            id: "Test".to_string(),
+            level,
+            process: None,
+            message: "Test".to_string(),
+            timestamp: DateTimeAsMicroseconds::now(),
+            ctx,
+        }
+    }
+
+    #[test]
+    fn test_dimension_top_values() {
+        let mut dimensions = StatisticDimensions::new(vec![StatisticDimensionSettings {
+            key: "Version".to_string(),
+            max_values: Some(2),
+        }]);
+
+        dimensions.update(&log_item(my_logger::LogLevel::Info, Some("0.1.16")));
+        dimensions.update(&log_item(my_logger::LogLevel::Info, Some("0.1.16")));
+        dimensions.update(&log_item(my_logger::LogLevel::Error, Some("0.1.17")));
+        dimensions.update(&log_item(my_logger::LogLevel::Error, Some("0.1.18")));
+        dimensions.update(&log_item(my_logger::LogLevel::Error, Some("0.1.19")));
+        dimensions.update(&log_item(my_logger::LogLevel::Error, None));
+
+        let now = DateTimeAsMicroseconds::now();
+        let from = DateTimeAsMicroseconds::new(now.unix_microseconds - 60 * 60 * 1_000_000);
+
+        let by_total = dimensions
+            .query("Version", from, now, DimensionOrder::Total, 10)
+            .unwrap();
+        let values: Vec<_> = by_total.iter().map(|v| v.value.as_str()).collect();
+        assert_eq!(values, vec![OTHER_VALUE, "0.1.16", NONE_VALUE, "0.1.17"]);
+
+        let by_errors = dimensions
+            .query("Version", from, now, DimensionOrder::Errors, 1)
+            .unwrap();
+        assert_eq!(by_errors.len(), 1);
+        assert_eq!(by_errors[0].value, OTHER_VALUE);
+        assert_eq!(by_errors[0].counts.error, 2);
+
+        assert!(dimensions
+            .query("Host", from, now, DimensionOrder::Total, 10)
+            .is_none());
+    }
+
+    #[test]
+    fn test_gc_keeps_values_of_kept_hours() {
+        let mut dimensions = StatisticDimensions::new(vec![StatisticDimensionSettings {
+            key: "Version".to_string(),
+            max_values: None,
+        }]);
+
+        let hour_start = 1_700_000_000_000_000 - 1_700_000_000_000_000 % HOUR_US;
+        let mut itm = log_item(my_logger::LogLevel::Error, Some("0.1.16"));
+        itm.timestamp = DateTimeAsMicroseconds::new(hour_start + 60_000_000);
+        dimensions.update(&itm);
+
+        let now = DateTimeAsMicroseconds::new(
+            hour_start + DIMENSION_HOURS_TO_KEEP * HOUR_US + 30 * 60_000_000,
+        );
+        dimensions.gc(now);
+
+        let values = dimensions
+            .query(
+                "Version",
+                DateTimeAsMicroseconds::new(hour_start),
+                now,
+                DimensionOrder::Total,
+                10,
+            )
+            .unwrap();
+        assert_eq!(values.len(), 1);
+        assert_eq!(values[0].seen.first_seen, hour_start + 60_000_000);
+    }
+}
@@ -3,9 +3,18 @@ use std::fmt::Write;
 use flurl::{body::UrlEncodedBody, FlUrl};
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 
-use crate::{app::LogItem, settings::TelegramSettings};
+use crate::{
+    alerts::{AlertNotification, AlertNotificationKind},
+    app::LogItem,
+    digest::{DigestPeriod, DigestReport},
+    error_signatures::NewErrorNotification,
+    settings::TelegramSettings,
+};
 
-use super::NotificationItem;
+use super::{
+    NotificationItem, TelegramChat, TelegramGetMeResponse, TelegramUpdate, TelegramUpdatesResponse,
+    TelegramUser,
+};
 
 pub fn log_item_level_to_telegram_str(log_item: &LogItem) -> &str {
     match &log_item.level {
@@ -17,26 +26,120 @@ pub fn log_item_level_to_telegram_str(log_item: &LogItem) -> &str {
     }
 }
 
-pub async fn send_notification_data(
+/// Telegram rejects messages longer than 4096 characters.
+pub const MAX_TELEGRAM_MESSAGE_LEN: usize = 4096;
+const TOP_MESSAGES_PER_APPLICATION: usize = 3;
+const MAX_SAMPLE_MESSAGE_LEN: usize = 200;
+const MAX_DIGEST_NAME_LEN: usize = 64;
+
+pub async fn send_message(
     telegram_settings: &TelegramSettings,
-    notification_data: &NotificationItem,
-    env_name: &str,
-    ui_url: String,
-) {
+    chat: TelegramChat,
+    parse_mode: &str,
+    text: &str,
+) -> Result<(), String> {
+    let url = format!(
+        "https://api.telegram.org/bot{}/sendMessage",
+        telegram_settings.api_key
+    );
+
+    let mut chat_id_buf = itoa::Buffer::new();
+    let mut thread_id_buf = itoa::Buffer::new();
+
+    let mut body = UrlEncodedBody::new().append("chat_id", chat_id_buf.format(chat.chat_id));
 
-    if notification_data.fatal_errors == 0 && notification_data.errors == 0 {
-        return;
+    if let Some(message_thread_id) = chat.message_thread_id {
+        body = body.append("message_thread_id", thread_id_buf.format(message_thread_id));
     }
+
+    let body = body.append("parse_mode", parse_mode).append("text", text);
+
+    let response = FlUrl::new(url.as_str())
+        .accept_invalid_certificate()
+        .post(body)
+        .await;
+
+    crate::notifications::check_response(response).await
+}
+
+/// Short poll of the bot updates starting from `offset` (the last seen `update_id` + 1).
+pub async fn get_updates(
+    telegram_settings: &TelegramSettings,
+    offset: i64,
+) -> Result<Vec<TelegramUpdate>, String> {
     let url = format!(
-        "https://api.telegram.org/bot{}/sendMessage",
+        "https://api.telegram.org/bot{}/getUpdates?offset={}&timeout=0&allowed_updates=%5B%22message%22%5D",
+        telegram_settings.api_key, offset
+    );
+
+    let mut response = FlUrl::new(url.as_str())
+        .accept_invalid_certificate()
+        .get()
+        .await
+        .map_err(|err| format!("getUpdates failed: {:?}", err))?;
+
+    let body = response
+        .get_body_as_slice()
+        .await
+        .map_err(|err| format!("getUpdates body read failed: {:?}", err))?;
+
+    let response: TelegramUpdatesResponse = serde_json::from_slice(body)
+        .map_err(|err| format!("getUpdates response is not valid: {}", err))?;
+
+    if !response.ok {
+        return Err(format!(
+            "getUpdates failed: {}",
+            response.description.unwrap_or_default()
+        ));
+    }
+
+    Ok(response.result)
+}
+
+/// The bot itself. Its username tells commands addressed to it in group chats.
+pub async fn get_me(telegram_settings: &TelegramSettings) -> Result<TelegramUser, String> {
+    let url = format!(
+        "https://api.telegram.org/bot{}/getMe",
         telegram_settings.api_key
     );
 
+    let mut response = FlUrl::new(url.as_str())
+        .accept_invalid_certificate()
+        .get()
+        .await
+        .map_err(|err| format!("getMe failed: {:?}", err))?;
+
+    let body = response
+        .get_body_as_slice()
+        .await
+        .map_err(|err| format!("getMe body read failed: {:?}", err))?;
+
+    let response: TelegramGetMeResponse = serde_json::from_slice(body)
+        .map_err(|err| format!("getMe response is not valid: {}", err))?;
+
+    match response.result {
+        Some(user) if response.ok => Ok(user),
+        _ => Err(format!(
+            "getMe failed: {}",
+            response.description.unwrap_or_default()
+        )),
+    }
+}
+
+/// Minute summary with a section per application. Sections which do not fit into
+/// [`MAX_TELEGRAM_MESSAGE_LEN`] are replaced by a counter of the skipped applications.
+pub fn compile_notification_text(
+    notification_data: &NotificationItem,
+    env_name: &str,
+    ui_url: &str,
+) -> String {
     let time_interval: DateTimeAsMicroseconds = notification_data.key.try_into().unwrap();
+    let minute_from = time_interval.unix_microseconds;
+    let minute_to = minute_from + 60_000_000 - 1;
 
-    let mut telegram_statistics = String::with_capacity(256);
+    let mut result = String::with_capacity(1024);
     let _ = write!(
-        telegram_statistics,
+        result,
         "---\n📊<b>EnvInfo</b>:{}\n<b>Statistics of minute</b>: {}\n☠️<b>FatalErrors</b>: {}\n🟥<b>Errors</b>: {}\n⚠️<b>Warnings</b>: {}\n",
         env_name,
         time_interval.to_rfc3339(),
@@ -45,45 +148,164 @@ pub async fn send_notification_data(
         notification_data.warnings,
     );
     if !ui_url.is_empty() {
-        let _ = write!(telegram_statistics, "<a href=\"{}\">LogsUi</a>", ui_url);
+        let _ = write!(result, "<a href=\"{}\">LogsUi</a>", ui_url);
     }
-    telegram_statistics.push('\n');
+    result.push('\n');
 
-    println!("Sending telegram stats: {}", telegram_statistics);
+    let applications = notification_data.get_applications_by_severity();
+    let mut result_len = result.chars().count();
 
-    let mut chat_id_buf = itoa::Buffer::new();
-    let mut thread_id_buf = itoa::Buffer::new();
+    for (no, (application, item)) in applications.iter().enumerate() {
+        let mut section = String::with_capacity(512);
+        section.push_str("\n<b>");
+        append_html_escaped(&mut section, application);
+        let _ = write!(
+            section,
+            "</b> ☠️{} 🟥{} ⚠️{}",
+            item.fatal_errors, item.errors, item.warnings
+        );
 
-    let body = UrlEncodedBody::new()
-        .append("chat_id", chat_id_buf.format(telegram_settings.chat_id))
-        .append(
-            "message_thread_id",
-            thread_id_buf.format(telegram_settings.message_thread_id),
-        )
-        .append("parse_mode", "HTML")
-        .append("text", &telegram_statistics);
+        let app_url = crate::app::ui_links::compile_application_range_url(
+            ui_url,
+            application,
+            minute_from,
+            minute_to,
+        );
+        if let Some(app_url) = app_url {
+            section.push_str(" <a href=\"");
+            append_html_escaped(&mut section, &app_url);
+            section.push_str("\">open</a>");
+        }
 
-    let response = FlUrl::new(url.as_str())
-        .accept_invalid_certificate()
-        .with_retries(3)
-        .post(body)
-        .await;
+        for (message, count) in item.get_top_messages(TOP_MESSAGES_PER_APPLICATION) {
+            let _ = write!(section, "\n • {}× ", count);
+            append_html_escaped(
+                &mut section,
+                truncate_chars(message, MAX_SAMPLE_MESSAGE_LEN),
+            );
+        }
+
+        let section_len = section.chars().count();
 
-    log_telegram_response("send_notification_data", response).await;
+        // Room is left for the line about the applications which do not fit.
+        if result_len + section_len + 64 > MAX_TELEGRAM_MESSAGE_LEN {
+            let _ = write!(
+                result,
+                "\n…and {} more applications",
+                applications.len() - no
+            );
+            break;
+        }
+
+        result.push_str(&section);
+        result_len += section_len;
+    }
 
-    println!("Minute Statistics{:?}", notification_data);
+    result
 }
-// Define a function to send a message using the Telegram Bot API
-pub async fn send_log_item(
-    telegram_settings: &TelegramSettings,
-    log_item: &LogItem,
+
+fn truncate_chars(src: &str, max_chars: usize) -> &str {
+    match src.char_indices().nth(max_chars) {
+        Some((index, _)) => &src[..index],
+        None => src,
+    }
+}
+
+pub fn compile_alert_text(alert: &AlertNotification, env_name: &str, ui_url: &str) -> String {
+    let mut text = String::with_capacity(512);
+    match alert.kind {
+        AlertNotificationKind::Firing => text.push_str("🚨<b>Alert</b>: "),
+        AlertNotificationKind::Resolved => text.push_str("✅<b>Resolved</b>: "),
+    }
+    append_html_escaped(&mut text, &alert.rule_name);
+    text.push_str("\n<b>EnvInfo</b>: ");
+    append_html_escaped(&mut text, env_name);
+
+    if let Some(application) = alert.application.as_deref() {
+        text.push_str("\n<b>Application</b>: ");
+        append_html_escaped(&mut text, application);
+    }
+
+    if let Some(group_by) = alert.group_by.as_deref() {
+        text.push_str("\n<b>");
+        append_html_escaped(&mut text, group_by);
+        text.push_str("</b>: ");
+        append_html_escaped(&mut text, &alert.group);
+    }
+
+    let _ = write!(
+        text,
+        "\n<b>Events</b>: {} in {}s (threshold {})\n<b>At</b>: {}",
+        alert.count,
+        alert.window_sec,
+        alert.threshold,
+        alert.moment.to_rfc3339(),
+    );
+
+    if let Some(sample_message) = alert.sample_message.as_deref() {
+        text.push_str("\n<b>Msg</b>: ");
+        append_html_escaped(&mut text, sample_message);
+    }
+
+    if !ui_url.is_empty() {
+        text.push_str("\n<a href=\"");
+        append_html_escaped(&mut text, ui_url);
+        text.push_str("\">LogsUi</a>");
+    }
+
+    let saved_search_url = alert
+        .saved_search_id
+        .as_deref()
+        .and_then(|saved_search_id| {
+            crate::app::ui_links::compile_saved_search_url(ui_url, saved_search_id)
+        });
+    if let Some(saved_search_url) = saved_search_url {
+        text.push_str(" <a href=\"");
+        append_html_escaped(&mut text, &saved_search_url);
+        text.push_str("\">SavedSearch</a>");
+    }
+
+    text
+}
+
+pub fn compile_new_error_text(
+    new_error: &NewErrorNotification,
     env_name: &str,
-) {
-    let url = format!(
-        "https://api.telegram.org/bot{}/sendMessage",
-        telegram_settings.api_key
+    ui_url: &str,
+) -> String {
+    let item = &new_error.item;
+
+    let mut text = String::with_capacity(512);
+    match new_error.reappeared_after_sec {
+        Some(sec) => {
+            let _ = write!(text, "🔁<b>Error is back after {} days</b>: ", sec / 86400);
+        }
+        None => text.push_str("🆕<b>New error</b>: "),
+    }
+    append_html_escaped(&mut text, &item.application);
+    text.push_str("\n<b>EnvInfo</b>: ");
+    append_html_escaped(&mut text, env_name);
+    let _ = write!(text, "\n<b>Level</b>: {:?}\n<b>Msg</b>: ", item.level);
+    append_html_escaped(&mut text, &item.sample_message);
+    let _ = write!(
+        text,
+        "\n<b>Signature</b>: <code>{}</code> (seen {} times since {})",
+        item.signature,
+        item.count,
+        DateTimeAsMicroseconds::new(item.first_seen).to_rfc3339()
     );
 
+    if !ui_url.is_empty() {
+        text.push_str("\n<a href=\"");
+        append_html_escaped(&mut text, ui_url);
+        text.push_str("\">LogsUi</a>");
+    }
+
+    text
+}
+
+/// Text for the `Markdown` parse mode.
+pub fn compile_log_item_text(log_item: &LogItem, env_name: &str) -> String {
     let process = log_item.process.as_deref().unwrap_or("");
 
     let mut text = String::with_capacity(512 + log_item.message.len() + log_item.ctx.len() * 32);
@@ -102,51 +324,7 @@ pub async fn send_log_item(
     append_code_escaped_debug(&mut text, &log_item.ctx);
     text.push_str("\n```\n");
 
-    let mut chat_id_buf = itoa::Buffer::new();
-    let mut thread_id_buf = itoa::Buffer::new();
-
-    let body = UrlEncodedBody::new()
-        .append("chat_id", chat_id_buf.format(telegram_settings.chat_id))
-        .append(
-            "message_thread_id",
-            thread_id_buf.format(telegram_settings.message_thread_id),
-        )
-        .append("parse_mode", "Markdown")
-        .append("text", &text);
-
-    let response = FlUrl::new(url.as_str())
-        .accept_invalid_certificate()
-        .with_retries(3)
-        .post(body)
-        .await;
-
-    log_telegram_response("send_log_item", response).await;
-}
-
-async fn log_telegram_response(
-    fn_name: &str,
-    response: Result<flurl::FlUrlResponse, flurl::FlUrlError>,
-) {
-    match response {
-        Ok(mut response) => {
-            let status = response.get_status_code();
-            if !(200..300).contains(&status) {
-                let body = response.get_body_as_slice().await;
-                let body_str = body
-                    .as_ref()
-                    .ok()
-                    .and_then(|b| std::str::from_utf8(b).ok())
-                    .unwrap_or("<binary>");
-                println!(
-                    "{}: telegram non-2xx status {}, body: {}",
-                    fn_name, status, body_str
-                );
-            }
-        }
-        Err(err) => {
-            println!("{}: telegram request failed: {:?}", fn_name, err);
-        }
-    }
+    text
 }
 
 fn append_telegram_escaped(dst: &mut String, src: &str) {
@@ -167,6 +345,166 @@ fn append_telegram_escaped(dst: &mut String, src: &str) {
     }
 }
 
+/// `12 (+5)` - the count with the change against the previous period.
+fn write_count_with_change(dst: &mut String, current: u64, previous: u64) {
+    let _ = write!(dst, "{}", current);
+
+    if current > previous {
+        let _ = write!(dst, " (+{})", current - previous);
+    } else if current < previous {
+        let _ = write!(dst, " (-{})", previous - current);
+    }
+}
+
+/// Appends `rows` under `title` while the text stays within `max_len` characters.
+/// Rows which do not fit are replaced by a counter. `false` if some rows were skipped.
+fn append_digest_section(
+    text: &mut String,
+    text_len: &mut usize,
+    max_len: usize,
+    title: &str,
+    rows: Vec<String>,
+) -> bool {
+    if rows.is_empty() {
+        return true;
+    }
+
+    let title_len = title.chars().count();
+
+    // Room is left for the line about the rows which do not fit.
+    if *text_len + title_len + 64 > max_len {
+        return false;
+    }
+
+    text.push_str(title);
+    *text_len += title_len;
+
+    for (no, row) in rows.iter().enumerate() {
+        let row_len = row.chars().count();
+
+        if *text_len + row_len + 64 > max_len {
+            let more = format!("\n…and {} more", rows.len() - no);
+            *text_len += more.chars().count();
+            text.push_str(&more);
+            return false;
+        }
+
+        text.push_str(row);
+        *text_len += row_len;
+    }
+
+    true
+}
+
+/// Items of every section are limited by `take`, names and templates are truncated. Rows which do not fit
+/// into [`MAX_TELEGRAM_MESSAGE_LEN`] are replaced by a counter and the sections after them are skipped.
+pub fn compile_digest_text(report: &DigestReport, take: usize, ui_url: &str) -> String {
+    let mut text = String::with_capacity(2048);
+
+    match report.period {
+        DigestPeriod::Daily => text.push_str("📊<b>Daily digest</b>: "),
+        DigestPeriod::Weekly => text.push_str("📊<b>Weekly digest</b>: "),
+    }
+    append_html_escaped(
+        &mut text,
+        truncate_chars(&report.env_name, MAX_DIGEST_NAME_LEN),
+    );
+
+    text.push_str("\n<b>Errors</b>: ");
+    write_count_with_change(&mut text, report.errors, report.prev_errors);
+    text.push_str("\n<b>Fatal errors</b>: ");
+    write_count_with_change(&mut text, report.fatal_errors, report.prev_fatal_errors);
+
+    let mut footer = String::new();
+    if !ui_url.is_empty() {
+        footer.push_str("\n<a href=\"");
+        append_html_escaped(&mut footer, ui_url);
+        footer.push_str("\">LogsUi</a>");
+    }
+
+    let max_len = MAX_TELEGRAM_MESSAGE_LEN.saturating_sub(footer.chars().count());
+    let mut text_len = text.chars().count();
+
+    let applications: Vec<String> = report
+        .applications
+        .iter()
+        .take(take)
+        .map(|item| {
+            let mut row = String::from("\n• ");
+            append_html_escaped(
+                &mut row,
+                truncate_chars(&item.application, MAX_DIGEST_NAME_LEN),
+            );
+            row.push_str(": ");
+            write_count_with_change(&mut row, item.fatal_errors, item.prev_fatal_errors);
+            row.push_str(" / ");
+            write_count_with_change(&mut row, item.errors, item.prev_errors);
+            row
+        })
+        .collect();
+
+    let new_errors: Vec<String> = report
+        .new_errors
+        .iter()
+        .take(take)
+        .map(|item| {
+            let mut row = format!("\n• {}× <b>", item.count);
+            append_html_escaped(
+                &mut row,
+                truncate_chars(&item.application, MAX_DIGEST_NAME_LEN),
+            );
+            row.push_str("</b>: ");
+            append_html_escaped(
+                &mut row,
+                truncate_chars(&item.template, MAX_SAMPLE_MESSAGE_LEN),
+            );
+            row
+        })
+        .collect();
+
+    let ignore_rules: Vec<String> = report
+        .ignore_rules
+        .iter()
+        .take(take)
+        .map(|item| {
+            let mut row = format!("\n• {} suppressed: {} <code>", item.suppressed, item.levels);
+            append_html_escaped(
+                &mut row,
+                truncate_chars(&item.conditions, MAX_SAMPLE_MESSAGE_LEN),
+            );
+            row.push_str("</code>");
+            row
+        })
+        .collect();
+
+    for (title, rows) in [
+        ("\n\n<b>Applications</b> (fatal / errors):", applications),
+        ("\n\n<b>New errors</b>:", new_errors),
+        ("\n\n<b>Busiest ignore rules</b>:", ignore_rules),
+    ] {
+        if !append_digest_section(&mut text, &mut text_len, max_len, title, rows) {
+            break;
+        }
+    }
+
+    text.push_str(&footer);
+
+    text
+}
+
+pub fn append_html_escaped(dst: &mut String, src: &str) {
+    dst.reserve(src.len());
+    for c in src.chars() {
+        match c {
+            '<' => dst.push_str("&lt;"),
+            '>' => dst.push_str("&gt;"),
+            '&' => dst.push_str("&amp;"),
+            '"' => dst.push_str("&quot;"),
+            _ => dst.push(c),
+        }
+    }
+}
+
 fn append_code_escaped_debug(dst: &mut String, ctx: &std::collections::BTreeMap<String, String>) {
     use std::fmt::Write;
     struct Escaper<'a>(&'a mut String);
@@ -187,3 +525,129 @@ fn append_code_escaped_debug(dst: &mut String, ctx: &std::collections::BTreeMap<
     }
     let _ = write!(Escaper(dst), "{:#?}", ctx);
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{
+        compile_alert_text, compile_digest_text, compile_notification_text,
+        MAX_TELEGRAM_MESSAGE_LEN,
+    };
+    use crate::{
+        alerts::{AlertNotification, AlertNotificationKind},
+        app::LogItem,
+        digest::{DigestApplicationItem, DigestNewErrorItem, DigestPeriod, DigestReport},
+        telegram::TelegramNotificationData,
+    };
+
+    #[test]
+    fn test_notification_is_grouped_by_application_and_truncated() {
+        let mut data = TelegramNotificationData::new();
+        let moment = DateTimeAsMicroseconds::new(1_700_000_000_000_000);
+
+        for app_no in 0..100 {
+            for msg_no in 0..5 {
+                let mut ctx = BTreeMap::new();
+                ctx.insert("Application".to_string(), format!("app-{:03}", app_no));
+
+                data.update(&LogItem {
+                    id: format!("{}-{}", app_no, msg_no),
+                    level: my_logger::LogLevel::Error,
+                    process: None,
+                    message: format!("<Failure> number {}", msg_no % 2),
+                    timestamp: moment,
+                    ctx,
+                });
+            }
+        }
+
+        let mut now = moment;
+        now.add_minutes(5);
+        let item = data.get_something_to_notify(now).unwrap();
+
+        assert_eq!(item.errors, 500);
+        assert_eq!(item.by_application.len(), 100);
+
+        let text = compile_notification_text(&item, "test", "https://logs-ui");
+
+        assert!(text.chars().count() <= MAX_TELEGRAM_MESSAGE_LEN);
+        assert!(text.contains("<b>app-000</b> ☠️0 🟥5 ⚠️0"));
+        assert!(text.contains(" • 3× &lt;Failure&gt; number 0"));
+        assert!(text.contains("more applications"));
+    }
+
+    #[test]
+    fn test_digest_is_truncated() {
+        let report = DigestReport {
+            env_name: "prod".to_string(),
+            period: DigestPeriod::Daily,
+            from: String::new(),
+            to: String::new(),
+            errors: 1000,
+            fatal_errors: 0,
+            prev_errors: 0,
+            prev_fatal_errors: 0,
+            applications: (0..200)
+                .map(|no| DigestApplicationItem {
+                    application: format!("{}-{}", "<app>".repeat(100), no),
+                    errors: 5,
+                    fatal_errors: 0,
+                    prev_errors: 0,
+                    prev_fatal_errors: 0,
+                })
+                .collect(),
+            new_errors: vec![DigestNewErrorItem {
+                application: "billing".to_string(),
+                template: "Failure".to_string(),
+                level: "Error".to_string(),
+                count: 1,
+                first_seen: String::new(),
+            }],
+            ignore_rules: vec![],
+        };
+
+        let text = compile_digest_text(&report, 200, "https://logs-ui");
+
+        assert!(text.chars().count() <= MAX_TELEGRAM_MESSAGE_LEN);
+        assert!(text.contains("more"));
+        assert!(!text.contains("<b>New errors</b>"));
+        assert!(text.ends_with("\">LogsUi</a>"));
+        assert!(!text.contains(&"&lt;app&gt;".repeat(14)));
+    }
+
+    #[test]
+    fn test_alert_links_saved_search() {
+        let mut alert = AlertNotification {
+            kind: AlertNotificationKind::Firing,
+            rule_id: "1".to_string(),
+            rule_name: "Payments errors".to_string(),
+            min_level: crate::repo::dto::LogLevelDto::Error,
+            application: Some("payments".to_string()),
+            group_by: None,
+            group: String::new(),
+            count: 3,
+            threshold: 2,
+            window_sec: 60,
+            sample_message: None,
+            moment: DateTimeAsMicroseconds::new(1_700_000_000_000_000),
+            channels: vec![],
+            saved_search_id: Some("payments errors".to_string()),
+        };
+
+        let text = compile_alert_text(&alert, "prod", "https://logs-ui/?tab=1");
+        assert!(text.ends_with(
+            "<a href=\"https://logs-ui/?tab=1&amp;savedSearch=payments%20errors\">SavedSearch</a>"
+        ));
+
+        // No link until the UI reported its url
+        let text = compile_alert_text(&alert, "prod", "");
+        assert!(!text.contains("SavedSearch"));
+
+        alert.saved_search_id = None;
+        let text = compile_alert_text(&alert, "prod", "https://logs-ui");
+        assert!(text.ends_with("\">LogsUi</a>"));
+    }
+}
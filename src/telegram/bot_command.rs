@@ -0,0 +1,195 @@
+use crate::repo::dto::LogLevelDto;
+
+pub const MAX_MUTE_SEC: i64 = 7 * 24 * 3600;
+const DEFAULT_LAST_TAKE: usize = 5;
+const MAX_LAST_TAKE: usize = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BotCommand {
+    /// Silences notifications. With `drop_events` temporary ignore rules are added instead.
+    Mute {
+        application: String,
+        duration_sec: i64,
+        drop_events: bool,
+    },
+    Unmute {
+        application: String,
+    },
+    Ack,
+    Stats,
+    Last {
+        levels: Vec<LogLevelDto>,
+        application: Option<String>,
+        take: usize,
+    },
+    Help,
+}
+
+pub const BOT_HELP: &str = "/mute &lt;app&gt; &lt;30m|2h|1d&gt; [drop] - silence notifications (drop: ignore the errors)\n\
+/unmute &lt;app&gt; - remove the silence\n\
+/ack - silence applications of the latest summary and firing alerts until they are quiet\n\
+/stats - counters of the current hour, firing alerts and silences\n\
+/last [errors|fatal|warnings|info|debug] [app] [count] - latest events";
+
+/// `30s`, `30m`, `2h`, `1d`. Plain numbers are minutes.
+pub fn parse_duration(src: &str) -> Result<i64, String> {
+    let (number, multiplier) = match src.chars().last() {
+        Some('s') => (&src[..src.len() - 1], 1),
+        Some('m') => (&src[..src.len() - 1], 60),
+        Some('h') => (&src[..src.len() - 1], 3600),
+        Some('d') => (&src[..src.len() - 1], 86400),
+        _ => (src, 60),
+    };
+
+    let value: i64 = number
+        .parse()
+        .map_err(|_| format!("Invalid duration '{}'", src))?;
+
+    let result = value.saturating_mul(multiplier);
+
+    if result <= 0 || result > MAX_MUTE_SEC {
+        return Err(format!(
+            "Duration must be between 1s and {}d",
+            MAX_MUTE_SEC / 86400
+        ));
+    }
+
+    Ok(result)
+}
+
+fn parse_levels(src: &str) -> Option<Vec<LogLevelDto>> {
+    let result = match src.to_lowercase().as_str() {
+        "errors" | "error" => vec![LogLevelDto::Error, LogLevelDto::FatalError],
+        "fatal" | "fatals" | "fatalerror" => vec![LogLevelDto::FatalError],
+        "warnings" | "warning" => vec![LogLevelDto::Warning],
+        "info" => vec![LogLevelDto::Info],
+        "debug" => vec![LogLevelDto::Debug],
+        _ => return None,
+    };
+
+    Some(result)
+}
+
+/// `None` if the text is not a command or is a command to another bot.
+/// Group chats send commands as `/cmd@BotName`.
+pub fn parse_bot_command(text: &str, bot_username: &str) -> Option<Result<BotCommand, String>> {
+    let text = text.trim();
+    if !text.starts_with('/') {
+        return None;
+    }
+
+    let mut parts = text.split_whitespace();
+    let command = parts.next()?;
+    let command = match command.split_once('@') {
+        Some((command, username)) => {
+            if !username.eq_ignore_ascii_case(bot_username) {
+                return None;
+            }
+            command
+        }
+        None => command,
+    };
+    let args: Vec<&str> = parts.collect();
+
+    let result = match command {
+        "/mute" => parse_mute(&args),
+        "/unmute" => match args.as_slice() {
+            [application] => Ok(BotCommand::Unmute {
+                application: application.to_string(),
+            }),
+            _ => Err("Usage: /unmute &lt;app&gt;".to_string()),
+        },
+        "/ack" => Ok(BotCommand::Ack),
+        "/stats" => Ok(BotCommand::Stats),
+        "/last" => parse_last(&args),
+        "/help" | "/start" => Ok(BotCommand::Help),
+        _ => Err(format!("Unknown command. Commands:\n{}", BOT_HELP)),
+    };
+
+    Some(result)
+}
+
+fn parse_mute(args: &[&str]) -> Result<BotCommand, String> {
+    let (application, duration, drop_events) = match args {
+        [application, duration] => (application, duration, false),
+        [application, duration, "drop"] => (application, duration, true),
+        _ => return Err("Usage: /mute &lt;app&gt; &lt;30m|2h|1d&gt; [drop]".to_string()),
+    };
+
+    Ok(BotCommand::Mute {
+        application: application.to_string(),
+        duration_sec: parse_duration(duration)?,
+        drop_events,
+    })
+}
+
+fn parse_last(args: &[&str]) -> Result<BotCommand, String> {
+    let mut levels = vec![LogLevelDto::Error, LogLevelDto::FatalError];
+    let mut application = None;
+    let mut take = DEFAULT_LAST_TAKE;
+
+    for (no, arg) in args.iter().enumerate() {
+        if no == 0 {
+            if let Some(parsed) = parse_levels(arg) {
+                levels = parsed;
+                continue;
+            }
+        }
+
+        if let Ok(value) = arg.parse::<usize>() {
+            take = value.clamp(1, MAX_LAST_TAKE);
+            continue;
+        }
+
+        if application.is_some() {
+            return Err(
+                "Usage: /last [errors|fatal|warnings|info|debug] [app] [count]".to_string(),
+            );
+        }
+
+        application = Some(arg.to_string());
+    }
+
+    Ok(BotCommand::Last {
+        levels,
+        application,
+        take,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_bot_command, BotCommand};
+    use crate::repo::dto::LogLevelDto;
+
+    #[test]
+    fn test_parse_bot_commands() {
+        assert_eq!(
+            parse_bot_command("/mute@LogsBot payments 30m", "LogsBot").unwrap(),
+            Ok(BotCommand::Mute {
+                application: "payments".to_string(),
+                duration_sec: 1800,
+                drop_events: false,
+            })
+        );
+
+        assert_eq!(
+            parse_bot_command("/last errors payments", "LogsBot").unwrap(),
+            Ok(BotCommand::Last {
+                levels: vec![LogLevelDto::Error, LogLevelDto::FatalError],
+                application: Some("payments".to_string()),
+                take: 5,
+            })
+        );
+
+        assert!(parse_bot_command("/mute payments 30y", "LogsBot")
+            .unwrap()
+            .is_err());
+        assert!(parse_bot_command("/mute payments 8d", "LogsBot")
+            .unwrap()
+            .is_err());
+        assert!(parse_bot_command("hello", "LogsBot").is_none());
+        assert!(parse_bot_command("/stats@logsbot", "LogsBot").is_some());
+        assert!(parse_bot_command("/stats@OtherBot", "LogsBot").is_none());
+    }
+}
@@ -1,3 +1,9 @@
 mod telegram_notification_data;
 pub use telegram_notification_data::*;
+mod telegram_routes;
+pub use telegram_routes::*;
+mod telegram_updates;
+pub use telegram_updates::*;
+mod bot_command;
+pub use bot_command::*;
 pub mod api;
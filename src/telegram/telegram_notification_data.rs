@@ -1,9 +1,57 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
 use my_logger::LogLevel;
 use rust_extensions::{date_time::*, sorted_vec::EntityWithKey};
 
-use crate::app::LogItem;
+use crate::{
+    app::{LogItem, APPLICATION_KEY, NO_APPLICATION},
+    repo::dto::LogLevelDto,
+};
+
+const MAX_DISTINCT_MESSAGES: usize = 50;
+const MAX_MESSAGE_KEY_LEN: usize = 256;
+
+#[derive(Debug, Clone, Default)]
+pub struct ApplicationNotificationItem {
+    pub fatal_errors: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    /// Distinct FatalError and Error messages with their counts.
+    pub messages: HashMap<String, usize>,
+    /// FatalError and Error events which did not fit into `messages`.
+    pub other_messages: usize,
+}
+
+impl ApplicationNotificationItem {
+    fn add_message(&mut self, message: &str) {
+        let message = truncate_message(message);
+
+        if let Some(count) = self.messages.get_mut(message) {
+            *count += 1;
+            return;
+        }
+
+        if self.messages.len() >= MAX_DISTINCT_MESSAGES {
+            self.other_messages += 1;
+            return;
+        }
+
+        self.messages.insert(message.to_string(), 1);
+    }
+
+    /// Most frequent messages first.
+    pub fn get_top_messages(&self, take: usize) -> Vec<(&str, usize)> {
+        let mut result: Vec<_> = self
+            .messages
+            .iter()
+            .map(|(message, count)| (message.as_str(), *count))
+            .collect();
+
+        result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        result.truncate(take);
+        result
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct NotificationItem {
@@ -11,6 +59,120 @@ pub struct NotificationItem {
     pub fatal_errors: usize,
     pub errors: usize,
     pub warnings: usize,
+    pub by_application: BTreeMap<String, ApplicationNotificationItem>,
+}
+
+impl NotificationItem {
+    fn new(key: IntervalKey<MinuteKey>) -> Self {
+        Self {
+            key,
+            fatal_errors: 0,
+            errors: 0,
+            warnings: 0,
+            by_application: BTreeMap::new(),
+        }
+    }
+
+    fn get_application_mut(&mut self, application: &str) -> &mut ApplicationNotificationItem {
+        if !self.by_application.contains_key(application) {
+            self.by_application.insert(
+                application.to_string(),
+                ApplicationNotificationItem::default(),
+            );
+        }
+
+        self.by_application.get_mut(application).unwrap()
+    }
+
+    /// Applications with the most severe problems first.
+    pub fn get_applications_by_severity(&self) -> Vec<(&str, &ApplicationNotificationItem)> {
+        let mut result: Vec<_> = self
+            .by_application
+            .iter()
+            .map(|(application, item)| (application.as_str(), item))
+            .collect();
+
+        result.sort_by(|a, b| {
+            b.1.fatal_errors
+                .cmp(&a.1.fatal_errors)
+                .then_with(|| b.1.errors.cmp(&a.1.errors))
+                .then_with(|| b.1.warnings.cmp(&a.1.warnings))
+                .then_with(|| a.0.cmp(b.0))
+        });
+
+        result
+    }
+
+    /// Copy with only the matching applications. Totals are recalculated.
+    pub fn filter_applications(&self, filter: impl Fn(&str) -> bool) -> Self {
+        let mut result = Self::new(self.key);
+
+        for (application, item) in self.by_application.iter() {
+            if !filter(application) {
+                continue;
+            }
+
+            result.fatal_errors += item.fatal_errors;
+            result.errors += item.errors;
+            result.warnings += item.warnings;
+            result
+                .by_application
+                .insert(application.to_string(), item.clone());
+        }
+
+        result
+    }
+
+    /// Splits the counters by the key of every application and level.
+    /// Messages go along with the most severe level of the application.
+    pub fn split_by<TKey: Ord>(
+        &self,
+        get_key: impl Fn(&str, &LogLevelDto) -> TKey,
+    ) -> BTreeMap<TKey, NotificationItem> {
+        let mut result = BTreeMap::new();
+
+        for (application, item) in self.by_application.iter() {
+            let levels = [
+                (LogLevelDto::FatalError, item.fatal_errors),
+                (LogLevelDto::Error, item.errors),
+                (LogLevelDto::Warning, item.warnings),
+            ];
+
+            let mut messages_taken = false;
+
+            for (level, count) in levels {
+                if count == 0 {
+                    continue;
+                }
+
+                let dst = result
+                    .entry(get_key(application, &level))
+                    .or_insert_with(|| NotificationItem::new(self.key));
+
+                match level {
+                    LogLevelDto::FatalError => dst.fatal_errors += count,
+                    LogLevelDto::Error => dst.errors += count,
+                    _ => dst.warnings += count,
+                }
+
+                let by_app = dst.get_application_mut(application);
+
+                match level {
+                    LogLevelDto::FatalError => by_app.fatal_errors += count,
+                    LogLevelDto::Error => by_app.errors += count,
+                    _ => by_app.warnings += count,
+                }
+
+                if !messages_taken && level != LogLevelDto::Warning {
+                    by_app.messages = item.messages.clone();
+                    by_app.other_messages = item.other_messages;
+                    messages_taken = true;
+                }
+            }
+        }
+
+        result
+    }
 }
 
 impl EntityWithKey<i64> for NotificationItem {
@@ -31,77 +193,44 @@ impl TelegramNotificationData {
     }
 
     pub fn update(&mut self, itm: &LogItem) {
-        match itm.level {
-            LogLevel::FatalError => self.inc_fatal_error(itm.timestamp),
-            LogLevel::Error => self.inc_error(itm.timestamp),
-            LogLevel::Warning => self.inc_warnings(itm.timestamp),
-            _ => {}
+        if !matches!(
+            itm.level,
+            LogLevel::FatalError | LogLevel::Error | LogLevel::Warning
+        ) {
+            return;
         }
-    }
 
-    pub fn inc_fatal_error(&mut self, dt: DateTimeAsMicroseconds) {
-        let key: IntervalKey<MinuteKey> = dt.into();
+        let key: IntervalKey<MinuteKey> = itm.timestamp.into();
 
-        let key_i64 = key.to_i64();
-        match self.items.get_mut(&key_i64) {
-            Some(item) => {
-                item.fatal_errors += 1;
-            }
-            None => {
-                self.items.insert(
-                    key_i64,
-                    NotificationItem {
-                        key: key,
-                        fatal_errors: 1,
-                        errors: 0,
-                        warnings: 0,
-                    },
-                );
-            }
-        }
-    }
+        let item = self
+            .items
+            .entry(key.to_i64())
+            .or_insert_with(|| NotificationItem::new(key));
 
-    pub fn inc_error(&mut self, dt: DateTimeAsMicroseconds) {
-        let key: IntervalKey<MinuteKey> = dt.into();
+        let application = itm
+            .ctx
+            .get(APPLICATION_KEY)
+            .map(|s| s.as_str())
+            .unwrap_or(NO_APPLICATION);
 
-        let key_i64 = key.to_i64();
-        match self.items.get_mut(&key_i64) {
-            Some(item) => {
-                item.errors += 1;
+        match itm.level {
+            LogLevel::FatalError => {
+                item.fatal_errors += 1;
+                let by_app = item.get_application_mut(application);
+                by_app.fatal_errors += 1;
+                by_app.add_message(&itm.message);
             }
-            None => {
-                self.items.insert(
-                    key_i64,
-                    NotificationItem {
-                        key: key,
-                        fatal_errors: 0,
-                        errors: 1,
-                        warnings: 0,
-                    },
-                );
+            LogLevel::Error => {
+                item.errors += 1;
+                let by_app = item.get_application_mut(application);
+                by_app.errors += 1;
+                by_app.add_message(&itm.message);
             }
-        }
-    }
-
-    pub fn inc_warnings(&mut self, dt: DateTimeAsMicroseconds) {
-        let key: IntervalKey<MinuteKey> = dt.into();
-
-        let key_i64 = key.to_i64();
-        match self.items.get_mut(&key_i64) {
-            Some(item) => {
+            LogLevel::Warning => {
                 item.warnings += 1;
+                item.get_application_mut(application).warnings += 1;
             }
-            None => {
-                self.items.insert(
-                    key_i64,
-                    NotificationItem {
-                        key: key,
-                        fatal_errors: 0,
-                        errors: 0,
-                        warnings: 1,
-                    },
-                );
-            }
+            _ => {}
         }
     }
 
@@ -127,3 +256,16 @@ impl TelegramNotificationData {
         self.items.remove(&result_key)
     }
 }
+
+fn truncate_message(src: &str) -> &str {
+    if src.len() <= MAX_MESSAGE_KEY_LEN {
+        return src;
+    }
+
+    let mut end = MAX_MESSAGE_KEY_LEN;
+    while !src.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    &src[..end]
+}
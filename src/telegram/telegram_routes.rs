@@ -0,0 +1,127 @@
+use crate::{
+    repo::dto::LogLevelDto,
+    settings::{TelegramRouteSettings, TelegramSettings},
+    text_matching::is_glob_match,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TelegramChat {
+    pub chat_id: i64,
+    pub message_thread_id: Option<i32>,
+}
+
+fn route_matches(
+    route: &TelegramRouteSettings,
+    application: Option<&str>,
+    level: &LogLevelDto,
+) -> bool {
+    if let Some(levels) = route.levels.as_ref() {
+        if !levels.is_empty() && !levels.contains(level) {
+            return false;
+        }
+    }
+
+    let Some(patterns) = route.applications.as_ref() else {
+        return true;
+    };
+
+    if patterns.is_empty() {
+        return true;
+    }
+
+    match application {
+        Some(application) => patterns
+            .iter()
+            .any(|pattern| is_glob_match(pattern, application)),
+        None => false,
+    }
+}
+
+/// Picks the chat of the first matching route. Falls back to the chat of the settings.
+pub fn get_telegram_chat(
+    settings: &TelegramSettings,
+    application: Option<&str>,
+    level: &LogLevelDto,
+) -> TelegramChat {
+    if let Some(routes) = settings.routes.as_ref() {
+        for route in routes {
+            if route_matches(route, application, level) {
+                return TelegramChat {
+                    chat_id: route.chat_id,
+                    message_thread_id: route.message_thread_id,
+                };
+            }
+        }
+    }
+
+    TelegramChat {
+        chat_id: settings.chat_id,
+        message_thread_id: Some(settings.message_thread_id),
+    }
+}
+
+/// Default chat and the chats of all routes.
+pub fn get_telegram_chat_ids(settings: &TelegramSettings) -> Vec<i64> {
+    let mut result = vec![settings.chat_id];
+
+    for route in settings.routes.iter().flatten() {
+        if !result.contains(&route.chat_id) {
+            result.push(route.chat_id);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_telegram_chat, TelegramChat};
+    use crate::{
+        repo::dto::LogLevelDto,
+        settings::{TelegramRouteSettings, TelegramSettings},
+    };
+
+    #[test]
+    fn test_first_matching_route_wins() {
+        let settings = TelegramSettings {
+            api_key: "key".to_string(),
+            chat_id: 1,
+            message_thread_id: 10,
+            routes: Some(vec![
+                TelegramRouteSettings {
+                    applications: Some(vec!["payments*".to_string()]),
+                    levels: Some(vec![LogLevelDto::FatalError]),
+                    chat_id: 2,
+                    message_thread_id: None,
+                },
+                TelegramRouteSettings {
+                    applications: Some(vec!["payments*".to_string(), "billing".to_string()]),
+                    levels: None,
+                    chat_id: 3,
+                    message_thread_id: Some(30),
+                },
+            ]),
+            bot_commands: None,
+        };
+
+        let chat = |application, level| get_telegram_chat(&settings, application, &level);
+
+        assert_eq!(
+            chat(Some("payments-api"), LogLevelDto::FatalError),
+            TelegramChat {
+                chat_id: 2,
+                message_thread_id: None
+            }
+        );
+        assert_eq!(chat(Some("payments-api"), LogLevelDto::Error).chat_id, 3);
+        assert_eq!(chat(Some("billing"), LogLevelDto::Warning).chat_id, 3);
+        assert_eq!(
+            chat(Some("orders"), LogLevelDto::Error),
+            TelegramChat {
+                chat_id: 1,
+                message_thread_id: Some(10)
+            }
+        );
+        assert_eq!(chat(None, LogLevelDto::Error).chat_id, 1);
+    }
+}
@@ -0,0 +1,49 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct TelegramUpdatesResponse {
+    pub ok: bool,
+    #[serde(default)]
+    pub result: Vec<TelegramUpdate>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TelegramGetMeResponse {
+    pub ok: bool,
+    pub result: Option<TelegramUser>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TelegramUpdate {
+    pub update_id: i64,
+    pub message: Option<TelegramMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TelegramMessage {
+    pub chat: TelegramMessageChat,
+    pub message_thread_id: Option<i32>,
+    pub from: Option<TelegramUser>,
+    /// Unix seconds.
+    pub date: i64,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TelegramMessageChat {
+    pub id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TelegramUser {
+    pub username: Option<String>,
+    pub first_name: String,
+}
+
+impl TelegramUser {
+    pub fn get_display_name(&self) -> &str {
+        self.username.as_deref().unwrap_or(&self.first_name)
+    }
+}
@@ -0,0 +1,110 @@
+use crate::repo::dto::{ConditionOperatorDto, MatchModeDto};
+
+use super::{eq_ignore_case, starts_with_ignore_case, TextMatcher, MAX_PATTERN_LEN};
+
+/// Value of an ignore rule condition compiled once per rule.
+/// `value` is lowercased when `case_insensitive` is set.
+#[derive(Debug, Clone)]
+pub enum ConditionMatcher {
+    Equals {
+        value: String,
+        case_insensitive: bool,
+    },
+    Prefix {
+        value: String,
+        case_insensitive: bool,
+    },
+    Text(TextMatcher),
+}
+
+impl ConditionMatcher {
+    pub fn new(
+        pattern: &str,
+        operator: ConditionOperatorDto,
+        case_insensitive: bool,
+    ) -> Result<Self, String> {
+        if pattern.len() > MAX_PATTERN_LEN {
+            return Err(format!(
+                "Pattern is too long: {} chars. Max is {}",
+                pattern.len(),
+                MAX_PATTERN_LEN
+            ));
+        }
+
+        let value = if case_insensitive {
+            pattern.to_lowercase()
+        } else {
+            pattern.to_string()
+        };
+
+        match operator {
+            ConditionOperatorDto::Equals => Ok(Self::Equals {
+                value,
+                case_insensitive,
+            }),
+            ConditionOperatorDto::Prefix | ConditionOperatorDto::Contains if pattern.is_empty() => {
+                Err(format!(
+                    "{:?} condition with an empty value would match every event",
+                    operator
+                ))
+            }
+            ConditionOperatorDto::Prefix => Ok(Self::Prefix {
+                value,
+                case_insensitive,
+            }),
+            ConditionOperatorDto::Contains => {
+                TextMatcher::new(pattern, MatchModeDto::Contains, case_insensitive).map(Self::Text)
+            }
+            ConditionOperatorDto::Regex => {
+                TextMatcher::new(pattern, MatchModeDto::Regex, case_insensitive).map(Self::Text)
+            }
+        }
+    }
+
+    pub fn is_match(&self, src: &str) -> bool {
+        match self {
+            Self::Equals {
+                value,
+                case_insensitive,
+            } => {
+                if *case_insensitive {
+                    eq_ignore_case(src, value)
+                } else {
+                    src == value
+                }
+            }
+            Self::Prefix {
+                value,
+                case_insensitive,
+            } => {
+                if *case_insensitive {
+                    starts_with_ignore_case(src, value)
+                } else {
+                    src.starts_with(value.as_str())
+                }
+            }
+            Self::Text(matcher) => matcher.is_match(src),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConditionMatcher;
+    use crate::repo::dto::ConditionOperatorDto;
+
+    #[test]
+    fn test_operators() {
+        let matcher =
+            ConditionMatcher::new("eu-west", ConditionOperatorDto::Prefix, false).unwrap();
+        assert!(matcher.is_match("eu-west-1"));
+        assert!(!matcher.is_match("us-eu-west"));
+
+        let matcher = ConditionMatcher::new("Healthy", ConditionOperatorDto::Equals, true).unwrap();
+        assert!(matcher.is_match("healthy"));
+        assert!(!matcher.is_match("healthy check"));
+
+        assert!(ConditionMatcher::new("", ConditionOperatorDto::Prefix, false).is_err());
+        assert!(ConditionMatcher::new(".*", ConditionOperatorDto::Regex, false).is_err());
+    }
+}
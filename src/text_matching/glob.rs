@@ -0,0 +1,48 @@
+/// Case-sensitive glob match where `*` matches any sequence and `?` any single character.
+pub fn is_glob_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+
+    let mut p = 0;
+    let mut v = 0;
+    let mut star: Option<usize> = None;
+    let mut star_v = 0;
+
+    while v < value.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == value[v]) {
+            p += 1;
+            v += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some(p);
+            star_v = v;
+            p += 1;
+        } else if let Some(star) = star {
+            p = star + 1;
+            star_v += 1;
+            v = star_v;
+        } else {
+            return false;
+        }
+    }
+
+    while p < pattern.len() && pattern[p] == '*' {
+        p += 1;
+    }
+
+    p == pattern.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_glob_match;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(is_glob_match("payments*", "payments-api"));
+        assert!(is_glob_match("*", ""));
+        assert!(is_glob_match("*-api", "orders-api"));
+        assert!(is_glob_match("svc-?", "svc-1"));
+        assert!(!is_glob_match("svc-?", "svc-10"));
+        assert!(!is_glob_match("payments", "payments-api"));
+    }
+}
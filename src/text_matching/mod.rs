@@ -0,0 +1,6 @@
+mod condition_matcher;
+mod glob;
+mod text_matcher;
+pub use condition_matcher::*;
+pub use glob::*;
+pub use text_matcher::*;
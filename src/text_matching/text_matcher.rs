@@ -0,0 +1,190 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::repo::dto::MatchModeDto;
+
+pub const MAX_PATTERN_LEN: usize = 1024;
+const REGEX_SIZE_LIMIT: usize = 1024 * 1024;
+const REGEX_DFA_SIZE_LIMIT: usize = 2 * 1024 * 1024;
+const REGEX_NEST_LIMIT: u32 = 32;
+
+/// Upper bound of stored records scanned by one shard when search is done with a [`TextMatcher`].
+pub const MAX_MATCHER_SCAN_ITEMS: usize = 100_000;
+
+/// Pattern compiled once per rule or search request.
+#[derive(Debug, Clone)]
+pub enum TextMatcher {
+    /// `value` is lowercased when `case_insensitive` is set.
+    Contains {
+        value: String,
+        case_insensitive: bool,
+    },
+    Regex(Regex),
+}
+
+impl TextMatcher {
+    pub fn new(pattern: &str, mode: MatchModeDto, case_insensitive: bool) -> Result<Self, String> {
+        if pattern.len() > MAX_PATTERN_LEN {
+            return Err(format!(
+                "Pattern is too long: {} chars. Max is {}",
+                pattern.len(),
+                MAX_PATTERN_LEN
+            ));
+        }
+
+        match mode {
+            MatchModeDto::Contains => {
+                let value = if case_insensitive {
+                    pattern.to_lowercase()
+                } else {
+                    pattern.to_string()
+                };
+                Ok(Self::Contains {
+                    value,
+                    case_insensitive,
+                })
+            }
+            MatchModeDto::Regex => {
+                let regex = compile_regex(pattern, case_insensitive)?;
+
+                if regex.is_match("") {
+                    return Err(format!(
+                        "Regex '{}' matches an empty string and would match every event",
+                        pattern
+                    ));
+                }
+
+                Ok(Self::Regex(regex))
+            }
+        }
+    }
+
+    pub fn is_match(&self, src: &str) -> bool {
+        match self {
+            Self::Contains {
+                value,
+                case_insensitive,
+            } => {
+                if *case_insensitive {
+                    contains_ignore_case(src, value)
+                } else {
+                    src.contains(value.as_str())
+                }
+            }
+            Self::Regex(regex) => regex.is_match(src),
+        }
+    }
+}
+
+/// Compiles user supplied pattern with the size and nesting limits applied.
+pub fn compile_regex(pattern: &str, case_insensitive: bool) -> Result<Regex, String> {
+    if pattern.len() > MAX_PATTERN_LEN {
+        return Err(format!(
+            "Pattern is too long: {} chars. Max is {}",
+            pattern.len(),
+            MAX_PATTERN_LEN
+        ));
+    }
+
+    RegexBuilder::new(pattern)
+        .case_insensitive(case_insensitive)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_DFA_SIZE_LIMIT)
+        .nest_limit(REGEX_NEST_LIMIT)
+        .build()
+        .map_err(|err| format!("Invalid regex '{}': {}", pattern, err))
+}
+
+/// Helpers below compare `src` ignoring its case with an already lowercased pattern, without allocations.
+pub fn starts_with_ignore_case(src: &str, lowercase: &str) -> bool {
+    if lowercase.is_ascii() {
+        return src.len() >= lowercase.len()
+            && src.as_bytes()[..lowercase.len()].eq_ignore_ascii_case(lowercase.as_bytes());
+    }
+
+    let mut expected = lowercase.chars();
+    for c in src.chars().flat_map(char::to_lowercase) {
+        match expected.next() {
+            Some(lowercase_char) if lowercase_char == c => {}
+            Some(_) => return false,
+            None => return true,
+        }
+    }
+
+    expected.next().is_none()
+}
+
+pub fn eq_ignore_case(src: &str, lowercase: &str) -> bool {
+    if lowercase.is_ascii() {
+        return src.eq_ignore_ascii_case(lowercase);
+    }
+
+    src.chars()
+        .flat_map(char::to_lowercase)
+        .eq(lowercase.chars())
+}
+
+pub fn contains_ignore_case(src: &str, lowercase: &str) -> bool {
+    if lowercase.is_ascii() {
+        let lowercase = lowercase.as_bytes();
+        if lowercase.is_empty() {
+            return true;
+        }
+
+        return src
+            .as_bytes()
+            .windows(lowercase.len())
+            .any(|window| window.eq_ignore_ascii_case(lowercase));
+    }
+
+    src.char_indices()
+        .any(|(index, _)| starts_with_ignore_case(&src[index..], lowercase))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{eq_ignore_case, starts_with_ignore_case, TextMatcher};
+    use crate::repo::dto::MatchModeDto;
+
+    #[test]
+    fn test_regex_matching() {
+        let matcher = TextMatcher::new(r"timeout after \d+ms", MatchModeDto::Regex, false).unwrap();
+
+        assert!(matcher.is_match("Request Timeout after 150ms"));
+        assert!(!matcher.is_match("timeout after ms"));
+    }
+
+    #[test]
+    fn test_case_insensitive_contains() {
+        let matcher = TextMatcher::new("Deadlock", MatchModeDto::Contains, false).unwrap();
+        assert!(matcher.is_match("Deadlock detected"));
+        assert!(!matcher.is_match("deadlock detected"));
+
+        let matcher = TextMatcher::new("Deadlock", MatchModeDto::Contains, true).unwrap();
+        assert!(matcher.is_match("DEADLOCK detected"));
+        assert!(matcher.is_match("Found deadlock"));
+        assert!(!matcher.is_match("Dead lock"));
+
+        let matcher = TextMatcher::new("Ошибка БД", MatchModeDto::Contains, true).unwrap();
+        assert!(matcher.is_match("Критическая ОШИБКА бд"));
+        assert!(!matcher.is_match("Ошибка"));
+    }
+
+    #[test]
+    fn test_ignore_case_helpers() {
+        assert!(eq_ignore_case("Healthy", "healthy"));
+        assert!(!eq_ignore_case("Healthy check", "healthy"));
+        assert!(eq_ignore_case("ÉTÉ", "été"));
+
+        assert!(starts_with_ignore_case("EU-West-1", "eu-west"));
+        assert!(!starts_with_ignore_case("EU", "eu-west"));
+        assert!(starts_with_ignore_case("ÉTÉ 2024", "été"));
+        assert!(!starts_with_ignore_case("ÉT", "été"));
+    }
+
+    #[test]
+    fn test_rejects_patterns_matching_everything() {
+        assert!(TextMatcher::new(".*", MatchModeDto::Regex, false).is_err());
+        assert!(TextMatcher::new("a{1000}{1000}", MatchModeDto::Regex, false).is_err());
+        assert!(TextMatcher::new("(unclosed", MatchModeDto::Regex, false).is_err());
+    }
+}
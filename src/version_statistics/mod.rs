@@ -0,0 +1,2 @@
+mod version_statistics;
+pub use version_statistics::*;
@@ -0,0 +1,401 @@
+use std::collections::BTreeMap;
+
+use my_logger::LogLevel;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::{LogItem, APPLICATION_KEY, VERSION_KEY},
+    hourly_statistics::HourlyStatisticsItem,
+};
+
+/// Versions first seen earlier are dropped above this limit.
+pub const MAX_VERSIONS_PER_APPLICATION: usize = 20;
+/// Applications seen least recently are dropped above this limit.
+pub const MAX_APPLICATIONS: usize = 1000;
+const MAX_VERSION_LEN: usize = 64;
+
+/// The current version needs at least this many events to be compared.
+pub const MIN_EVENTS_TO_COMPARE: u64 = 100;
+/// A rate differing by less than this share of the previous one is considered the same.
+const RATE_TOLERANCE: f64 = 0.2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionSummary {
+    pub application: String,
+    pub version: String,
+    /// Unix microseconds of the first and the last event of the version, as received by the server.
+    pub first_seen: i64,
+    pub last_seen: i64,
+    pub counts: HourlyStatisticsItem,
+}
+
+impl VersionSummary {
+    pub fn get_total(&self) -> u64 {
+        self.counts.info as u64
+            + self.counts.warning as u64
+            + self.counts.error as u64
+            + self.counts.fatal_error as u64
+            + self.counts.debug as u64
+    }
+
+    /// Share of Error events among all events of the version.
+    pub fn get_error_rate(&self) -> f64 {
+        get_rate(self.counts.error, self.get_total())
+    }
+
+    /// Share of FatalError events among all events of the version.
+    pub fn get_fatal_rate(&self) -> f64 {
+        get_rate(self.counts.fatal_error, self.get_total())
+    }
+}
+
+fn get_rate(count: u32, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+
+    count as f64 / total as f64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegressionVerdict {
+    /// No previous version or too few events of the current one.
+    NotEnoughData,
+    Same,
+    Better,
+    Worse,
+}
+
+impl RegressionVerdict {
+    pub fn evaluate(
+        current: &VersionSummary,
+        previous: Option<&VersionSummary>,
+        new_signatures: usize,
+    ) -> Self {
+        let Some(previous) = previous else {
+            return Self::NotEnoughData;
+        };
+
+        if current.get_total() < MIN_EVENTS_TO_COMPARE {
+            return Self::NotEnoughData;
+        }
+
+        let error = compare_rates(current.get_error_rate(), previous.get_error_rate());
+        let fatal = compare_rates(current.get_fatal_rate(), previous.get_fatal_rate());
+
+        if new_signatures > 0 || error.is_gt() || fatal.is_gt() {
+            return Self::Worse;
+        }
+
+        if error.is_lt() || fatal.is_lt() {
+            return Self::Better;
+        }
+
+        Self::Same
+    }
+}
+
+fn compare_rates(current: f64, previous: f64) -> std::cmp::Ordering {
+    if current > previous * (1.0 + RATE_TOLERANCE) && current > 0.0 {
+        return std::cmp::Ordering::Greater;
+    }
+
+    if current < previous * (1.0 - RATE_TOLERANCE) {
+        return std::cmp::Ordering::Less;
+    }
+
+    std::cmp::Ordering::Equal
+}
+
+/// Counts by level of every `Version` of every application, with the moment each version was first seen.
+pub struct VersionStatistics {
+    items: BTreeMap<String, BTreeMap<String, VersionSummary>>,
+    has_changes: bool,
+}
+
+impl VersionStatistics {
+    pub fn new() -> Self {
+        Self {
+            items: BTreeMap::new(),
+            has_changes: false,
+        }
+    }
+
+    /// Versions are ordered by `now`, the moment the server received the event,
+    /// so the clocks of the applications do not matter.
+    pub fn update(&mut self, itm: &LogItem, now: DateTimeAsMicroseconds) {
+        let Some(application) = itm.ctx.get(APPLICATION_KEY) else {
+            return;
+        };
+
+        let version = match itm.ctx.get(VERSION_KEY).map(|value| value.trim()) {
+            Some(value) if !value.is_empty() => value,
+            _ => return,
+        };
+
+        let version: String = version.chars().take(MAX_VERSION_LEN).collect();
+        let moment = now.unix_microseconds;
+
+        if !self.items.contains_key(application) {
+            self.items.insert(application.to_string(), BTreeMap::new());
+            remove_least_recent_applications(&mut self.items);
+        }
+
+        let Some(versions) = self.items.get_mut(application) else {
+            return;
+        };
+
+        if !versions.contains_key(&version) {
+            versions.insert(
+                version.clone(),
+                VersionSummary {
+                    application: application.to_string(),
+                    version: version.clone(),
+                    first_seen: moment,
+                    last_seen: moment,
+                    counts: HourlyStatisticsItem::default(),
+                },
+            );
+            remove_earliest_versions(versions);
+        }
+
+        let Some(item) = versions.get_mut(&version) else {
+            return;
+        };
+
+        item.last_seen = item.last_seen.max(moment);
+
+        match itm.level {
+            LogLevel::Info => item.counts.info += 1,
+            LogLevel::Warning => item.counts.warning += 1,
+            LogLevel::Error => item.counts.error += 1,
+            LogLevel::FatalError => item.counts.fatal_error += 1,
+            LogLevel::Debug => item.counts.debug += 1,
+        }
+
+        self.has_changes = true;
+    }
+
+    pub fn get_applications(&self) -> Vec<String> {
+        self.items.keys().cloned().collect()
+    }
+
+    /// The given version, or the latest one if not set, together with the version first seen before it.
+    pub fn get_with_previous(
+        &self,
+        application: &str,
+        version: Option<&str>,
+    ) -> Option<(VersionSummary, Option<VersionSummary>)> {
+        let mut versions: Vec<&VersionSummary> = self.items.get(application)?.values().collect();
+        versions.sort_by(|a, b| {
+            a.first_seen
+                .cmp(&b.first_seen)
+                .then_with(|| a.version.cmp(&b.version))
+        });
+
+        let index = match version {
+            Some(version) => versions.iter().position(|item| item.version == version)?,
+            None => versions.len().checked_sub(1)?,
+        };
+
+        let previous = index
+            .checked_sub(1)
+            .map(|previous| versions[previous].clone());
+
+        Some((versions[index].clone(), previous))
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.values().map(|versions| versions.len()).sum()
+    }
+
+    /// Applies the limits of applications and versions to the restored items.
+    pub fn restore(&mut self, items: Vec<VersionSummary>) {
+        for item in items {
+            self.items
+                .entry(item.application.clone())
+                .or_default()
+                .insert(item.version.clone(), item);
+        }
+
+        for versions in self.items.values_mut() {
+            remove_earliest_versions(versions);
+        }
+
+        remove_least_recent_applications(&mut self.items);
+    }
+
+    /// `None` if nothing changed since the previous call.
+    pub fn get_snapshot_to_persist(&mut self) -> Option<Vec<VersionSummary>> {
+        if !self.has_changes {
+            return None;
+        }
+
+        self.has_changes = false;
+
+        Some(
+            self.items
+                .values()
+                .flat_map(|versions| versions.values().cloned())
+                .collect(),
+        )
+    }
+}
+
+fn remove_earliest_versions(versions: &mut BTreeMap<String, VersionSummary>) {
+    while versions.len() > MAX_VERSIONS_PER_APPLICATION {
+        let earliest = versions
+            .values()
+            .min_by_key(|item| item.first_seen)
+            .map(|item| item.version.clone());
+
+        match earliest {
+            Some(earliest) => versions.remove(&earliest),
+            None => return,
+        };
+    }
+}
+
+/// An application without versions yet counts as seen right now.
+fn remove_least_recent_applications(
+    items: &mut BTreeMap<String, BTreeMap<String, VersionSummary>>,
+) {
+    while items.len() > MAX_APPLICATIONS {
+        let least_recent = items
+            .iter()
+            .min_by_key(|(_, versions)| {
+                versions
+                    .values()
+                    .map(|item| item.last_seen)
+                    .max()
+                    .unwrap_or(i64::MAX)
+            })
+            .map(|(application, _)| application.clone());
+
+        match least_recent {
+            Some(least_recent) => items.remove(&least_recent),
+            None => return,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::app::LogItem;
+
+    use super::{
+        RegressionVerdict, VersionStatistics, VersionSummary, MAX_VERSIONS_PER_APPLICATION,
+    };
+
+    fn log_item(unix_sec: i64, version: &str, level: my_logger::LogLevel) -> LogItem {
+        let mut ctx = BTreeMap::new();
+        ctx.insert("Application".to_string(), "payments".to_string());
+        ctx.insert("Version".to_string(), version.to_string());
+
+        LogItem {
+            id: "Test".to_string(),
+            level,
+            process: None,
+            message: "Test".to_string(),
+            timestamp: DateTimeAsMicroseconds::new(unix_sec * 1_000_000),
+            ctx,
+        }
+    }
+
+    #[test]
+    fn test_version_regression() {
+        let mut statistics = VersionStatistics::new();
+
+        for i in 0..200 {
+            let level = if i % 20 == 0 {
+                my_logger::LogLevel::Error
+            } else {
+                my_logger::LogLevel::Info
+            };
+            let itm = log_item(1_000 + i, "0.1.17", level);
+            statistics.update(&itm, itm.timestamp);
+        }
+
+        for i in 0..200 {
+            let level = if i % 5 == 0 {
+                my_logger::LogLevel::Error
+            } else {
+                my_logger::LogLevel::Info
+            };
+            let itm = log_item(2_000 + i, "0.1.18", level);
+            statistics.update(&itm, itm.timestamp);
+        }
+
+        let (current, previous) = statistics.get_with_previous("payments", None).unwrap();
+        let previous = previous.unwrap();
+        assert_eq!(current.version, "0.1.18");
+        assert_eq!(previous.version, "0.1.17");
+        assert_eq!(current.first_seen, 2_000_000_000);
+        assert_eq!(current.counts.error, 40);
+        assert_eq!(
+            RegressionVerdict::evaluate(&current, Some(&previous), 0),
+            RegressionVerdict::Worse
+        );
+        assert_eq!(
+            RegressionVerdict::evaluate(&previous, Some(&current), 0),
+            RegressionVerdict::Better
+        );
+        assert_eq!(
+            RegressionVerdict::evaluate(&current, None, 0),
+            RegressionVerdict::NotEnoughData
+        );
+
+        let (first, before_first) = statistics
+            .get_with_previous("payments", Some("0.1.17"))
+            .unwrap();
+        assert_eq!(first.version, "0.1.17");
+        assert!(before_first.is_none());
+        assert!(statistics
+            .get_with_previous("payments", Some("0.2.0"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_versions_ordered_by_receive_time() {
+        let mut statistics = VersionStatistics::new();
+
+        let newer = log_item(1_000, "0.1.18", my_logger::LogLevel::Info);
+        statistics.update(&newer, DateTimeAsMicroseconds::new(5_000_000_000));
+
+        let skewed = log_item(9_000, "0.1.17", my_logger::LogLevel::Info);
+        statistics.update(&skewed, DateTimeAsMicroseconds::new(4_000_000_000));
+
+        let (current, previous) = statistics.get_with_previous("payments", None).unwrap();
+        assert_eq!(current.version, "0.1.18");
+        assert_eq!(previous.unwrap().version, "0.1.17");
+    }
+
+    #[test]
+    fn test_restore_applies_limits() {
+        let items: Vec<_> = (0..MAX_VERSIONS_PER_APPLICATION as i64 + 5)
+            .map(|i| VersionSummary {
+                application: "payments".to_string(),
+                version: format!("0.1.{}", i),
+                first_seen: i,
+                last_seen: i,
+                counts: Default::default(),
+            })
+            .collect();
+
+        let mut statistics = VersionStatistics::new();
+        statistics.restore(items);
+
+        assert_eq!(statistics.len(), MAX_VERSIONS_PER_APPLICATION);
+        assert!(statistics
+            .get_with_previous("payments", Some("0.1.4"))
+            .is_none());
+        assert!(statistics
+            .get_with_previous("payments", Some("0.1.5"))
+            .is_some());
+    }
+}